@@ -0,0 +1,41 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use datafusion_geo::config::default_dialect;
+use datafusion_geo::geo::GeometryArrayBuilder;
+
+fn polygons(len: usize) -> Vec<Option<geo::Geometry>> {
+    (0..len)
+        .map(|i| {
+            let x = (i % 1000) as f64;
+            let y = (i / 1000) as f64;
+            let exterior = (0..32)
+                .map(|j| {
+                    let angle = j as f64 / 32.0 * std::f64::consts::TAU;
+                    (x + angle.cos() * 0.4, y + angle.sin() * 0.4)
+                })
+                .chain(std::iter::once((x + 0.4, y)))
+                .collect::<Vec<_>>();
+            Some(geo::Polygon::new(exterior.into(), vec![]).into())
+        })
+        .collect()
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let geoms = polygons(200_000);
+    c.bench_function("serial builder with 200k polygons", |b| {
+        b.iter(|| {
+            GeometryArrayBuilder::<i32>::try_from(geoms.as_slice())
+                .unwrap()
+                .build()
+        })
+    });
+    c.bench_function("parallel builder with 200k polygons", |b| {
+        b.iter(|| {
+            GeometryArrayBuilder::<i32>::from_geo_parallel(&geoms, default_dialect())
+                .unwrap()
+                .build()
+        })
+    });
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
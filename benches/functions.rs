@@ -0,0 +1,57 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use datafusion::prelude::SessionContext;
+use datafusion_geo::function::register_all;
+
+mod util;
+
+const ROWS: usize = 10_000;
+
+/// The code path of the functions having both, chosen by the geos feature flag.
+#[cfg(feature = "geos")]
+const PATH: &str = "geos";
+#[cfg(not(feature = "geos"))]
+const PATH: &str = "geo";
+
+const POLYGON: &str = "ST_GeomFromText('POLYGON((0 0,500 0,500 500,0 500,0 0))')";
+/// Probe of the intersects benchmark, a polygon probe would take the point in polygon fast path
+/// on the points workload instead of the general predicate.
+const LINESTRING: &str = "ST_GeomFromText('LINESTRING(0 0,500 250,0 500,500 750)')";
+
+async fn computation(ctx: SessionContext, sql: &str) {
+    let df = ctx.sql(sql).await.unwrap();
+    let _ = df.collect().await.unwrap();
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let rt = util::create_tokio_runtime();
+    let ctx = util::create_session_with_workloads(ROWS);
+    register_all(&ctx, true);
+
+    #[allow(unused_mut)]
+    let mut functions = vec![
+        ("intersects", format!("ST_Intersects(geom, {})", LINESTRING)),
+        ("contains", format!("ST_Contains({}, geom)", POLYGON)),
+    ];
+    // only implemented with geos, the geo build has no fallback to compare with
+    #[cfg(feature = "geos")]
+    functions.extend([
+        ("buffer", "ST_Buffer(geom, 1.0, 8::Integer)".to_string()),
+        ("boundary", "ST_Boundary(geom)".to_string()),
+        ("equals", "ST_Equals(geom, geom)".to_string()),
+    ]);
+
+    for (function, expr) in functions {
+        let mut group = c.benchmark_group(format!("{}/{}", function, PATH));
+        group.throughput(Throughput::Elements(ROWS as u64));
+        for table in util::WORKLOADS {
+            let sql = format!("select {} from {}", expr, table);
+            group.bench_with_input(BenchmarkId::from_parameter(table), &sql, |b, sql| {
+                b.to_async(&rt).iter(|| computation(ctx.clone(), sql))
+            });
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,73 @@
+use arrow_array::RecordBatch;
+use arrow_schema::{DataType, Field, Schema};
+use criterion::{criterion_group, criterion_main, Criterion};
+use datafusion::datasource::MemTable;
+use datafusion::prelude::SessionContext;
+use datafusion_expr::ScalarUDF;
+use datafusion_geo::function::{ContainsUdf, GeomFromTextUdf, IntersectsUdf, WithinUdf};
+use datafusion_geo::geo::GeometryArrayBuilder;
+use std::sync::Arc;
+
+/// One million points stored as points, which take the point in polygon fast path,
+/// and the same points stored as single member multipoints, which are evaluated by the
+/// generic predicate.
+fn create_session_with_points() -> SessionContext {
+    let schema = Arc::new(Schema::new(vec![Field::new(
+        "geom",
+        DataType::Binary,
+        true,
+    )]));
+
+    let mut point_vec = vec![];
+    let mut multi_point_vec = vec![];
+    for i in 0..1000000 {
+        let point = geo::Point::new((i % 1000) as f64, (i / 1000) as f64);
+        point_vec.push(Some(point));
+        multi_point_vec.push(Some(geo::MultiPoint::new(vec![point])));
+    }
+    let point_builder: GeometryArrayBuilder<i32> = point_vec.as_slice().try_into().unwrap();
+    let multi_point_builder: GeometryArrayBuilder<i32> =
+        multi_point_vec.as_slice().try_into().unwrap();
+
+    let ctx = SessionContext::new();
+    for (name, builder) in [
+        ("point_table", point_builder),
+        ("multi_point_table", multi_point_builder),
+    ] {
+        let record = RecordBatch::try_new(schema.clone(), vec![Arc::new(builder.build())]).unwrap();
+        let mem_table = MemTable::try_new(schema.clone(), vec![vec![record]]).unwrap();
+        ctx.register_table(name, Arc::new(mem_table)).unwrap();
+    }
+    ctx.register_udf(ScalarUDF::from(IntersectsUdf::new()));
+    ctx.register_udf(ScalarUDF::from(ContainsUdf::new()));
+    ctx.register_udf(ScalarUDF::from(WithinUdf::new()));
+    ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+    ctx
+}
+
+async fn computation(ctx: SessionContext, sql: &str) {
+    let df = ctx.sql(sql).await.unwrap();
+    let _ = df.collect().await.unwrap();
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let ctx = create_session_with_points();
+    let polygon = "ST_GeomFromText('POLYGON((100 100,600 100,600 600,100 600,100 100))')";
+    for table in ["point_table", "multi_point_table"] {
+        // the interior only tests of contains and within take the fast path as well
+        for predicate in [
+            format!("ST_Intersects({}, geom)", polygon),
+            format!("ST_Contains({}, geom)", polygon),
+            format!("ST_Within(geom, {})", polygon),
+        ] {
+            let sql = format!("select {} from {}", predicate, table);
+            c.bench_function(&format!("point_in_polygon with sql: {}", sql), |b| {
+                b.to_async(&rt).iter(|| computation(ctx.clone(), &sql))
+            });
+        }
+    }
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
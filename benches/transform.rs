@@ -0,0 +1,85 @@
+use arrow_array::cast::AsArray;
+use arrow_array::RecordBatch;
+use arrow_schema::{DataType, Field, Schema};
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use datafusion::datasource::MemTable;
+use datafusion::prelude::SessionContext;
+use datafusion_expr::ScalarUDF;
+use datafusion_geo::function::TransformUdf;
+use datafusion_geo::geo::{GeometryArray, GeometryArrayBuilder};
+use geozero::wkb::WkbDialect;
+use proj::Proj;
+use std::sync::Arc;
+
+const POINTS: usize = 1_000_000;
+const BATCH_SIZE: usize = 8192;
+
+/// One million lon/lat points with srid 4326 in batches of the default batch size, so
+/// `ST_Transform` is invoked once per batch.
+fn create_session_with_points() -> (SessionContext, Vec<RecordBatch>) {
+    let schema = Arc::new(Schema::new(vec![Field::new(
+        "geom",
+        DataType::Binary,
+        true,
+    )]));
+    let batches = (0..POINTS)
+        .step_by(BATCH_SIZE)
+        .map(|start| {
+            let rows = BATCH_SIZE.min(POINTS - start);
+            let mut builder = GeometryArrayBuilder::<i32>::new(WkbDialect::Ewkb, rows);
+            for i in start..start + rows {
+                let point = geo::Point::new(
+                    (i % 3600) as f64 / 10.0 - 180.0,
+                    (i % 1700) as f64 / 10.0 - 85.0,
+                );
+                builder
+                    .append_geo_geometry_with_srid(&Some(point.into()), Some(4326))
+                    .unwrap();
+            }
+            RecordBatch::try_new(schema.clone(), vec![Arc::new(builder.build())]).unwrap()
+        })
+        .collect::<Vec<_>>();
+    let mem_table = MemTable::try_new(schema, vec![batches.clone()]).unwrap();
+
+    let ctx = SessionContext::new();
+    ctx.register_table("points", Arc::new(mem_table)).unwrap();
+    ctx.register_udf(ScalarUDF::from(TransformUdf::new()));
+    (ctx, batches)
+}
+
+async fn computation(ctx: SessionContext, sql: &str) {
+    let df = ctx.sql(sql).await.unwrap();
+    let _ = df.collect().await.unwrap();
+}
+
+/// The transformation built for every batch, as without the cache.
+fn transform_with_setup_per_batch(batches: &[RecordBatch]) {
+    for batch in batches {
+        let proj = Proj::new_known_crs("EPSG:4326", "EPSG:3857", None).unwrap();
+        let arr = batch.column(0).as_binary::<i32>();
+        for i in 0..arr.geom_len() {
+            if let Some(geo::Geometry::Point(point)) = arr.geo_value(i).unwrap() {
+                proj.convert((point.x(), point.y())).unwrap();
+            }
+        }
+    }
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let (ctx, batches) = create_session_with_points();
+    let mut group = c.benchmark_group("transform");
+    group.throughput(Throughput::Elements(POINTS as u64));
+    group.sample_size(10);
+    let sql = "select ST_Transform(geom, 3857) from points";
+    group.bench_function("cached", |b| {
+        b.to_async(&rt).iter(|| computation(ctx.clone(), sql))
+    });
+    group.bench_function("setup_per_batch", |b| {
+        b.iter(|| transform_with_setup_per_batch(&batches))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
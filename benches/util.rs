@@ -3,7 +3,7 @@ use arrow_schema::{DataType, Field, Schema};
 use datafusion::datasource::MemTable;
 use datafusion::prelude::SessionContext;
 use datafusion_geo::geo::GeometryArrayBuilder;
-use geo::line_string;
+use geo::{line_string, LineString, Polygon};
 use geoarrow::array::WKBArray;
 use geoarrow::trait_::IntoArrow;
 use std::sync::Arc;
@@ -17,6 +17,7 @@ pub fn create_tokio_runtime() -> Runtime {
         .unwrap()
 }
 
+#[allow(dead_code)]
 pub fn create_session_with_data() -> SessionContext {
     let schema = Arc::new(Schema::new(vec![Field::new(
         "geom",
@@ -35,7 +36,7 @@ pub fn create_session_with_data() -> SessionContext {
         linestring_vec.push(Some(geo::Geometry::LineString(linestring)));
     }
 
-    let builder: GeometryArrayBuilder<i32> = linestring_vec.as_slice().into();
+    let builder: GeometryArrayBuilder<i32> = linestring_vec.as_slice().try_into().unwrap();
     let record = RecordBatch::try_new(schema.clone(), vec![Arc::new(builder.build())]).unwrap();
 
     let wkb_arr: WKBArray<i32> = linestring_vec.as_slice().try_into().unwrap();
@@ -68,3 +69,60 @@ pub fn create_session_with_data() -> SessionContext {
         .unwrap();
     ctx
 }
+
+/// Tables of the geometry workloads benchmarked per function.
+#[allow(dead_code)]
+pub const WORKLOADS: [&str; 3] = ["points", "dense_linestrings", "polygons_with_holes"];
+
+/// Registers a table per workload with `rows` geometries spread over a 1000 x 1000 square: points,
+/// linestrings of 100 vertices and squares with a square hole.
+#[allow(dead_code)]
+pub fn create_session_with_workloads(rows: usize) -> SessionContext {
+    let schema = Arc::new(Schema::new(vec![Field::new(
+        "geom",
+        DataType::Binary,
+        true,
+    )]));
+    let origin = |i: usize| ((i % 100) as f64 * 10.0, (i / 100 % 100) as f64 * 10.0);
+
+    let points = (0..rows)
+        .map(|i| {
+            let (x, y) = origin(i);
+            Some(geo::Geometry::Point(geo::Point::new(x, y)))
+        })
+        .collect::<Vec<_>>();
+    let linestrings = (0..rows)
+        .map(|i| {
+            let (x, y) = origin(i);
+            let coords = (0..100)
+                .map(|j| (x + j as f64 * 0.1, y + (j % 2) as f64 * 0.1))
+                .collect::<Vec<_>>();
+            Some(geo::Geometry::LineString(LineString::from(coords)))
+        })
+        .collect::<Vec<_>>();
+    let polygons = (0..rows)
+        .map(|i| {
+            let (x, y) = origin(i);
+            let square = |min: f64, max: f64| {
+                LineString::from(vec![
+                    (x + min, y + min),
+                    (x + max, y + min),
+                    (x + max, y + max),
+                    (x + min, y + max),
+                    (x + min, y + min),
+                ])
+            };
+            let polygon = Polygon::new(square(0.0, 8.0), vec![square(2.0, 6.0)]);
+            Some(geo::Geometry::Polygon(polygon))
+        })
+        .collect::<Vec<_>>();
+
+    let ctx = SessionContext::new();
+    for (name, geoms) in WORKLOADS.into_iter().zip([points, linestrings, polygons]) {
+        let builder: GeometryArrayBuilder<i32> = geoms.as_slice().try_into().unwrap();
+        let record = RecordBatch::try_new(schema.clone(), vec![Arc::new(builder.build())]).unwrap();
+        let mem_table = MemTable::try_new(schema.clone(), vec![vec![record]]).unwrap();
+        ctx.register_table(name, Arc::new(mem_table)).unwrap();
+    }
+    ctx
+}
@@ -0,0 +1,112 @@
+//! Builds a vector tile of the places of a wkt csv file: the rows intersecting the envelope of the
+//! tile from `ST_TileEnvelope` are transformed into tile coordinates by `ST_AsMVTGeom` and
+//! aggregated into a mapbox vector tile by `st_asmvt`, which is written to a file.
+//!
+//! Run with `cargo run --example tile_pipeline`, `cargo test --examples` runs it as a test.
+
+use arrow_array::cast::AsArray;
+use datafusion::prelude::{CsvReadOptions, SessionContext};
+use datafusion_geo::function::register_all;
+use datafusion_geo::geo::mvt::decode_tile;
+use datafusion_geo::DFResult;
+use geo::{line_string, point};
+use std::path::PathBuf;
+
+/// Half the width of the web mercator world.
+const WORLD: f64 = 20037508.342789244;
+
+fn write_places_csv(dir: &PathBuf) -> std::io::Result<PathBuf> {
+    let w = WORLD;
+    let rows = [
+        ("a", format!("POINT({} {})", -w * 0.75, w * 0.75)),
+        ("b", format!("POINT({} {})", -w * 0.25, w * 0.5)),
+        ("c", format!("POINT({} {})", w * 0.5, w * 0.5)),
+        (
+            "d",
+            format!(
+                "LINESTRING({} {},{} {})",
+                -w * 0.75,
+                w * 0.25,
+                -w * 0.25,
+                w * 0.25
+            ),
+        ),
+    ];
+    let mut csv = "id,name,wkt\n".to_string();
+    for (id, (name, wkt)) in rows.iter().enumerate() {
+        csv.push_str(&format!("{},{},\"{}\"\n", id, name, wkt));
+    }
+    let path = dir.join("places.csv");
+    std::fs::write(&path, csv)?;
+    Ok(path)
+}
+
+/// Runs the pipeline for tile 1/0/0 and returns the tile bytes.
+async fn run(dir: &PathBuf) -> DFResult<Vec<u8>> {
+    let ctx = SessionContext::new();
+    register_all(&ctx, true);
+
+    let csv = write_places_csv(dir)?;
+    ctx.register_csv("places", csv.to_str().unwrap(), CsvReadOptions::new())
+        .await?;
+
+    let sql = "select st_asmvt(\
+        ST_AsMVTGeom(geom, Box2D(ST_TileEnvelope(1, 0, 0)), 4096, 64, true), 'places', 4096, \
+        'name', name) as tile \
+        from (select name, ST_GeomFromText(wkt) as geom from places) \
+        where ST_Intersects(geom, ST_TileEnvelope(1, 0, 0))";
+    let batches = ctx.sql(sql).await?.collect().await?;
+    let tile = batches[0].column(0).as_binary::<i32>().value(0).to_vec();
+    std::fs::write(dir.join("tile_1_0_0.mvt"), &tile)?;
+
+    // decode the tile again to check its contents
+    let layers = decode_tile(&tile)?;
+    assert_eq!(layers.len(), 1);
+    assert_eq!(layers[0].name, "places");
+    let mut geometries = layers[0]
+        .features
+        .iter()
+        .map(|feature| feature.geometry.clone())
+        .collect::<Vec<_>>();
+    for member in [
+        point!(x: 1024., y: 1024.).into(),
+        point!(x: 3072., y: 2048.).into(),
+        line_string![(x: 1024., y: 3072.), (x: 3072., y: 3072.)].into(),
+    ] {
+        let Some(index) = geometries.iter().position(|geom| *geom == member) else {
+            panic!("{:?} is not in the tile: {:?}", member, geometries);
+        };
+        geometries.remove(index);
+    }
+    // place c is in the tile east of this one
+    assert!(
+        geometries.is_empty(),
+        "unexpected features {:?}",
+        geometries
+    );
+    Ok(tile)
+}
+
+#[tokio::main]
+async fn main() -> DFResult<()> {
+    let dir = std::env::temp_dir().join("datafusion_geo_tile_pipeline");
+    std::fs::create_dir_all(&dir)?;
+    let tile = run(&dir).await?;
+    println!(
+        "Wrote a tile of {} bytes to {}",
+        tile.len(),
+        dir.join("tile_1_0_0.mvt").display()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    #[tokio::test]
+    async fn tile_pipeline() {
+        let dir = std::env::temp_dir().join("datafusion_geo_tile_pipeline_test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let tile = super::run(&dir).await.unwrap();
+        assert!(!tile.is_empty());
+    }
+}
@@ -0,0 +1,193 @@
+//! Crate wide configuration.
+//!
+//! The configuration can be set once, before any geometry is built, e.g. at the start of the
+//! program. Reading it first without setting it fixes the defaults.
+use crate::DFResult;
+use datafusion_common::{exec_datafusion_err, exec_err, DataFusionError};
+use geozero::wkb::WkbDialect;
+use std::cell::RefCell;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+
+static CONFIG: OnceLock<GeoConfig> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeoConfig {
+    /// Dialect of the geometries built by the functions and the array builders. Building a
+    /// geometry with a non-zero srid fails with plain wkb, which has no srid.
+    pub default_dialect: WkbDialect,
+    /// How geometries are checked when they are ingested, e.g. by `ST_GeomFromText`.
+    pub validation: ValidationMode,
+    /// What happens to invalid geometries with full validation.
+    pub on_invalid: InvalidGeometryAction,
+    /// How numbers are printed by `ST_AsText`, `ST_AsEWKT` and `ST_AsGeoJSON`.
+    pub number_format: NumberFormat,
+    /// Maximum size in bytes of a built geometry value.
+    pub max_wkb_bytes: usize,
+    /// Maximum number of vertices of a geometry produced by a function.
+    pub max_vertices: usize,
+    /// Maximum number of cells of a distance matrix, see [`crate::ops::distance_matrix`].
+    pub max_distance_matrix_cells: usize,
+    /// Whether the binary predicates fail on rows whose geometries carry different non-zero srids.
+    pub check_srids: bool,
+    /// Maximum number of proj transformations kept by `ST_Transform` across queries, the least
+    /// recently used one is dropped first. Zero builds them for every invocation.
+    pub proj_cache_capacity: usize,
+}
+
+impl GeoConfig {
+    /// Sets the crate wide configuration, fails if it was already set or read.
+    pub fn set(config: GeoConfig) -> DFResult<()> {
+        CONFIG
+            .set(config)
+            .map_err(|_| exec_datafusion_err!("GeoConfig is already set"))
+    }
+
+    pub fn get() -> &'static GeoConfig {
+        CONFIG.get_or_init(GeoConfig::default)
+    }
+}
+
+impl Default for GeoConfig {
+    fn default() -> Self {
+        Self {
+            default_dialect: WkbDialect::Ewkb,
+            validation: ValidationMode::Parse,
+            on_invalid: InvalidGeometryAction::Reject,
+            number_format: NumberFormat::Postgis,
+            max_wkb_bytes: 16 * 1024 * 1024,
+            max_vertices: 1_000_000,
+            max_distance_matrix_cells: 10_000_000,
+            check_srids: true,
+            proj_cache_capacity: 64,
+        }
+    }
+}
+
+/// Dialect used for newly built geometries, ewkb unless configured otherwise.
+pub fn default_dialect() -> WkbDialect {
+    GeoConfig::get().default_dialect
+}
+
+/// Number of rows between two checks of the cancellation flag.
+const CANCELLATION_CHECK_ROWS: usize = 16;
+
+/// Cancels the running geometry loops of a query once set, e.g. by the code cancelling the query.
+/// The flag is installed per session as an extension of its config, see
+/// [`register_all`](crate::function::register_all), so cancelling one query leaves the queries of
+/// other sessions running. The flag stays set until it is reset.
+#[derive(Debug, Default)]
+pub struct CancellationFlag(AtomicBool);
+
+impl CancellationFlag {
+    pub const fn new() -> Self {
+        Self(AtomicBool::new(false))
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn reset(&self) {
+        self.0.store(false, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+thread_local! {
+    static CANCELLATION: RefCell<Option<Arc<CancellationFlag>>> = const { RefCell::new(None) };
+}
+
+/// Runs `f` with the flag checked by the geometry loops on this thread, e.g. to make a call of
+/// [`distance_matrix`](crate::ops::distance_matrix) cancellable. The previous flag is restored
+/// afterwards.
+pub fn with_cancellation<T>(flag: Option<Arc<CancellationFlag>>, f: impl FnOnce() -> T) -> T {
+    let previous = CANCELLATION.with(|cancellation| cancellation.replace(flag));
+    let result = f();
+    CANCELLATION.with(|cancellation| *cancellation.borrow_mut() = previous);
+    result
+}
+
+/// The flag of the query running on this thread.
+pub(crate) fn current_cancellation() -> Option<Arc<CancellationFlag>> {
+    CANCELLATION.with(|cancellation| cancellation.borrow().clone())
+}
+
+/// Fails with "query cancelled" if the flag of the running query is set, the flag is only read
+/// every few rows.
+pub(crate) fn check_cancelled(row: usize) -> DFResult<()> {
+    if row % CANCELLATION_CHECK_ROWS != 0 {
+        return Ok(());
+    }
+    let cancelled = CANCELLATION.with(|cancellation| {
+        cancellation
+            .borrow()
+            .as_ref()
+            .is_some_and(|flag| flag.is_cancelled())
+    });
+    if cancelled {
+        return exec_err!("query cancelled");
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationMode {
+    /// The geometry is stored as converted.
+    None,
+    /// The geometry is checked to be decodable.
+    Parse,
+    /// The geometry is checked to be decodable and valid, requires the geos feature.
+    Full,
+}
+
+impl FromStr for ValidationMode {
+    type Err = DataFusionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "none" => Ok(ValidationMode::None),
+            "parse" => Ok(ValidationMode::Parse),
+            "full" => Ok(ValidationMode::Full),
+            _ => exec_err!(
+                "Unknown validation mode {}, expected none, parse or full",
+                s
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidGeometryAction {
+    /// Fails the query.
+    Reject,
+    /// Repairs the geometry with geos `make_valid`.
+    Repair,
+}
+
+impl FromStr for InvalidGeometryAction {
+    type Err = DataFusionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "reject" => Ok(InvalidGeometryAction::Reject),
+            "repair" => Ok(InvalidGeometryAction::Repair),
+            _ => exec_err!(
+                "Unknown invalid geometry action {}, expected reject or repair",
+                s
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumberFormat {
+    /// At most 15 decimals without trailing zeros, scientific notation from 1e15 on.
+    Postgis,
+    /// The shortest representation which round trips, never in scientific notation.
+    Rust,
+}
@@ -0,0 +1,311 @@
+use crate::expr::extract_bbox_constraint;
+use crate::geo::{Box2d, GeometryArray};
+use crate::DFResult;
+use arrow_array::cast::AsArray;
+use arrow_array::{Array, ArrayRef, RecordBatch};
+use arrow_schema::{DataType, SchemaRef};
+use async_trait::async_trait;
+use datafusion::datasource::{MemTable, TableProvider};
+use datafusion::execution::context::SessionState;
+use datafusion::physical_plan::memory::MemoryExec;
+use datafusion::physical_plan::{collect_partitioned, ExecutionPlan};
+use datafusion_common::Column;
+use datafusion_expr::{Expr, TableProviderFilterPushDown, TableType};
+use geo::BoundingRect;
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Scans each partition of the mem table once and wraps it with the bounding box of every
+/// geometry column per partition, see [`GeometryStatisticsTable`].
+pub async fn with_geometry_statistics(
+    state: &SessionState,
+    mem_table: &MemTable,
+) -> DFResult<GeometryStatisticsTable> {
+    let plan = mem_table.scan(state, None, &[], None).await?;
+    let partitions = collect_partitioned(plan, state.task_ctx()).await?;
+    GeometryStatisticsTable::try_new(mem_table.schema(), partitions)
+}
+
+/// An in memory table knowing the bounding box of its geometry columns per partition.
+///
+/// Scans skip the partitions whose box cannot intersect the box a filter constrains a geometry
+/// column to, see [`extract_bbox_constraint`], the filter itself is still applied on the kept rows.
+#[derive(Debug)]
+pub struct GeometryStatisticsTable {
+    schema: SchemaRef,
+    partitions: Vec<Vec<RecordBatch>>,
+    /// Box of each partition by geometry column name, empty partitions have an empty box.
+    boxes: HashMap<String, Vec<Box2d>>,
+}
+
+impl GeometryStatisticsTable {
+    pub fn try_new(schema: SchemaRef, partitions: Vec<Vec<RecordBatch>>) -> DFResult<Self> {
+        let mut boxes = HashMap::new();
+        for (index, field) in schema.fields().iter().enumerate() {
+            if !matches!(field.data_type(), DataType::Binary | DataType::LargeBinary) {
+                continue;
+            }
+            // binary columns which are not geometries get no statistics
+            let column_boxes = partitions
+                .iter()
+                .map(|batches| partition_box(batches, index))
+                .collect::<DFResult<Vec<_>>>();
+            if let Ok(column_boxes) = column_boxes {
+                boxes.insert(field.name().clone(), column_boxes);
+            }
+        }
+        Ok(Self {
+            schema,
+            partitions,
+            boxes,
+        })
+    }
+
+    /// Bounding boxes of the partitions for the geometry column.
+    pub fn partition_boxes(&self, column: &str) -> Option<&[Box2d]> {
+        self.boxes.get(column).map(|boxes| boxes.as_slice())
+    }
+
+    /// The boxes the filter constrains the geometry columns to.
+    fn filter_boxes(&self, filter: &Expr) -> Vec<(String, Box2d)> {
+        self.boxes
+            .keys()
+            .filter_map(|column| {
+                extract_bbox_constraint(filter, &Column::from_name(column))
+                    .map(|filter_box| (column.clone(), filter_box))
+            })
+            .collect()
+    }
+
+    fn may_match(&self, partition: usize, filter_boxes: &[(String, Box2d)]) -> bool {
+        filter_boxes.iter().all(|(column, filter_box)| {
+            self.boxes
+                .get(column)
+                .map_or(true, |boxes| boxes[partition].intersects(filter_box))
+        })
+    }
+}
+
+#[async_trait]
+impl TableProvider for GeometryStatisticsTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+
+    async fn scan(
+        &self,
+        _state: &SessionState,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> DFResult<Arc<dyn ExecutionPlan>> {
+        let filter_boxes = filters
+            .iter()
+            .flat_map(|filter| self.filter_boxes(filter))
+            .collect::<Vec<_>>();
+        let mut partitions = self
+            .partitions
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| self.may_match(*i, &filter_boxes))
+            .map(|(_, batches)| batches.clone())
+            .collect::<Vec<_>>();
+        if partitions.is_empty() {
+            partitions.push(vec![]);
+        }
+        Ok(Arc::new(MemoryExec::try_new(
+            &partitions,
+            self.schema(),
+            projection.cloned(),
+        )?))
+    }
+
+    fn supports_filters_pushdown(
+        &self,
+        filters: &[&Expr],
+    ) -> DFResult<Vec<TableProviderFilterPushDown>> {
+        Ok(filters
+            .iter()
+            .map(|filter| {
+                if self.filter_boxes(filter).is_empty() {
+                    TableProviderFilterPushDown::Unsupported
+                } else {
+                    TableProviderFilterPushDown::Inexact
+                }
+            })
+            .collect())
+    }
+}
+
+fn partition_box(batches: &[RecordBatch], column: usize) -> DFResult<Box2d> {
+    let mut partition_box = Box2d::new();
+    for batch in batches {
+        let arr = batch.column(column);
+        for i in 0..arr.len() {
+            if let Some(rect) = geo_value(arr, i)?.and_then(|geom| geom.bounding_rect()) {
+                partition_box.xmin = partition_box.xmin.min(rect.min().x);
+                partition_box.ymin = partition_box.ymin.min(rect.min().y);
+                partition_box.xmax = partition_box.xmax.max(rect.max().x);
+                partition_box.ymax = partition_box.ymax.max(rect.max().y);
+            }
+        }
+    }
+    Ok(partition_box)
+}
+
+fn geo_value(arr: &ArrayRef, index: usize) -> DFResult<Option<geo::Geometry>> {
+    match arr.data_type() {
+        DataType::Binary => arr.as_binary::<i32>().geo_value(index),
+        _ => arr.as_binary::<i64>().geo_value(index),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::datasource::with_geometry_statistics;
+    use crate::function::{GeomFromTextUdf, IntersectsUdf};
+    use crate::geo::GeometryArrayBuilder;
+    use arrow::util::pretty::pretty_format_batches;
+    use arrow_array::{Int64Array, RecordBatch};
+    use arrow_schema::{DataType, Field, Schema};
+    use datafusion::datasource::MemTable;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+    use geo::point;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn prune_partitions_by_box() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("geom", DataType::Binary, true),
+        ]));
+        // three partitions of points around (0 0), (10 10) and (20 20)
+        let partitions = (0..3)
+            .map(|p| {
+                let offset = p as f64 * 10.0;
+                let points = vec![
+                    Some(point!(x: offset, y: offset)),
+                    Some(point!(x: offset + 1.0, y: offset + 1.0)),
+                    None,
+                ];
+                let builder: GeometryArrayBuilder<i32> = points.as_slice().try_into().unwrap();
+                let ids = Int64Array::from(vec![p * 3, p * 3 + 1, p * 3 + 2]);
+                vec![RecordBatch::try_new(
+                    schema.clone(),
+                    vec![Arc::new(ids), Arc::new(builder.build())],
+                )
+                .unwrap()]
+            })
+            .collect::<Vec<_>>();
+        let mem_table = MemTable::try_new(schema, partitions).unwrap();
+
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(IntersectsUdf::new()));
+        let table = with_geometry_statistics(&ctx.state(), &mem_table)
+            .await
+            .unwrap();
+        let boxes = table.partition_boxes("geom").unwrap();
+        assert_eq!(boxes.len(), 3);
+        assert_eq!((boxes[1].xmin, boxes[1].ymax), (10.0, 11.0));
+        assert!(table.partition_boxes("id").is_none());
+        ctx.register_table("t", Arc::new(table)).unwrap();
+
+        let sql = "select id from t \
+        where ST_Intersects(geom, ST_GeomFromText('POLYGON((9 9,12 9,12 12,9 12,9 9))')) \
+        order by id";
+        assert_eq!(scanned_partitions(&ctx, sql).await, 1);
+        let df = ctx.sql(sql).await.unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+----+
+| id |
++----+
+| 3  |
+| 4  |
++----+"
+        );
+
+        let sql = "select id from t \
+        where ST_Intersects(geom, ST_GeomFromText('LINESTRING(0.5 0.5,10.5 10.5)'))";
+        assert_eq!(scanned_partitions(&ctx, sql).await, 2);
+
+        // far away from every partition
+        let sql = "select id from t where ST_Intersects(ST_GeomFromText('POINT(50 50)'), geom)";
+        let batches = ctx.sql(sql).await.unwrap().collect().await.unwrap();
+        assert!(batches.iter().all(|batch| batch.num_rows() == 0));
+
+        // other filters do not prune
+        let sql = "select id from t where id > 4";
+        assert_eq!(scanned_partitions(&ctx, sql).await, 3);
+    }
+
+    #[tokio::test]
+    async fn keep_geometries_intersecting_disjoint_boxes() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("geom", DataType::Binary, true),
+        ]));
+        let line: geo::Geometry = geo::line_string![(x: 0.0, y: 0.0), (x: 6.0, y: 6.0)].into();
+        let builder: GeometryArrayBuilder<i32> = vec![Some(line)].as_slice().try_into().unwrap();
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int64Array::from(vec![1])),
+                Arc::new(builder.build()),
+            ],
+        )
+        .unwrap();
+        let mem_table = MemTable::try_new(schema, vec![vec![batch]]).unwrap();
+
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(IntersectsUdf::new()));
+        let table = with_geometry_statistics(&ctx.state(), &mem_table)
+            .await
+            .unwrap();
+        ctx.register_table("t", Arc::new(table)).unwrap();
+
+        let sql = "select id from t \
+        where ST_Intersects(geom, ST_GeomFromText('POLYGON((0 0,2 0,2 2,0 2,0 0))')) \
+        and ST_Intersects(geom, ST_GeomFromText('POLYGON((5 5,6 5,6 6,5 6,5 5))'))";
+        let df = ctx.sql(sql).await.unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+----+
+| id |
++----+
+| 1  |
++----+"
+        );
+    }
+
+    async fn scanned_partitions(ctx: &SessionContext, sql: &str) -> usize {
+        let mut plan = ctx
+            .sql(sql)
+            .await
+            .unwrap()
+            .create_physical_plan()
+            .await
+            .unwrap();
+        // the scan of the table is the leaf of the plan
+        while let Some(child) = plan.children().first() {
+            plan = child.clone();
+        }
+        plan.output_partitioning().partition_count()
+    }
+}
@@ -0,0 +1,453 @@
+use crate::geo::dialect::{decode_srid, decode_wkb_dialect, wkb_type_id};
+use crate::geo::GeometryArray;
+use crate::DFResult;
+use arrow_array::cast::AsArray;
+use arrow_array::{
+    Array, ArrayRef, GenericBinaryArray, GenericBinaryBuilder, OffsetSizeTrait, RecordBatch,
+};
+use arrow_ipc::reader::StreamReader;
+use arrow_ipc::writer::StreamWriter;
+use arrow_schema::{DataType, Field, FieldRef, Schema, SchemaRef};
+use datafusion_common::{exec_err, internal_datafusion_err, DataFusionError};
+use geozero::error::Result as GeozeroResult;
+use geozero::wkb::{process_wkb_type_geom, WkbDialect, WkbWriter};
+use geozero::{CoordDimensions, GeomProcessor};
+use serde_json::{json, Map, Value};
+use std::io::{Read, Write};
+use std::sync::Arc;
+
+/// Field metadata key of the arrow extension type name.
+pub const EXTENSION_NAME_KEY: &str = "ARROW:extension:name";
+/// Field metadata key of the arrow extension type metadata.
+pub const EXTENSION_METADATA_KEY: &str = "ARROW:extension:metadata";
+/// The geoarrow extension type of plain iso wkb geometries.
+pub const GEOARROW_WKB: &str = "geoarrow.wkb";
+
+/// Writes the batches as an arrow ipc stream. Geometry columns, binary columns tagged as
+/// `geoarrow.wkb` or whose values all are geometries, are written as plain iso wkb with the
+/// `geoarrow.wkb` extension metadata so other geoarrow readers understand them.
+///
+/// Iso wkb has no srid, the srid of a column is written as the `EPSG:<srid>` crs of its extension
+/// metadata instead. A column mixing srids cannot be written.
+pub fn write_geometry_table<W: Write>(writer: W, batches: &[RecordBatch]) -> DFResult<()> {
+    let Some(first) = batches.first() else {
+        return exec_err!("Cannot write a geometry table without batches");
+    };
+    let schema = first.schema();
+    let geometry_columns = (0..schema.fields().len())
+        .map(|index| is_geometry_column(schema.field(index), batches, index))
+        .collect::<Vec<_>>();
+    let fields = schema
+        .fields()
+        .iter()
+        .zip(&geometry_columns)
+        .enumerate()
+        .map(|(index, (field, is_geometry))| match is_geometry {
+            true => geoarrow_field(field, column_srid(field, batches, index)?),
+            false => Ok(field.clone()),
+        })
+        .collect::<DFResult<Vec<_>>>()?;
+    let ipc_schema = Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone()));
+
+    let mut writer = StreamWriter::try_new(writer, &ipc_schema)?;
+    for batch in batches {
+        let columns = batch
+            .columns()
+            .iter()
+            .zip(&geometry_columns)
+            .map(|(arr, is_geometry)| match is_geometry {
+                true => map_binary(arr, to_iso_wkb),
+                false => Ok(arr.clone()),
+            })
+            .collect::<DFResult<Vec<_>>>()?;
+        writer.write(&RecordBatch::try_new(ipc_schema.clone(), columns)?)?;
+    }
+    writer.finish()?;
+    Ok(())
+}
+
+/// Reads an arrow ipc stream, the `geoarrow.wkb` columns are validated and turned into geometry
+/// columns the geometry functions can work on. An `EPSG:<srid>` crs in the extension metadata
+/// becomes the srid of the geometries.
+pub fn read_geometry_table<R: Read>(reader: R) -> DFResult<(SchemaRef, Vec<RecordBatch>)> {
+    let reader = StreamReader::try_new(reader, None)?;
+    let ipc_schema = reader.schema();
+    let mut geometry_columns = vec![];
+    for field in ipc_schema.fields() {
+        let is_geometry = field
+            .metadata()
+            .get(EXTENSION_NAME_KEY)
+            .is_some_and(|name| name == GEOARROW_WKB);
+        if is_geometry && !matches!(field.data_type(), DataType::Binary | DataType::LargeBinary) {
+            return exec_err!(
+                "Column {} is tagged as {} but has data type {}",
+                field.name(),
+                GEOARROW_WKB,
+                field.data_type()
+            );
+        }
+        let srid = match is_geometry {
+            true => crs_srid(field)?,
+            false => None,
+        };
+        geometry_columns.push(is_geometry.then_some(srid));
+    }
+
+    let mut batches = vec![];
+    for batch in reader {
+        let batch = batch?;
+        let columns = batch
+            .columns()
+            .iter()
+            .zip(&geometry_columns)
+            .map(|(arr, geometry)| match geometry {
+                Some(srid) => from_iso_wkb(arr, *srid),
+                None => Ok(arr.clone()),
+            })
+            .collect::<DFResult<Vec<_>>>()?;
+        batches.push(RecordBatch::try_new(ipc_schema.clone(), columns)?);
+    }
+    Ok((ipc_schema, batches))
+}
+
+fn is_geometry_column(field: &Field, batches: &[RecordBatch], index: usize) -> bool {
+    if !matches!(field.data_type(), DataType::Binary | DataType::LargeBinary) {
+        return false;
+    }
+    if field
+        .metadata()
+        .get(EXTENSION_NAME_KEY)
+        .is_some_and(|name| name == GEOARROW_WKB)
+    {
+        return true;
+    }
+    // untagged binary columns are geometries when every value is one
+    let mut has_value = false;
+    for batch in batches {
+        let arr = batch.column(index);
+        for i in 0..arr.len() {
+            match geo_value(arr, i) {
+                Ok(Some(_)) => has_value = true,
+                Ok(None) => {}
+                Err(_) => return false,
+            }
+        }
+    }
+    has_value
+}
+
+/// The srid shared by the geometries of the column, None when they have none.
+fn column_srid(field: &Field, batches: &[RecordBatch], index: usize) -> DFResult<Option<i32>> {
+    let mut shared = None;
+    for batch in batches {
+        let arr = batch.column(index);
+        for i in 0..arr.len() {
+            let Some(wkb) = binary_value(arr, i) else {
+                continue;
+            };
+            let srid = decode_srid(wkb)?.filter(|srid| *srid != 0);
+            match shared {
+                None => shared = Some(srid),
+                Some(shared) if shared != srid => {
+                    return exec_err!(
+                        "Column {} mixes the srids {} and {}, geoarrow keeps one crs per column",
+                        field.name(),
+                        shared.unwrap_or(0),
+                        srid.unwrap_or(0)
+                    );
+                }
+                Some(_) => {}
+            }
+        }
+    }
+    Ok(shared.flatten())
+}
+
+fn geoarrow_field(field: &FieldRef, srid: Option<i32>) -> DFResult<FieldRef> {
+    let mut metadata = field.metadata().clone();
+    metadata.insert(EXTENSION_NAME_KEY.to_string(), GEOARROW_WKB.to_string());
+    let mut extension = extension_metadata(field)?;
+    if let Some(srid) = srid {
+        extension.insert("crs".to_string(), json!(format!("EPSG:{}", srid)));
+        extension.insert("crs_type".to_string(), json!("authority_code"));
+    }
+    metadata.insert(
+        EXTENSION_METADATA_KEY.to_string(),
+        Value::Object(extension).to_string(),
+    );
+    Ok(Arc::new(field.as_ref().clone().with_metadata(metadata)))
+}
+
+fn extension_metadata(field: &Field) -> DFResult<Map<String, Value>> {
+    let Some(metadata) = field.metadata().get(EXTENSION_METADATA_KEY) else {
+        return Ok(Map::new());
+    };
+    match serde_json::from_str(metadata) {
+        Ok(Value::Object(extension)) => Ok(extension),
+        _ => exec_err!(
+            "Column {} has invalid extension metadata {}",
+            field.name(),
+            metadata
+        ),
+    }
+}
+
+/// Reads the srid of an `EPSG:<srid>` crs or a projjson crs with an EPSG id, other crs are
+/// ignored.
+fn crs_srid(field: &Field) -> DFResult<Option<i32>> {
+    let extension = extension_metadata(field)?;
+    let srid = match extension.get("crs") {
+        Some(Value::String(crs)) => crs
+            .split_once(':')
+            .filter(|(authority, _)| authority.eq_ignore_ascii_case("EPSG"))
+            .and_then(|(_, code)| code.parse::<i32>().ok()),
+        Some(Value::Object(projjson)) => projjson
+            .get("id")
+            .filter(|id| id["authority"] == "EPSG")
+            .and_then(|id| id["code"].as_i64())
+            .and_then(|code| i32::try_from(code).ok()),
+        _ => None,
+    };
+    Ok(srid)
+}
+
+fn binary_value(arr: &ArrayRef, index: usize) -> Option<&[u8]> {
+    if arr.is_null(index) {
+        return None;
+    }
+    match arr.data_type() {
+        DataType::Binary => Some(arr.as_binary::<i32>().value(index)),
+        _ => Some(arr.as_binary::<i64>().value(index)),
+    }
+}
+
+fn geo_value(arr: &ArrayRef, index: usize) -> DFResult<Option<geo::Geometry>> {
+    match arr.data_type() {
+        DataType::Binary => arr.as_binary::<i32>().geo_value(index),
+        _ => arr.as_binary::<i64>().geo_value(index),
+    }
+}
+
+fn map_binary(arr: &ArrayRef, f: impl Fn(&[u8]) -> DFResult<Vec<u8>>) -> DFResult<ArrayRef> {
+    match arr.data_type() {
+        DataType::Binary => map_binary_values(arr.as_binary::<i32>(), f),
+        _ => map_binary_values(arr.as_binary::<i64>(), f),
+    }
+}
+
+fn map_binary_values<O: OffsetSizeTrait>(
+    arr: &GenericBinaryArray<O>,
+    f: impl Fn(&[u8]) -> DFResult<Vec<u8>>,
+) -> DFResult<ArrayRef> {
+    let mut builder = GenericBinaryBuilder::<O>::with_capacity(arr.len(), arr.value_data().len());
+    for value in arr.iter() {
+        match value {
+            Some(value) => builder.append_value(f(value)?),
+            None => builder.append_null(),
+        }
+    }
+    Ok(Arc::new(builder.finish()))
+}
+
+/// Transcodes a wkb prefixed by its dialect type id to iso wkb, keeping the z ordinate.
+fn to_iso_wkb(wkb: &[u8]) -> DFResult<Vec<u8>> {
+    let Some((type_id, data)) = wkb.split_first() else {
+        return exec_err!("Wkb is empty");
+    };
+    let dialect = decode_wkb_dialect(*type_id)?;
+    if dialect == WkbDialect::Wkb {
+        return Ok(data.to_vec());
+    }
+    transcode(data, dialect, WkbDialect::Wkb, None)
+}
+
+/// Rewrites the wkb in another dialect, keeping the z ordinate.
+fn transcode(
+    data: &[u8],
+    dialect: WkbDialect,
+    out_dialect: WkbDialect,
+    srid: Option<i32>,
+) -> DFResult<Vec<u8>> {
+    let mut detector = ZDetector { has_z: false };
+    process_wkb_type_geom(&mut std::io::Cursor::new(data), &mut detector, dialect)
+        .map_err(|e| internal_datafusion_err!("Failed to read wkb, error: {}", e))?;
+    let dims = match detector.has_z {
+        true => CoordDimensions::xyz(),
+        false => CoordDimensions::xy(),
+    };
+    let mut out = vec![];
+    let mut writer = WkbWriter::with_opts(&mut out, out_dialect, dims, srid, vec![]);
+    process_wkb_type_geom(&mut std::io::Cursor::new(data), &mut writer, dialect)
+        .map_err(|e| internal_datafusion_err!("Failed to write wkb, error: {}", e))?;
+    Ok(out)
+}
+
+/// Prefixes the iso wkb values with their dialect type id, every value has to be a geometry. With
+/// a srid the values are written as ewkb carrying it.
+fn from_iso_wkb(arr: &ArrayRef, srid: Option<i32>) -> DFResult<ArrayRef> {
+    let prefixed = map_binary(arr, |wkb| {
+        let (dialect, wkb) = match srid {
+            Some(srid) => (
+                WkbDialect::Ewkb,
+                transcode(wkb, WkbDialect::Wkb, WkbDialect::Ewkb, Some(srid)).map_err(|e| {
+                    DataFusionError::Execution(format!("Invalid {} value: {}", GEOARROW_WKB, e))
+                })?,
+            ),
+            None => (WkbDialect::Wkb, wkb.to_vec()),
+        };
+        let mut prefixed = Vec::with_capacity(wkb.len() + 1);
+        prefixed.push(wkb_type_id(dialect));
+        prefixed.extend_from_slice(&wkb);
+        Ok(prefixed)
+    })?;
+    for i in 0..prefixed.len() {
+        geo_value(&prefixed, i).map_err(|e| {
+            DataFusionError::Execution(format!(
+                "Invalid {} value at row {}: {}",
+                GEOARROW_WKB, i, e
+            ))
+        })?;
+    }
+    Ok(prefixed)
+}
+
+/// Records whether any coordinate of a geometry has a z ordinate.
+struct ZDetector {
+    has_z: bool,
+}
+
+impl GeomProcessor for ZDetector {
+    fn dimensions(&self) -> CoordDimensions {
+        CoordDimensions::xyz()
+    }
+
+    fn coordinate(
+        &mut self,
+        _x: f64,
+        _y: f64,
+        z: Option<f64>,
+        _m: Option<f64>,
+        _t: Option<f64>,
+        _tm: Option<u64>,
+        _idx: usize,
+    ) -> GeozeroResult<()> {
+        self.has_z |= z.is_some();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::datasource::{read_geometry_table, write_geometry_table, GEOARROW_WKB};
+    use crate::function::{AsTextUdf, GeomFromTextUdf, IntersectsUdf, SridUdf};
+    use arrow::util::pretty::pretty_format_batches;
+    use datafusion::datasource::MemTable;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn ipc_round_trip() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(IntersectsUdf::new()));
+        ctx.register_udf(ScalarUDF::from(SridUdf::new()));
+        let batches = ctx
+            .sql(
+                "select id, ST_GeomFromText(wkt, 4326) as geom, 'x' as name \
+                from (values (1, 'POINT(1 1)'), (2, 'LINESTRING(5 5,6 6)'), \
+                (3, 'POLYGON((0 0,2 0,2 2,0 2,0 0))'), (4, null)) as t(id, wkt)",
+            )
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+
+        let mut buffer = vec![];
+        write_geometry_table(&mut buffer, &batches).unwrap();
+        let (schema, batches) = read_geometry_table(buffer.as_slice()).unwrap();
+        let extension = |name: &str| {
+            let field = schema.field_with_name(name).unwrap();
+            field.metadata().get("ARROW:extension:name").cloned()
+        };
+        assert_eq!(extension("geom").as_deref(), Some(GEOARROW_WKB));
+        assert_eq!(extension("id"), None);
+        assert_eq!(extension("name"), None);
+        let metadata = schema.field_with_name("geom").unwrap().metadata();
+        let crs: serde_json::Value =
+            serde_json::from_str(&metadata["ARROW:extension:metadata"]).unwrap();
+        assert_eq!(crs["crs"], "EPSG:4326");
+
+        let table = MemTable::try_new(schema, vec![batches]).unwrap();
+        ctx.register_table("t", Arc::new(table)).unwrap();
+        let df = ctx
+            .sql(
+                "select id, ST_AsText(geom), ST_SRID(geom) from t \
+                where ST_Intersects(geom, ST_GeomFromText('POLYGON((0 0,3 0,3 3,0 3,0 0))')) \
+                order by id",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+----+--------------------------------+-----------------+
+| id | ST_AsText(t.geom)              | ST_SRID(t.geom) |
++----+--------------------------------+-----------------+
+| 1  | POINT(1 1)                     | 4326            |
+| 3  | POLYGON((0 0,2 0,2 2,0 2,0 0)) | 4326            |
++----+--------------------------------+-----------------+"
+        );
+    }
+
+    #[tokio::test]
+    async fn write_mixed_srids() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        let batches = ctx
+            .sql(
+                "select ST_GeomFromText(wkt) as geom \
+                from (values ('SRID=4326;POINT(1 1)'), ('SRID=3857;POINT(2 2)')) as t(wkt)",
+            )
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        let err = write_geometry_table(Vec::<u8>::new(), &batches).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Column geom mixes the srids 4326 and 3857"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn read_invalid_geoarrow_wkb() {
+        use arrow_array::{BinaryArray, RecordBatch};
+        use arrow_ipc::writer::StreamWriter;
+        use arrow_schema::{DataType, Field, Schema};
+        use std::collections::HashMap;
+
+        let field = Field::new("geom", DataType::Binary, true).with_metadata(HashMap::from([(
+            "ARROW:extension:name".to_string(),
+            GEOARROW_WKB.to_string(),
+        )]));
+        let schema = Arc::new(Schema::new(vec![field]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(BinaryArray::from_vec(vec![b"not wkb".as_slice()]))],
+        )
+        .unwrap();
+        let mut buffer = vec![];
+        let mut writer = StreamWriter::try_new(&mut buffer, &schema).unwrap();
+        writer.write(&batch).unwrap();
+        writer.finish().unwrap();
+        drop(writer);
+        assert!(read_geometry_table(buffer.as_slice()).is_err());
+    }
+}
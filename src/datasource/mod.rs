@@ -0,0 +1,7 @@
+#[cfg(feature = "datasource")]
+mod geometry_statistics;
+mod ipc;
+
+#[cfg(feature = "datasource")]
+pub use geometry_statistics::*;
+pub use ipc::*;
@@ -0,0 +1,140 @@
+use crate::function::AffineUdf;
+use datafusion_expr::{lit, Expr, ScalarUDF};
+use geo::AffineTransform;
+
+/// Composes translations, rotations and scales into a single affine transformation,
+/// so that a chain of transforms is applied with one `ST_Affine` call.
+///
+/// Transforms are applied in the order they are added, e.g.
+/// `AffineBuilder::new().translate(1.0, 2.0).rotate(FRAC_PI_2).build(col("geom"))`
+/// translates the geometry first and then rotates it around the origin.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AffineBuilder {
+    a: f64,
+    b: f64,
+    d: f64,
+    e: f64,
+    xoff: f64,
+    yoff: f64,
+}
+
+impl AffineBuilder {
+    pub fn new() -> Self {
+        Self {
+            a: 1.0,
+            b: 0.0,
+            d: 0.0,
+            e: 1.0,
+            xoff: 0.0,
+            yoff: 0.0,
+        }
+    }
+
+    /// Appends the transformation `x' = ax + by + xoff, y' = dx + ey + yoff`,
+    /// the argument order matches `ST_Affine`.
+    pub fn affine(self, a: f64, b: f64, d: f64, e: f64, xoff: f64, yoff: f64) -> Self {
+        Self {
+            a: a * self.a + b * self.d,
+            b: a * self.b + b * self.e,
+            d: d * self.a + e * self.d,
+            e: d * self.b + e * self.e,
+            xoff: a * self.xoff + b * self.yoff + xoff,
+            yoff: d * self.xoff + e * self.yoff + yoff,
+        }
+    }
+
+    /// Appends all transformations of `next`.
+    pub fn then(self, next: AffineBuilder) -> Self {
+        self.affine(next.a, next.b, next.d, next.e, next.xoff, next.yoff)
+    }
+
+    pub fn translate(self, x_offset: f64, y_offset: f64) -> Self {
+        self.affine(1.0, 0.0, 0.0, 1.0, x_offset, y_offset)
+    }
+
+    /// Appends a counter-clockwise rotation around the origin, the angle is in radians.
+    pub fn rotate(self, angle: f64) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        self.affine(cos, -sin, sin, cos, 0.0, 0.0)
+    }
+
+    /// Appends a counter-clockwise rotation around the point `(x, y)`, the angle is in radians.
+    pub fn rotate_around(self, angle: f64, x: f64, y: f64) -> Self {
+        self.translate(-x, -y).rotate(angle).translate(x, y)
+    }
+
+    /// Appends a scale relative to the origin.
+    pub fn scale(self, x_factor: f64, y_factor: f64) -> Self {
+        self.affine(x_factor, 0.0, 0.0, y_factor, 0.0, 0.0)
+    }
+
+    pub fn transform(&self) -> AffineTransform {
+        AffineTransform::new(self.a, self.b, self.xoff, self.d, self.e, self.yoff)
+    }
+
+    /// Builds the `ST_Affine` call applying the composed transformation to the geometry expr.
+    pub fn build(&self, geom: Expr) -> Expr {
+        ScalarUDF::from(AffineUdf::new()).call(vec![
+            geom,
+            lit(self.a),
+            lit(self.b),
+            lit(self.d),
+            lit(self.e),
+            lit(self.xoff),
+            lit(self.yoff),
+        ])
+    }
+}
+
+impl Default for AffineBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::expr::AffineBuilder;
+    use crate::function::{GeomFromTextUdf, RotateUdf, ScaleUdf, TranslateUdf};
+    use crate::test_utils::assert_geometry_array_eq;
+    use datafusion::logical_expr::{lit, ScalarUDF};
+    use datafusion::prelude::{col, SessionContext};
+    use std::f64::consts::FRAC_PI_2;
+
+    #[tokio::test]
+    async fn affine_builder() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        let df = ctx
+            .sql("select ST_GeomFromText('LINESTRING(1 0,2 1)') as geom, ST_GeomFromText('LINESTRING(-4 6,-6 9)') as expected")
+            .await
+            .unwrap();
+
+        let translate = ScalarUDF::from(TranslateUdf::new());
+        let rotate = ScalarUDF::from(RotateUdf::new());
+        let scale = ScalarUDF::from(ScaleUdf::new());
+        let chained = scale.call(vec![
+            rotate.call(vec![
+                translate.call(vec![col("geom"), lit(1.0), lit(2.0)]),
+                lit(FRAC_PI_2),
+            ]),
+            lit(2.0),
+            lit(3.0),
+        ]);
+        let fused = AffineBuilder::new()
+            .translate(1.0, 2.0)
+            .rotate(FRAC_PI_2)
+            .scale(2.0, 3.0)
+            .build(col("geom"));
+
+        let batches = df
+            .select(vec![chained, fused, col("expected")])
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        // (1 0) is translated to (2 2), rotated to (-2 2) and scaled to (-4 6)
+        assert_geometry_array_eq(batches[0].column(1), batches[0].column(2), 1e-9);
+        assert_geometry_array_eq(batches[0].column(0), batches[0].column(1), 1e-9);
+    }
+}
@@ -0,0 +1,133 @@
+use crate::expr::rewrite::{literal_f64, rewrite_plan_exprs};
+use crate::expr::AffineBuilder;
+use crate::DFResult;
+use datafusion_common::config::ConfigOptions;
+use datafusion_common::tree_node::{Transformed, TreeNode};
+use datafusion_expr::expr::ScalarFunction;
+use datafusion_expr::{Expr, LogicalPlan, ScalarFunctionDefinition};
+use datafusion_optimizer::AnalyzerRule;
+
+/// Fuses nested `ST_Translate`, `ST_Rotate`, `ST_Scale` and `ST_Affine` calls into a single
+/// `ST_Affine` call when all transform parameters are literals, so the geometry is decoded
+/// and encoded only once.
+///
+/// Only projections and filters are rewritten, projected expressions keep their original name.
+#[derive(Debug, Default)]
+pub struct AffineFusionRule {}
+
+impl AffineFusionRule {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl AnalyzerRule for AffineFusionRule {
+    fn analyze(&self, plan: LogicalPlan, _config: &ConfigOptions) -> DFResult<LogicalPlan> {
+        plan.transform_up(&|plan| rewrite_plan_exprs(plan, &fuse_expr))
+    }
+
+    fn name(&self) -> &str {
+        "affine_fusion"
+    }
+}
+
+fn fuse_expr(expr: Expr) -> DFResult<Transformed<Expr>> {
+    let Some((inner, outer)) = affine_call(&expr) else {
+        return Ok(Transformed::No(expr));
+    };
+    let Some((geom, inner)) = affine_call(&inner) else {
+        return Ok(Transformed::No(expr));
+    };
+    Ok(Transformed::Yes(inner.then(outer).build(geom)))
+}
+
+/// Returns the geometry arg and the transformation of a transform call with literal params.
+fn affine_call(expr: &Expr) -> Option<(Expr, AffineBuilder)> {
+    let Expr::ScalarFunction(ScalarFunction {
+        func_def: ScalarFunctionDefinition::UDF(udf),
+        args,
+    }) = expr
+    else {
+        return None;
+    };
+    let params = args
+        .iter()
+        .skip(1)
+        .map(literal_f64)
+        .collect::<Option<Vec<_>>>()?;
+    let builder = AffineBuilder::new();
+    let builder = match (udf.name(), params.as_slice()) {
+        ("ST_Translate", [x_offset, y_offset]) => builder.translate(*x_offset, *y_offset),
+        ("ST_Rotate", [angle]) => builder.rotate(*angle),
+        ("ST_Rotate", [angle, x, y]) => builder.rotate_around(*angle, *x, *y),
+        ("ST_Scale", [x_factor, y_factor]) => builder.scale(*x_factor, *y_factor),
+        ("ST_Affine", [a, b, d, e, xoff, yoff]) => builder.affine(*a, *b, *d, *e, *xoff, *yoff),
+        _ => return None,
+    };
+    Some((args[0].clone(), builder))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::expr::AffineFusionRule;
+    use crate::function::{AsTextUdf, GeomFromTextUdf, RotateUdf, ScaleUdf, SridUdf, TranslateUdf};
+    use arrow::util::pretty::pretty_format_batches;
+    use datafusion::execution::context::SessionState;
+    use datafusion::execution::runtime_env::RuntimeEnv;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::{SessionConfig, SessionContext};
+    use std::sync::Arc;
+
+    fn session_context(fusion: bool) -> SessionContext {
+        let mut state =
+            SessionState::new_with_config_rt(SessionConfig::new(), Arc::new(RuntimeEnv::default()));
+        if fusion {
+            state = state.add_analyzer_rule(Arc::new(AffineFusionRule::new()));
+        }
+        let ctx = SessionContext::new_with_state(state);
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(TranslateUdf::new()));
+        ctx.register_udf(ScalarUDF::from(ScaleUdf::new()));
+        ctx.register_udf(ScalarUDF::from(RotateUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(SridUdf::new()));
+        ctx
+    }
+
+    #[tokio::test]
+    async fn affine_fusion() {
+        let sql = "select ST_AsText(ST_Scale(ST_Translate(ST_GeomFromText(wkt), 1, 2.5), 2, 0.5)) as geom, \
+        ST_AsText(ST_Translate(ST_Translate(ST_GeomFromText(wkt), 1, 1), 1, 1)), \
+        ST_SRID(ST_Scale(ST_Translate(ST_GeomFromText(wkt, 4326), 1, 2.5), 2, 0.5)) as scaled_srid, \
+        ST_SRID(ST_Rotate(ST_Translate(ST_GeomFromText(wkt, 4326), 1, 1), 0.5)) as rotated_srid \
+        from (values ('POINT(1 2)'), ('LINESTRING(0 0,1 1)'), (null)) as t(wkt)";
+
+        let fused_ctx = session_context(true);
+        let plan = fused_ctx
+            .sql(sql)
+            .await
+            .unwrap()
+            .into_optimized_plan()
+            .unwrap()
+            .display_indent()
+            .to_string();
+        assert_eq!(plan.matches("ST_Affine(").count(), 4);
+        assert!(!plan.contains("ST_Scale"));
+        assert!(!plan.contains("ST_Rotate"));
+
+        let fused = fused_ctx.sql(sql).await.unwrap().collect().await.unwrap();
+        let unfused = session_context(false)
+            .sql(sql)
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        let fused = pretty_format_batches(&fused).unwrap().to_string();
+        assert_eq!(fused, pretty_format_batches(&unfused).unwrap().to_string());
+        assert!(fused.contains("| POINT(4 2.25)"));
+        assert!(fused.contains("| LINESTRING(2 1.25,4 1.75)"));
+        // every fused path keeps the srid like the chain of transforms
+        assert_eq!(fused.matches("| 4326 ").count(), 4, "{}", fused);
+    }
+}
@@ -0,0 +1,289 @@
+use crate::expr::rewrite::literal_f64;
+use crate::geo::{scalar_to_geometry, Box2d};
+use datafusion_common::{Column, ScalarValue};
+use datafusion_expr::expr::ScalarFunction;
+use datafusion_expr::{BinaryExpr, Expr, Operator, ScalarFunctionDefinition};
+use geo::BoundingRect;
+
+/// Returns a box which the bounding box of the geometry column intersects on every row passing
+/// the filter, e.g. to prune partitions or row groups whose extent is disjoint from it.
+///
+/// Recognizes spatial predicates between the column and one literal geometry, like
+/// `ST_Intersects(geom, literal)` or `ST_DWithin(geom, literal, distance)` which expands the box
+/// by the distance, and their combinations under AND and OR. Under AND the boxes are only
+/// intersected when both sides contain the column in their box, like `ST_Within(geom, literal)`,
+/// otherwise the smaller box is kept, as a geometry may intersect two disjoint boxes. A filter
+/// which can never match produces an empty box. Negations and other expressions produce None as
+/// they don't constrain the column.
+pub fn extract_bbox_constraint(expr: &Expr, geom_col: &Column) -> Option<Box2d> {
+    constraint(expr, geom_col).map(|constraint| constraint.bbox)
+}
+
+struct Constraint {
+    bbox: Box2d,
+    /// Whether the bounding box of the column is inside the box rather than intersecting it.
+    contains: bool,
+}
+
+fn constraint(expr: &Expr, geom_col: &Column) -> Option<Constraint> {
+    match expr {
+        Expr::BinaryExpr(BinaryExpr {
+            left,
+            op: Operator::And,
+            right,
+        }) => match (constraint(left, geom_col), constraint(right, geom_col)) {
+            (Some(left), Some(right)) if left.contains && right.contains => Some(Constraint {
+                bbox: left.bbox.intersection(&right.bbox).unwrap_or_default(),
+                contains: true,
+            }),
+            (Some(left), Some(right)) => {
+                if area(&left.bbox) <= area(&right.bbox) {
+                    Some(left)
+                } else {
+                    Some(right)
+                }
+            }
+            (Some(constraint), None) | (None, Some(constraint)) => Some(constraint),
+            (None, None) => None,
+        },
+        Expr::BinaryExpr(BinaryExpr {
+            left,
+            op: Operator::Or,
+            right,
+        }) => {
+            let left = constraint(left, geom_col)?;
+            let right = constraint(right, geom_col)?;
+            Some(Constraint {
+                bbox: left.bbox.union(&right.bbox),
+                contains: left.contains && right.contains,
+            })
+        }
+        Expr::ScalarFunction(ScalarFunction {
+            func_def: ScalarFunctionDefinition::UDF(udf),
+            args,
+        }) => predicate_constraint(udf.name(), args, geom_col),
+        _ => None,
+    }
+}
+
+/// The area of the box, zero for the empty box which matches nothing.
+fn area(bbox: &Box2d) -> f64 {
+    (bbox.xmax - bbox.xmin).max(0.0) * (bbox.ymax - bbox.ymin).max(0.0)
+}
+
+fn predicate_constraint(name: &str, args: &[Expr], geom_col: &Column) -> Option<Constraint> {
+    let distance = match (name, args.len()) {
+        (
+            "ST_Intersects"
+            | "ST_Within"
+            | "ST_Contains"
+            | "ST_ContainsProperly"
+            | "ST_Covers"
+            | "ST_CoveredBy",
+            2,
+        ) => 0.0,
+        ("ST_DWithin", 3) => literal_f64(&args[2]).filter(|distance| *distance >= 0.0)?,
+        _ => return None,
+    };
+    let (value, column_first) = match (&args[0], &args[1]) {
+        (Expr::Column(column), Expr::Literal(value)) if same_column(column, geom_col) => {
+            (value, true)
+        }
+        (Expr::Literal(value), Expr::Column(column)) if same_column(column, geom_col) => {
+            (value, false)
+        }
+        _ => return None,
+    };
+    if !matches!(value, ScalarValue::Binary(_) | ScalarValue::LargeBinary(_)) {
+        return None;
+    }
+    // the column is inside the literal
+    let contains = match name {
+        "ST_Within" | "ST_CoveredBy" => column_first,
+        "ST_Contains" | "ST_ContainsProperly" | "ST_Covers" => !column_first,
+        _ => false,
+    };
+    let rect = scalar_to_geometry(value).ok()??.bounding_rect()?;
+    Some(Constraint {
+        bbox: Box2d {
+            xmin: rect.min().x - distance,
+            ymin: rect.min().y - distance,
+            xmax: rect.max().x + distance,
+            ymax: rect.max().y + distance,
+        },
+        contains,
+    })
+}
+
+/// Columns match by name, the relations are only compared when both columns are qualified.
+fn same_column(column: &Column, geom_col: &Column) -> bool {
+    column.name == geom_col.name
+        && match (&column.relation, &geom_col.relation) {
+            (Some(relation), Some(geom_relation)) => relation == geom_relation,
+            _ => true,
+        }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::expr::extract_bbox_constraint;
+    use crate::geo::{geometry_scalar, Box2d};
+    use crate::DFResult;
+    use arrow_schema::DataType;
+    use datafusion_common::{Column, ScalarValue};
+    use datafusion_expr::{col, create_udf, lit, not, ColumnarValue, Expr, Volatility};
+    use std::sync::Arc;
+
+    fn call(name: &str, args: Vec<Expr>) -> Expr {
+        let input_types = args.iter().map(|_| DataType::Binary).collect();
+        create_udf(
+            name,
+            input_types,
+            Arc::new(DataType::Boolean),
+            Volatility::Immutable,
+            Arc::new(|_: &[ColumnarValue]| -> DFResult<ColumnarValue> { unreachable!() }),
+        )
+        .call(args)
+    }
+
+    fn rect(xmin: f64, ymin: f64, xmax: f64, ymax: f64) -> Expr {
+        let rect = geo::Rect::new(
+            geo::coord! { x: xmin, y: ymin },
+            geo::coord! { x: xmax, y: ymax },
+        );
+        lit(geometry_scalar(&rect.to_polygon().into(), None).unwrap())
+    }
+
+    fn bounds(constraint: Option<Box2d>) -> Option<[f64; 4]> {
+        constraint.map(|b| [b.xmin, b.ymin, b.xmax, b.ymax])
+    }
+
+    fn extract(expr: &Expr) -> Option<[f64; 4]> {
+        bounds(extract_bbox_constraint(expr, &Column::from_name("geom")))
+    }
+
+    #[test]
+    fn spatial_predicates() {
+        for name in ["ST_Intersects", "ST_Within", "ST_Contains", "ST_Covers"] {
+            let expr = call(name, vec![col("geom"), rect(0.0, 0.0, 2.0, 3.0)]);
+            assert_eq!(extract(&expr), Some([0.0, 0.0, 2.0, 3.0]), "{}", name);
+        }
+        // the literal may come first
+        let expr = call("ST_Intersects", vec![rect(1.0, 1.0, 2.0, 2.0), col("geom")]);
+        assert_eq!(extract(&expr), Some([1.0, 1.0, 2.0, 2.0]));
+    }
+
+    #[test]
+    fn dwithin_expands_by_distance() {
+        let point = lit(geometry_scalar(&geo::point! { x: 1.0, y: 2.0 }.into(), None).unwrap());
+        let expr = call("ST_DWithin", vec![col("geom"), point.clone(), lit(0.5)]);
+        assert_eq!(extract(&expr), Some([0.5, 1.5, 1.5, 2.5]));
+
+        let expr = call("ST_DWithin", vec![col("geom"), point, col("distance")]);
+        assert_eq!(extract(&expr), None);
+    }
+
+    #[test]
+    fn and_intersects_containing_boxes() {
+        let left = call("ST_Within", vec![col("geom"), rect(0.0, 0.0, 2.0, 2.0)]);
+        let right = call("ST_Contains", vec![rect(1.0, 1.0, 3.0, 3.0), col("geom")]);
+        assert_eq!(
+            extract(&left.clone().and(right)),
+            Some([1.0, 1.0, 2.0, 2.0])
+        );
+
+        // other conjuncts don't loosen the constraint
+        let expr = left.clone().and(col("id").gt(lit(1)));
+        assert_eq!(extract(&expr), Some([0.0, 0.0, 2.0, 2.0]));
+
+        // no geometry is within two disjoint boxes
+        let disjoint = call("ST_CoveredBy", vec![col("geom"), rect(5.0, 5.0, 6.0, 6.0)]);
+        let constraint = extract_bbox_constraint(&left.and(disjoint), &Column::from_name("geom"));
+        assert!(!constraint.unwrap().intersects(&Box2d {
+            xmin: f64::MIN,
+            ymin: f64::MIN,
+            xmax: f64::MAX,
+            ymax: f64::MAX,
+        }));
+    }
+
+    #[test]
+    fn and_keeps_the_smaller_box() {
+        let left = call("ST_Intersects", vec![col("geom"), rect(0.0, 0.0, 2.0, 2.0)]);
+        let right = call("ST_Within", vec![col("geom"), rect(1.0, 1.0, 4.0, 4.0)]);
+        assert_eq!(
+            extract(&left.clone().and(right)),
+            Some([0.0, 0.0, 2.0, 2.0])
+        );
+
+        // LINESTRING(0 0,6 6) intersects both disjoint boxes
+        let disjoint = call("ST_Intersects", vec![col("geom"), rect(5.0, 5.0, 6.0, 6.0)]);
+        let constraint = extract_bbox_constraint(&left.and(disjoint), &Column::from_name("geom"));
+        assert!(constraint.unwrap().intersects(&Box2d {
+            xmin: 0.0,
+            ymin: 0.0,
+            xmax: 6.0,
+            ymax: 6.0,
+        }));
+    }
+
+    #[test]
+    fn or_unions_boxes() {
+        let left = call("ST_Intersects", vec![col("geom"), rect(0.0, 0.0, 1.0, 1.0)]);
+        let right = call("ST_Intersects", vec![col("geom"), rect(5.0, 5.0, 6.0, 6.0)]);
+        assert_eq!(extract(&left.clone().or(right)), Some([0.0, 0.0, 6.0, 6.0]));
+
+        // an unconstrained side may match any geometry
+        assert_eq!(extract(&left.or(col("id").gt(lit(1)))), None);
+    }
+
+    #[test]
+    fn negations_are_unconstrained() {
+        let intersects = call("ST_Intersects", vec![col("geom"), rect(0.0, 0.0, 1.0, 1.0)]);
+        assert_eq!(extract(&not(intersects.clone())), None);
+        assert_eq!(extract(&intersects.clone().eq(lit(false))), None);
+        assert_eq!(extract(&not(intersects.clone().and(col("flag")))), None);
+        // not(a or b) is not(a) and not(b), neither side is constrained
+        let other = call("ST_Intersects", vec![col("geom"), rect(2.0, 2.0, 3.0, 3.0)]);
+        assert_eq!(extract(&not(intersects.or(other))), None);
+    }
+
+    #[test]
+    fn unrelated_expressions() {
+        let geom = rect(0.0, 0.0, 1.0, 1.0);
+        // another geometry column
+        let expr = call("ST_Intersects", vec![col("other"), geom.clone()]);
+        assert_eq!(extract(&expr), None);
+        // two columns
+        let expr = call("ST_Intersects", vec![col("geom"), col("other")]);
+        assert_eq!(extract(&expr), None);
+        // not a spatial predicate
+        let expr = call("ST_Disjoint", vec![col("geom"), geom.clone()]);
+        assert_eq!(extract(&expr), None);
+        // a null literal
+        let expr = call(
+            "ST_Intersects",
+            vec![col("geom"), lit(ScalarValue::Binary(None))],
+        );
+        assert_eq!(extract(&expr), None);
+        // a column of another relation
+        let expr = call(
+            "ST_Intersects",
+            vec![Expr::Column(Column::new(Some("t"), "geom")), geom],
+        );
+        assert_eq!(
+            bounds(extract_bbox_constraint(
+                &expr,
+                &Column::new(Some("s"), "geom")
+            )),
+            None
+        );
+        assert_eq!(
+            bounds(extract_bbox_constraint(
+                &expr,
+                &Column::new(Some("t"), "geom")
+            )),
+            Some([0.0, 0.0, 1.0, 1.0])
+        );
+    }
+}
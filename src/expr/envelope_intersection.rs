@@ -0,0 +1,182 @@
+use crate::expr::rewrite::{literal_f64, rewrite_plan_exprs};
+use crate::geo::Box2d;
+use crate::DFResult;
+use datafusion_common::config::ConfigOptions;
+use datafusion_common::tree_node::{Transformed, TreeNode};
+use datafusion_expr::expr::ScalarFunction;
+use datafusion_expr::{Expr, LogicalPlan, ScalarFunctionDefinition};
+use datafusion_optimizer::AnalyzerRule;
+
+/// Rewrites `ST_Intersection(geom, ST_MakeEnvelope(...))` with literal envelope bounds into
+/// `ST_Intersection(geom, box2d)`, which clips by the rectangle instead of a general overlay.
+///
+/// Only projections and filters are rewritten, projected expressions keep their original name.
+#[derive(Debug, Default)]
+pub struct EnvelopeIntersectionRule {}
+
+impl EnvelopeIntersectionRule {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl AnalyzerRule for EnvelopeIntersectionRule {
+    fn analyze(&self, plan: LogicalPlan, _config: &ConfigOptions) -> DFResult<LogicalPlan> {
+        plan.transform_up(&|plan| rewrite_plan_exprs(plan, &rewrite_expr))
+    }
+
+    fn name(&self) -> &str {
+        "envelope_intersection"
+    }
+}
+
+fn rewrite_expr(expr: Expr) -> DFResult<Transformed<Expr>> {
+    let Expr::ScalarFunction(ScalarFunction {
+        func_def: ScalarFunctionDefinition::UDF(udf),
+        args,
+    }) = &expr
+    else {
+        return Ok(Transformed::No(expr));
+    };
+    if udf.name() != "ST_Intersection" {
+        return Ok(Transformed::No(expr));
+    }
+    let Some(box2d) = envelope_box2d(&args[1]) else {
+        return Ok(Transformed::No(expr));
+    };
+    Ok(Transformed::Yes(Expr::ScalarFunction(
+        ScalarFunction::new_udf(
+            udf.clone(),
+            vec![args[0].clone(), Expr::Literal(box2d.into())],
+        ),
+    )))
+}
+
+/// Returns the box2d of a `ST_MakeEnvelope` call with literal bounds, an envelope with a srid is
+/// not rewritten as the box2d has none.
+fn envelope_box2d(expr: &Expr) -> Option<Box2d> {
+    let Expr::ScalarFunction(ScalarFunction {
+        func_def: ScalarFunctionDefinition::UDF(udf),
+        args,
+    }) = expr
+    else {
+        return None;
+    };
+    if udf.name() != "ST_MakeEnvelope" || args.len() != 4 {
+        return None;
+    }
+    let bounds = args.iter().map(literal_f64).collect::<Option<Vec<_>>>()?;
+    let [xmin, ymin, xmax, ymax] = bounds[..] else {
+        return None;
+    };
+    Some(Box2d {
+        xmin,
+        ymin,
+        xmax,
+        ymax,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::expr::EnvelopeIntersectionRule;
+    use crate::function::{AsTextUdf, GeomFromTextUdf, IntersectionUdf, MakeEnvelopeUdf};
+    use crate::geo::GeometryArray;
+    use arrow::util::pretty::pretty_format_batches;
+    use arrow_array::cast::AsArray;
+    use datafusion::execution::context::SessionState;
+    use datafusion::execution::runtime_env::RuntimeEnv;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::{SessionConfig, SessionContext};
+    use geos::Geom;
+    use std::sync::Arc;
+
+    fn session_context(rewrite: bool) -> SessionContext {
+        let mut state =
+            SessionState::new_with_config_rt(SessionConfig::new(), Arc::new(RuntimeEnv::default()));
+        if rewrite {
+            state = state.add_analyzer_rule(Arc::new(EnvelopeIntersectionRule::new()));
+        }
+        let ctx = SessionContext::new_with_state(state);
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(MakeEnvelopeUdf::new()));
+        ctx.register_udf(ScalarUDF::from(IntersectionUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
+        ctx
+    }
+
+    #[tokio::test]
+    async fn envelope_intersection() {
+        let sql = "select ST_Intersection(ST_GeomFromText(wkt), ST_MakeEnvelope(0, 0, 2, 2)) \
+        from (values ('POLYGON((1 1,3 1,3 3,1 3,1 1))'), \
+        ('POLYGON((-1 -1,3 -1,3 3,1.5 3,1.5 0.5,0.5 0.5,0.5 3,-1 3,-1 -1))'), \
+        ('POLYGON((-1 -1,3 -1,3 3,-1 3,-1 -1),(1 0.5,2.5 0.5,2.5 1.5,1 1.5,1 0.5))'), \
+        ('POLYGON((5 5,6 5,6 6,5 6,5 5))'), (null)) as t(wkt)";
+
+        let rewrite_ctx = session_context(true);
+        let explain = rewrite_ctx
+            .sql(&format!("explain {}", sql))
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        let plan = pretty_format_batches(&explain).unwrap().to_string();
+        // the envelope is a box2d literal instead of a folded geometry literal
+        assert!(plan.contains("Struct("));
+        assert!(!plan.contains("Binary(\""));
+
+        let rewritten = rewrite_ctx.sql(sql).await.unwrap().collect().await.unwrap();
+        let general = session_context(false)
+            .sql(sql)
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        // projected name is kept
+        assert_eq!(rewritten[0].schema(), general[0].schema());
+
+        let rewritten = rewritten[0].column(0).as_binary::<i32>();
+        let general = general[0].column(0).as_binary::<i32>();
+        for i in 0..general.geom_len() {
+            match (
+                rewritten.geos_value(i).unwrap(),
+                general.geos_value(i).unwrap(),
+            ) {
+                (Some(clipped), Some(overlay)) => {
+                    assert!(clipped.is_valid(), "row {}", i);
+                    if overlay.is_empty().unwrap() {
+                        assert!(clipped.is_empty().unwrap(), "row {}", i);
+                        continue;
+                    }
+                    assert_eq!(
+                        clipped.geometry_type(),
+                        overlay.geometry_type(),
+                        "row {}",
+                        i
+                    );
+                    assert!(clipped.equals(&overlay).unwrap(), "row {}", i);
+                }
+                (clipped, overlay) => {
+                    assert!(clipped.is_none() && overlay.is_none(), "row {}", i)
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn envelope_with_srid_is_kept() {
+        let sql = "explain select ST_Intersection(ST_GeomFromText(wkt, 4326), \
+        ST_MakeEnvelope(0, 0, 2, 2, 4326)) from (values ('POINT(1 1)')) as t(wkt)";
+        let explain = session_context(true)
+            .sql(sql)
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        let plan = pretty_format_batches(&explain).unwrap().to_string();
+        assert!(!plan.contains("Struct("), "{}", plan);
+    }
+}
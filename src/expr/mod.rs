@@ -0,0 +1,12 @@
+mod affine;
+mod affine_fusion;
+mod bbox_constraint;
+#[cfg(feature = "geos")]
+mod envelope_intersection;
+mod rewrite;
+
+pub use affine::*;
+pub use affine_fusion::*;
+pub use bbox_constraint::*;
+#[cfg(feature = "geos")]
+pub use envelope_intersection::*;
@@ -0,0 +1,58 @@
+use crate::DFResult;
+use arrow_schema::DataType;
+use datafusion_common::tree_node::{Transformed, TreeNode};
+use datafusion_common::ScalarValue;
+use datafusion_expr::{Cast, Expr, LogicalPlan};
+
+/// Rewrites the expressions of projections and filters bottom up, projected expressions keep
+/// their original name.
+pub(crate) fn rewrite_plan_exprs(
+    plan: LogicalPlan,
+    rewrite: &impl Fn(Expr) -> DFResult<Transformed<Expr>>,
+) -> DFResult<Transformed<LogicalPlan>> {
+    let keep_name = match plan {
+        LogicalPlan::Projection(_) => true,
+        LogicalPlan::Filter(_) => false,
+        _ => return Ok(Transformed::No(plan)),
+    };
+
+    let mut rewritten = false;
+    let mut exprs = vec![];
+    for expr in plan.expressions() {
+        let new_expr = expr.clone().transform_up(rewrite)?;
+        if new_expr == expr {
+            exprs.push(expr);
+            continue;
+        }
+        rewritten = true;
+        match expr {
+            Expr::Alias(_) => exprs.push(new_expr),
+            _ if keep_name => exprs.push(new_expr.alias(expr.display_name()?)),
+            _ => exprs.push(new_expr),
+        }
+    }
+    if !rewritten {
+        return Ok(Transformed::No(plan));
+    }
+    let inputs = plan.inputs().into_iter().cloned().collect::<Vec<_>>();
+    Ok(Transformed::Yes(plan.with_new_exprs(exprs, &inputs)?))
+}
+
+/// Float64 literals, including other literals casted to Float64 by type coercion.
+pub(crate) fn literal_f64(expr: &Expr) -> Option<f64> {
+    let value = match expr {
+        Expr::Literal(value) => value.clone(),
+        Expr::Cast(Cast {
+            expr,
+            data_type: DataType::Float64,
+        }) => match expr.as_ref() {
+            Expr::Literal(value) => value.cast_to(&DataType::Float64).ok()?,
+            _ => return None,
+        },
+        _ => return None,
+    };
+    match value {
+        ScalarValue::Float64(Some(value)) => Some(value),
+        _ => None,
+    }
+}
@@ -0,0 +1,146 @@
+use crate::config::default_dialect;
+use crate::function::args::geometry_args;
+use crate::geo::dialect::decode_srid;
+use crate::geo::map::map_geometry_recursive;
+use crate::geo::{GeometryArray, GeometryArrayBuilder};
+use crate::DFResult;
+use arrow_array::cast::AsArray;
+use arrow_array::{GenericBinaryArray, OffsetSizeTrait};
+use arrow_schema::DataType;
+use datafusion_common::{exec_err, DataFusionError, ScalarValue};
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, TypeSignature, Volatility};
+use geo::{AffineOps, AffineTransform};
+use std::any::Any;
+use std::sync::Arc;
+
+/// Applies the 2d affine transformation `x' = ax + by + xoff, y' = dx + ey + yoff`.
+#[derive(Debug)]
+pub struct AffineUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl AffineUdf {
+    pub fn new() -> Self {
+        let signature = |geom_type: DataType| {
+            let mut arg_types = vec![geom_type];
+            arg_types.extend(vec![DataType::Float64; 6]);
+            TypeSignature::Exact(arg_types)
+        };
+        Self {
+            signature: Signature::one_of(
+                vec![
+                    signature(DataType::Binary),
+                    signature(DataType::LargeBinary),
+                ],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_affine".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for AffineUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_Affine"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(arg_types[0].clone())
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let mut params = [0.0; 6];
+        for (param, arg) in params.iter_mut().zip(args[1..].iter()) {
+            let ColumnarValue::Scalar(ScalarValue::Float64(Some(value))) = arg else {
+                return exec_err!("The transform args should be f64 scalars");
+            };
+            *param = *value;
+        }
+        let [a, b, d, e, xoff, yoff] = params;
+        let transform = AffineTransform::new(a, b, xoff, d, e, yoff);
+        affine_transform(self.name(), args, &transform)
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for AffineUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Applies the transform to the geometry arg, keeping the srid of each geometry.
+pub(crate) fn affine_transform(
+    name: &str,
+    args: &[ColumnarValue],
+    transform: &AffineTransform,
+) -> DFResult<ColumnarValue> {
+    let (arrays, _recorder) = geometry_args(name, args)?;
+    let arr = &arrays[0];
+    match arr.data_type() {
+        DataType::Binary => transform_array::<i32>(arr.as_binary::<i32>(), transform),
+        DataType::LargeBinary => transform_array::<i64>(arr.as_binary::<i64>(), transform),
+        _ => unreachable!(),
+    }
+}
+
+fn transform_array<O: OffsetSizeTrait>(
+    wkb_arr: &GenericBinaryArray<O>,
+    transform: &AffineTransform,
+) -> DFResult<ColumnarValue> {
+    let mut builder = GeometryArrayBuilder::<O>::new(default_dialect(), wkb_arr.geom_len());
+    for i in 0..wkb_arr.geom_len() {
+        let Some(wkb) = wkb_arr.wkb(i) else {
+            builder.append_null();
+            continue;
+        };
+        let geom = wkb_arr
+            .geo_value(i)?
+            .map(|geom| map_geometry_recursive(geom, &mut |geom| geom.affine_transform(transform)));
+        builder.append_geo_geometry_with_srid(&geom, decode_srid(wkb)?)?;
+    }
+    Ok(ColumnarValue::Array(Arc::new(builder.build())))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{AffineUdf, AsTextUdf, GeomFromTextUdf};
+    use arrow::util::pretty::pretty_format_batches;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+
+    #[tokio::test]
+    async fn affine() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AffineUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
+        let df = ctx
+            .sql("select ST_AsText(ST_Affine(ST_GeomFromText(wkt), 2, 1, 0, 3, 10, 20)) as geom from (values ('LINESTRING(0 0,1 1,2 0)'), (null)) as t(wkt)")
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+-------------------------------+
+| geom                          |
++-------------------------------+
+| LINESTRING(10 20,13 23,14 20) |
+|                               |
++-------------------------------+"
+        );
+    }
+}
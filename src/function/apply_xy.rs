@@ -0,0 +1,322 @@
+use crate::config::default_dialect;
+use crate::function::args::geometry_args;
+use crate::geo::dialect::decode_srid;
+use crate::geo::map::map_geometry_recursive;
+use crate::geo::{GeometryArray, GeometryArrayBuilder};
+use crate::DFResult;
+use arrow_array::cast::AsArray;
+use arrow_array::{GenericBinaryArray, OffsetSizeTrait};
+use arrow_schema::DataType;
+use datafusion_common::{exec_datafusion_err, exec_err, DataFusionError, ScalarValue};
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, TypeSignature, Volatility};
+use geo::{Coord, CoordsIter, MapCoords};
+use std::any::Any;
+use std::iter::Peekable;
+use std::str::Chars;
+use std::sync::Arc;
+
+/// Applies two arithmetic formulas over the variables `x` and `y` to every coordinate,
+/// e.g. `ST_ApplyXY(geom, 'x * 0.3048', 'y * 0.3048')` converts feet to meters.
+#[derive(Debug)]
+pub struct ApplyXYUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl ApplyXYUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                vec![
+                    TypeSignature::Exact(vec![DataType::Binary, DataType::Utf8, DataType::Utf8]),
+                    TypeSignature::Exact(vec![
+                        DataType::LargeBinary,
+                        DataType::Utf8,
+                        DataType::Utf8,
+                    ]),
+                ],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_applyxy".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for ApplyXYUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_ApplyXY"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(arg_types[0].clone())
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let ColumnarValue::Scalar(ScalarValue::Utf8(Some(x_expr))) = &args[1] else {
+            return exec_err!("The second arg should be a non-null utf8 scalar");
+        };
+        let ColumnarValue::Scalar(ScalarValue::Utf8(Some(y_expr))) = &args[2] else {
+            return exec_err!("The third arg should be a non-null utf8 scalar");
+        };
+        let x_formula = Formula::parse(x_expr)?;
+        let y_formula = Formula::parse(y_expr)?;
+
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let arr = &arrays[0];
+        match arr.data_type() {
+            DataType::Binary => apply_xy::<i32>(arr.as_binary::<i32>(), &x_formula, &y_formula),
+            DataType::LargeBinary => {
+                apply_xy::<i64>(arr.as_binary::<i64>(), &x_formula, &y_formula)
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for ApplyXYUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn apply_xy<O: OffsetSizeTrait>(
+    wkb_arr: &GenericBinaryArray<O>,
+    x_formula: &Formula,
+    y_formula: &Formula,
+) -> DFResult<ColumnarValue> {
+    let mut builder = GeometryArrayBuilder::<O>::new(default_dialect(), wkb_arr.geom_len());
+    for i in 0..wkb_arr.geom_len() {
+        let Some(wkb) = wkb_arr.wkb(i) else {
+            builder.append_null();
+            continue;
+        };
+        let srid = decode_srid(wkb)?;
+        let geom = wkb_arr.geo_value(i)?.and_then(|geom| {
+            let mut finite = true;
+            let geom = map_geometry_recursive(geom, &mut |geom| {
+                let geom = geom.map_coords(|c| Coord {
+                    x: x_formula.eval(c.x, c.y),
+                    y: y_formula.eval(c.x, c.y),
+                });
+                finite &= geom
+                    .coords_iter()
+                    .all(|c| c.x.is_finite() && c.y.is_finite());
+                geom
+            });
+            finite.then_some(geom)
+        });
+        builder.append_geo_geometry_with_srid(&geom, srid)?;
+    }
+    Ok(ColumnarValue::Array(Arc::new(builder.build())))
+}
+
+/// A tiny arithmetic expression over `x` and `y` supporting `+ - * /`, unary minus and parentheses.
+#[derive(Debug)]
+enum Formula {
+    Number(f64),
+    X,
+    Y,
+    Neg(Box<Formula>),
+    Add(Box<Formula>, Box<Formula>),
+    Sub(Box<Formula>, Box<Formula>),
+    Mul(Box<Formula>, Box<Formula>),
+    Div(Box<Formula>, Box<Formula>),
+}
+
+impl Formula {
+    fn parse(expr: &str) -> DFResult<Formula> {
+        let mut chars = expr.chars().peekable();
+        let formula = parse_sum(&mut chars, expr)?;
+        skip_whitespace(&mut chars);
+        if let Some(c) = chars.next() {
+            return exec_err!("Unexpected character '{}' in formula '{}'", c, expr);
+        }
+        Ok(formula)
+    }
+
+    fn eval(&self, x: f64, y: f64) -> f64 {
+        match self {
+            Formula::Number(n) => *n,
+            Formula::X => x,
+            Formula::Y => y,
+            Formula::Neg(f) => -f.eval(x, y),
+            Formula::Add(l, r) => l.eval(x, y) + r.eval(x, y),
+            Formula::Sub(l, r) => l.eval(x, y) - r.eval(x, y),
+            Formula::Mul(l, r) => l.eval(x, y) * r.eval(x, y),
+            Formula::Div(l, r) => l.eval(x, y) / r.eval(x, y),
+        }
+    }
+}
+
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+    while chars.peek().is_some_and(|c| c.is_whitespace()) {
+        chars.next();
+    }
+}
+
+fn parse_sum(chars: &mut Peekable<Chars>, expr: &str) -> DFResult<Formula> {
+    let mut left = parse_product(chars, expr)?;
+    loop {
+        skip_whitespace(chars);
+        match chars.peek() {
+            Some('+') => {
+                chars.next();
+                left = Formula::Add(Box::new(left), Box::new(parse_product(chars, expr)?));
+            }
+            Some('-') => {
+                chars.next();
+                left = Formula::Sub(Box::new(left), Box::new(parse_product(chars, expr)?));
+            }
+            _ => return Ok(left),
+        }
+    }
+}
+
+fn parse_product(chars: &mut Peekable<Chars>, expr: &str) -> DFResult<Formula> {
+    let mut left = parse_unary(chars, expr)?;
+    loop {
+        skip_whitespace(chars);
+        match chars.peek() {
+            Some('*') => {
+                chars.next();
+                left = Formula::Mul(Box::new(left), Box::new(parse_unary(chars, expr)?));
+            }
+            Some('/') => {
+                chars.next();
+                left = Formula::Div(Box::new(left), Box::new(parse_unary(chars, expr)?));
+            }
+            _ => return Ok(left),
+        }
+    }
+}
+
+fn parse_unary(chars: &mut Peekable<Chars>, expr: &str) -> DFResult<Formula> {
+    skip_whitespace(chars);
+    match chars.peek() {
+        Some('-') => {
+            chars.next();
+            Ok(Formula::Neg(Box::new(parse_unary(chars, expr)?)))
+        }
+        Some('+') => {
+            chars.next();
+            parse_unary(chars, expr)
+        }
+        _ => parse_atom(chars, expr),
+    }
+}
+
+fn parse_atom(chars: &mut Peekable<Chars>, expr: &str) -> DFResult<Formula> {
+    skip_whitespace(chars);
+    match chars.next() {
+        Some('x') | Some('X') => Ok(Formula::X),
+        Some('y') | Some('Y') => Ok(Formula::Y),
+        Some('(') => {
+            let formula = parse_sum(chars, expr)?;
+            skip_whitespace(chars);
+            match chars.next() {
+                Some(')') => Ok(formula),
+                _ => exec_err!("Missing closing parenthesis in formula '{}'", expr),
+            }
+        }
+        Some(c) if c.is_ascii_digit() || c == '.' => {
+            let mut number = c.to_string();
+            while let Some(&c) = chars.peek() {
+                if c.is_ascii_digit() || c == '.' {
+                    number.push(c);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            let number = number.parse::<f64>().map_err(|e| {
+                exec_datafusion_err!("Invalid number '{}' in formula '{}': {}", number, expr, e)
+            })?;
+            Ok(Formula::Number(number))
+        }
+        Some(c) => exec_err!("Unexpected character '{}' in formula '{}'", c, expr),
+        None => exec_err!("Unexpected end of formula '{}'", expr),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{ApplyXYUdf, AsTextUdf, GeomFromTextUdf, SridUdf};
+    use crate::test_utils::assert_geometry_array_eq;
+    use arrow::util::pretty::pretty_format_batches;
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::Int32Type;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+
+    #[tokio::test]
+    async fn apply_xy() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(ApplyXYUdf::new()));
+        let df = ctx
+            .sql(
+                "select ST_ApplyXY(ST_GeomFromText('LINESTRING(0 0,10 20,100 1000)'), \
+                'x * 0.3048', 'y * 0.3048'), \
+                ST_GeomFromText('LINESTRING(0 0,3.048 6.096,30.48 304.8)')",
+            )
+            .await
+            .unwrap();
+        let batches = df.collect().await.unwrap();
+        assert_geometry_array_eq(batches[0].column(0), batches[0].column(1), 1e-9);
+
+        // the srid is kept
+        ctx.register_udf(ScalarUDF::from(SridUdf::new()));
+        let df = ctx
+            .sql(
+                "select ST_SRID(ST_ApplyXY(ST_GeomFromText('POINT(10 20)', 2263), \
+                'x * 0.3048', 'y * 0.3048'))",
+            )
+            .await
+            .unwrap();
+        let batches = df.collect().await.unwrap();
+        assert_eq!(
+            batches[0].column(0).as_primitive::<Int32Type>().value(0),
+            2263
+        );
+    }
+
+    #[tokio::test]
+    async fn apply_xy_invalid() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(ApplyXYUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
+        let df = ctx
+            .sql("select ST_AsText(ST_ApplyXY(ST_GeomFromText('POINT(0 1)'), 'x / 0', 'y'))")
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+------------------------------------------------------------------------------------+
+| ST_AsText(ST_ApplyXY(ST_GeomFromText(Utf8(\"POINT(0 1)\")),Utf8(\"x / 0\"),Utf8(\"y\"))) |
++------------------------------------------------------------------------------------+
+|                                                                                    |
++------------------------------------------------------------------------------------+"
+        );
+
+        let df = ctx
+            .sql("select ST_ApplyXY(ST_GeomFromText('POINT(0 1)'), 'x * (2', 'y')")
+            .await
+            .unwrap();
+        assert!(df.collect().await.is_err());
+    }
+}
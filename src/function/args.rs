@@ -0,0 +1,936 @@
+use crate::config::{
+    check_cancelled, current_cancellation, with_cancellation, GeoConfig, InvalidGeometryAction,
+    ValidationMode,
+};
+use crate::geo::dialect::{decode_point, WkbHeader};
+use crate::geo::{scalar_to_geometry, Box2d, GeometryArray};
+use crate::metrics::{current_call, record_call, with_call, Recorder};
+use crate::DFResult;
+use arrow_array::cast::AsArray;
+use arrow_array::{ArrayRef, BooleanArray};
+use arrow_schema::DataType;
+use datafusion_common::{exec_err, internal_err, DataFusionError, ScalarValue};
+use datafusion_expr::{ColumnarValue, TypeSignature};
+use geo::{Contains, Intersects};
+use rayon::prelude::*;
+use std::borrow::Cow;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Converts all args into arrays of the same length, scalars are broadcast to the length of the array args.
+/// The invocation is recorded in the function metrics.
+pub(crate) fn geometry_args(
+    name: &str,
+    args: &[ColumnarValue],
+) -> DFResult<(Vec<ArrayRef>, Recorder)> {
+    let num_rows = num_rows(args);
+    let arrays = args
+        .iter()
+        .map(|arg| arg.clone().into_array(num_rows))
+        .collect::<DFResult<Vec<ArrayRef>>>()?;
+    if arrays.iter().any(|arr| arr.len() != num_rows) {
+        return internal_err!("Args arrays length is not same");
+    }
+    Ok((arrays, record_call(name, num_rows)))
+}
+
+/// The length of the array args, 1 if all args are scalars.
+fn num_rows(args: &[ColumnarValue]) -> usize {
+    args.iter()
+        .find_map(|arg| match arg {
+            ColumnarValue::Array(arr) => Some(arr.len()),
+            ColumnarValue::Scalar(_) => None,
+        })
+        .unwrap_or(1)
+}
+
+/// Converts a single row result into a scalar when all args are scalars, so constant geometries stay
+/// scalars and are folded into literals at plan time instead of being rebuilt for every batch.
+pub(crate) fn scalar_if_constant(
+    args: &[ColumnarValue],
+    value: ColumnarValue,
+) -> DFResult<ColumnarValue> {
+    match value {
+        ColumnarValue::Array(arr)
+            if arr.len() == 1
+                && args
+                    .iter()
+                    .all(|arg| matches!(arg, ColumnarValue::Scalar(_))) =>
+        {
+            Ok(ColumnarValue::Scalar(ScalarValue::try_from_array(&arr, 0)?))
+        }
+        value => Ok(value),
+    }
+}
+
+/// Signatures of a geometry constructor taking `input_type`, i.e.
+/// `(input [, srid] [, validation [, on_invalid]])`.
+pub(crate) fn ingestion_signatures(input_type: DataType) -> Vec<TypeSignature> {
+    let mut signatures = vec![];
+    for srid in [vec![], vec![DataType::Int64]] {
+        let mut arg_types = vec![input_type.clone()];
+        arg_types.extend(srid);
+        for _ in 0..3 {
+            signatures.push(TypeSignature::Exact(arg_types.clone()));
+            arg_types.push(DataType::Utf8);
+        }
+    }
+    signatures
+}
+
+pub(crate) struct IngestionArgs {
+    pub(crate) srid: Option<i32>,
+    pub(crate) validation: ValidationMode,
+    pub(crate) on_invalid: InvalidGeometryAction,
+}
+
+/// Reads the args following the input of a geometry constructor, the validation args default to
+/// the crate config.
+pub(crate) fn ingestion_args(args: &[ColumnarValue]) -> DFResult<IngestionArgs> {
+    let mut rest = &args[1..];
+    let mut srid = None;
+    if let Some(ColumnarValue::Scalar(ScalarValue::Int64(value))) = rest.first() {
+        srid = value.map(|srid| srid as i32);
+        rest = &rest[1..];
+    }
+    let config = GeoConfig::get();
+    let validation = match rest.first() {
+        None => config.validation,
+        Some(ColumnarValue::Scalar(ScalarValue::Utf8(Some(validation)))) => validation.parse()?,
+        Some(_) => return exec_err!("The validation mode should be a utf8 scalar"),
+    };
+    let on_invalid = match rest.get(1) {
+        None => config.on_invalid,
+        Some(ColumnarValue::Scalar(ScalarValue::Utf8(Some(on_invalid)))) => on_invalid.parse()?,
+        Some(_) => return exec_err!("The invalid geometry action should be a utf8 scalar"),
+    };
+    Ok(IngestionArgs {
+        srid,
+        validation,
+        on_invalid,
+    })
+}
+
+pub(crate) fn as_geometry_array(arr: &ArrayRef) -> DFResult<&(dyn GeometryArray + Sync)> {
+    match arr.data_type() {
+        DataType::Binary => Ok(arr.as_binary::<i32>()),
+        DataType::LargeBinary => Ok(arr.as_binary::<i64>()),
+        data_type => internal_err!("Unsupported geometry array type: {}", data_type),
+    }
+}
+
+/// Fast path of predicates which are a point in polygon test (boundary included) when one side is
+/// a point, e.g. `ST_Intersects`, `ST_Covers(polygon, point)` and `ST_CoveredBy(point, polygon)`.
+/// It applies when the arg at `polygon_index` is a scalar polygon and every row of the other arg is
+/// a point, the points are read from the wkb without decoding and tested against the pre-parsed
+/// polygon. Returns None if the fast path does not apply.
+pub(crate) fn point_in_polygon(
+    name: &str,
+    args: &[ColumnarValue],
+    polygon_index: usize,
+) -> DFResult<Option<ColumnarValue>> {
+    points_against_polygon(name, args, polygon_index, |polygon, coord| {
+        polygon.intersects(&coord)
+    })
+}
+
+/// Fast path like [`point_in_polygon`] of the predicates which are true for the points in the
+/// interior of the polygon only, points on the boundary are false, e.g. `ST_Contains(polygon,
+/// point)` and `ST_Within(point, polygon)`.
+pub(crate) fn point_in_polygon_interior(
+    name: &str,
+    args: &[ColumnarValue],
+    polygon_index: usize,
+) -> DFResult<Option<ColumnarValue>> {
+    points_against_polygon(name, args, polygon_index, |polygon, coord| {
+        polygon.contains(&coord)
+    })
+}
+
+fn points_against_polygon(
+    name: &str,
+    args: &[ColumnarValue],
+    polygon_index: usize,
+    test: impl Fn(&geo::Geometry, geo::Coord) -> bool + Sync,
+) -> DFResult<Option<ColumnarValue>> {
+    let (ColumnarValue::Scalar(polygon), ColumnarValue::Array(points)) =
+        (&args[polygon_index], &args[1 - polygon_index])
+    else {
+        return Ok(None);
+    };
+    let polygon_wkb = match polygon {
+        ScalarValue::Binary(Some(wkb)) | ScalarValue::LargeBinary(Some(wkb)) => wkb.as_slice(),
+        _ => return Ok(None),
+    };
+    let polygon = match scalar_to_geometry(polygon)? {
+        Some(polygon @ (geo::Geometry::Polygon(_) | geo::Geometry::MultiPolygon(_))) => polygon,
+        _ => return Ok(None),
+    };
+
+    let point_arr = as_geometry_array(points)?;
+    let mut coords = Vec::with_capacity(point_arr.geom_len());
+    for i in 0..point_arr.geom_len() {
+        match point_arr.wkb(i) {
+            Some(wkb) => match decode_point(wkb)? {
+                Some(coord) => {
+                    if polygon_index == 0 {
+                        check_srids(i, polygon_wkb, wkb)?;
+                    } else {
+                        check_srids(i, wkb, polygon_wkb)?;
+                    }
+                    coords.push(Some(coord))
+                }
+                None => return Ok(None),
+            },
+            None => coords.push(None),
+        }
+    }
+
+    let recorder = record_call(name, coords.len());
+    let bool_vec = recorder.compute(|| {
+        coords
+            .par_iter()
+            .map(|coord| coord.map(|coord| test(&polygon, coord)))
+            .collect::<Vec<Option<bool>>>()
+    });
+    Ok(Some(ColumnarValue::Array(Arc::new(BooleanArray::from(
+        bool_vec,
+    )))))
+}
+
+/// Fails with "mixed SRIDs" if both geometries of a row carry a non-zero srid and the srids
+/// differ, unless the check is disabled in the crate config. Only the wkb headers are read, the
+/// geometries are expected to be decoded already so that broken values fail while decoding.
+pub(crate) fn check_srids(row: usize, wkb0: &[u8], wkb1: &[u8]) -> DFResult<()> {
+    if !GeoConfig::get().check_srids {
+        return Ok(());
+    }
+    match (WkbHeader::parse(wkb0)?.srid, WkbHeader::parse(wkb1)?.srid) {
+        (Some(srid0), Some(srid1)) if srid0 != 0 && srid1 != 0 && srid0 != srid1 => {
+            exec_err!("mixed SRIDs {} vs {} at row {}", srid0, srid1, row)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Evaluates the rows in parallel with the result of a serial evaluation: the values in row
+/// order, or the error of the first failing row. Once a row failed the rows not started yet are
+/// skipped, the skipped rows before the first failure are evaluated again serially.
+pub(crate) fn par_rows<T: Send>(
+    len: usize,
+    f: impl Fn(usize) -> DFResult<T> + Sync,
+) -> DFResult<Vec<T>> {
+    let failed = AtomicBool::new(false);
+    // the rayon threads check the flag of the query and record the metrics of the function too
+    let cancellation = current_cancellation();
+    let call = current_call();
+    let rows = (0..len)
+        .into_par_iter()
+        .map(|index| {
+            if failed.load(Ordering::Relaxed) {
+                return None;
+            }
+            let row = with_call(call.clone(), || {
+                with_cancellation(cancellation.clone(), || f(index))
+            });
+            if row.is_err() {
+                failed.store(true, Ordering::Relaxed);
+            }
+            Some(row)
+        })
+        .collect::<Vec<Option<DFResult<T>>>>();
+    let mut values = Vec::with_capacity(len);
+    for (index, row) in rows.into_iter().enumerate() {
+        values.push(match row {
+            Some(row) => row?,
+            None => f(index)?,
+        });
+    }
+    Ok(values)
+}
+
+/// Signatures of a predicate taking a geometry or a box2d on either side.
+pub(crate) fn box_predicate_signatures() -> Vec<TypeSignature> {
+    let types = [DataType::Binary, DataType::LargeBinary, Box2d::data_type()];
+    let mut signatures = vec![];
+    for type0 in types.iter() {
+        for type1 in types.iter() {
+            signatures.push(TypeSignature::Exact(vec![type0.clone(), type1.clone()]));
+        }
+    }
+    signatures
+}
+
+/// Evaluates a predicate row by row using geo when at least one arg is a box2d, the boxes are
+/// read as `geo::Rect` without any wkb decoding. Returns None if no arg is a box2d.
+pub(crate) fn box_predicate(
+    name: &str,
+    args: &[ColumnarValue],
+    predicate: impl Fn(&geo::Geometry, &geo::Geometry) -> bool + Sync,
+) -> DFResult<Option<ColumnarValue>> {
+    if !args.iter().any(|arg| arg.data_type() == Box2d::data_type()) {
+        return Ok(None);
+    }
+    let num_rows = num_rows(args);
+    let recorder = record_call(name, num_rows);
+    let arg0 = PredicateArg::new(&args[0], scalar_box_or_geometry)?;
+    let arg1 = PredicateArg::new(&args[1], scalar_box_or_geometry)?;
+    let bool_vec = par_rows(num_rows, |index| {
+        check_cancelled(index)?;
+        let geoms = (
+            arg0.value(index, box_or_geo_value)?,
+            arg1.value(index, box_or_geo_value)?,
+        );
+        match geoms {
+            (Some(geom0), Some(geom1)) => Ok(Some(recorder.compute(|| predicate(&geom0, &geom1)))),
+            _ => Ok(None),
+        }
+    })?;
+    Ok(Some(ColumnarValue::Array(Arc::new(BooleanArray::from(
+        bool_vec,
+    )))))
+}
+
+fn box_or_geo_value(arr: &ArrayRef, index: usize) -> DFResult<Option<geo::Geometry>> {
+    match arr.data_type() {
+        DataType::Struct(_) => Ok(
+            Box2d::value(arr.as_struct(), index)?.map(|box2d| geo::Geometry::Rect(box2d.into()))
+        ),
+        _ => as_geometry_array(arr)?.geo_value(index),
+    }
+}
+
+fn scalar_box_or_geometry(value: &ScalarValue) -> DFResult<Option<geo::Geometry>> {
+    match value {
+        ScalarValue::Struct(..) => box_or_geo_value(&value.to_array()?, 0),
+        _ => scalar_to_geometry(value),
+    }
+}
+
+/// A geometry arg of a predicate, a scalar is decoded once and shared by all rows instead of
+/// being broadcast to an array and decoded again for every row.
+enum PredicateArg<'a, G> {
+    Scalar {
+        geom: Option<G>,
+        wkb: Option<&'a [u8]>,
+    },
+    Array(&'a ArrayRef),
+}
+
+impl<'a, G: Clone> PredicateArg<'a, G> {
+    fn new(
+        arg: &'a ColumnarValue,
+        decode_scalar: impl FnOnce(&ScalarValue) -> DFResult<Option<G>>,
+    ) -> DFResult<Self> {
+        match arg {
+            ColumnarValue::Array(arr) => Ok(Self::Array(arr)),
+            ColumnarValue::Scalar(value) => {
+                let wkb = match value {
+                    ScalarValue::Binary(wkb) | ScalarValue::LargeBinary(wkb) => wkb.as_deref(),
+                    _ => None,
+                };
+                Ok(Self::Scalar {
+                    geom: decode_scalar(value)?,
+                    wkb,
+                })
+            }
+        }
+    }
+
+    fn value(
+        &self,
+        row: usize,
+        decode: impl Fn(&ArrayRef, usize) -> DFResult<Option<G>>,
+    ) -> DFResult<Option<Cow<'_, G>>> {
+        match self {
+            Self::Scalar { geom, .. } => Ok(geom.as_ref().map(Cow::Borrowed)),
+            Self::Array(arr) => Ok(decode(arr, row)?.map(Cow::Owned)),
+        }
+    }
+
+    fn wkb(&self, row: usize) -> DFResult<Option<&[u8]>> {
+        match self {
+            Self::Scalar { wkb, .. } => Ok(*wkb),
+            Self::Array(arr) => Ok(as_geometry_array(arr)?.wkb(row)),
+        }
+    }
+}
+
+#[cfg(feature = "geos")]
+fn geos_value(arr: &ArrayRef, index: usize) -> DFResult<Option<geos::Geometry>> {
+    as_geometry_array(arr)?.geos_value(index)
+}
+
+#[cfg(feature = "geos")]
+fn scalar_to_geos(value: &ScalarValue) -> DFResult<Option<geos::Geometry>> {
+    geos_value(&value.to_array()?, 0)
+}
+
+/// GEOS version providing the fixed precision overlays of OverlayNG.
+#[cfg(feature = "geos")]
+pub(crate) const GRID_SIZE_GEOS: crate::geo::GeosVersion = crate::geo::GeosVersion::new(3, 9, 0);
+
+/// Reads the optional grid size arg of an overlay, a positive f64 scalar. Passing one fails if the
+/// linked GEOS has no fixed precision overlays.
+#[cfg(feature = "geos")]
+pub(crate) fn grid_size_arg(
+    name: &str,
+    args: &[ColumnarValue],
+    index: usize,
+) -> DFResult<Option<f64>> {
+    match args.get(index) {
+        None => Ok(None),
+        Some(ColumnarValue::Scalar(ScalarValue::Float64(Some(grid_size)))) if *grid_size > 0.0 => {
+            crate::geo::geos_capabilities()
+                .check(&format!("{} with a grid size", name), GRID_SIZE_GEOS)?;
+            Ok(Some(*grid_size))
+        }
+        Some(_) => exec_err!("The grid size should be a positive f64 scalar"),
+    }
+}
+
+/// Evaluates a predicate on two geometry args row by row using geos.
+#[cfg(feature = "geos")]
+pub(crate) fn geos_predicate(
+    name: &str,
+    args: &[ColumnarValue],
+    predicate: impl Fn(&geos::Geometry, &geos::Geometry) -> DFResult<bool> + Sync,
+) -> DFResult<ColumnarValue> {
+    let num_rows = num_rows(args);
+    let recorder = record_call(name, num_rows);
+    let arg0 = PredicateArg::new(&args[0], scalar_to_geos)?;
+    let arg1 = PredicateArg::new(&args[1], scalar_to_geos)?;
+    let bool_vec = par_rows(num_rows, |geom_index| {
+        check_cancelled(geom_index)?;
+        let geoms = (
+            arg0.value(geom_index, geos_value)?,
+            arg1.value(geom_index, geos_value)?,
+        );
+        match geoms {
+            (Some(geom0), Some(geom1)) => {
+                if let (Some(wkb0), Some(wkb1)) = (arg0.wkb(geom_index)?, arg1.wkb(geom_index)?) {
+                    check_srids(geom_index, wkb0, wkb1)?;
+                }
+                Ok(Some(recorder.compute(|| predicate(&geom0, &geom1))?))
+            }
+            _ => Ok(None),
+        }
+    })?;
+    Ok(ColumnarValue::Array(Arc::new(BooleanArray::from(bool_vec))))
+}
+
+/// Evaluates a predicate of a prepared first geometry row by row using geos. A scalar first arg
+/// is prepared once and shared by all rows, the geometries of an array are prepared per row.
+#[cfg(feature = "geos")]
+pub(crate) fn geos_prepared_predicate(
+    name: &str,
+    args: &[ColumnarValue],
+    predicate: impl Fn(&geos::PreparedGeometry, &geos::Geometry) -> DFResult<bool> + Sync,
+) -> DFResult<ColumnarValue> {
+    use datafusion_common::internal_datafusion_err;
+    use geos::Geom;
+
+    let prepare = |geom: &geos::Geometry| {
+        geom.to_prepared_geom()
+            .map_err(|e| internal_datafusion_err!("Failed to prepare geometry, error: {}", e))
+    };
+    let arg0 = PredicateArg::new(&args[0], scalar_to_geos)?;
+    let PredicateArg::Scalar { geom, wkb: wkb0 } = &arg0 else {
+        return geos_predicate(name, args, |geom0, geom1| {
+            predicate(&prepare(geom0)?, geom1)
+        });
+    };
+    let num_rows = num_rows(args);
+    let recorder = record_call(name, num_rows);
+    let Some(geom0) = geom else {
+        let bool_vec: Vec<Option<bool>> = vec![None; num_rows];
+        return Ok(ColumnarValue::Array(Arc::new(BooleanArray::from(bool_vec))));
+    };
+    let prepared = prepare(geom0)?;
+    let arg1 = PredicateArg::new(&args[1], scalar_to_geos)?;
+    let bool_vec = par_rows(num_rows, |geom_index| {
+        check_cancelled(geom_index)?;
+        let Some(geom1) = arg1.value(geom_index, geos_value)? else {
+            return Ok(None);
+        };
+        if let (Some(wkb0), Some(wkb1)) = (wkb0, arg1.wkb(geom_index)?) {
+            check_srids(geom_index, wkb0, wkb1)?;
+        }
+        Ok(Some(recorder.compute(|| predicate(&prepared, &geom1))?))
+    })?;
+    Ok(ColumnarValue::Array(Arc::new(BooleanArray::from(bool_vec))))
+}
+
+/// Signature of an overlay of two geometries with an optional f64 grid size.
+#[cfg(feature = "geos")]
+pub(crate) fn overlay_signature() -> datafusion_expr::Signature {
+    let types = [DataType::Binary, DataType::LargeBinary];
+    let mut type_signatures = vec![];
+    for type0 in types.iter() {
+        for type1 in types.iter() {
+            type_signatures.push(TypeSignature::Exact(vec![type0.clone(), type1.clone()]));
+            type_signatures.push(TypeSignature::Exact(vec![
+                type0.clone(),
+                type1.clone(),
+                DataType::Float64,
+            ]));
+        }
+    }
+    datafusion_expr::Signature::one_of(type_signatures, datafusion_expr::Volatility::Immutable)
+}
+
+/// Overlays two geometry args row by row, an optional third arg is the grid size of a fixed
+/// precision overlay. The result has the binary type of the first arg.
+#[cfg(feature = "geos")]
+pub(crate) fn geos_overlay(
+    name: &str,
+    args: &[ColumnarValue],
+    op: crate::geo::overlay::Overlay,
+) -> DFResult<ColumnarValue> {
+    use crate::config::default_dialect;
+    use crate::geo::overlay::overlay;
+    use crate::geo::GeometryArrayBuilder;
+
+    let grid_size = grid_size_arg(name, args, 2)?;
+    let (arrays, recorder) = geometry_args(name, &args[..2])?;
+    let arr0 = as_geometry_array(&arrays[0])?;
+    let arr1 = as_geometry_array(&arrays[1])?;
+    let geom_vec = par_rows(arr0.geom_len(), |geom_index| {
+        check_cancelled(geom_index)?;
+        let geoms = (arr0.geos_value(geom_index)?, arr1.geos_value(geom_index)?);
+        match geoms {
+            (Some(geom0), Some(geom1)) => {
+                if let (Some(wkb0), Some(wkb1)) = (arr0.wkb(geom_index), arr1.wkb(geom_index)) {
+                    check_srids(geom_index, wkb0, wkb1)?;
+                }
+                let geom = recorder.compute(|| overlay(op, &geom0, &geom1, grid_size))?;
+                Ok(Some(geom))
+            }
+            _ => Ok(None),
+        }
+    })?;
+    let arr: ArrayRef = match arrays[0].data_type() {
+        DataType::LargeBinary => Arc::new(
+            GeometryArrayBuilder::<i64>::from_geos_parallel(&geom_vec, default_dialect())?.build(),
+        ),
+        _ => Arc::new(
+            GeometryArrayBuilder::<i32>::from_geos_parallel(&geom_vec, default_dialect())?.build(),
+        ),
+    };
+    Ok(ColumnarValue::Array(arr))
+}
+
+#[cfg(not(feature = "geos"))]
+fn geo_value(arr: &ArrayRef, index: usize) -> DFResult<Option<geo::Geometry>> {
+    as_geometry_array(arr)?.geo_value(index)
+}
+
+/// Evaluates a predicate on two geometry args row by row using geo.
+#[cfg(not(feature = "geos"))]
+pub(crate) fn geo_predicate(
+    name: &str,
+    args: &[ColumnarValue],
+    predicate: impl Fn(&geo::Geometry, &geo::Geometry) -> bool + Sync,
+) -> DFResult<ColumnarValue> {
+    let num_rows = num_rows(args);
+    let recorder = record_call(name, num_rows);
+    let arg0 = PredicateArg::new(&args[0], scalar_to_geometry)?;
+    let arg1 = PredicateArg::new(&args[1], scalar_to_geometry)?;
+    let bool_vec = par_rows(num_rows, |geom_index| {
+        check_cancelled(geom_index)?;
+        let geoms = (
+            arg0.value(geom_index, geo_value)?,
+            arg1.value(geom_index, geo_value)?,
+        );
+        match geoms {
+            (Some(geom0), Some(geom1)) => {
+                if let (Some(wkb0), Some(wkb1)) = (arg0.wkb(geom_index)?, arg1.wkb(geom_index)?) {
+                    check_srids(geom_index, wkb0, wkb1)?;
+                }
+                Ok(Some(recorder.compute(|| predicate(&geom0, &geom1))))
+            }
+            _ => Ok(None),
+        }
+    })?;
+    Ok(ColumnarValue::Array(Arc::new(BooleanArray::from(bool_vec))))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::default_dialect;
+    use crate::function::IntersectsUdf;
+    use crate::geo::{GeometryArray, GeometryArrayBuilder};
+    use crate::DFResult;
+    use arrow_array::cast::AsArray;
+    use arrow_array::{Array, BinaryArray, BooleanArray};
+    use datafusion_expr::{ColumnarValue, ScalarUDFImpl};
+    use geo::{coord, point, Rect};
+    use proptest::prelude::*;
+    use std::sync::Arc;
+
+    #[derive(Debug, Clone)]
+    enum Row {
+        Null,
+        Geometry(geo::Geometry),
+        /// Bytes too short to be any geometry, with a known or an unknown dialect prefix.
+        Invalid(Vec<u8>),
+    }
+
+    fn row() -> impl Strategy<Value = Row> {
+        prop_oneof![
+            Just(Row::Null),
+            (-5i32..5, -5i32..5)
+                .prop_map(|(x, y)| Row::Geometry(point!(x: x as f64, y: y as f64).into())),
+            (-5i32..5, -5i32..5, 1i32..4, 1i32..4).prop_map(|(x, y, w, h)| {
+                let rect = Rect::new(
+                    coord! {x: x as f64, y: y as f64},
+                    coord! {x: (x + w) as f64, y: (y + h) as f64},
+                );
+                Row::Geometry(rect.to_polygon().into())
+            }),
+            (0u8..7, proptest::collection::vec(any::<u8>(), 0..9)).prop_map(|(type_id, tail)| {
+                let mut bytes = vec![type_id];
+                bytes.extend(tail);
+                Row::Invalid(bytes)
+            }),
+            Just(Row::Invalid(vec![])),
+        ]
+    }
+
+    fn binary_array(rows: &[Row]) -> BinaryArray {
+        rows.iter()
+            .map(|row| match row {
+                Row::Null => None,
+                Row::Geometry(geom) => {
+                    let mut builder = GeometryArrayBuilder::<i32>::new(default_dialect(), 1);
+                    builder.append_geo_geometry(&Some(geom.clone())).unwrap();
+                    Some(builder.build().value(0).to_vec())
+                }
+                Row::Invalid(bytes) => Some(bytes.clone()),
+            })
+            .collect()
+    }
+
+    fn invoke(
+        udf: &dyn ScalarUDFImpl,
+        arr0: &BinaryArray,
+        arr1: &BinaryArray,
+    ) -> DFResult<ColumnarValue> {
+        udf.invoke(&[
+            ColumnarValue::Array(Arc::new(arr0.clone())),
+            ColumnarValue::Array(Arc::new(arr1.clone())),
+        ])
+    }
+
+    /// Serial reference of `geos_predicate`.
+    #[cfg(feature = "geos")]
+    fn serial_geos_predicate(
+        arr0: &BinaryArray,
+        arr1: &BinaryArray,
+        predicate: impl Fn(&geos::Geometry, &geos::Geometry) -> geos::GResult<bool>,
+    ) -> DFResult<BooleanArray> {
+        let mut values = vec![];
+        for i in 0..arr0.len() {
+            match (arr0.geos_value(i)?, arr1.geos_value(i)?) {
+                (Some(geom0), Some(geom1)) => values.push(Some(predicate(&geom0, &geom1).unwrap())),
+                _ => values.push(None),
+            }
+        }
+        Ok(BooleanArray::from(values))
+    }
+
+    /// Serial reference of `geo_predicate`.
+    #[cfg(not(feature = "geos"))]
+    fn serial_geo_predicate(
+        arr0: &BinaryArray,
+        arr1: &BinaryArray,
+        predicate: impl Fn(&geo::Geometry, &geo::Geometry) -> bool,
+    ) -> DFResult<BooleanArray> {
+        let mut values = vec![];
+        for i in 0..arr0.len() {
+            match (arr0.geo_value(i)?, arr1.geo_value(i)?) {
+                (Some(geom0), Some(geom1)) => values.push(Some(predicate(&geom0, &geom1))),
+                _ => values.push(None),
+            }
+        }
+        Ok(BooleanArray::from(values))
+    }
+
+    fn assert_same(
+        parallel: DFResult<ColumnarValue>,
+        serial: DFResult<BooleanArray>,
+    ) -> Result<(), TestCaseError> {
+        match (parallel, serial) {
+            (Ok(ColumnarValue::Array(arr)), Ok(expected)) => {
+                prop_assert_eq!(arr.as_boolean(), &expected)
+            }
+            (Err(e), Err(expected)) => prop_assert_eq!(e.to_string(), expected.to_string()),
+            (parallel, serial) => {
+                prop_assert!(false, "parallel: {:?}, serial: {:?}", parallel, serial)
+            }
+        }
+        Ok(())
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn intersects_matches_serial(rows in proptest::collection::vec((row(), row()), 0..200)) {
+            let (rows0, rows1): (Vec<_>, Vec<_>) = rows.into_iter().unzip();
+            let (arr0, arr1) = (binary_array(&rows0), binary_array(&rows1));
+            let parallel = invoke(&IntersectsUdf::new(), &arr0, &arr1);
+            #[cfg(feature = "geos")]
+            let serial = {
+                use geos::Geom;
+                serial_geos_predicate(&arr0, &arr1, |geom0, geom1| geom0.intersects(geom1))
+            };
+            #[cfg(not(feature = "geos"))]
+            let serial = {
+                use geo::Intersects;
+                serial_geo_predicate(&arr0, &arr1, |geom0, geom1| geom0.intersects(geom1))
+            };
+            assert_same(parallel, serial)?;
+        }
+
+        #[cfg(feature = "geos")]
+        #[test]
+        fn equals_matches_serial(rows in proptest::collection::vec((row(), row()), 0..200)) {
+            use crate::function::EqualsUdf;
+            use geos::Geom;
+            let (rows0, rows1): (Vec<_>, Vec<_>) = rows.into_iter().unzip();
+            let (arr0, arr1) = (binary_array(&rows0), binary_array(&rows1));
+            let parallel = invoke(&EqualsUdf::new(), &arr0, &arr1);
+            let serial = serial_geos_predicate(&arr0, &arr1, |geom0, geom1| geom0.equals(geom1));
+            assert_same(parallel, serial)?;
+        }
+
+        #[cfg(feature = "geos")]
+        #[test]
+        fn covers_matches_serial(rows in proptest::collection::vec((row(), row()), 0..200)) {
+            use crate::function::CoversUdf;
+            use geos::Geom;
+            let (rows0, rows1): (Vec<_>, Vec<_>) = rows.into_iter().unzip();
+            let (arr0, arr1) = (binary_array(&rows0), binary_array(&rows1));
+            let parallel = invoke(&CoversUdf::new(), &arr0, &arr1);
+            let serial = serial_geos_predicate(&arr0, &arr1, |geom0, geom1| geom0.covers(geom1));
+            assert_same(parallel, serial)?;
+        }
+    }
+
+    #[test]
+    fn first_failing_row_wins() {
+        let rows = (0..1000)
+            .map(|i| Row::Invalid(vec![1, (i % 7) as u8]))
+            .collect::<Vec<_>>();
+        let arr = binary_array(&rows);
+        for _ in 0..10 {
+            let err = invoke(&IntersectsUdf::new(), &arr, &arr).unwrap_err();
+            assert!(err.to_string().contains("at row 0,"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn mixed_srids() {
+        use crate::geo::geometry_scalar;
+        use geo::polygon;
+
+        let square: geo::Geometry = polygon![
+            (x: 0.0, y: 0.0),
+            (x: 2.0, y: 0.0),
+            (x: 2.0, y: 2.0),
+            (x: 0.0, y: 2.0),
+            (x: 0.0, y: 0.0),
+        ]
+        .into();
+        let point: geo::Geometry = point!(x: 1.0, y: 1.0).into();
+        let build = |srids: &[Option<i32>], geom: &geo::Geometry| {
+            let mut builder = GeometryArrayBuilder::<i32>::new(default_dialect(), srids.len());
+            for srid in srids {
+                builder
+                    .append_geo_geometry_with_srid(&Some(geom.clone()), *srid)
+                    .unwrap();
+            }
+            builder.build()
+        };
+
+        // matching srids and a srid on one side only are compared
+        let squares = build(&[Some(4326), Some(4326), None], &square);
+        let points = build(&[Some(4326), None, Some(3857)], &point);
+        let ColumnarValue::Array(result) =
+            invoke(&IntersectsUdf::new(), &squares, &points).unwrap()
+        else {
+            panic!("intersects should return an array");
+        };
+        assert_eq!(
+            result.as_boolean(),
+            &BooleanArray::from(vec![Some(true), Some(true), Some(true)])
+        );
+
+        let squares = build(&[Some(4326), Some(4326), Some(4326)], &square);
+        let points = build(&[Some(4326), None, Some(3857)], &point);
+        let err = invoke(&IntersectsUdf::new(), &squares, &points).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("mixed SRIDs 4326 vs 3857 at row 2"),
+            "{}",
+            err
+        );
+
+        // the point in polygon fast path checks the srids too
+        let err = IntersectsUdf::new()
+            .invoke(&[
+                ColumnarValue::Array(Arc::new(points)),
+                ColumnarValue::Scalar(geometry_scalar(&square, Some(4326)).unwrap()),
+            ])
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("mixed SRIDs 3857 vs 4326 at row 2"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn scalar_predicate_args() {
+        use crate::geo::geometry_scalar;
+        use datafusion_common::ScalarValue;
+        use geo::{line_string, polygon};
+
+        let square: geo::Geometry = polygon![
+            (x: 0.0, y: 0.0),
+            (x: 2.0, y: 0.0),
+            (x: 2.0, y: 2.0),
+            (x: 0.0, y: 2.0),
+            (x: 0.0, y: 0.0),
+        ]
+        .into();
+        let rows = vec![
+            Row::Geometry(line_string![(x: 1.0, y: 1.0), (x: 3.0, y: 3.0)].into()),
+            Row::Geometry(line_string![(x: 3.0, y: 3.0), (x: 4.0, y: 4.0)].into()),
+            Row::Null,
+            Row::Geometry(square.clone()),
+        ];
+        let arr = binary_array(&rows);
+        let scalar = geometry_scalar(&square, None).unwrap();
+        let broadcast = binary_array(&vec![Row::Geometry(square.clone()); rows.len()]);
+
+        // a scalar on either side matches the broadcast scalar
+        let ColumnarValue::Array(expected) =
+            invoke(&IntersectsUdf::new(), &arr, &broadcast).unwrap()
+        else {
+            panic!("intersects should return an array");
+        };
+        let args = [
+            [
+                ColumnarValue::Array(Arc::new(arr.clone())),
+                ColumnarValue::Scalar(scalar.clone()),
+            ],
+            [
+                ColumnarValue::Scalar(scalar.clone()),
+                ColumnarValue::Array(Arc::new(arr.clone())),
+            ],
+        ];
+        for args in args {
+            let ColumnarValue::Array(result) = IntersectsUdf::new().invoke(&args).unwrap() else {
+                panic!("intersects should return an array");
+            };
+            assert_eq!(result.as_boolean(), expected.as_boolean());
+        }
+
+        // a null scalar gives null rows
+        let ColumnarValue::Array(result) = IntersectsUdf::new()
+            .invoke(&[
+                ColumnarValue::Array(Arc::new(arr.clone())),
+                ColumnarValue::Scalar(ScalarValue::Binary(None)),
+            ])
+            .unwrap()
+        else {
+            panic!("intersects should return an array");
+        };
+        assert_eq!(result.null_count(), rows.len());
+
+        // the srid of the scalar is checked against every row
+        let mut builder = GeometryArrayBuilder::<i32>::new(default_dialect(), 1);
+        builder
+            .append_geo_geometry_with_srid(
+                &Some(line_string![(x: 1.0, y: 1.0), (x: 3.0, y: 3.0)].into()),
+                Some(3857),
+            )
+            .unwrap();
+        let err = IntersectsUdf::new()
+            .invoke(&[
+                ColumnarValue::Array(Arc::new(builder.build())),
+                ColumnarValue::Scalar(geometry_scalar(&square, Some(4326)).unwrap()),
+            ])
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("mixed SRIDs 3857 vs 4326 at row 0"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn constant_geometry_stays_scalar() {
+        use crate::function::box2d::Box2dUdf;
+        use crate::function::{AsTextUdf, GeometryTypeUdf, SridUdf};
+        use crate::geo::geometry_scalar;
+
+        let geom = geometry_scalar(&point!(x: 1.0, y: 2.0).into(), Some(4326)).unwrap();
+        let udfs: [&dyn ScalarUDFImpl; 4] = [
+            &AsTextUdf::new(),
+            &Box2dUdf::new(),
+            &GeometryTypeUdf::new(),
+            &SridUdf::new(),
+        ];
+        for udf in udfs {
+            let value = udf.invoke(&[ColumnarValue::Scalar(geom.clone())]).unwrap();
+            assert!(
+                matches!(value, ColumnarValue::Scalar(_)),
+                "{} should return a scalar",
+                udf.name()
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn constant_geometry_with_table_rows() {
+        use crate::function::box2d::Box2dUdf;
+        use crate::function::{AsTextUdf, GeomFromTextUdf, GeometryTypeUdf, SridUdf};
+        use arrow::util::pretty::pretty_format_batches;
+        use datafusion::logical_expr::ScalarUDF;
+        use datafusion::prelude::SessionContext;
+
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(Box2dUdf::new()));
+        ctx.register_udf(ScalarUDF::from(GeometryTypeUdf::new()));
+        ctx.register_udf(ScalarUDF::from(SridUdf::new()));
+        let df = ctx
+            .sql(
+                "select id, ST_AsText(ST_GeomFromText('POINT(1 2)', 4326)) as wkt, \
+                ST_AsText(Box2D(ST_GeomFromText('POINT(1 2)', 4326))) as box2d, \
+                ST_GeometryType(ST_GeomFromText('POINT(1 2)', 4326)) as type, \
+                ST_SRID(ST_GeomFromText('POINT(1 2)', 4326)) as srid \
+                from (values (1), (2), (3)) as t(id)",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+----+------------+--------------+----------+------+
+| id | wkt        | box2d        | type     | srid |
++----+------------+--------------+----------+------+
+| 1  | POINT(1 2) | BOX(1 2,1 2) | ST_Point | 4326 |
+| 2  | POINT(1 2) | BOX(1 2,1 2) | ST_Point | 4326 |
+| 3  | POINT(1 2) | BOX(1 2,1 2) | ST_Point | 4326 |
++----+------------+--------------+----------+------+"
+        );
+    }
+}
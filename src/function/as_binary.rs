@@ -0,0 +1,263 @@
+use crate::function::args::geometry_args;
+use crate::geo::GeometryArray;
+use crate::DFResult;
+use arrow_array::cast::AsArray;
+use arrow_array::{GenericBinaryArray, OffsetSizeTrait};
+use arrow_schema::DataType;
+use datafusion_common::{
+    exec_err, internal_datafusion_err, internal_err, DataFusionError, ScalarValue,
+};
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, TypeSignature, Volatility};
+use geozero::{CoordDimensions, ToWkb};
+use std::any::Any;
+use std::sync::Arc;
+
+/// Returns the OGC wkb of a geometry without dialect prefix and srid, in little endian (NDR)
+/// by default or in big endian when the second arg is 'XDR'.
+#[derive(Debug)]
+pub struct AsBinaryUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl AsBinaryUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                vec![
+                    TypeSignature::Exact(vec![DataType::Binary]),
+                    TypeSignature::Exact(vec![DataType::LargeBinary]),
+                    TypeSignature::Exact(vec![DataType::Binary, DataType::Utf8]),
+                    TypeSignature::Exact(vec![DataType::LargeBinary, DataType::Utf8]),
+                ],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_asbinary".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for AsBinaryUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_AsBinary"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(arg_types[0].clone())
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let big_endian = if args.len() == 2 {
+            let ColumnarValue::Scalar(ScalarValue::Utf8(Some(byte_order))) = &args[1] else {
+                return exec_err!("The second arg should be utf8 scalar");
+            };
+            match byte_order.to_uppercase().as_str() {
+                "NDR" => false,
+                "XDR" => true,
+                _ => return exec_err!("Byte order should be 'NDR' or 'XDR'"),
+            }
+        } else {
+            false
+        };
+
+        let (arrays, _recorder) = geometry_args(self.name(), &args[..1])?;
+        let arr = &arrays[0];
+        match arr.data_type() {
+            DataType::Binary => as_binary::<i32>(arr.as_binary::<i32>(), big_endian),
+            DataType::LargeBinary => as_binary::<i64>(arr.as_binary::<i64>(), big_endian),
+            _ => unreachable!(),
+        }
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for AsBinaryUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn as_binary<O: OffsetSizeTrait>(
+    wkb_arr: &GenericBinaryArray<O>,
+    big_endian: bool,
+) -> DFResult<ColumnarValue> {
+    let mut wkb_vec = vec![];
+    for i in 0..wkb_arr.geom_len() {
+        let Some(geom) = wkb_arr.geo_value(i)? else {
+            wkb_vec.push(None);
+            continue;
+        };
+        let wkb = geom
+            .to_wkb(CoordDimensions::xy())
+            .map_err(|e| internal_datafusion_err!("Failed to convert to wkb, error: {}", e))?;
+        if big_endian {
+            let mut xdr = Vec::with_capacity(wkb.len());
+            let mut offset = 0;
+            swap_geometry(&wkb, &mut offset, &mut xdr)?;
+            wkb_vec.push(Some(xdr));
+        } else {
+            wkb_vec.push(Some(wkb));
+        }
+    }
+    Ok(ColumnarValue::Array(Arc::new(
+        GenericBinaryArray::<O>::from_iter(wkb_vec),
+    )))
+}
+
+/// Rewrites one little endian OGC wkb geometry starting at `offset` into big endian.
+fn swap_geometry(wkb: &[u8], offset: &mut usize, out: &mut Vec<u8>) -> DFResult<()> {
+    if wkb.get(*offset) != Some(&1) {
+        return internal_err!("Wkb is not little endian");
+    }
+    out.push(0);
+    *offset += 1;
+    let type_id = swap_u32(wkb, offset, out)?;
+    let dims = match type_id / 1000 {
+        0 => 2,
+        1 | 2 => 3,
+        3 => 4,
+        _ => return internal_err!("Unsupported wkb type {}", type_id),
+    };
+    match type_id % 1000 {
+        // point
+        1 => swap_f64s(wkb, offset, out, dims),
+        // linestring
+        2 => {
+            let num_coords = swap_u32(wkb, offset, out)? as usize;
+            swap_f64s(wkb, offset, out, num_coords * dims)
+        }
+        // polygon
+        3 => {
+            let num_rings = swap_u32(wkb, offset, out)?;
+            for _ in 0..num_rings {
+                let num_coords = swap_u32(wkb, offset, out)? as usize;
+                swap_f64s(wkb, offset, out, num_coords * dims)?;
+            }
+            Ok(())
+        }
+        // multi geometries and geometry collection
+        4..=7 => {
+            let num_geoms = swap_u32(wkb, offset, out)?;
+            for _ in 0..num_geoms {
+                swap_geometry(wkb, offset, out)?;
+            }
+            Ok(())
+        }
+        _ => internal_err!("Unsupported wkb type {}", type_id),
+    }
+}
+
+fn swap_u32(wkb: &[u8], offset: &mut usize, out: &mut Vec<u8>) -> DFResult<u32> {
+    let bytes: [u8; 4] = wkb
+        .get(*offset..*offset + 4)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| internal_datafusion_err!("Wkb is truncated"))?;
+    let value = u32::from_le_bytes(bytes);
+    out.extend_from_slice(&value.to_be_bytes());
+    *offset += 4;
+    Ok(value)
+}
+
+fn swap_f64s(wkb: &[u8], offset: &mut usize, out: &mut Vec<u8>, count: usize) -> DFResult<()> {
+    let Some(bytes) = wkb.get(*offset..*offset + count * 8) else {
+        return internal_err!("Wkb is truncated");
+    };
+    for chunk in bytes.chunks_exact(8) {
+        out.extend(chunk.iter().rev());
+    }
+    *offset += count * 8;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{AsBinaryUdf, GeomFromTextUdf};
+    use arrow_array::cast::AsArray;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+
+    #[tokio::test]
+    async fn as_binary() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsBinaryUdf::new()));
+        let df = ctx
+            .sql(
+                "select ST_AsBinary(ST_GeomFromText('POINT(1 2)', 4326)), \
+            ST_AsBinary(ST_GeomFromText('POINT(1 2)'), 'NDR'), \
+            ST_AsBinary(ST_GeomFromText('POINT(1 2)'), 'xdr'), \
+            ST_AsBinary(ST_GeomFromText('POLYGON((0 0,2 0,2 2,0 0))'), 'XDR')",
+            )
+            .await
+            .unwrap();
+        let batches = df.collect().await.unwrap();
+        let ndr_point = [
+            0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xf0, 0x3f, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40,
+        ];
+        let xdr_point = [
+            0x00, 0x00, 0x00, 0x00, 0x01, 0x3f, 0xf0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+        let xdr_polygon = [
+            0x00, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00,
+            0x04, // header
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, // (0 0)
+            0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, // (2 0)
+            0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, // (2 2)
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, // (0 0)
+        ];
+        assert_eq!(batches[0].column(0).as_binary::<i32>().value(0), ndr_point);
+        assert_eq!(batches[0].column(1).as_binary::<i32>().value(0), ndr_point);
+        assert_eq!(batches[0].column(2).as_binary::<i32>().value(0), xdr_point);
+        assert_eq!(
+            batches[0].column(3).as_binary::<i32>().value(0),
+            xdr_polygon
+        );
+    }
+
+    #[cfg(feature = "geos")]
+    #[tokio::test]
+    async fn as_binary_round_trip() {
+        use crate::function::geom_from_wkb::GeomFromWkbUdf;
+        use crate::function::EqualsUdf;
+        use arrow_array::Array;
+
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(GeomFromWkbUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsBinaryUdf::new()));
+        ctx.register_udf(ScalarUDF::from(EqualsUdf::new()));
+        let df = ctx
+            .sql(
+                "select ST_Equals(geom, ST_GeomFromWKB(ST_AsBinary(geom))) \
+            from (select ST_GeomFromText(wkt) as geom from (values \
+            ('POINT(1 2)'), \
+            ('LINESTRING(0 0,1 1,2 0)'), \
+            ('POLYGON((0 0,4 0,4 4,0 4,0 0),(1 1,2 1,2 2,1 2,1 1))'), \
+            ('MULTIPOLYGON(((0 0,1 0,1 1,0 0)),((2 2,3 2,3 3,2 2)))'), \
+            ('MULTIPOINT(1 2,3 4)')) as t(wkt))",
+            )
+            .await
+            .unwrap();
+        let batches = df.collect().await.unwrap();
+        let equals = batches[0].column(0).as_boolean();
+        assert_eq!(equals.len(), 5);
+        assert!(equals.iter().all(|equal| equal == Some(true)));
+    }
+}
@@ -0,0 +1,179 @@
+use crate::function::args::geometry_args;
+use crate::geo::dialect::WkbHeader;
+use crate::geo::GeometryArray;
+use crate::DFResult;
+use arrow_array::cast::AsArray;
+use arrow_array::{GenericBinaryArray, OffsetSizeTrait};
+use arrow_schema::DataType;
+use datafusion_common::{internal_datafusion_err, DataFusionError};
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+use geozero::wkb::{process_wkb_type_geom, WkbDialect, WkbWriter};
+use geozero::CoordDimensions;
+use std::any::Any;
+use std::sync::Arc;
+
+/// Returns the little endian ewkb of a geometry without dialect prefix, the srid and the z and m
+/// ordinates are kept when the geometry has them.
+#[derive(Debug)]
+pub struct AsEwkbUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl AsEwkbUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::uniform(
+                1,
+                vec![DataType::Binary, DataType::LargeBinary],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_asewkb".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for AsEwkbUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_AsEWKB"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(arg_types[0].clone())
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let arr = &arrays[0];
+        match arr.data_type() {
+            DataType::Binary => as_ewkb::<i32>(arr.as_binary::<i32>()),
+            DataType::LargeBinary => as_ewkb::<i64>(arr.as_binary::<i64>()),
+            _ => unreachable!(),
+        }
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for AsEwkbUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn as_ewkb<O: OffsetSizeTrait>(wkb_arr: &GenericBinaryArray<O>) -> DFResult<ColumnarValue> {
+    let mut ewkb_vec = vec![];
+    for i in 0..wkb_arr.geom_len() {
+        let Some(wkb) = wkb_arr.wkb(i) else {
+            ewkb_vec.push(None);
+            continue;
+        };
+        let header = WkbHeader::parse(wkb)?;
+        let dims = CoordDimensions {
+            z: header.has_z,
+            m: header.has_m,
+            t: false,
+            tm: false,
+        };
+        let srid = header.srid.filter(|srid| *srid != 0);
+        let mut ewkb = vec![];
+        let mut writer = WkbWriter::with_opts(&mut ewkb, WkbDialect::Ewkb, dims, srid, vec![]);
+        process_wkb_type_geom(
+            &mut std::io::Cursor::new(&wkb[1..]),
+            &mut writer,
+            header.dialect,
+        )
+        .map_err(|e| internal_datafusion_err!("Failed to convert to ewkb, error: {}", e))?;
+        ewkb_vec.push(Some(ewkb));
+    }
+    Ok(ColumnarValue::Array(Arc::new(
+        GenericBinaryArray::<O>::from_iter(ewkb_vec),
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{AsEwkbUdf, GeomFromTextUdf};
+    use arrow_array::cast::AsArray;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+
+    #[tokio::test]
+    async fn as_ewkb() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsEwkbUdf::new()));
+        let df = ctx
+            .sql(
+                "select ST_AsEWKB(ST_GeomFromText('POINT(1 2)', 4326)), \
+            ST_AsEWKB(ST_GeomFromText('POINT(1 2)'))",
+            )
+            .await
+            .unwrap();
+        let batches = df.collect().await.unwrap();
+        let coords = [
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xf0, 0x3f, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x40,
+        ];
+        // the srid flag is set in the type and the srid follows it
+        let mut point_with_srid = vec![0x01, 0x01, 0x00, 0x00, 0x20, 0xe6, 0x10, 0x00, 0x00];
+        point_with_srid.extend_from_slice(&coords);
+        let mut point = vec![0x01, 0x01, 0x00, 0x00, 0x00];
+        point.extend_from_slice(&coords);
+        assert_eq!(
+            batches[0].column(0).as_binary::<i32>().value(0),
+            point_with_srid
+        );
+        assert_eq!(batches[0].column(1).as_binary::<i32>().value(0), point);
+    }
+
+    #[test]
+    fn as_ewkb_keeps_z_and_m() {
+        use crate::geo::dialect::wkb_type_id;
+        use arrow_array::BinaryArray;
+        use datafusion_expr::{ColumnarValue, ScalarUDFImpl};
+        use geozero::wkb::WkbDialect;
+        use std::sync::Arc;
+
+        let ordinates = |values: &[f64]| {
+            values
+                .iter()
+                .flat_map(|v| v.to_le_bytes())
+                .collect::<Vec<_>>()
+        };
+        // POINT Z (1 2 3) with srid 4326 and POINT ZM (1 2 3 4) without srid
+        let mut point_z = vec![0x01, 0x01, 0x00, 0x00, 0xa0, 0xe6, 0x10, 0x00, 0x00];
+        point_z.extend(ordinates(&[1.0, 2.0, 3.0]));
+        let mut point_zm = vec![0x01, 0x01, 0x00, 0x00, 0xc0];
+        point_zm.extend(ordinates(&[1.0, 2.0, 3.0, 4.0]));
+
+        let prefixed = [&point_z, &point_zm]
+            .iter()
+            .map(|ewkb| {
+                let mut prefixed = vec![wkb_type_id(WkbDialect::Ewkb)];
+                prefixed.extend_from_slice(ewkb);
+                prefixed
+            })
+            .collect::<Vec<_>>();
+        let arr = BinaryArray::from_iter_values(prefixed.iter());
+        let ColumnarValue::Array(result) = AsEwkbUdf::new()
+            .invoke(&[ColumnarValue::Array(Arc::new(arr))])
+            .unwrap()
+        else {
+            panic!("ST_AsEWKB should return an array");
+        };
+        let result = result.as_binary::<i32>();
+        assert_eq!(result.value(0), point_z);
+        assert_eq!(result.value(1), point_zm);
+    }
+}
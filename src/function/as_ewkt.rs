@@ -1,3 +1,5 @@
+use crate::function::args::geometry_args;
+use crate::geo::format::format_numbers;
 use crate::geo::GeometryArray;
 use crate::DFResult;
 use arrow_array::cast::AsArray;
@@ -52,8 +54,9 @@ impl ScalarUDFImpl for AsEwktUdf {
     }
 
     fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
-        let arr = args[0].clone().into_array(1)?;
-        match args[0].data_type() {
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let arr = &arrays[0];
+        match arr.data_type() {
             DataType::Binary => {
                 let wkb_arr = arr.as_binary::<i32>();
 
@@ -91,10 +94,9 @@ fn to_ewkt<O: OffsetSizeTrait>(
 ) -> DFResult<Option<String>> {
     let geom = wkb_arr.geos_value(geom_index)?;
     let ewkt = match geom {
-        Some(geom) => Some(
-            geom.to_ewkt(geom.srid())
-                .map_err(|_| internal_datafusion_err!("Failed to convert geometry to ewkt"))?,
-        ),
+        Some(geom) => Some(format_numbers(geom.to_ewkt(geom.srid()).map_err(|_| {
+            internal_datafusion_err!("Failed to convert geometry to ewkt")
+        })?)),
         None => None,
     };
     Ok(ewkt)
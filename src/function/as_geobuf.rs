@@ -0,0 +1,234 @@
+use crate::config::check_cancelled;
+use crate::function::args::as_geometry_array;
+use crate::geo::geobuf::{
+    decode_geobuf, encode_feature_collection, GeobufData, GeobufFeature, GeobufValue,
+};
+use crate::geo::GeometryArray;
+use crate::metrics::record_call;
+use arrow_array::cast::AsArray;
+use arrow_array::ArrayRef;
+use arrow_schema::DataType;
+use datafusion_common::{exec_err, internal_err, DataFusionError, ScalarValue};
+use datafusion_expr::{Accumulator, AggregateUDFImpl, Signature, Volatility};
+use std::any::Any;
+
+/// Aggregates geometries into a geobuf feature collection, e.g. to ship query results to browsers.
+/// The args after the geometry are pairs of a property name and its value, like
+/// `st_asgeobuf(geom, 'name', name, 'population', population)`. Null values are left out.
+#[derive(Debug)]
+pub struct AsGeobufUdaf {
+    signature: Signature,
+}
+
+impl AsGeobufUdaf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::variadic_any(Volatility::Immutable),
+        }
+    }
+}
+
+impl AggregateUDFImpl for AsGeobufUdaf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        // uadf not support alias
+        "st_asgeobuf"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        if !matches!(arg_types[0], DataType::Binary | DataType::LargeBinary) {
+            return exec_err!("The first arg of st_asgeobuf should be a geometry");
+        }
+        if arg_types.len() % 2 == 0 {
+            return exec_err!("The properties of st_asgeobuf should be name and value pairs");
+        }
+        Ok(DataType::Binary)
+    }
+
+    fn accumulator(&self, _arg: &DataType) -> datafusion_common::Result<Box<dyn Accumulator>> {
+        Ok(Box::new(GeobufAccumulator::new()))
+    }
+
+    fn state_type(&self, _return_type: &DataType) -> datafusion_common::Result<Vec<DataType>> {
+        Ok(vec![DataType::Binary])
+    }
+}
+
+impl Default for AsGeobufUdaf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug)]
+pub struct GeobufAccumulator {
+    features: Vec<GeobufFeature>,
+}
+
+impl GeobufAccumulator {
+    pub fn new() -> Self {
+        Self { features: vec![] }
+    }
+}
+
+impl Default for GeobufAccumulator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Accumulator for GeobufAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> datafusion_common::Result<()> {
+        if values.is_empty() {
+            return Ok(());
+        }
+        let wkb_arr = as_geometry_array(&values[0])?;
+        let _recorder = record_call("st_asgeobuf", wkb_arr.geom_len());
+        for i in 0..wkb_arr.geom_len() {
+            check_cancelled(i)?;
+            let mut properties = vec![];
+            for pair in values[1..].chunks(2) {
+                let [key_arr, value_arr] = pair else {
+                    return internal_err!("Property name without value");
+                };
+                let ScalarValue::Utf8(Some(key)) = ScalarValue::try_from_array(key_arr, i)? else {
+                    return exec_err!("The property name should be a non null string");
+                };
+                if let Some(value) = to_geobuf_value(ScalarValue::try_from_array(value_arr, i)?) {
+                    properties.push((key, value));
+                }
+            }
+            self.features.push(GeobufFeature {
+                geometry: wkb_arr.geo_value(i)?,
+                properties,
+            });
+        }
+        Ok(())
+    }
+
+    fn evaluate(&mut self) -> datafusion_common::Result<ScalarValue> {
+        Ok(ScalarValue::Binary(Some(encode_feature_collection(
+            &self.features,
+        ))))
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self)
+            + self.features.capacity() * std::mem::size_of::<GeobufFeature>()
+    }
+
+    fn state(&mut self) -> datafusion_common::Result<Vec<ScalarValue>> {
+        Ok(vec![self.evaluate()?])
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> datafusion_common::Result<()> {
+        if states.is_empty() {
+            return Ok(());
+        }
+        for geobuf in states[0].as_binary::<i32>().iter().flatten() {
+            match decode_geobuf(geobuf)? {
+                GeobufData::FeatureCollection(features) => self.features.extend(features),
+                data => return internal_err!("Geobuf state is not a collection: {:?}", data),
+            }
+        }
+        Ok(())
+    }
+}
+
+fn to_geobuf_value(value: ScalarValue) -> Option<GeobufValue> {
+    if value.is_null() {
+        return None;
+    }
+    let value = match value {
+        ScalarValue::Utf8(Some(v)) | ScalarValue::LargeUtf8(Some(v)) => GeobufValue::String(v),
+        ScalarValue::Boolean(Some(v)) => GeobufValue::Bool(v),
+        ScalarValue::Float32(Some(v)) => GeobufValue::Double(v as f64),
+        ScalarValue::Float64(Some(v)) => GeobufValue::Double(v),
+        ScalarValue::Int8(Some(v)) => GeobufValue::Int(v as i64),
+        ScalarValue::Int16(Some(v)) => GeobufValue::Int(v as i64),
+        ScalarValue::Int32(Some(v)) => GeobufValue::Int(v as i64),
+        ScalarValue::Int64(Some(v)) => GeobufValue::Int(v),
+        ScalarValue::UInt8(Some(v)) => GeobufValue::Int(v as i64),
+        ScalarValue::UInt16(Some(v)) => GeobufValue::Int(v as i64),
+        ScalarValue::UInt32(Some(v)) => GeobufValue::Int(v as i64),
+        ScalarValue::UInt64(Some(v)) => match i64::try_from(v) {
+            Ok(v) => GeobufValue::Int(v),
+            Err(_) => GeobufValue::Double(v as f64),
+        },
+        value => GeobufValue::String(value.to_string()),
+    };
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{AsGeobufUdaf, AsTextUdf, FromGeobufUdf, GeomFromTextUdf};
+    use crate::geo::geobuf::{decode_geobuf, GeobufData, GeobufValue};
+    use arrow::util::pretty::pretty_format_batches;
+    use arrow_array::cast::AsArray;
+    use datafusion::logical_expr::{AggregateUDF, ScalarUDF};
+    use datafusion::prelude::SessionContext;
+
+    #[tokio::test]
+    async fn geobuf_round_trip() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(FromGeobufUdf::new()));
+        ctx.register_udaf(AggregateUDF::from(AsGeobufUdaf::new()));
+        ctx.sql(
+            "create view cities as select * from (values \
+            ('POINT(1 2)', 'a', 10), ('LINESTRING(0 0,1.5 1)', 'b', null)) as t(wkt, name, pop)",
+        )
+        .await
+        .unwrap();
+
+        let df = ctx
+            .sql(
+                "select ST_AsText(ST_FromGeobuf(geobuf)) as geom from \
+                (select st_asgeobuf(ST_GeomFromText(wkt), 'name', name, 'pop', pop) as geobuf \
+                from cities)",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+------------------------------------------------------+
+| geom                                                 |
++------------------------------------------------------+
+| GEOMETRYCOLLECTION(POINT(1 2),LINESTRING(0 0,1.5 1)) |
++------------------------------------------------------+"
+        );
+
+        let df = ctx
+            .sql("select st_asgeobuf(ST_GeomFromText(wkt), 'name', name, 'pop', pop) from cities")
+            .await
+            .unwrap();
+        let batches = df.collect().await.unwrap();
+        let geobuf = batches[0].column(0).as_binary::<i32>().value(0);
+        let GeobufData::FeatureCollection(features) = decode_geobuf(geobuf).unwrap() else {
+            panic!("geobuf should be a feature collection");
+        };
+        assert_eq!(features.len(), 2);
+        assert_eq!(
+            features[0].properties,
+            vec![
+                ("name".to_string(), GeobufValue::String("a".to_string())),
+                ("pop".to_string(), GeobufValue::Int(10)),
+            ]
+        );
+        assert_eq!(
+            features[1].properties,
+            vec![("name".to_string(), GeobufValue::String("b".to_string()))]
+        );
+    }
+}
@@ -1,14 +1,21 @@
+use crate::function::args::geometry_args;
+use crate::geo::format::{format_numbers, round_numbers};
 use crate::geo::GeometryArray;
 use crate::DFResult;
 use arrow_array::cast::AsArray;
-use arrow_array::{GenericBinaryArray, LargeStringArray, OffsetSizeTrait, StringArray};
+use arrow_array::types::Int64Type;
+use arrow_array::{
+    Array, GenericBinaryArray, Int64Array, LargeStringArray, OffsetSizeTrait, StringArray,
+};
 use arrow_schema::DataType;
-use datafusion_common::{internal_datafusion_err, DataFusionError};
+use datafusion_common::{exec_err, internal_datafusion_err, DataFusionError};
 use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, TypeSignature, Volatility};
 use geozero::ToJson;
 use std::any::Any;
 use std::sync::Arc;
 
+/// Returns the GeoJSON geometry object of a geometry, `ST_AsGeoJSON(geom, maxdecimaldigits)`
+/// rounds the coordinates to at most the given number of decimals.
 #[derive(Debug)]
 pub struct AsGeoJsonUdf {
     signature: Signature,
@@ -17,14 +24,13 @@ pub struct AsGeoJsonUdf {
 
 impl AsGeoJsonUdf {
     pub fn new() -> Self {
+        let mut signatures = vec![];
+        for geometry_type in [DataType::Binary, DataType::LargeBinary] {
+            signatures.push(TypeSignature::Exact(vec![geometry_type.clone()]));
+            signatures.push(TypeSignature::Exact(vec![geometry_type, DataType::Int64]));
+        }
         Self {
-            signature: Signature::one_of(
-                vec![
-                    TypeSignature::Exact(vec![DataType::Binary]),
-                    TypeSignature::Exact(vec![DataType::LargeBinary]),
-                ],
-                Volatility::Immutable,
-            ),
+            signature: Signature::one_of(signatures, Volatility::Immutable),
             aliases: vec!["st_asgeojson".to_string()],
         }
     }
@@ -52,26 +58,16 @@ impl ScalarUDFImpl for AsGeoJsonUdf {
     }
 
     fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
-        let arr = args[0].clone().into_array(1)?;
-        match args[0].data_type() {
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let decimals = arrays.get(1).map(|arr| arr.as_primitive::<Int64Type>());
+        let arr = &arrays[0];
+        match arr.data_type() {
             DataType::Binary => {
-                let wkb_arr = arr.as_binary::<i32>();
-
-                let mut json_vec = vec![];
-                for i in 0..wkb_arr.geom_len() {
-                    json_vec.push(to_geojson::<i32>(wkb_arr, i)?);
-                }
-
+                let json_vec = to_geojson_vec::<i32>(arr.as_binary::<i32>(), decimals)?;
                 Ok(ColumnarValue::Array(Arc::new(StringArray::from(json_vec))))
             }
             DataType::LargeBinary => {
-                let wkb_arr = arr.as_binary::<i64>();
-
-                let mut json_vec = vec![];
-                for i in 0..wkb_arr.geom_len() {
-                    json_vec.push(to_geojson::<i64>(wkb_arr, i)?);
-                }
-
+                let json_vec = to_geojson_vec::<i64>(arr.as_binary::<i64>(), decimals)?;
                 Ok(ColumnarValue::Array(Arc::new(LargeStringArray::from(
                     json_vec,
                 ))))
@@ -85,6 +81,37 @@ impl ScalarUDFImpl for AsGeoJsonUdf {
     }
 }
 
+fn to_geojson_vec<O: OffsetSizeTrait>(
+    wkb_arr: &GenericBinaryArray<O>,
+    decimals: Option<&Int64Array>,
+) -> DFResult<Vec<Option<String>>> {
+    let mut json_vec = vec![];
+    for i in 0..wkb_arr.geom_len() {
+        let decimals = match decimals {
+            None => None,
+            Some(decimals) if decimals.is_null(i) => {
+                json_vec.push(None);
+                continue;
+            }
+            Some(decimals) => match u32::try_from(decimals.value(i)) {
+                Ok(decimals) => Some(decimals),
+                Err(_) => {
+                    return exec_err!(
+                        "ST_AsGeoJSON: maxdecimaldigits should not be negative, got {}",
+                        decimals.value(i)
+                    )
+                }
+            },
+        };
+        let json = to_geojson::<O>(wkb_arr, i)?;
+        json_vec.push(match decimals {
+            Some(decimals) => json.map(|json| round_numbers(&json, decimals)),
+            None => json,
+        });
+    }
+    Ok(json_vec)
+}
+
 fn to_geojson<O: OffsetSizeTrait>(
     wkb_arr: &GenericBinaryArray<O>,
     geom_index: usize,
@@ -100,10 +127,9 @@ fn to_geojson<O: OffsetSizeTrait>(
         }
     };
     let json = match geom {
-        Some(geom) => Some(
-            geom.to_json()
-                .map_err(|_| internal_datafusion_err!("Failed to convert geometry to geo json"))?,
-        ),
+        Some(geom) => Some(format_numbers(geom.to_json().map_err(|_| {
+            internal_datafusion_err!("Failed to convert geometry to geo json")
+        })?)),
         None => None,
     };
     Ok(json)
@@ -142,4 +168,29 @@ mod tests {
 +-------------------------------------------------------------------+"
         );
     }
+
+    #[tokio::test]
+    async fn as_geojson_with_precision() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsGeoJsonUdf::new()));
+        let df = ctx
+            .sql(
+                "select ST_AsGeoJSON(ST_GeomFromText('POINT(-71.064544 42.28787)'), 3) as json, \
+                arrow_typeof(ST_AsGeoJSON(ST_GeomFromText(arrow_cast('POINT(1 2)', 'LargeUtf8')))) \
+                as large_type",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+----------------------------------------------------+------------+
+| json                                               | large_type |
++----------------------------------------------------+------------+
+| {\"type\": \"Point\", \"coordinates\": [-71.065,42.288]} | LargeUtf8  |
++----------------------------------------------------+------------+"
+        );
+    }
 }
@@ -0,0 +1,253 @@
+use crate::config::check_cancelled;
+use crate::function::args::as_geometry_array;
+use crate::geo::mvt::{decode_tile, encode_tile, MvtFeature, MvtLayer, MvtValue};
+use crate::geo::GeometryArray;
+use crate::metrics::record_call;
+use arrow_array::cast::AsArray;
+use arrow_array::ArrayRef;
+use arrow_schema::DataType;
+use datafusion_common::{exec_err, internal_err, DataFusionError, ScalarValue};
+use datafusion_expr::{Accumulator, AggregateUDFImpl, Signature, Volatility};
+use std::any::Any;
+
+/// Aggregates geometries in tile coordinates, e.g. from `ST_AsMVTGeom`, into a mapbox vector tile
+/// of a single layer. The args are the geometry, the layer name, the tile extent and pairs of a
+/// property name and its value, like `st_asmvt(geom, 'places', 4096, 'name', name)`. Null values
+/// are left out, as are geometries which can not be encoded, i.e. nulls, empty geometries and
+/// geometry collections.
+#[derive(Debug)]
+pub struct AsMvtUdaf {
+    signature: Signature,
+}
+
+impl AsMvtUdaf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::variadic_any(Volatility::Immutable),
+        }
+    }
+}
+
+impl AggregateUDFImpl for AsMvtUdaf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        // uadf not support alias
+        "st_asmvt"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        if arg_types.len() < 3 {
+            return exec_err!("st_asmvt takes a geometry, a layer name and an extent");
+        }
+        if !matches!(arg_types[0], DataType::Binary | DataType::LargeBinary) {
+            return exec_err!("The first arg of st_asmvt should be a geometry");
+        }
+        if arg_types.len() % 2 == 0 {
+            return exec_err!("The properties of st_asmvt should be name and value pairs");
+        }
+        Ok(DataType::Binary)
+    }
+
+    fn accumulator(&self, _arg: &DataType) -> datafusion_common::Result<Box<dyn Accumulator>> {
+        Ok(Box::new(MvtAccumulator::new()))
+    }
+
+    fn state_type(&self, _return_type: &DataType) -> datafusion_common::Result<Vec<DataType>> {
+        Ok(vec![DataType::Binary])
+    }
+}
+
+impl Default for AsMvtUdaf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Collects the features of a layer, the state is the tile built so far.
+#[derive(Debug)]
+pub struct MvtAccumulator {
+    layer: Option<MvtLayer>,
+}
+
+impl MvtAccumulator {
+    pub fn new() -> Self {
+        Self { layer: None }
+    }
+
+    fn layer(&mut self, name: String, extent: u32) -> datafusion_common::Result<&mut MvtLayer> {
+        let layer = self.layer.get_or_insert_with(|| MvtLayer {
+            name: name.clone(),
+            extent,
+            features: vec![],
+        });
+        if layer.name != name || layer.extent != extent {
+            return exec_err!(
+                "st_asmvt builds a single layer, got {} with extent {} and {} with extent {}",
+                layer.name,
+                layer.extent,
+                name,
+                extent
+            );
+        }
+        Ok(layer)
+    }
+}
+
+impl Default for MvtAccumulator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Accumulator for MvtAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> datafusion_common::Result<()> {
+        if values.is_empty() {
+            return Ok(());
+        }
+        let wkb_arr = as_geometry_array(&values[0])?;
+        let _recorder = record_call("st_asmvt", wkb_arr.geom_len());
+        for i in 0..wkb_arr.geom_len() {
+            check_cancelled(i)?;
+            let Some(geometry) = wkb_arr.geo_value(i)? else {
+                continue;
+            };
+            let ScalarValue::Utf8(Some(name)) = ScalarValue::try_from_array(&values[1], i)? else {
+                return exec_err!("The layer name of st_asmvt should be a non null string");
+            };
+            let extent = match ScalarValue::try_from_array(&values[2], i)? {
+                ScalarValue::Int64(Some(extent)) if extent > 0 && extent <= u32::MAX as i64 => {
+                    extent as u32
+                }
+                extent => return exec_err!("Invalid st_asmvt extent {}", extent),
+            };
+            let mut properties = vec![];
+            for pair in values[3..].chunks(2) {
+                let [key_arr, value_arr] = pair else {
+                    return internal_err!("Property name without value");
+                };
+                let ScalarValue::Utf8(Some(key)) = ScalarValue::try_from_array(key_arr, i)? else {
+                    return exec_err!("The property name should be a non null string");
+                };
+                if let Some(value) = to_mvt_value(ScalarValue::try_from_array(value_arr, i)?) {
+                    properties.push((key, value));
+                }
+            }
+            self.layer(name, extent)?.features.push(MvtFeature {
+                geometry,
+                properties,
+            });
+        }
+        Ok(())
+    }
+
+    fn evaluate(&mut self) -> datafusion_common::Result<ScalarValue> {
+        let layers = self.layer.iter().cloned().collect::<Vec<_>>();
+        Ok(ScalarValue::Binary(Some(encode_tile(&layers))))
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self)
+            + self.layer.as_ref().map_or(0, |layer| {
+                layer.features.capacity() * std::mem::size_of::<MvtFeature>()
+            })
+    }
+
+    fn state(&mut self) -> datafusion_common::Result<Vec<ScalarValue>> {
+        Ok(vec![self.evaluate()?])
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> datafusion_common::Result<()> {
+        if states.is_empty() {
+            return Ok(());
+        }
+        for tile in states[0].as_binary::<i32>().iter().flatten() {
+            for layer in decode_tile(tile)? {
+                self.layer(layer.name, layer.extent)?
+                    .features
+                    .extend(layer.features);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn to_mvt_value(value: ScalarValue) -> Option<MvtValue> {
+    if value.is_null() {
+        return None;
+    }
+    let value = match value {
+        ScalarValue::Utf8(Some(v)) | ScalarValue::LargeUtf8(Some(v)) => MvtValue::String(v),
+        ScalarValue::Boolean(Some(v)) => MvtValue::Bool(v),
+        ScalarValue::Float32(Some(v)) => MvtValue::Double(v as f64),
+        ScalarValue::Float64(Some(v)) => MvtValue::Double(v),
+        ScalarValue::Int8(Some(v)) => MvtValue::Int(v as i64),
+        ScalarValue::Int16(Some(v)) => MvtValue::Int(v as i64),
+        ScalarValue::Int32(Some(v)) => MvtValue::Int(v as i64),
+        ScalarValue::Int64(Some(v)) => MvtValue::Int(v),
+        ScalarValue::UInt8(Some(v)) => MvtValue::Int(v as i64),
+        ScalarValue::UInt16(Some(v)) => MvtValue::Int(v as i64),
+        ScalarValue::UInt32(Some(v)) => MvtValue::Int(v as i64),
+        ScalarValue::UInt64(Some(v)) => match i64::try_from(v) {
+            Ok(v) => MvtValue::Int(v),
+            Err(_) => MvtValue::Double(v as f64),
+        },
+        value => MvtValue::String(value.to_string()),
+    };
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{AsMvtUdaf, GeomFromTextUdf};
+    use crate::geo::mvt::{decode_tile, MvtValue};
+    use arrow_array::cast::AsArray;
+    use datafusion::logical_expr::{AggregateUDF, ScalarUDF};
+    use datafusion::prelude::SessionContext;
+    use geo::{line_string, point};
+
+    #[tokio::test]
+    async fn as_mvt() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udaf(AggregateUDF::from(AsMvtUdaf::new()));
+        let df = ctx
+            .sql(
+                "select st_asmvt(ST_GeomFromText(wkt), 'places', 4096, 'name', name, 'pop', pop) \
+                from (values ('POINT(1 2)', 'a', 10), ('LINESTRING(0 0,3 4)', 'b', null), \
+                (null, 'c', 1)) as t(wkt, name, pop)",
+            )
+            .await
+            .unwrap();
+        let batches = df.collect().await.unwrap();
+        let tile = batches[0].column(0).as_binary::<i32>().value(0);
+        let layers = decode_tile(tile).unwrap();
+        assert_eq!(layers.len(), 1);
+        assert_eq!(layers[0].name, "places");
+        assert_eq!(layers[0].extent, 4096);
+        let features = &layers[0].features;
+        assert_eq!(features.len(), 2);
+        assert_eq!(features[0].geometry, point!(x: 1., y: 2.).into());
+        assert_eq!(
+            features[0].properties,
+            vec![
+                ("name".to_string(), MvtValue::String("a".to_string())),
+                ("pop".to_string(), MvtValue::Int(10)),
+            ]
+        );
+        assert_eq!(
+            features[1].geometry,
+            line_string![(x: 0., y: 0.), (x: 3., y: 4.)].into()
+        );
+        assert_eq!(
+            features[1].properties,
+            vec![("name".to_string(), MvtValue::String("b".to_string()))]
+        );
+    }
+}
@@ -1,11 +1,17 @@
+use crate::config::default_dialect;
+use crate::function::args::geometry_args;
 use crate::geo::{Box2d, GeometryArray, GeometryArrayBuilder};
 use crate::DFResult;
 use arrow_array::cast::AsArray;
 use arrow_array::{Array, GenericBinaryArray, OffsetSizeTrait, StructArray};
 use arrow_schema::DataType;
+use datafusion_common::{internal_err, DataFusionError, ScalarValue};
 use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, TypeSignature, Volatility};
-use geo::{AffineOps, AffineTransform};
-use geozero::wkb::WkbDialect;
+use geo::{
+    coord, AffineOps, BooleanOps, BoundingRect, GeometryCollection, HasDimensions, Intersects,
+    LineString, MapCoords, MultiLineString, MultiPoint, MultiPolygon, Polygon, Rect,
+    RemoveRepeatedPoints,
+};
 use std::any::Any;
 use std::sync::Arc;
 
@@ -17,14 +23,17 @@ pub struct AsMVTGeomUdf {
 
 impl AsMVTGeomUdf {
     pub fn new() -> Self {
+        let mut type_signatures = vec![];
+        for geom_type in [DataType::Binary, DataType::LargeBinary] {
+            let mut arg_types = vec![geom_type, Box2d::data_type()];
+            type_signatures.push(TypeSignature::Exact(arg_types.clone()));
+            for arg_type in [DataType::Int64, DataType::Int64, DataType::Boolean] {
+                arg_types.push(arg_type);
+                type_signatures.push(TypeSignature::Exact(arg_types.clone()));
+            }
+        }
         Self {
-            signature: Signature::one_of(
-                vec![
-                    TypeSignature::Exact(vec![DataType::Binary, Box2d::data_type()]),
-                    TypeSignature::Exact(vec![DataType::LargeBinary, Box2d::data_type()]),
-                ],
-                Volatility::Immutable,
-            ),
+            signature: Signature::one_of(type_signatures, Volatility::Immutable),
             aliases: vec!["st_asmvtgeom".to_string()],
         }
     }
@@ -48,20 +57,43 @@ impl ScalarUDFImpl for AsMVTGeomUdf {
     }
 
     fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
-        let arr = args[0].clone().into_array(1)?;
-        let arr1 = args[1].clone().into_array(1)?;
-        let box_arr = arr1.as_struct();
-        match args[0].data_type() {
+        let extent = match args.get(2) {
+            None => DEFAULT_EXTENT,
+            Some(ColumnarValue::Scalar(ScalarValue::Int64(Some(extent)))) if *extent > 0 => *extent,
+            _ => return internal_err!("The third arg should be positive int64 scalar"),
+        };
+        let buffer = match args.get(3) {
+            None => DEFAULT_BUFFER,
+            Some(ColumnarValue::Scalar(ScalarValue::Int64(Some(buffer)))) if *buffer >= 0 => {
+                *buffer
+            }
+            _ => return internal_err!("The fourth arg should be non-negative int64 scalar"),
+        };
+        let clip_geom = match args.get(4) {
+            None => true,
+            Some(ColumnarValue::Scalar(ScalarValue::Boolean(Some(clip_geom)))) => *clip_geom,
+            _ => return internal_err!("The fifth arg should be boolean scalar"),
+        };
+        let options = MvtOptions {
+            extent: extent as f64,
+            buffer: buffer as f64,
+            clip_geom,
+        };
+
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let arr = &arrays[0];
+        let box_arr = arrays[1].as_struct();
+        match arr.data_type() {
             DataType::Binary => {
                 let wkb_arr = arr.as_binary::<i32>();
                 Ok(ColumnarValue::Array(Arc::new(as_mvt_geom(
-                    wkb_arr, box_arr,
+                    wkb_arr, box_arr, &options,
                 )?)))
             }
             DataType::LargeBinary => {
                 let wkb_arr = arr.as_binary::<i64>();
                 Ok(ColumnarValue::Array(Arc::new(as_mvt_geom(
-                    wkb_arr, box_arr,
+                    wkb_arr, box_arr, &options,
                 )?)))
             }
             _ => unreachable!(),
@@ -73,34 +105,160 @@ impl ScalarUDFImpl for AsMVTGeomUdf {
     }
 }
 
+const DEFAULT_EXTENT: i64 = 4096;
+const DEFAULT_BUFFER: i64 = 256;
+
+struct MvtOptions {
+    extent: f64,
+    buffer: f64,
+    clip_geom: bool,
+}
+
 fn as_mvt_geom<O: OffsetSizeTrait>(
     wkb_arr: &GenericBinaryArray<O>,
     box_arr: &StructArray,
+    options: &MvtOptions,
 ) -> DFResult<GenericBinaryArray<O>> {
-    let mut builder = GeometryArrayBuilder::<O>::new(WkbDialect::Ewkb, wkb_arr.len());
+    let mut builder = GeometryArrayBuilder::<O>::new(default_dialect(), wkb_arr.len());
     for i in 0..wkb_arr.geom_len() {
         let geom = wkb_arr.geo_value(i)?;
-        let box2d = Box2d::value(box_arr, i)?.unwrap();
-
-        match geom {
-            Some(geom) => {
-                let width = box2d.xmax - box2d.xmin;
-                let height = box2d.ymax - box2d.ymin;
-                let fx = 4096. / width;
-                let fy = -4096. / height;
+        let box2d = Box2d::value(box_arr, i)?;
 
-                let transform =
-                    AffineTransform::new(fx, 0.0, -box2d.xmin * fx, 0.0, fy, -box2d.ymax * fy);
+        match (geom, box2d) {
+            (Some(geom), Some(box2d)) => {
+                let tile = Box2d {
+                    xmin: 0.0,
+                    ymin: 0.0,
+                    xmax: options.extent,
+                    ymax: options.extent,
+                };
+                let transform = box2d.transform_to(&tile, true)?;
 
-                let geom = geom.affine_transform(&transform);
-                builder.append_geo_geometry(&Some(geom))?;
+                let mut geom = Some(geom.affine_transform(&transform));
+                if options.clip_geom {
+                    let clip_rect = Rect::new(
+                        coord! { x: -options.buffer, y: -options.buffer },
+                        coord! { x: options.extent + options.buffer, y: options.extent + options.buffer },
+                    );
+                    geom = geom.and_then(|geom| clip(geom, &clip_rect));
+                }
+                let geom = geom.and_then(snap_to_grid);
+                builder.append_geo_geometry(&geom)?;
             }
-            None => builder.append_null(),
+            _ => builder.append_null(),
         }
     }
     Ok(builder.build())
 }
 
+/// Clips the geometry to the rectangle, returns None if nothing is left.
+fn clip(geom: geo::Geometry, clip_rect: &Rect) -> Option<geo::Geometry> {
+    let rect = geom.bounding_rect()?;
+    if clip_rect.min().x <= rect.min().x
+        && clip_rect.min().y <= rect.min().y
+        && clip_rect.max().x >= rect.max().x
+        && clip_rect.max().y >= rect.max().y
+    {
+        return Some(geom);
+    }
+    let clip_poly = clip_rect.to_polygon();
+    let clipped = match geom {
+        geo::Geometry::Point(p) => clip_rect.intersects(&p).then_some(p.into()),
+        geo::Geometry::MultiPoint(mp) => Some(
+            MultiPoint::new(mp.into_iter().filter(|p| clip_rect.intersects(p)).collect()).into(),
+        ),
+        geo::Geometry::Line(line) => {
+            single_line(clip_poly.clip(&MultiLineString::new(vec![line.into()]), false))
+        }
+        geo::Geometry::LineString(ls) => {
+            single_line(clip_poly.clip(&MultiLineString::new(vec![ls]), false))
+        }
+        geo::Geometry::MultiLineString(mls) => Some(clip_poly.clip(&mls, false).into()),
+        geo::Geometry::Polygon(p) => single_polygon(p.intersection(&clip_poly)),
+        geo::Geometry::Rect(r) => single_polygon(r.to_polygon().intersection(&clip_poly)),
+        geo::Geometry::Triangle(t) => single_polygon(t.to_polygon().intersection(&clip_poly)),
+        geo::Geometry::MultiPolygon(mp) => {
+            Some(mp.intersection(&MultiPolygon::new(vec![clip_poly])).into())
+        }
+        geo::Geometry::GeometryCollection(gc) => Some(
+            GeometryCollection::new_from(
+                gc.into_iter()
+                    .filter_map(|geom| clip(geom, clip_rect))
+                    .collect(),
+            )
+            .into(),
+        ),
+    };
+    clipped.filter(|geom| !geom.is_empty())
+}
+
+fn single_line(mls: MultiLineString) -> Option<geo::Geometry> {
+    match mls.0.len() {
+        0 => None,
+        1 => mls.0.into_iter().next().map(geo::Geometry::LineString),
+        _ => Some(mls.into()),
+    }
+}
+
+fn single_polygon(mp: MultiPolygon) -> Option<geo::Geometry> {
+    match mp.0.len() {
+        0 => None,
+        1 => mp.0.into_iter().next().map(geo::Geometry::Polygon),
+        _ => Some(mp.into()),
+    }
+}
+
+/// Snaps coordinates to the integer grid and drops the parts which become degenerate.
+fn snap_to_grid(geom: geo::Geometry) -> Option<geo::Geometry> {
+    let geom = geom
+        .map_coords(|c| coord! { x: c.x.round(), y: c.y.round() })
+        .remove_repeated_points();
+    match geom {
+        geo::Geometry::Point(_) => Some(geom),
+        geo::Geometry::MultiPoint(mp) => (!mp.0.is_empty()).then_some(mp.into()),
+        geo::Geometry::Line(line) => (line.start != line.end).then_some(line.into()),
+        geo::Geometry::LineString(ls) => valid_line(&ls).then_some(ls.into()),
+        geo::Geometry::MultiLineString(mls) => {
+            let lines = mls.into_iter().filter(valid_line).collect::<Vec<_>>();
+            (!lines.is_empty()).then_some(MultiLineString::new(lines).into())
+        }
+        geo::Geometry::Polygon(p) => valid_polygon(p).map(geo::Geometry::Polygon),
+        geo::Geometry::Rect(r) => valid_polygon(r.to_polygon()).map(geo::Geometry::Polygon),
+        geo::Geometry::Triangle(t) => valid_polygon(t.to_polygon()).map(geo::Geometry::Polygon),
+        geo::Geometry::MultiPolygon(mp) => {
+            let polygons = mp.into_iter().filter_map(valid_polygon).collect::<Vec<_>>();
+            (!polygons.is_empty()).then_some(MultiPolygon::new(polygons).into())
+        }
+        geo::Geometry::GeometryCollection(gc) => {
+            let geoms = gc.into_iter().filter_map(snap_to_grid).collect::<Vec<_>>();
+            (!geoms.is_empty()).then_some(GeometryCollection::new_from(geoms).into())
+        }
+    }
+}
+
+fn valid_line(ls: &LineString) -> bool {
+    ls.0.len() >= 2
+}
+
+fn valid_ring(ring: &LineString) -> bool {
+    // a ring needs at least three points which are not collinear
+    let coords = &ring.0;
+    coords.len() >= 4
+        && coords.iter().any(|c| {
+            let (a, b) = (coords[0], coords[1]);
+            (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x) != 0.0
+        })
+}
+
+fn valid_polygon(polygon: Polygon) -> Option<Polygon> {
+    let (exterior, interiors) = polygon.into_inner();
+    if !valid_ring(&exterior) {
+        return None;
+    }
+    let interiors = interiors.into_iter().filter(valid_ring).collect();
+    Some(Polygon::new(exterior, interiors))
+}
+
 impl Default for AsMVTGeomUdf {
     fn default() -> Self {
         Self::new()
@@ -112,9 +270,15 @@ mod tests {
     use crate::function::as_mvt_geom::AsMVTGeomUdf;
     use crate::function::box2d::Box2dUdf;
     use crate::function::{AsTextUdf, GeomFromTextUdf};
+    use crate::geo::{build_box2d_array, Box2d, GeometryArrayBuilder};
     use arrow::util::pretty::pretty_format_batches;
+    use arrow_array::RecordBatch;
+    use arrow_schema::{DataType, Field, Schema};
+    use datafusion::datasource::MemTable;
     use datafusion::logical_expr::ScalarUDF;
     use datafusion::prelude::SessionContext;
+    use geo::point;
+    use std::sync::Arc;
 
     #[tokio::test]
     async fn as_mvt_geom() {
@@ -138,4 +302,97 @@ mod tests {
 +-----------------------------------------------------------------------------------------------------------------------------------------------------+"
         );
     }
+
+    #[tokio::test]
+    async fn as_mvt_geom_clip() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsMVTGeomUdf::new()));
+        ctx.register_udf(ScalarUDF::from(Box2dUdf::new()));
+        let df = ctx
+            .sql("select ST_AsText(Box2D(ST_AsMVTGeom(ST_GeomFromText(wkt), Box2D(ST_GeomFromText('LINESTRING(0 0, 4096 4096)')), 4096, 0))) as clipped, \
+            ST_AsText(Box2D(ST_AsMVTGeom(ST_GeomFromText(wkt), Box2D(ST_GeomFromText('LINESTRING(0 0, 4096 4096)')), 4096, 0, false))) as unclipped, \
+            ST_AsText(Box2D(ST_AsMVTGeom(ST_GeomFromText(wkt), Box2D(ST_GeomFromText('LINESTRING(0 0, 4096 4096)')), 256, 0, true))) as small_extent \
+            from (values ('POLYGON((-10 -10, 100 -10, 100 100, -10 100, -10 -10))')) as t(wkt)")
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+----------------------+------------------------+------------------+
+| clipped              | unclipped              | small_extent     |
++----------------------+------------------------+------------------+
+| BOX(0 3996,100 4096) | BOX(-10 3996,100 4106) | BOX(0 250,6 256) |
++----------------------+------------------------+------------------+"
+        );
+
+        let df = ctx
+            .sql("select ST_AsText(ST_AsMVTGeom(ST_GeomFromText(wkt), Box2D(ST_GeomFromText('LINESTRING(0 0, 4096 4096)')))) as mvt_geom \
+            from (values ('POLYGON((0.1 0.1, 0.2 0.1, 0.2 0.2, 0.1 0.1))'), ('POLYGON((5000 5000, 5100 5000, 5100 5100, 5000 5000))'), ('POINT(10.4 20.6)')) as t(wkt)")
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+----------------+
+| mvt_geom       |
++----------------+
+|                |
+|                |
+| POINT(10 4075) |
++----------------+"
+        );
+    }
+
+    #[tokio::test]
+    async fn as_mvt_geom_null_box() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsMVTGeomUdf::new()));
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("geom", DataType::Binary, true),
+            Field::new("bounds", Box2d::data_type(), true),
+        ]));
+        let geom_builder: GeometryArrayBuilder<i32> =
+            vec![Some(point!(x: 1.0, y: 1.0)), Some(point!(x: 2.0, y: 2.0))]
+                .as_slice()
+                .into();
+        let bounds = build_box2d_array(vec![
+            None,
+            Some(Box2d {
+                xmin: 0.0,
+                ymin: 0.0,
+                xmax: 4096.0,
+                ymax: 4096.0,
+            }),
+        ]);
+        let record = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(geom_builder.build()), Arc::new(bounds)],
+        )
+        .unwrap();
+        let mem_table = MemTable::try_new(schema.clone(), vec![vec![record]]).unwrap();
+        ctx.register_table("geom_table", Arc::new(mem_table))
+            .unwrap();
+
+        let df = ctx
+            .sql("select ST_AsText(ST_AsMVTGeom(geom, bounds)) from geom_table")
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+------------------------------------------------------------+
+| ST_AsText(ST_AsMVTGeom(geom_table.geom,geom_table.bounds)) |
++------------------------------------------------------------+
+|                                                            |
+| POINT(2 4094)                                              |
++------------------------------------------------------------+"
+        );
+    }
 }
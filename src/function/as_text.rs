@@ -1,7 +1,9 @@
-use crate::geo::GeometryArray;
+use crate::function::args::{geometry_args, scalar_if_constant};
+use crate::geo::format::format_numbers;
+use crate::geo::{Box2d, GeometryArray};
 use crate::DFResult;
 use arrow_array::cast::AsArray;
-use arrow_array::{GenericBinaryArray, LargeStringArray, OffsetSizeTrait, StringArray};
+use arrow_array::{Array, GenericBinaryArray, LargeStringArray, OffsetSizeTrait, StringArray};
 use arrow_schema::DataType;
 use datafusion_common::{internal_datafusion_err, DataFusionError};
 use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, TypeSignature, Volatility};
@@ -9,6 +11,7 @@ use geozero::ToWkt;
 use std::any::Any;
 use std::sync::Arc;
 
+/// Returns the wkt of a geometry, or the `BOX(xmin ymin,xmax ymax)` text of a box.
 #[derive(Debug)]
 pub struct AsTextUdf {
     signature: Signature,
@@ -22,6 +25,7 @@ impl AsTextUdf {
                 vec![
                     TypeSignature::Exact(vec![DataType::Binary]),
                     TypeSignature::Exact(vec![DataType::LargeBinary]),
+                    TypeSignature::Exact(vec![Box2d::data_type()]),
                 ],
                 Volatility::Immutable,
             ),
@@ -47,13 +51,15 @@ impl ScalarUDFImpl for AsTextUdf {
         match arg_types[0] {
             DataType::Binary => Ok(DataType::Utf8),
             DataType::LargeBinary => Ok(DataType::LargeUtf8),
+            DataType::Struct(_) => Ok(DataType::Utf8),
             _ => unreachable!(),
         }
     }
 
     fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
-        let arr = args[0].clone().into_array(1)?;
-        match args[0].data_type() {
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let arr = &arrays[0];
+        let value: DFResult<ColumnarValue> = match arr.data_type() {
             DataType::Binary => {
                 let wkb_arr = arr.as_binary::<i32>();
 
@@ -76,8 +82,19 @@ impl ScalarUDFImpl for AsTextUdf {
                     wkt_vec,
                 ))))
             }
+            DataType::Struct(_) => {
+                let box2d_arr = arr.as_struct();
+
+                let mut text_vec = vec![];
+                for i in 0..box2d_arr.len() {
+                    text_vec.push(Box2d::value(box2d_arr, i)?.map(|box2d| box2d_to_text(&box2d)));
+                }
+
+                Ok(ColumnarValue::Array(Arc::new(StringArray::from(text_vec))))
+            }
             _ => unreachable!(),
-        }
+        };
+        scalar_if_constant(args, value?)
     }
 
     fn aliases(&self) -> &[String] {
@@ -100,15 +117,22 @@ fn to_wkt<O: OffsetSizeTrait>(
         }
     };
     let wkt = match geom {
-        Some(geom) => Some(
-            geom.to_wkt()
-                .map_err(|_| internal_datafusion_err!("Failed to convert geometry to wkt"))?,
-        ),
+        Some(geom) => Some(format_numbers(geom.to_wkt().map_err(|_| {
+            internal_datafusion_err!("Failed to convert geometry to wkt")
+        })?)),
         None => None,
     };
     Ok(wkt)
 }
 
+/// Formats a box like PostGIS, `BOX(xmin ymin,xmax ymax)`.
+fn box2d_to_text(box2d: &Box2d) -> String {
+    format_numbers(format!(
+        "BOX({} {},{} {})",
+        box2d.xmin, box2d.ymin, box2d.xmax, box2d.ymax
+    ))
+}
+
 impl Default for AsTextUdf {
     fn default() -> Self {
         Self::new()
@@ -142,4 +166,59 @@ mod tests {
 +----------------------------------------------------------------+"
         );
     }
+
+    #[tokio::test]
+    async fn as_text_postgis_numbers() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
+        let df = ctx
+            .sql(
+                "select ST_AsText(ST_GeomFromText(wkt)) as wkt from (values \
+                ('POINT(0.30000000000000004 100000000000000000000)'), \
+                ('LINESTRING(1.0 -0.0000000000000001,-2.50 123456789012345.6)')) as t(wkt)",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+----------------------------------------+
+| wkt                                    |
++----------------------------------------+
+| POINT(0.3 1e+20)                       |
+| LINESTRING(1 0,-2.5 123456789012345.6) |
++----------------------------------------+"
+        );
+    }
+
+    #[tokio::test]
+    async fn box2d_as_text() {
+        use crate::function::box2d::Box2dUdf;
+
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(Box2dUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
+        let df = ctx
+            .sql(
+                "select ST_AsText(Box2D(ST_GeomFromText(wkt))) as box2d from (values \
+                ('LINESTRING(1 2,3.5 -4)'), ('POINT(0.1 0.2)'), (null)) as t(wkt)",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+----------------------+
+| box2d                |
++----------------------+
+| BOX(1 -4,3.5 2)      |
+| BOX(0.1 0.2,0.1 0.2) |
+|                      |
++----------------------+"
+        );
+    }
 }
@@ -0,0 +1,180 @@
+use crate::function::args::{as_geometry_array, geometry_args};
+use crate::function::geometry_type::geometry_type;
+use arrow_schema::DataType;
+use datafusion_common::{exec_err, DataFusionError, ScalarValue};
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, TypeSignature, Volatility};
+use std::any::Any;
+
+const GEOMETRY_TYPES: [&str; 7] = [
+    "POINT",
+    "LINESTRING",
+    "POLYGON",
+    "MULTIPOINT",
+    "MULTILINESTRING",
+    "MULTIPOLYGON",
+    "GEOMETRYCOLLECTION",
+];
+
+/// Returns the geometry unchanged if it has the expected type, e.g. 'POLYGON' or 'ST_Polygon',
+/// and fails the query otherwise. A true third arg also accepts the multi type of the expected
+/// single type, e.g. a multipolygon for 'POLYGON'. Nulls are passed through.
+#[derive(Debug)]
+pub struct AssertGeometryTypeUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl AssertGeometryTypeUdf {
+    pub fn new() -> Self {
+        let mut type_signatures = vec![];
+        for geom_type in [DataType::Binary, DataType::LargeBinary] {
+            type_signatures.push(TypeSignature::Exact(vec![
+                geom_type.clone(),
+                DataType::Utf8,
+            ]));
+            type_signatures.push(TypeSignature::Exact(vec![
+                geom_type,
+                DataType::Utf8,
+                DataType::Boolean,
+            ]));
+        }
+        Self {
+            signature: Signature::one_of(type_signatures, Volatility::Immutable),
+            aliases: vec!["st_assertgeometrytype".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for AssertGeometryTypeUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_AssertGeometryType"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(arg_types[0].clone())
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let ColumnarValue::Scalar(ScalarValue::Utf8(Some(expected))) = &args[1] else {
+            return exec_err!("The second arg should be utf8 scalar");
+        };
+        let allow_promotion = match args.get(2) {
+            None => false,
+            Some(ColumnarValue::Scalar(ScalarValue::Boolean(Some(allow)))) => *allow,
+            Some(_) => return exec_err!("The third arg should be boolean scalar"),
+        };
+        let upper = expected.to_uppercase();
+        let Some(expected) = GEOMETRY_TYPES
+            .into_iter()
+            .find(|name| upper.strip_prefix("ST_").unwrap_or(&upper) == *name)
+        else {
+            return exec_err!("Unknown geometry type {}", expected);
+        };
+        let promoted = format!("MULTI{}", expected);
+
+        let (arrays, _recorder) = geometry_args(self.name(), &args[..1])?;
+        let wkb_arr = as_geometry_array(&arrays[0])?;
+        for i in 0..wkb_arr.geom_len() {
+            let Some(geom) = wkb_arr.geo_value(i)? else {
+                continue;
+            };
+            let actual = geometry_type(geom);
+            let actual_name = actual.trim_start_matches("ST_").to_uppercase();
+            if actual_name != expected && !(allow_promotion && actual_name == promoted) {
+                return exec_err!(
+                    "Geometry at row {} is a {}, expected {}",
+                    i,
+                    actual,
+                    expected
+                );
+            }
+        }
+        Ok(args[0].clone())
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for AssertGeometryTypeUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{AsTextUdf, AssertGeometryTypeUdf, GeomFromTextUdf};
+    use arrow::util::pretty::pretty_format_batches;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+
+    fn context() -> SessionContext {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AssertGeometryTypeUdf::new()));
+        ctx
+    }
+
+    #[tokio::test]
+    async fn assert_geometry_type() {
+        let ctx = context();
+        let df = ctx
+            .sql(
+                "select ST_AsText(ST_AssertGeometryType(ST_GeomFromText(wkt), 'ST_Polygon')) as polygon \
+                from (values ('POLYGON((0 0,1 0,1 1,0 0))'), (null)) as t(wkt)",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+----------------------------+
+| polygon                    |
++----------------------------+
+| POLYGON((0 0,1 0,1 1,0 0)) |
+|                            |
++----------------------------+"
+        );
+
+        let sql = "select ST_AssertGeometryType(ST_GeomFromText(wkt), 'polygon') \
+            from (values ('POLYGON((0 0,1 0,1 1,0 0))'), ('MULTIPOLYGON(((0 0,1 0,1 1,0 0)))')) as t(wkt)";
+        let err = ctx.sql(sql).await.unwrap().collect().await.unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Geometry at row 1 is a ST_MultiPolygon, expected POLYGON"),
+            "{}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn assert_geometry_type_promotion() {
+        let ctx = context();
+        let sql = "select ST_AssertGeometryType(ST_GeomFromText(wkt), 'POLYGON', true) \
+            from (values ('POLYGON((0 0,1 0,1 1,0 0))'), ('MULTIPOLYGON(((0 0,1 0,1 1,0 0)))')) as t(wkt)";
+        let batches = ctx.sql(sql).await.unwrap().collect().await.unwrap();
+        assert_eq!(batches[0].num_rows(), 2);
+
+        let sql = "select ST_AssertGeometryType(ST_GeomFromText(wkt), 'POLYGON', true) \
+            from (values ('LINESTRING(0 0,1 1)')) as t(wkt)";
+        let err = ctx.sql(sql).await.unwrap().collect().await.unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Geometry at row 0 is a ST_LineString, expected POLYGON"),
+            "{}",
+            err
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use crate::config::default_dialect;
+use crate::function::args::geometry_args;
 use crate::geo::{GeometryArray, GeometryArrayBuilder};
 use crate::DFResult;
 use arrow_array::cast::AsArray;
@@ -6,7 +8,6 @@ use arrow_schema::DataType;
 use datafusion_common::{internal_datafusion_err, DataFusionError};
 use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, TypeSignature, Volatility};
 use geos::Geom;
-use geozero::wkb::WkbDialect;
 use std::any::Any;
 use std::sync::Arc;
 
@@ -49,8 +50,9 @@ impl ScalarUDFImpl for BoundaryUdf {
     }
 
     fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
-        let arr = args[0].clone().into_array(1)?;
-        match args[0].data_type() {
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let arr = &arrays[0];
+        match arr.data_type() {
             DataType::Binary => {
                 let wkb_arr = arr.as_binary::<i32>();
                 build_boundary_arr::<i32>(wkb_arr)
@@ -71,7 +73,7 @@ impl ScalarUDFImpl for BoundaryUdf {
 fn build_boundary_arr<O: OffsetSizeTrait>(
     wkb_arr: &GenericBinaryArray<O>,
 ) -> DFResult<ColumnarValue> {
-    let mut builder = GeometryArrayBuilder::<O>::new(WkbDialect::Ewkb, wkb_arr.geom_len());
+    let mut builder = GeometryArrayBuilder::<O>::new(default_dialect(), wkb_arr.geom_len());
     for i in 0..wkb_arr.geom_len() {
         if let Some(geom) = wkb_arr.geos_value(i)? {
             builder
@@ -1,4 +1,6 @@
+use crate::function::args::{geometry_args, scalar_if_constant};
 use crate::geo::{build_box2d_array, Box2d, GeometryArray};
+use crate::DFResult;
 use arrow_array::cast::AsArray;
 use arrow_array::Array;
 use arrow_schema::DataType;
@@ -44,8 +46,9 @@ impl ScalarUDFImpl for Box2dUdf {
     }
 
     fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
-        let arr = args[0].clone().into_array(1)?;
-        match arr.data_type() {
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let arr = &arrays[0];
+        let value: DFResult<ColumnarValue> = match arr.data_type() {
             DataType::Binary => {
                 let wkb_arr = arr.as_binary::<i32>();
                 let mut box2d_vec: Vec<Option<Box2d>> = vec![];
@@ -73,7 +76,8 @@ impl ScalarUDFImpl for Box2dUdf {
                 Ok(ColumnarValue::Array(Arc::new(arr)))
             }
             _ => unreachable!(),
-        }
+        };
+        scalar_if_constant(args, value?)
     }
 
     fn aliases(&self) -> &[String] {
@@ -90,7 +94,7 @@ impl Default for Box2dUdf {
 #[cfg(test)]
 mod tests {
     use crate::function::box2d::Box2dUdf;
-    use crate::function::GeomFromTextUdf;
+    use crate::function::{AsTextUdf, GeomFromTextUdf};
     use arrow::util::pretty::pretty_format_batches;
     use datafusion::logical_expr::ScalarUDF;
     use datafusion::prelude::SessionContext;
@@ -100,19 +104,20 @@ mod tests {
         let ctx = SessionContext::new();
         ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
         ctx.register_udf(ScalarUDF::from(Box2dUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
         let df = ctx
-            .sql("select Box2D(ST_GeomFromText('LINESTRING(1 2, 3 4, 5 6)'))")
+            .sql("select ST_AsText(Box2D(ST_GeomFromText('LINESTRING(1 2, 3 4, 5 6)'))) as box2d")
             .await
             .unwrap();
         assert_eq!(
             pretty_format_batches(&df.collect().await.unwrap())
                 .unwrap()
                 .to_string(),
-            "+-----------------------------------------------------------+
-| Box2D(ST_GeomFromText(Utf8(\"LINESTRING(1 2, 3 4, 5 6)\"))) |
-+-----------------------------------------------------------+
-| {xmin: 1.0, ymin: 2.0, xmax: 5.0, ymax: 6.0}              |
-+-----------------------------------------------------------+"
+            "+--------------+
+| box2d        |
++--------------+
+| BOX(1 2,5 6) |
++--------------+"
         );
     }
 }
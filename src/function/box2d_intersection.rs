@@ -0,0 +1,104 @@
+use crate::function::args::geometry_args;
+use crate::geo::{combine_box2d_arrays, Box2d};
+use arrow_array::cast::AsArray;
+use arrow_schema::DataType;
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+use std::any::Any;
+use std::sync::Arc;
+
+/// Returns the box shared by both boxes row by row, null if they don't intersect. Either side can
+/// be a scalar box.
+#[derive(Debug)]
+pub struct Box2dIntersectionUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl Box2dIntersectionUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::exact(
+                vec![Box2d::data_type(), Box2d::data_type()],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["box2d_intersection".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for Box2dIntersectionUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "Box2D_Intersection"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(Box2d::data_type())
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let arr = combine_box2d_arrays(arrays[0].as_struct(), arrays[1].as_struct(), |a, b| {
+            a.intersection(b)
+        })?;
+        Ok(ColumnarValue::Array(Arc::new(arr)))
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for Box2dIntersectionUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::box2d::Box2dUdf;
+    use crate::function::{AsTextUdf, Box2dIntersectionUdf, GeomFromTextUdf};
+    use arrow::util::pretty::pretty_format_batches;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+
+    #[tokio::test]
+    async fn box2d_intersection() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(Box2dUdf::new()));
+        ctx.register_udf(ScalarUDF::from(Box2dIntersectionUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
+        let df = ctx
+            .sql(
+                "select ST_AsText(Box2D_Intersection(Box2D(ST_GeomFromText(a)), Box2D(ST_GeomFromText(b)))) as boxes, \
+                ST_AsText(Box2D_Intersection(Box2D(ST_GeomFromText(a)), Box2D(ST_GeomFromText('LINESTRING(0 0,10 10)')))) as tile \
+                from (values \
+                ('LINESTRING(0 0,1 1)', 'POINT(3 -2)'), \
+                ('LINESTRING(6 6,12 8)', 'LINESTRING(5 5,7 7)'), \
+                ('POINT(20 20)', null)) as t(a, b)",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+--------------+---------------+
+| boxes        | tile          |
++--------------+---------------+
+|              | BOX(0 0,1 1)  |
+| BOX(6 6,7 7) | BOX(6 6,10 8) |
+|              |               |
++--------------+---------------+"
+        );
+    }
+}
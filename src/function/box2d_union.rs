@@ -0,0 +1,103 @@
+use crate::function::args::geometry_args;
+use crate::geo::{combine_box2d_arrays, Box2d};
+use arrow_array::cast::AsArray;
+use arrow_schema::DataType;
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+use std::any::Any;
+use std::sync::Arc;
+
+/// Returns the smallest box containing both boxes, row by row. Either side can be a scalar box.
+#[derive(Debug)]
+pub struct Box2dUnionUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl Box2dUnionUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::exact(
+                vec![Box2d::data_type(), Box2d::data_type()],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["box2d_union".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for Box2dUnionUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "Box2D_Union"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(Box2d::data_type())
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let arr = combine_box2d_arrays(arrays[0].as_struct(), arrays[1].as_struct(), |a, b| {
+            Some(a.union(b))
+        })?;
+        Ok(ColumnarValue::Array(Arc::new(arr)))
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for Box2dUnionUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::box2d::Box2dUdf;
+    use crate::function::{AsTextUdf, Box2dUnionUdf, GeomFromTextUdf};
+    use arrow::util::pretty::pretty_format_batches;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+
+    #[tokio::test]
+    async fn box2d_union() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(Box2dUdf::new()));
+        ctx.register_udf(ScalarUDF::from(Box2dUnionUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
+        let df = ctx
+            .sql(
+                "select ST_AsText(Box2D_Union(Box2D(ST_GeomFromText(a)), Box2D(ST_GeomFromText(b)))) as boxes, \
+                ST_AsText(Box2D_Union(Box2D(ST_GeomFromText(a)), Box2D(ST_GeomFromText('POINT(10 10)')))) as tile \
+                from (values \
+                ('LINESTRING(0 0,1 1)', 'POINT(3 -2)'), \
+                ('POINT(5 5)', 'LINESTRING(6 6,7 8)'), \
+                ('POINT(0 0)', null)) as t(a, b)",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+---------------+----------------+
+| boxes         | tile           |
++---------------+----------------+
+| BOX(0 -2,3 1) | BOX(0 0,10 10) |
+| BOX(5 5,7 8)  | BOX(5 5,10 10) |
+|               | BOX(0 0,10 10) |
++---------------+----------------+"
+        );
+    }
+}
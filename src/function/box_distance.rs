@@ -0,0 +1,154 @@
+use crate::function::args::{as_geometry_array, geometry_args};
+use crate::geo::Box2d;
+use crate::DFResult;
+use arrow_array::cast::AsArray;
+use arrow_array::{ArrayRef, Float64Array};
+use arrow_schema::DataType;
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, TypeSignature, Volatility};
+use geo::BoundingRect;
+use std::any::Any;
+use std::sync::Arc;
+
+/// Returns the minimum distance between the bounding boxes of two geometries, 0 if they overlap.
+/// Either side can be a geometry or a Box2d, box2d args are used as is without decoding.
+#[derive(Debug)]
+pub struct BoxDistanceUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl BoxDistanceUdf {
+    pub fn new() -> Self {
+        let arg_types = [DataType::Binary, DataType::LargeBinary, Box2d::data_type()];
+        let mut type_signatures = vec![];
+        for left in arg_types.iter() {
+            for right in arg_types.iter() {
+                type_signatures.push(TypeSignature::Exact(vec![left.clone(), right.clone()]));
+            }
+        }
+        Self {
+            signature: Signature::one_of(type_signatures, Volatility::Immutable),
+            aliases: vec!["st_boxdistance".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for BoxDistanceUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_BoxDistance"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(DataType::Float64)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let left = box2d_values(&arrays[0])?;
+        let right = box2d_values(&arrays[1])?;
+
+        let distance_vec = left
+            .iter()
+            .zip(right.iter())
+            .map(|(left, right)| match (left, right) {
+                (Some(left), Some(right)) => Some(box_distance(left, right)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        Ok(ColumnarValue::Array(Arc::new(Float64Array::from(
+            distance_vec,
+        ))))
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for BoxDistanceUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn box2d_values(arr: &ArrayRef) -> DFResult<Vec<Option<Box2d>>> {
+    let mut box2d_vec = vec![];
+    if let DataType::Struct(_) = arr.data_type() {
+        let struct_arr = arr.as_struct();
+        for i in 0..struct_arr.len() {
+            box2d_vec.push(Box2d::value(struct_arr, i)?);
+        }
+    } else {
+        let wkb_arr = as_geometry_array(arr)?;
+        for i in 0..wkb_arr.geom_len() {
+            box2d_vec.push(
+                wkb_arr
+                    .geo_value(i)?
+                    .and_then(|geom| geom.bounding_rect().map(Box2d::from)),
+            );
+        }
+    }
+    Ok(box2d_vec)
+}
+
+fn box_distance(left: &Box2d, right: &Box2d) -> f64 {
+    let dx = (left.xmin - right.xmax)
+        .max(right.xmin - left.xmax)
+        .max(0.0);
+    let dy = (left.ymin - right.ymax)
+        .max(right.ymin - left.ymax)
+        .max(0.0);
+    dx.hypot(dy)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::box2d::Box2dUdf;
+    use crate::function::{BoxDistanceUdf, GeomFromTextUdf};
+    use arrow::util::pretty::pretty_format_batches;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+
+    #[tokio::test]
+    async fn box_distance() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(Box2dUdf::new()));
+        ctx.register_udf(ScalarUDF::from(BoxDistanceUdf::new()));
+        let df = ctx
+            .sql(
+                "select ST_BoxDistance(ST_GeomFromText(a), ST_GeomFromText(b)) as geom_geom, \
+            ST_BoxDistance(Box2D(ST_GeomFromText(a)), ST_GeomFromText(b)) as box_geom, \
+            ST_BoxDistance(ST_GeomFromText(a), Box2D(ST_GeomFromText(b))) as geom_box, \
+            ST_BoxDistance(Box2D(ST_GeomFromText(a)), Box2D(ST_GeomFromText(b))) as box_box \
+            from (values \
+            ('LINESTRING(0 0,1 1)', 'POLYGON((4 5,6 5,6 6,4 6,4 5))'), \
+            ('LINESTRING(0 0,1 1)', 'POINT(3 0.5)'), \
+            ('LINESTRING(0 0,2 2)', 'LINESTRING(1 1,3 0)'), \
+            ('POINT(0 0)', null)) as t(a, b)",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+-----------+----------+----------+---------+
+| geom_geom | box_geom | geom_box | box_box |
++-----------+----------+----------+---------+
+| 5.0       | 5.0      | 5.0      | 5.0     |
+| 2.0       | 2.0      | 2.0      | 2.0     |
+| 0.0       | 0.0      | 0.0      | 0.0     |
+|           |          |          |         |
++-----------+----------+----------+---------+"
+        );
+    }
+}
@@ -1,12 +1,14 @@
-use crate::geo::{GeometryArray, GeometryArrayBuilder};
+use crate::config::{check_cancelled, default_dialect};
+use crate::function::args::geometry_args;
+use crate::geo::{check_vertex_limit, GeometryArray, GeometryArrayBuilder};
 use crate::DFResult;
 use arrow_array::cast::AsArray;
-use arrow_array::{GenericBinaryArray, OffsetSizeTrait};
+use arrow_array::types::Float64Type;
+use arrow_array::{Array, ArrayRef, GenericBinaryArray, OffsetSizeTrait};
 use arrow_schema::DataType;
 use datafusion_common::{internal_datafusion_err, internal_err, DataFusionError, ScalarValue};
 use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, TypeSignature, Volatility};
 use geos::Geom;
-use geozero::wkb::WkbDialect;
 use std::any::Any;
 use std::sync::Arc;
 
@@ -57,24 +59,26 @@ impl ScalarUDFImpl for BufferUdf {
     }
 
     fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
-        let arr = args[0].clone().into_array(1)?;
-        let ColumnarValue::Scalar(ScalarValue::Float64(Some(width))) = args[1] else {
-            return internal_err!("The second arg should be f64 scalar");
-        };
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let arr = &arrays[0];
         let ColumnarValue::Scalar(ScalarValue::Int32(Some(quadsegs))) = args[2] else {
             return internal_err!("The third arg should be i32 scalar");
         };
 
-        match args[0].data_type() {
-            DataType::Binary => {
-                let wkb_arr = arr.as_binary::<i32>();
-                build_buffer_arr(wkb_arr, width, quadsegs)
+        match &args[1] {
+            // a scalar width is read once instead of per row
+            ColumnarValue::Scalar(ScalarValue::Float64(width)) => {
+                let width = *width;
+                buffer_array(arr, |_| width, quadsegs)
             }
-            DataType::LargeBinary => {
-                let wkb_arr = arr.as_binary::<i64>();
-                build_buffer_arr(wkb_arr, width, quadsegs)
+            _ => {
+                let widths = arrays[1].as_primitive::<Float64Type>();
+                buffer_array(
+                    arr,
+                    |i| widths.is_valid(i).then(|| widths.value(i)),
+                    quadsegs,
+                )
             }
-            _ => unreachable!(),
         }
     }
 
@@ -83,18 +87,50 @@ impl ScalarUDFImpl for BufferUdf {
     }
 }
 
+fn buffer_array(
+    arr: &ArrayRef,
+    width: impl Fn(usize) -> Option<f64>,
+    quadsegs: i32,
+) -> DFResult<ColumnarValue> {
+    match arr.data_type() {
+        DataType::Binary => build_buffer_arr(arr.as_binary::<i32>(), width, quadsegs),
+        DataType::LargeBinary => build_buffer_arr(arr.as_binary::<i64>(), width, quadsegs),
+        _ => unreachable!(),
+    }
+}
+
+/// Buffers every geometry by the width of its row, rows with a null width are null.
 fn build_buffer_arr<O: OffsetSizeTrait>(
     wkb_arr: &GenericBinaryArray<O>,
-    width: f64,
+    width: impl Fn(usize) -> Option<f64>,
     quadsegs: i32,
 ) -> DFResult<ColumnarValue> {
-    let mut builder = GeometryArrayBuilder::<O>::new(WkbDialect::Ewkb, wkb_arr.geom_len());
+    let mut builder = GeometryArrayBuilder::<O>::new(default_dialect(), wkb_arr.geom_len());
     for i in 0..wkb_arr.geom_len() {
+        check_cancelled(i)?;
+        let Some(width) = width(i) else {
+            builder.append_null();
+            continue;
+        };
         if let Some(geom) = wkb_arr.geos_value(i)? {
-            builder.append_geos_geometry(&Some(
-                geom.buffer(width, quadsegs)
+            // a buffer has at least a full circle of 4 * quadsegs vertices besides the input ones,
+            // checked before the buffer is computed
+            let vertices = geom
+                .get_num_coordinates()
+                .map_err(|e| internal_datafusion_err!("Failed to count coordinates, e: {}", e))?;
+            check_vertex_limit(i, vertices + 4 * quadsegs.max(0) as usize)?;
+            let shortcut = if width == 0.0 {
+                buffer_zero(&geom)?
+            } else {
+                None
+            };
+            let buffer = match shortcut {
+                Some(buffer) => buffer,
+                None => geom
+                    .buffer(width, quadsegs)
                     .map_err(|e| internal_datafusion_err!("Failed to call buffer, e: {}", e))?,
-            ))?;
+            };
+            builder.append_geos_geometry(&Some(buffer))?;
         } else {
             builder.append_null();
         }
@@ -103,6 +139,46 @@ fn build_buffer_arr<O: OffsetSizeTrait>(
     Ok(ColumnarValue::Array(Arc::new(builder.build())))
 }
 
+/// Shortcut of `ST_Buffer(geom, 0)`, the classic validity fix of polygonal geometries, without the
+/// buffer machinery: valid geometries are dissolved with a unary union, invalid ones are repaired
+/// with make valid first. Returns None where the shortcut does not apply, i.e. for non polygonal
+/// geometries, repairs yielding lower dimension parts and geos versions without make valid.
+fn buffer_zero<'a>(geom: &geos::Geometry<'a>) -> DFResult<Option<geos::Geometry<'a>>> {
+    use crate::geo::{geos_capabilities, GeosVersion};
+
+    if !is_polygonal(geom) {
+        return Ok(None);
+    }
+    let valid = if geom.is_valid() {
+        geom.clone()
+    } else {
+        if !geos_capabilities().supports(GeosVersion::new(3, 8, 0)) {
+            return Ok(None);
+        }
+        let repaired = geom
+            .make_valid()
+            .map_err(|e| internal_datafusion_err!("Failed to repair geometry, e: {}", e))?;
+        if !is_polygonal(&repaired) {
+            return Ok(None);
+        }
+        repaired
+    };
+    let mut union = valid
+        .unary_union()
+        .map_err(|e| internal_datafusion_err!("Failed to do unary union, e: {}", e))?;
+    if let Ok(srid) = geom.get_srid() {
+        union.set_srid(srid);
+    }
+    Ok(Some(union))
+}
+
+fn is_polygonal(geom: &geos::Geometry) -> bool {
+    matches!(
+        geom.geometry_type(),
+        geos::GeometryTypes::Polygon | geos::GeometryTypes::MultiPolygon
+    )
+}
+
 impl Default for BufferUdf {
     fn default() -> Self {
         Self::new()
@@ -111,8 +187,8 @@ impl Default for BufferUdf {
 
 #[cfg(test)]
 mod tests {
-    use crate::function::{AsTextUdf, BufferUdf, GeomFromTextUdf};
-    use arrow::util::pretty::pretty_format_batches;
+    use crate::function::{BufferUdf, GeomFromTextUdf};
+    use crate::test_utils::assert_geometry_array_eq;
     use datafusion::logical_expr::ScalarUDF;
     use datafusion::prelude::SessionContext;
 
@@ -120,21 +196,100 @@ mod tests {
     async fn buffer() {
         let ctx = SessionContext::new();
         ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
-        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
         ctx.register_udf(ScalarUDF::from(BufferUdf::new()));
         let df = ctx
-            .sql("SELECT ST_AsText(ST_Buffer(ST_GeomFromText('POINT(100 90)'), 50.0, 2::Integer));")
+            .sql(
+                "select ST_Buffer(ST_GeomFromText('POINT(100 90)'), 50.0, 2::Integer), \
+                ST_GeomFromText('POLYGON((150 90,135.35533905932738 54.64466094067263,100 40,\
+                64.64466094067262 54.64466094067262,50 90,64.64466094067262 125.35533905932738,\
+                100 140,135.35533905932738 125.35533905932738,150 90))')",
+            )
             .await
             .unwrap();
-        assert_eq!(
-            pretty_format_batches(&df.collect().await.unwrap())
+        let batches = df.collect().await.unwrap();
+        assert_geometry_array_eq(batches[0].column(0), batches[0].column(1), 1e-9);
+    }
+
+    #[tokio::test]
+    async fn buffer_zero() {
+        use crate::geo::GeometryArray;
+        use arrow_array::cast::AsArray;
+        use geo::Area;
+        use geos::Geom;
+
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(BufferUdf::new()));
+        let buffer_zero = |wkt: &'static str| {
+            let sql = format!(
+                "select ST_Buffer(ST_GeomFromText('{}'), 0.0, 8::Integer)",
+                wkt
+            );
+            let ctx = &ctx;
+            async move {
+                let batches = ctx.sql(&sql).await.unwrap().collect().await.unwrap();
+                let arr = batches[0].column(0).as_binary::<i32>();
+                (
+                    arr.geos_value(0).unwrap().unwrap(),
+                    arr.geo_value(0).unwrap().unwrap(),
+                )
+            }
+        };
+
+        // valid inputs match the naive buffer
+        for wkt in [
+            "POLYGON((0 0,10 0,10 10,0 10,0 0),(2 2,2 4,4 4,4 2,2 2))",
+            "MULTIPOLYGON(((0 0,1 0,1 1,0 1,0 0)),((2 2,3 2,3 3,2 3,2 2)))",
+        ] {
+            let naive = geos::Geometry::new_from_wkt(wkt)
                 .unwrap()
-                .to_string(),
-            "+-----------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------+
-| ST_AsText(ST_Buffer(ST_GeomFromText(Utf8(\"POINT(100 90)\")),Float64(50),Int64(2)))                                                                                                                               |
-+-----------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------+
-| POLYGON((150 90,135.35533905932738 54.64466094067263,100 40,64.64466094067262 54.64466094067262,50 90,64.64466094067262 125.35533905932738,99.99999999999999 140,135.35533905932738 125.35533905932738,150 90)) |
-+-----------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------+"
-        );
+                .buffer(0.0, 8)
+                .unwrap();
+            let (shortcut, _) = buffer_zero(wkt).await;
+            assert!(shortcut.is_valid());
+            assert_eq!(
+                shortcut.get_num_coordinates().unwrap(),
+                naive.get_num_coordinates().unwrap()
+            );
+            assert_eq!(shortcut.area().unwrap(), naive.area().unwrap());
+        }
+
+        // the repaired bowtie keeps both lobes where the naive buffer drops one
+        let bowtie = "POLYGON((0 0,2 2,2 0,0 2,0 0))";
+        let naive = geos::Geometry::new_from_wkt(bowtie)
+            .unwrap()
+            .buffer(0.0, 8)
+            .unwrap();
+        let (shortcut, shortcut_geo) = buffer_zero(bowtie).await;
+        assert!(naive.is_valid());
+        assert!(shortcut.is_valid());
+        assert_eq!(shortcut_geo.unsigned_area(), 2.0);
+        assert!(naive.area().unwrap() < 2.0);
+    }
+
+    #[tokio::test]
+    async fn buffer_by_column() {
+        use crate::geo::GeometryArray;
+        use arrow_array::cast::AsArray;
+        use geo::BoundingRect;
+
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(BufferUdf::new()));
+        let df = ctx
+            .sql(
+                "select ST_Buffer(ST_GeomFromText('POINT(0 0)'), width, 2::Integer) \
+                from (values (1.0), (2.5), (null)) as t(width)",
+            )
+            .await
+            .unwrap();
+        let batches = df.collect().await.unwrap();
+        let arr = batches[0].column(0).as_binary::<i32>();
+        for (i, width) in [1.0, 2.5].into_iter().enumerate() {
+            let rect = arr.geo_value(i).unwrap().unwrap().bounding_rect().unwrap();
+            assert!((rect.width() - 2.0 * width).abs() < 1e-9, "{:?}", rect);
+            assert!((rect.height() - 2.0 * width).abs() < 1e-9, "{:?}", rect);
+        }
+        assert!(arr.geo_value(2).unwrap().is_none());
     }
 }
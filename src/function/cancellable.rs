@@ -0,0 +1,129 @@
+use crate::config::{with_cancellation, CancellationFlag};
+use arrow_array::ArrayRef;
+use arrow_schema::DataType;
+use datafusion_common::ScalarValue;
+use datafusion_expr::{
+    Accumulator, AggregateUDF, AggregateUDFImpl, ColumnarValue, ScalarUDF, ScalarUDFImpl, Signature,
+};
+use std::any::Any;
+use std::sync::Arc;
+
+/// Runs a scalar function with the cancellation flag of a session, the expensive functions fail
+/// with "query cancelled" once the flag is set. [`register_all`](crate::function::register_all)
+/// wraps the scalar functions when the session config carries a [`CancellationFlag`] extension.
+#[derive(Debug)]
+pub struct CancellableUdf {
+    inner: ScalarUDF,
+    flag: Arc<CancellationFlag>,
+}
+
+impl CancellableUdf {
+    pub fn new(inner: ScalarUDF, flag: Arc<CancellationFlag>) -> Self {
+        Self { inner, flag }
+    }
+}
+
+impl ScalarUDFImpl for CancellableUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn signature(&self) -> &Signature {
+        self.inner.signature()
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        self.inner.return_type(arg_types)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        with_cancellation(Some(self.flag.clone()), || self.inner.invoke(args))
+    }
+
+    fn aliases(&self) -> &[String] {
+        self.inner.aliases()
+    }
+}
+
+/// Runs the accumulators of an aggregate function with the cancellation flag of a session, like
+/// [`CancellableUdf`] for the scalar functions.
+#[derive(Debug)]
+pub struct CancellableUdaf {
+    inner: AggregateUDF,
+    flag: Arc<CancellationFlag>,
+}
+
+impl CancellableUdaf {
+    pub fn new(inner: AggregateUDF, flag: Arc<CancellationFlag>) -> Self {
+        Self { inner, flag }
+    }
+}
+
+impl AggregateUDFImpl for CancellableUdaf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn signature(&self) -> &Signature {
+        self.inner.signature()
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        self.inner.return_type(arg_types)
+    }
+
+    fn accumulator(&self, arg: &DataType) -> datafusion_common::Result<Box<dyn Accumulator>> {
+        Ok(Box::new(CancellableAccumulator {
+            inner: self.inner.accumulator(arg)?,
+            flag: self.flag.clone(),
+        }))
+    }
+
+    fn state_type(&self, return_type: &DataType) -> datafusion_common::Result<Vec<DataType>> {
+        self.inner.state_type(return_type)
+    }
+}
+
+#[derive(Debug)]
+struct CancellableAccumulator {
+    inner: Box<dyn Accumulator>,
+    flag: Arc<CancellationFlag>,
+}
+
+impl Accumulator for CancellableAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> datafusion_common::Result<()> {
+        with_cancellation(Some(self.flag.clone()), || self.inner.update_batch(values))
+    }
+
+    fn evaluate(&mut self) -> datafusion_common::Result<ScalarValue> {
+        with_cancellation(Some(self.flag.clone()), || self.inner.evaluate())
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self) + self.inner.size()
+    }
+
+    fn state(&mut self) -> datafusion_common::Result<Vec<ScalarValue>> {
+        with_cancellation(Some(self.flag.clone()), || self.inner.state())
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> datafusion_common::Result<()> {
+        with_cancellation(Some(self.flag.clone()), || self.inner.merge_batch(states))
+    }
+
+    fn retract_batch(&mut self, values: &[ArrayRef]) -> datafusion_common::Result<()> {
+        with_cancellation(Some(self.flag.clone()), || self.inner.retract_batch(values))
+    }
+
+    fn supports_retract_batch(&self) -> bool {
+        self.inner.supports_retract_batch()
+    }
+}
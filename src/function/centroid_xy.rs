@@ -0,0 +1,139 @@
+use crate::function::args::geometry_args;
+use crate::geo::{build_f64_struct_array, GeometryArray};
+use crate::DFResult;
+use arrow_array::cast::AsArray;
+use arrow_array::{GenericBinaryArray, OffsetSizeTrait};
+use arrow_schema::{DataType, Field};
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+use geo::Centroid;
+use std::any::Any;
+use std::sync::Arc;
+
+/// Returns the centroid of a geometry as a struct of x and y, decoding the geometry only once.
+#[derive(Debug)]
+pub struct CentroidXYUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl CentroidXYUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::uniform(
+                1,
+                vec![DataType::Binary, DataType::LargeBinary],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_centroidxy".to_string()],
+        }
+    }
+
+    pub fn fields() -> Vec<Field> {
+        vec![
+            Field::new("x", DataType::Float64, false),
+            Field::new("y", DataType::Float64, false),
+        ]
+    }
+}
+
+impl ScalarUDFImpl for CentroidXYUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_CentroidXY"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(DataType::Struct(Self::fields().into()))
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let arr = &arrays[0];
+        match arr.data_type() {
+            DataType::Binary => centroid_xy::<i32>(arr.as_binary::<i32>()),
+            DataType::LargeBinary => centroid_xy::<i64>(arr.as_binary::<i64>()),
+            _ => unreachable!(),
+        }
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for CentroidXYUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn centroid_xy<O: OffsetSizeTrait>(wkb_arr: &GenericBinaryArray<O>) -> DFResult<ColumnarValue> {
+    let mut xy_vec = vec![];
+    for i in 0..wkb_arr.geom_len() {
+        xy_vec.push(
+            wkb_arr
+                .geo_value(i)?
+                .and_then(|geom| geom.centroid())
+                .map(|p| [p.x(), p.y()]),
+        );
+    }
+    let arr = build_f64_struct_array(CentroidXYUdf::fields(), &xy_vec);
+    Ok(ColumnarValue::Array(Arc::new(arr)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{CentroidXYUdf, GeomFromTextUdf};
+    use arrow::util::pretty::pretty_format_batches;
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::Float64Type;
+    use arrow_array::Array;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+
+    #[tokio::test]
+    async fn centroid_xy() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(CentroidXYUdf::new()));
+        let df = ctx
+            .sql("select ST_CentroidXY(ST_GeomFromText(wkt)) as c from (values ('POLYGON((0 0,4 0,4 2,0 2,0 0))'), (null), ('LINESTRING(0 0,2 2)')) as t(wkt)")
+            .await
+            .unwrap();
+        let batches = df.collect().await.unwrap();
+        assert_eq!(
+            pretty_format_batches(&batches).unwrap().to_string(),
+            "+------------------+
+| c                |
++------------------+
+| {x: 2.0, y: 1.0} |
+|                  |
+| {x: 1.0, y: 1.0} |
++------------------+"
+        );
+
+        let arr = batches[0].column(0).as_struct();
+        assert!(arr.is_valid(0));
+        assert!(arr.is_null(1));
+        assert!(arr.is_valid(2));
+        let x = arr
+            .column_by_name("x")
+            .unwrap()
+            .as_primitive::<Float64Type>();
+        let y = arr
+            .column_by_name("y")
+            .unwrap()
+            .as_primitive::<Float64Type>();
+        assert_eq!(x.value(0), 2.0);
+        assert_eq!(y.value(0), 1.0);
+        assert_eq!(x.value(2), 1.0);
+        assert_eq!(y.value(2), 1.0);
+    }
+}
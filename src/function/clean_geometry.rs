@@ -0,0 +1,255 @@
+use crate::config::default_dialect;
+use crate::function::args::geometry_args;
+use crate::geo::dialect::decode_srid;
+use crate::geo::{GeometryArray, GeometryArrayBuilder};
+use crate::DFResult;
+use arrow_array::cast::AsArray;
+use arrow_array::{GenericBinaryArray, OffsetSizeTrait};
+use arrow_schema::DataType;
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+use geo::RemoveRepeatedPoints;
+use std::any::Any;
+use std::sync::Arc;
+
+/// Cleans a geometry in one call: invalid geometries are repaired with make valid (with geos 3.8
+/// or newer), consecutive repeated points are removed and collections are homogenized to the
+/// simplest type holding their parts.
+#[derive(Debug)]
+pub struct CleanGeometryUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl CleanGeometryUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::uniform(
+                1,
+                vec![DataType::Binary, DataType::LargeBinary],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_cleangeometry".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for CleanGeometryUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_CleanGeometry"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(arg_types[0].clone())
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let arr = &arrays[0];
+        match arr.data_type() {
+            DataType::Binary => clean_geometry::<i32>(arr.as_binary::<i32>()),
+            DataType::LargeBinary => clean_geometry::<i64>(arr.as_binary::<i64>()),
+            _ => unreachable!(),
+        }
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for CleanGeometryUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn clean_geometry<O: OffsetSizeTrait>(wkb_arr: &GenericBinaryArray<O>) -> DFResult<ColumnarValue> {
+    let mut builder = GeometryArrayBuilder::<O>::new(default_dialect(), wkb_arr.geom_len());
+    for i in 0..wkb_arr.geom_len() {
+        let Some(wkb) = wkb_arr.wkb(i) else {
+            builder.append_null();
+            continue;
+        };
+        let srid = decode_srid(wkb)?;
+        let geom =
+            valid_geo_value(wkb_arr, i)?.map(|geom| homogenize(geom.remove_repeated_points()));
+        builder.append_geo_geometry_with_srid(&geom, srid)?;
+    }
+    Ok(ColumnarValue::Array(Arc::new(builder.build())))
+}
+
+/// Reads the geometry at the given row, repaired with make valid if it is invalid. Geometries are
+/// read as is with geos versions before 3.8.
+#[cfg(feature = "geos")]
+fn valid_geo_value<O: OffsetSizeTrait>(
+    wkb_arr: &GenericBinaryArray<O>,
+    geom_index: usize,
+) -> DFResult<Option<geo::Geometry>> {
+    use crate::geo::{geos_capabilities, GeosVersion};
+    use datafusion_common::{internal_datafusion_err, DataFusionError};
+    use geos::Geom;
+    use geozero::ToGeo;
+
+    let Some(geom) = wkb_arr.geos_value(geom_index)? else {
+        return Ok(None);
+    };
+    if geom.is_valid() || !geos_capabilities().supports(GeosVersion::new(3, 8, 0)) {
+        return wkb_arr.geo_value(geom_index);
+    }
+    let repaired = geom
+        .make_valid()
+        .map_err(|e| internal_datafusion_err!("Failed to repair geometry, e: {}", e))?;
+    let repaired = repaired
+        .to_geo()
+        .map_err(|e| internal_datafusion_err!("Failed to convert geometry, e: {}", e))?;
+    Ok(Some(repaired))
+}
+
+/// Reads the geometry at the given row, without geos it cannot be repaired.
+#[cfg(not(feature = "geos"))]
+fn valid_geo_value<O: OffsetSizeTrait>(
+    wkb_arr: &GenericBinaryArray<O>,
+    geom_index: usize,
+) -> DFResult<Option<geo::Geometry>> {
+    wkb_arr.geo_value(geom_index)
+}
+
+/// Flattens nested collections and returns the simplest geometry holding the parts: a single part
+/// as itself, parts of one type as the multi geometry of that type and parts of mixed types as a
+/// collection of one geometry per type. Geometries without parts are kept as is.
+fn homogenize(geom: geo::Geometry) -> geo::Geometry {
+    if matches!(
+        geom,
+        geo::Geometry::Point(_) | geo::Geometry::LineString(_) | geo::Geometry::Polygon(_)
+    ) {
+        return geom;
+    }
+    let mut points = vec![];
+    let mut line_strings = vec![];
+    let mut polygons = vec![];
+    collect_parts(&geom, &mut points, &mut line_strings, &mut polygons);
+
+    let mut groups: Vec<geo::Geometry> = vec![];
+    if !points.is_empty() {
+        groups.push(match points.len() {
+            1 => points.remove(0).into(),
+            _ => geo::MultiPoint::new(points).into(),
+        });
+    }
+    if !line_strings.is_empty() {
+        groups.push(match line_strings.len() {
+            1 => line_strings.remove(0).into(),
+            _ => geo::MultiLineString::new(line_strings).into(),
+        });
+    }
+    if !polygons.is_empty() {
+        groups.push(match polygons.len() {
+            1 => polygons.remove(0).into(),
+            _ => geo::MultiPolygon::new(polygons).into(),
+        });
+    }
+    match groups.len() {
+        0 => geom,
+        1 => groups.remove(0),
+        _ => geo::GeometryCollection::new_from(groups).into(),
+    }
+}
+
+fn collect_parts(
+    geom: &geo::Geometry,
+    points: &mut Vec<geo::Point>,
+    line_strings: &mut Vec<geo::LineString>,
+    polygons: &mut Vec<geo::Polygon>,
+) {
+    match geom {
+        geo::Geometry::Point(point) => points.push(*point),
+        geo::Geometry::Line(line) => line_strings.push((*line).into()),
+        geo::Geometry::LineString(line_string) => line_strings.push(line_string.clone()),
+        geo::Geometry::Polygon(polygon) => polygons.push(polygon.clone()),
+        geo::Geometry::MultiPoint(multi_point) => points.extend(multi_point.iter().copied()),
+        geo::Geometry::MultiLineString(multi_line_string) => {
+            line_strings.extend(multi_line_string.iter().cloned())
+        }
+        geo::Geometry::MultiPolygon(multi_polygon) => {
+            polygons.extend(multi_polygon.iter().cloned())
+        }
+        geo::Geometry::GeometryCollection(collection) => {
+            for geom in collection.iter() {
+                collect_parts(geom, points, line_strings, polygons);
+            }
+        }
+        geo::Geometry::Rect(rect) => polygons.push(rect.to_polygon()),
+        geo::Geometry::Triangle(triangle) => polygons.push(triangle.to_polygon()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{AsTextUdf, CleanGeometryUdf, GeomFromTextUdf};
+    use arrow_array::cast::AsArray;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+
+    #[tokio::test]
+    async fn clean_geometry() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(CleanGeometryUdf::new()));
+        for (wkt, expected) in [
+            ("LINESTRING(0 0,0 0,1 1,1 1,2 2)", "LINESTRING(0 0,1 1,2 2)"),
+            (
+                "GEOMETRYCOLLECTION(POINT(1 1),GEOMETRYCOLLECTION(POINT(2 2)))",
+                "MULTIPOINT(1 1,2 2)",
+            ),
+            (
+                "MULTIPOLYGON(((0 0,1 0,1 1,0 0)))",
+                "POLYGON((0 0,1 0,1 1,0 0))",
+            ),
+            (
+                "GEOMETRYCOLLECTION(POINT(1 1),LINESTRING(0 0,1 1),POINT(2 2))",
+                "GEOMETRYCOLLECTION(MULTIPOINT(1 1,2 2),LINESTRING(0 0,1 1))",
+            ),
+        ] {
+            let df = ctx
+                .sql(&format!(
+                    "select ST_AsText(ST_CleanGeometry(ST_GeomFromText('{}')))",
+                    wkt
+                ))
+                .await
+                .unwrap();
+            let batches = df.collect().await.unwrap();
+            assert_eq!(batches[0].column(0).as_string::<i32>().value(0), expected);
+        }
+    }
+
+    #[cfg(feature = "geos")]
+    #[tokio::test]
+    async fn clean_invalid_geometry() {
+        use crate::geo::GeometryArray;
+        use geo::Area;
+        use geos::Geom;
+
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(CleanGeometryUdf::new()));
+        let df = ctx
+            .sql("select ST_CleanGeometry(ST_GeomFromText('POLYGON((0 0,2 2,2 2,2 0,0 2,0 0))'))")
+            .await
+            .unwrap();
+        let batches = df.collect().await.unwrap();
+        let arr = batches[0].column(0).as_binary::<i32>();
+        assert!(arr.geos_value(0).unwrap().unwrap().is_valid());
+        let geom = arr.geo_value(0).unwrap().unwrap();
+        assert!(matches!(geom, geo::Geometry::MultiPolygon(_)));
+        assert_eq!(geom.unsigned_area(), 2.0);
+    }
+}
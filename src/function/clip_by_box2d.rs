@@ -0,0 +1,133 @@
+use crate::config::default_dialect;
+use crate::function::args::geometry_args;
+use crate::geo::dialect::decode_srid;
+use crate::geo::{clip_by_rect, Box2d, GeometryArray, GeometryArrayBuilder};
+use crate::DFResult;
+use arrow_array::cast::AsArray;
+use arrow_array::{GenericBinaryArray, OffsetSizeTrait, StructArray};
+use arrow_schema::DataType;
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, TypeSignature, Volatility};
+use geo::coord;
+use std::any::Any;
+use std::sync::Arc;
+
+/// Clips a geometry by a box2d without a general overlay, the result keeps the geometry srid.
+#[derive(Debug)]
+pub struct ClipByBox2dUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl ClipByBox2dUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                vec![
+                    TypeSignature::Exact(vec![DataType::Binary, Box2d::data_type()]),
+                    TypeSignature::Exact(vec![DataType::LargeBinary, Box2d::data_type()]),
+                ],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_clipbybox2d".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for ClipByBox2dUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_ClipByBox2D"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(arg_types[0].clone())
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let box_arr = arrays[1].as_struct();
+        match arrays[0].data_type() {
+            DataType::Binary => clip_by_box2d::<i32>(arrays[0].as_binary::<i32>(), box_arr),
+            DataType::LargeBinary => clip_by_box2d::<i64>(arrays[0].as_binary::<i64>(), box_arr),
+            _ => unreachable!(),
+        }
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for ClipByBox2dUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub(crate) fn clip_by_box2d<O: OffsetSizeTrait>(
+    wkb_arr: &GenericBinaryArray<O>,
+    box_arr: &StructArray,
+) -> DFResult<ColumnarValue> {
+    let mut builder = GeometryArrayBuilder::<O>::new(default_dialect(), wkb_arr.geom_len());
+    for i in 0..wkb_arr.geom_len() {
+        let (Some(wkb), Some(box2d)) = (wkb_arr.wkb(i), Box2d::value(box_arr, i)?) else {
+            builder.append_null();
+            continue;
+        };
+        let rect = geo::Rect::new(
+            coord! { x: box2d.xmin, y: box2d.ymin },
+            coord! { x: box2d.xmax, y: box2d.ymax },
+        );
+        let geom = wkb_arr.geo_value(i)?.map(|geom| clip_by_rect(&geom, &rect));
+        builder.append_geo_geometry_with_srid(&geom, decode_srid(wkb)?)?;
+    }
+    Ok(ColumnarValue::Array(Arc::new(builder.build())))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::box2d::Box2dUdf;
+    use crate::function::{AsTextUdf, ClipByBox2dUdf, GeomFromTextUdf};
+    use arrow::util::pretty::pretty_format_batches;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+
+    #[tokio::test]
+    async fn clip_by_box2d() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(Box2dUdf::new()));
+        ctx.register_udf(ScalarUDF::from(ClipByBox2dUdf::new()));
+        let df = ctx
+            .sql(
+                "select ST_AsText(ST_ClipByBox2D(ST_GeomFromText(wkt), \
+                Box2D(ST_GeomFromText('LINESTRING(0 0,2 2)')))) as clipped \
+                from (values ('POINT(1 1)'), ('POINT(3 3)'), ('LINESTRING(-1 1,3 1)'), \
+                ('POLYGON((1 1,3 1,3 3,1 3,1 1))'), (null)) as t(wkt)",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+--------------------------------+
+| clipped                        |
++--------------------------------+
+| POINT(1 1)                     |
+| MULTIPOINT EMPTY               |
+| MULTILINESTRING((0 1,2 1))     |
+| POLYGON((1 1,2 1,2 2,1 2,1 1)) |
+|                                |
++--------------------------------+"
+        );
+    }
+}
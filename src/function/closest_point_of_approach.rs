@@ -0,0 +1,317 @@
+use crate::function::args::{as_geometry_array, geometry_args};
+use crate::function::geometry_type::geometry_type;
+use crate::DFResult;
+use arrow_array::cast::AsArray;
+use arrow_array::types::{
+    TimestampMicrosecondType, TimestampMillisecondType, TimestampNanosecondType,
+    TimestampSecondType,
+};
+use arrow_array::{make_array, Array, ArrayRef, Int64Array};
+use arrow_schema::{DataType, TimeUnit};
+use datafusion_common::{exec_err, DataFusionError};
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+use geo::Coord;
+use std::any::Any;
+
+/// Returns the time at which two trajectories are closest, null if their time ranges do not
+/// overlap. A trajectory is a linestring and a list of the timestamps of its vertices, the
+/// positions between the vertices are interpolated linearly.
+#[derive(Debug)]
+pub struct ClosestPointOfApproachUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl ClosestPointOfApproachUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(4, Volatility::Immutable),
+            aliases: vec!["st_closestpointofapproach".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for ClosestPointOfApproachUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_ClosestPointOfApproach"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        trajectory_time_type(self.name(), arg_types)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let time_type = trajectory_time_type(
+            self.name(),
+            &args.iter().map(|arg| arg.data_type()).collect::<Vec<_>>(),
+        )?;
+        let approaches = closest_approaches(self.name(), args)?;
+        let times = approaches
+            .into_iter()
+            .map(|approach| approach.map(|(time, _)| time))
+            .collect::<Int64Array>();
+        let arr = times
+            .to_data()
+            .into_builder()
+            .data_type(time_type)
+            .build()?;
+        Ok(ColumnarValue::Array(make_array(arr)))
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for ClosestPointOfApproachUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Checks the args are `(line, List<Timestamp>, line, List<Timestamp>)` with the same timestamp
+/// type on both sides and returns it.
+pub(crate) fn trajectory_time_type(name: &str, arg_types: &[DataType]) -> DFResult<DataType> {
+    let time_type = |data_type: &DataType| match data_type {
+        DataType::List(field) if matches!(field.data_type(), DataType::Timestamp(_, _)) => {
+            Some(field.data_type().clone())
+        }
+        _ => None,
+    };
+    let geometry =
+        |data_type: &DataType| matches!(data_type, DataType::Binary | DataType::LargeBinary);
+    match (time_type(&arg_types[1]), time_type(&arg_types[3])) {
+        (Some(time_a), Some(time_b))
+            if time_a == time_b && geometry(&arg_types[0]) && geometry(&arg_types[2]) =>
+        {
+            Ok(time_a)
+        }
+        _ => exec_err!(
+            "{} expects a linestring and a list of timestamps of the same type per trajectory",
+            name
+        ),
+    }
+}
+
+/// Time and distance of the closest approach of the trajectories of every row.
+pub(crate) fn closest_approaches(
+    name: &str,
+    args: &[ColumnarValue],
+) -> DFResult<Vec<Option<(i64, f64)>>> {
+    let (arrays, _recorder) = geometry_args(name, args)?;
+    let lines_a = as_geometry_array(&arrays[0])?;
+    let lines_b = as_geometry_array(&arrays[2])?;
+    let times_a = arrays[1].as_list::<i32>();
+    let times_b = arrays[3].as_list::<i32>();
+
+    let mut approaches = vec![];
+    for i in 0..lines_a.geom_len() {
+        let (Some(line_a), Some(line_b)) = (lines_a.geo_value(i)?, lines_b.geo_value(i)?) else {
+            approaches.push(None);
+            continue;
+        };
+        if times_a.is_null(i) || times_b.is_null(i) {
+            approaches.push(None);
+            continue;
+        }
+        let a = Trajectory::try_new(line_a, &times_a.value(i))?;
+        let b = Trajectory::try_new(line_b, &times_b.value(i))?;
+        approaches.push(closest_approach(&a, &b));
+    }
+    Ok(approaches)
+}
+
+struct Trajectory {
+    coords: Vec<Coord>,
+    times: Vec<i64>,
+}
+
+impl Trajectory {
+    fn try_new(geom: geo::Geometry, times: &ArrayRef) -> DFResult<Self> {
+        let geo::Geometry::LineString(line) = geom else {
+            return exec_err!(
+                "A trajectory should be a linestring, got {}",
+                geometry_type(geom)
+            );
+        };
+        if times.null_count() > 0 {
+            return exec_err!("The timestamps of a trajectory should not be null");
+        }
+        let times = match times.data_type() {
+            DataType::Timestamp(TimeUnit::Second, _) => times
+                .as_primitive::<TimestampSecondType>()
+                .values()
+                .to_vec(),
+            DataType::Timestamp(TimeUnit::Millisecond, _) => times
+                .as_primitive::<TimestampMillisecondType>()
+                .values()
+                .to_vec(),
+            DataType::Timestamp(TimeUnit::Microsecond, _) => times
+                .as_primitive::<TimestampMicrosecondType>()
+                .values()
+                .to_vec(),
+            DataType::Timestamp(TimeUnit::Nanosecond, _) => times
+                .as_primitive::<TimestampNanosecondType>()
+                .values()
+                .to_vec(),
+            data_type => return exec_err!("Unsupported trajectory time type {}", data_type),
+        };
+        if line.0.len() != times.len() {
+            return exec_err!(
+                "A trajectory has {} points but {} timestamps",
+                line.0.len(),
+                times.len()
+            );
+        }
+        if times.is_empty() {
+            return exec_err!("A trajectory should not be empty");
+        }
+        if times.windows(2).any(|w| w[0] >= w[1]) {
+            return exec_err!("The timestamps of a trajectory should be increasing");
+        }
+        Ok(Self {
+            coords: line.0,
+            times,
+        })
+    }
+
+    fn position(&self, time: i64) -> Coord {
+        let i = self.times.partition_point(|t| *t <= time).max(1) - 1;
+        if i + 1 >= self.times.len() {
+            return self.coords[i];
+        }
+        let fraction = (time - self.times[i]) as f64 / (self.times[i + 1] - self.times[i]) as f64;
+        self.coords[i] + (self.coords[i + 1] - self.coords[i]) * fraction
+    }
+}
+
+/// Both trajectories move linearly between the timestamps of either, so the distance is
+/// minimized interval by interval on the shared time range. Ties keep the earliest time.
+fn closest_approach(a: &Trajectory, b: &Trajectory) -> Option<(i64, f64)> {
+    let start = a.times[0].max(b.times[0]);
+    let end = a.times[a.times.len() - 1].min(b.times[b.times.len() - 1]);
+    if start > end {
+        return None;
+    }
+    let mut breaks = a
+        .times
+        .iter()
+        .chain(b.times.iter())
+        .copied()
+        .filter(|t| *t > start && *t < end)
+        .collect::<Vec<_>>();
+    breaks.push(start);
+    breaks.push(end);
+    breaks.sort_unstable();
+    breaks.dedup();
+
+    let distance_at = |time: i64| {
+        let delta = b.position(time) - a.position(time);
+        delta.x.hypot(delta.y)
+    };
+    let mut closest = (start, distance_at(start));
+    for interval in breaks.windows(2) {
+        let (t0, t1) = (interval[0], interval[1]);
+        let p = b.position(t0) - a.position(t0);
+        let v = (b.position(t1) - a.position(t1)) - p;
+        let vv = v.x * v.x + v.y * v.y;
+        let s = if vv > 0.0 {
+            (-(p.x * v.x + p.y * v.y) / vv).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let time = t0 + ((t1 - t0) as f64 * s).round() as i64;
+        let distance = distance_at(time);
+        if distance < closest.1 {
+            closest = (time, distance);
+        }
+    }
+    Some(closest)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{ClosestPointOfApproachUdf, DistanceCPAUdf, GeomFromTextUdf};
+    use arrow::util::pretty::pretty_format_batches;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+
+    fn trajectories(rows: &str) -> String {
+        format!(
+            "select ST_ClosestPointOfApproach(ST_GeomFromText(a), ta, ST_GeomFromText(b), tb) as cpa, \
+            ST_DistanceCPA(ST_GeomFromText(a), ta, ST_GeomFromText(b), tb) as distance \
+            from (values {}) as t(a, ta, b, tb)",
+            rows
+        )
+    }
+
+    fn times(millis: &[i64]) -> String {
+        let times = millis
+            .iter()
+            .map(|t| format!("to_timestamp_millis({})", t))
+            .collect::<Vec<_>>();
+        format!("make_array({})", times.join(", "))
+    }
+
+    #[tokio::test]
+    async fn closest_point_of_approach() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(ClosestPointOfApproachUdf::new()));
+        ctx.register_udf(ScalarUDF::from(DistanceCPAUdf::new()));
+        let rows = [
+            // crossing tracks meet half way
+            format!(
+                "('LINESTRING(0 0,10 0)', {}, 'LINESTRING(5 -5,5 5)', {})",
+                times(&[0, 10000]),
+                times(&[0, 10000])
+            ),
+            // opposite tracks 3 apart, the vertex at 5s is the closest point
+            format!(
+                "('LINESTRING(0 0,5 0,10 0)', {}, 'LINESTRING(10 3,0 3)', {})",
+                times(&[0, 5000, 10000]),
+                times(&[0, 10000])
+            ),
+            // no shared time range
+            format!(
+                "('LINESTRING(0 0,10 0)', {}, 'LINESTRING(5 -5,5 5)', {})",
+                times(&[0, 10000]),
+                times(&[20000, 30000])
+            ),
+        ];
+        let df = ctx.sql(&trajectories(&rows.join(", "))).await.unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+---------------------+----------+
+| cpa                 | distance |
++---------------------+----------+
+| 1970-01-01T00:00:05 | 0.0      |
+| 1970-01-01T00:00:05 | 3.0      |
+|                     |          |
++---------------------+----------+"
+        );
+
+        let mismatched = format!(
+            "('LINESTRING(0 0,10 0)', {}, 'LINESTRING(5 -5,5 5)', {})",
+            times(&[0, 5000, 10000]),
+            times(&[0, 10000])
+        );
+        let result = ctx
+            .sql(&trajectories(&mismatched))
+            .await
+            .unwrap()
+            .collect()
+            .await;
+        assert!(result.is_err());
+    }
+}
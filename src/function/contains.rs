@@ -0,0 +1,148 @@
+use crate::function::args::point_in_polygon_interior;
+use arrow_schema::DataType;
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+use std::any::Any;
+
+/// Whether the second geometry lies in the first one and touches its interior, points on the
+/// boundary are not contained.
+#[derive(Debug)]
+pub struct ContainsUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl ContainsUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::uniform(
+                2,
+                vec![DataType::Binary, DataType::LargeBinary],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_contains".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for ContainsUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_Contains"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(DataType::Boolean)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        if let Some(result) = point_in_polygon_interior(self.name(), args, 0)? {
+            return Ok(result);
+        }
+        #[cfg(feature = "geos")]
+        {
+            use crate::function::args::geos_predicate;
+            use datafusion_common::{internal_datafusion_err, DataFusionError};
+            use geos::Geom;
+            geos_predicate(self.name(), args, |geom0, geom1| {
+                geom0
+                    .contains(geom1)
+                    .map_err(|e| internal_datafusion_err!("Failed to do contains, error: {}", e))
+            })
+        }
+        #[cfg(not(feature = "geos"))]
+        {
+            use crate::function::args::geo_predicate;
+            use geo::Contains;
+            geo_predicate(self.name(), args, |geom0, geom1| geom0.contains(geom1))
+        }
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for ContainsUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{ContainsUdf, GeomFromTextUdf};
+    use arrow::util::pretty::pretty_format_batches;
+    use datafusion::prelude::SessionContext;
+    use datafusion_expr::ScalarUDF;
+
+    #[tokio::test]
+    async fn contains() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(ContainsUdf::new()));
+        let df = ctx
+            .sql(
+                "select id, ST_Contains(ST_GeomFromText(polygon), ST_GeomFromText(point)) as contains \
+                from (values \
+                (1, 'POLYGON((0 0,2 0,2 2,0 2,0 0))', 'POINT(1 1)'), \
+                (2, 'POLYGON((0 0,2 0,2 2,0 2,0 0))', 'POINT(3 1)'), \
+                (3, 'POLYGON((0 0,2 0,2 2,0 2,0 0))', 'POINT(2 1)'), \
+                (4, null, 'POINT(1 1)'), \
+                (5, 'POLYGON((0 0,2 0,2 2,0 2,0 0))', null)) as t(id, polygon, point)",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+----+----------+
+| id | contains |
++----+----------+
+| 1  | true     |
+| 2  | false    |
+| 3  | false    |
+| 4  |          |
+| 5  |          |
++----+----------+"
+        );
+    }
+
+    #[tokio::test]
+    async fn contains_points() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(ContainsUdf::new()));
+        // a polygon literal and a column of points take the point in polygon fast path, the
+        // points on an edge or a vertex are on the boundary
+        let df = ctx
+            .sql(
+                "select id, ST_Contains(ST_GeomFromText('POLYGON((0 0,2 0,2 2,0 2,0 0))'), ST_GeomFromText(point)) as contains \
+                from (values (1, 'POINT(1 1)'), (2, 'POINT(2 1)'), (3, 'POINT(0 0)'), \
+                (4, 'POINT(3 1)'), (5, null)) as t(id, point)",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+----+----------+
+| id | contains |
++----+----------+
+| 1  | true     |
+| 2  | false    |
+| 3  | false    |
+| 4  | false    |
+| 5  |          |
++----+----------+"
+        );
+    }
+}
@@ -0,0 +1,129 @@
+use crate::function::args::geos_prepared_predicate;
+use arrow_schema::DataType;
+use datafusion_common::{internal_datafusion_err, DataFusionError};
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+use std::any::Any;
+
+/// Returns true if the second geometry lies in the interior of the first one without touching
+/// its boundary, e.g. points on the edge of a polygon are not contained properly.
+#[derive(Debug)]
+pub struct ContainsProperlyUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl ContainsProperlyUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::uniform(
+                2,
+                vec![DataType::Binary, DataType::LargeBinary],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_containsproperly".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for ContainsProperlyUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_ContainsProperly"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(DataType::Boolean)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        // geos only offers contains properly on prepared geometries
+        geos_prepared_predicate(self.name(), args, |prepared, geom1| {
+            prepared.contains_properly(geom1).map_err(|e| {
+                internal_datafusion_err!("Failed to do contains properly, error: {}", e)
+            })
+        })
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for ContainsProperlyUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{ContainsProperlyUdf, GeomFromTextUdf};
+    use arrow::util::pretty::pretty_format_batches;
+    use datafusion::prelude::SessionContext;
+    use datafusion_expr::ScalarUDF;
+
+    #[tokio::test]
+    async fn contains_properly() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(ContainsProperlyUdf::new()));
+        let df = ctx
+            .sql(
+                "select ST_ContainsProperly(ST_GeomFromText('POLYGON((0 0,2 0,2 2,0 2,0 0))'), \
+                ST_GeomFromText('POINT(1 1)')) as interior, \
+                ST_ContainsProperly(ST_GeomFromText('POLYGON((0 0,2 0,2 2,0 2,0 0))'), \
+                ST_GeomFromText('POINT(0 1)')) as boundary",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+----------+----------+
+| interior | boundary |
++----------+----------+
+| true     | false    |
++----------+----------+"
+        );
+    }
+
+    #[tokio::test]
+    async fn contains_properly_rows() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(ContainsProperlyUdf::new()));
+        // the polygon literal is prepared once, the polygons of the column once per row
+        let df = ctx
+            .sql(
+                "select id, \
+                ST_ContainsProperly(ST_GeomFromText('POLYGON((0 0,2 0,2 2,0 2,0 0))'), \
+                ST_GeomFromText(point)) as scalar, \
+                ST_ContainsProperly(ST_GeomFromText(polygon), ST_GeomFromText(point)) as array \
+                from (values (1, 'POLYGON((0 0,3 0,3 3,0 3,0 0))', 'POINT(1 1)'), \
+                (2, 'POLYGON((0 0,1 0,1 1,0 1,0 0))', 'POINT(2 1)'), \
+                (3, null, null)) as t(id, polygon, point)",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+----+--------+-------+
+| id | scalar | array |
++----+--------+-------+
+| 1  | true   | true  |
+| 2  | false  | false |
+| 3  |        |       |
++----+--------+-------+"
+        );
+    }
+}
@@ -0,0 +1,264 @@
+use crate::function::coverage_union::CoverageAccumulator;
+use crate::DFResult;
+use arrow_schema::DataType;
+use datafusion_common::{internal_datafusion_err, DataFusionError};
+use datafusion_expr::{Accumulator, AggregateUDFImpl, Signature, Volatility};
+use geo::BoundingRect;
+use geos::Geom;
+use geozero::geos::ToGeos;
+use geozero::ToGeo;
+use rstar::primitives::{GeomWithData, Rectangle};
+use rstar::{RTree, AABB};
+use std::any::Any;
+
+const NAME: &str = "st_coverageinvalidedges";
+
+/// Validates a polygonal coverage, returns the edges of polygons which lie in the interior of
+/// another polygon of the coverage and the edges around the gaps between the polygons, or null
+/// if the coverage is valid. Holes of a single polygon are not gaps.
+#[derive(Debug)]
+pub struct CoverageInvalidEdgesUdaf {
+    signature: Signature,
+}
+
+impl CoverageInvalidEdgesUdaf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::uniform(
+                1,
+                vec![DataType::Binary, DataType::LargeBinary],
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl AggregateUDFImpl for CoverageInvalidEdgesUdaf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        // uadf not support alias
+        NAME
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(DataType::Binary)
+    }
+
+    fn accumulator(&self, _arg: &DataType) -> datafusion_common::Result<Box<dyn Accumulator>> {
+        Ok(Box::new(CoverageAccumulator::new(
+            NAME,
+            coverage_invalid_edges,
+        )))
+    }
+
+    fn state_type(&self, _return_type: &DataType) -> datafusion_common::Result<Vec<DataType>> {
+        Ok(vec![CoverageAccumulator::state_data_type()])
+    }
+}
+
+impl Default for CoverageInvalidEdgesUdaf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+type EnvelopeIndex = RTree<GeomWithData<Rectangle<[f64; 2]>, usize>>;
+
+fn coverage_invalid_edges(geoms: Vec<geos::Geometry>) -> DFResult<Option<geos::Geometry>> {
+    let mut polygons = vec![];
+    for geom in geoms {
+        if !geom.is_empty().map_err(to_err)? {
+            polygons.push(geom);
+        }
+    }
+    let boundaries = polygons
+        .iter()
+        .map(|geom| geom.boundary())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(to_err)?;
+    let envelopes = polygons
+        .iter()
+        .map(envelope)
+        .collect::<DFResult<Vec<_>>>()?;
+    let index: EnvelopeIndex = RTree::bulk_load(
+        envelopes
+            .iter()
+            .enumerate()
+            .map(|(i, envelope)| GeomWithData::new(Rectangle::from_aabb(*envelope), i))
+            .collect(),
+    );
+
+    let mut edges = vec![];
+    for (i, boundary) in boundaries.iter().enumerate() {
+        // only the polygons with an intersecting envelope can overlap
+        for candidate in index.locate_in_envelope_intersecting(&envelopes[i]) {
+            let j = candidate.data;
+            if i == j || !boundary.intersects(&polygons[j]).map_err(to_err)? {
+                continue;
+            }
+            // shared edges are on the other boundary, only those crossing its interior remain
+            let edge = boundary
+                .intersection(&polygons[j])
+                .and_then(|edge| edge.difference(&boundaries[j]))
+                .map_err(to_err)?;
+            if !edge.is_empty().map_err(to_err)? {
+                edges.push(edge);
+            }
+        }
+    }
+    edges.extend(gap_edges(&polygons, &boundaries, &index)?);
+    if edges.is_empty() {
+        return Ok(None);
+    }
+    let collection = geos::Geometry::create_geometry_collection(edges).map_err(to_err)?;
+    Ok(Some(collection.unary_union().map_err(to_err)?))
+}
+
+/// Returns the rings around the gaps of the coverage. A gap is a hole of the coverage union which
+/// is not the hole of a single polygon, i.e. its ring is not covered by the boundary of one of the
+/// polygons around it.
+fn gap_edges(
+    polygons: &[geos::Geometry],
+    boundaries: &[geos::Geometry],
+    index: &EnvelopeIndex,
+) -> DFResult<Vec<geos::Geometry>> {
+    if polygons.is_empty() {
+        return Ok(vec![]);
+    }
+    let collection =
+        geos::Geometry::create_geometry_collection(polygons.to_vec()).map_err(to_err)?;
+    let union = collection
+        .unary_union()
+        .map_err(to_err)?
+        .to_geo()
+        .map_err(|e| internal_datafusion_err!("Failed to convert to geo, error: {}", e))?;
+    let union = match union {
+        geo::Geometry::Polygon(polygon) => vec![polygon],
+        geo::Geometry::MultiPolygon(multi_polygon) => multi_polygon.0,
+        _ => vec![],
+    };
+
+    let mut gaps = vec![];
+    for hole in union.iter().flat_map(|polygon| polygon.interiors()) {
+        let Some(rect) = hole.bounding_rect() else {
+            continue;
+        };
+        let ring = geo::Geometry::LineString(hole.clone())
+            .to_geos()
+            .map_err(|e| internal_datafusion_err!("Failed to convert to geos, error: {}", e))?;
+        let envelope = AABB::from_corners(rect.min().into(), rect.max().into());
+        let mut is_hole = false;
+        for candidate in index.locate_in_envelope_intersecting(&envelope) {
+            if boundaries[candidate.data].covers(&ring).map_err(to_err)? {
+                is_hole = true;
+                break;
+            }
+        }
+        if !is_hole {
+            gaps.push(ring);
+        }
+    }
+    Ok(gaps)
+}
+
+fn envelope(geom: &geos::Geometry) -> DFResult<AABB<[f64; 2]>> {
+    let min = [
+        geom.get_x_min().map_err(to_err)?,
+        geom.get_y_min().map_err(to_err)?,
+    ];
+    let max = [
+        geom.get_x_max().map_err(to_err)?,
+        geom.get_y_max().map_err(to_err)?,
+    ];
+    Ok(AABB::from_corners(min, max))
+}
+
+fn to_err(e: geos::Error) -> DataFusionError {
+    internal_datafusion_err!("Failed to validate coverage, e: {}", e)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{CoverageInvalidEdgesUdaf, GeomFromTextUdf};
+    use crate::geo::GeometryArray;
+    use arrow_array::cast::AsArray;
+    use arrow_array::Array;
+    use datafusion::prelude::SessionContext;
+    use datafusion_expr::{AggregateUDF, ScalarUDF};
+    use geo::{BoundingRect, EuclideanLength, MultiLineString};
+
+    #[tokio::test]
+    async fn coverage_invalid_edges() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udaf(AggregateUDF::from(CoverageInvalidEdgesUdaf::new()));
+
+        let df = ctx
+            .sql(
+                "select ST_CoverageInvalidEdges(ST_GeomFromText(wkt)) from (values \
+                ('POLYGON((0 0,1 0,1 1,0 1,0 0))'), \
+                ('POLYGON((1 0,2 0,2 1,1 1,1 0))'), \
+                ('POLYGON((2 0,3 0,3 1,2 1,2 0))')) as t(wkt)",
+            )
+            .await
+            .unwrap();
+        let batches = df.collect().await.unwrap();
+        assert!(batches[0].column(0).is_null(0));
+
+        let df = ctx
+            .sql(
+                "select ST_CoverageInvalidEdges(ST_GeomFromText(wkt)) from (values \
+                ('POLYGON((0 0,3 0,3 3,0 3,0 0))'), \
+                ('POLYGON((2 0,5 0,5 3,2 3,2 0))')) as t(wkt)",
+            )
+            .await
+            .unwrap();
+        let batches = df.collect().await.unwrap();
+        let arr = batches[0].column(0).as_binary::<i32>();
+        let geo::Geometry::MultiLineString(edges) = arr.geo_value(0).unwrap().unwrap() else {
+            panic!("invalid edges should be a multi linestring");
+        };
+        // the right edge of the first square and the left edge of the second one
+        assert_eq!(edges.0.len(), 2);
+        assert_eq!(edges.euclidean_length(), 6.0);
+        let rect = edges.bounding_rect().unwrap();
+        assert_eq!((rect.min().x, rect.max().x), (2.0, 3.0));
+        assert_eq!((rect.min().y, rect.max().y), (0.0, 3.0));
+    }
+
+    #[tokio::test]
+    async fn coverage_gaps() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udaf(AggregateUDF::from(CoverageInvalidEdgesUdaf::new()));
+
+        // the hole of the last square is not a gap, the notch closed by the cap is
+        let df = ctx
+            .sql(
+                "select ST_CoverageInvalidEdges(ST_GeomFromText(wkt)) from (values \
+                ('POLYGON((0 0,3 0,3 3,2 3,2 1,1 1,1 3,0 3,0 0))'), \
+                ('POLYGON((0 3,3 3,3 4,0 4,0 3))'), \
+                ('POLYGON((3 0,6 0,6 3,3 3,3 0),(4 1,5 1,5 2,4 2,4 1))')) as t(wkt)",
+            )
+            .await
+            .unwrap();
+        let batches = df.collect().await.unwrap();
+        let arr = batches[0].column(0).as_binary::<i32>();
+        let edges = match arr.geo_value(0).unwrap().unwrap() {
+            geo::Geometry::LineString(edge) => MultiLineString::new(vec![edge]),
+            geo::Geometry::MultiLineString(edges) => edges,
+            geom => panic!("invalid edges should be linear, got {:?}", geom),
+        };
+        assert_eq!(edges.euclidean_length(), 6.0);
+        let rect = edges.bounding_rect().unwrap();
+        assert_eq!((rect.min().x, rect.max().x), (1.0, 2.0));
+        assert_eq!((rect.min().y, rect.max().y), (1.0, 3.0));
+    }
+}
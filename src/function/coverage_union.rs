@@ -0,0 +1,225 @@
+use crate::config::{check_cancelled, default_dialect};
+use crate::geo::{geos_capabilities, GeometryArray, GeometryArrayBuilder, GeosVersion};
+use crate::metrics::record_call;
+use crate::DFResult;
+use arrow_array::cast::AsArray;
+use arrow_array::{Array, ArrayRef, BinaryArray};
+use arrow_schema::{DataType, Field};
+use datafusion_common::{
+    exec_datafusion_err, internal_datafusion_err, DataFusionError, ScalarValue,
+};
+use datafusion_expr::{Accumulator, AggregateUDFImpl, Signature, Volatility};
+use geos::Geom;
+use std::any::Any;
+use std::sync::Arc;
+
+const NAME: &str = "st_coverageunion";
+
+/// GEOS version providing the coverage union.
+pub(crate) const COVERAGE_UNION_GEOS: GeosVersion = GeosVersion::new(3, 8, 0);
+
+/// Unions a polygonal coverage, i.e. polygons which only share edges, by merging the shared
+/// linework instead of running a full overlay. The input is expected to be a valid coverage,
+/// see `st_coverageinvalidedges`.
+#[derive(Debug)]
+pub struct CoverageUnionUdaf {
+    signature: Signature,
+}
+
+impl CoverageUnionUdaf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::uniform(
+                1,
+                vec![DataType::Binary, DataType::LargeBinary],
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl AggregateUDFImpl for CoverageUnionUdaf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        // uadf not support alias
+        NAME
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(DataType::Binary)
+    }
+
+    fn accumulator(&self, _arg: &DataType) -> datafusion_common::Result<Box<dyn Accumulator>> {
+        geos_capabilities().check(self.name(), COVERAGE_UNION_GEOS)?;
+        Ok(Box::new(CoverageAccumulator::new(NAME, coverage_union)))
+    }
+
+    fn state_type(&self, _return_type: &DataType) -> datafusion_common::Result<Vec<DataType>> {
+        Ok(vec![CoverageAccumulator::state_data_type()])
+    }
+}
+
+impl Default for CoverageUnionUdaf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn coverage_union(geoms: Vec<geos::Geometry>) -> DFResult<Option<geos::Geometry>> {
+    if geoms.is_empty() {
+        return Ok(None);
+    }
+    let collection = geos::Geometry::create_geometry_collection(geoms)
+        .map_err(|e| internal_datafusion_err!("Failed to create collection, e: {}", e))?;
+    let union = collection
+        .coverage_union()
+        .map_err(|e| exec_datafusion_err!("Failed to union coverage, is it valid? e: {}", e))?;
+    Ok(Some(union))
+}
+
+/// Collects the geometries of a group and evaluates a coverage operation on all of them at once.
+pub(crate) struct CoverageAccumulator {
+    name: &'static str,
+    wkbs: Vec<Vec<u8>>,
+    evaluate: fn(Vec<geos::Geometry>) -> DFResult<Option<geos::Geometry>>,
+}
+
+impl CoverageAccumulator {
+    pub(crate) fn new(
+        name: &'static str,
+        evaluate: fn(Vec<geos::Geometry>) -> DFResult<Option<geos::Geometry>>,
+    ) -> Self {
+        Self {
+            name,
+            wkbs: vec![],
+            evaluate,
+        }
+    }
+
+    pub(crate) fn state_data_type() -> DataType {
+        DataType::List(Arc::new(Field::new("item", DataType::Binary, true)))
+    }
+
+    /// Evaluates an operation other than the one of the accumulator on the collected geometries.
+    pub(crate) fn evaluate_with(
+        &self,
+        evaluate: impl FnOnce(Vec<geos::Geometry>) -> DFResult<Option<geos::Geometry>>,
+    ) -> DFResult<ScalarValue> {
+        let wkb_arr = BinaryArray::from_iter_values(self.wkbs.iter());
+        let mut geoms = vec![];
+        for i in 0..wkb_arr.geom_len() {
+            check_cancelled(i)?;
+            if let Some(geom) = wkb_arr.geos_value(i)? {
+                geoms.push(geom);
+            }
+        }
+        let mut builder = GeometryArrayBuilder::<i32>::new(default_dialect(), 1);
+        builder.append_geos_geometry(&evaluate(geoms)?)?;
+        ScalarValue::try_from_array(&builder.build(), 0)
+    }
+
+    pub(crate) fn append(&mut self, arr: &dyn GeometryArray) -> DFResult<()> {
+        for i in 0..arr.geom_len() {
+            check_cancelled(i)?;
+            if let Some(wkb) = arr.wkb(i) {
+                self.wkbs.push(wkb.to_vec());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for CoverageAccumulator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CoverageAccumulator")
+            .field("geoms", &self.wkbs.len())
+            .finish()
+    }
+}
+
+impl Accumulator for CoverageAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> datafusion_common::Result<()> {
+        if values.is_empty() {
+            return Ok(());
+        }
+        let arr = &values[0];
+        let _recorder = record_call(self.name, arr.len());
+        match arr.data_type() {
+            DataType::Binary => self.append(arr.as_binary::<i32>()),
+            DataType::LargeBinary => self.append(arr.as_binary::<i64>()),
+            _ => unreachable!(),
+        }
+    }
+
+    fn evaluate(&mut self) -> datafusion_common::Result<ScalarValue> {
+        self.evaluate_with(self.evaluate)
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self) + self.wkbs.iter().map(|wkb| wkb.capacity()).sum::<usize>()
+    }
+
+    fn state(&mut self) -> datafusion_common::Result<Vec<ScalarValue>> {
+        let values = self
+            .wkbs
+            .iter()
+            .map(|wkb| ScalarValue::Binary(Some(wkb.clone())))
+            .collect::<Vec<_>>();
+        Ok(vec![ScalarValue::List(ScalarValue::new_list(
+            &values,
+            &DataType::Binary,
+        ))])
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> datafusion_common::Result<()> {
+        if states.is_empty() {
+            return Ok(());
+        }
+        let list_arr = states[0].as_list::<i32>();
+        for list in list_arr.iter().flatten() {
+            self.append(list.as_binary::<i32>())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{CoverageUnionUdaf, GeomFromTextUdf};
+    use crate::geo::GeometryArray;
+    use arrow_array::cast::AsArray;
+    use datafusion::prelude::SessionContext;
+    use datafusion_expr::{AggregateUDF, ScalarUDF};
+    use geo::Area;
+
+    #[tokio::test]
+    async fn coverage_union() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udaf(AggregateUDF::from(CoverageUnionUdaf::new()));
+        let df = ctx
+            .sql(
+                "select ST_CoverageUnion(ST_GeomFromText(wkt)) from (values \
+                ('POLYGON((0 0,1 0,1 1,0 1,0 0))'), \
+                ('POLYGON((1 0,2 0,2 1,1 1,1 0))'), \
+                ('POLYGON((2 0,3 0,3 1,2 1,2 0))')) as t(wkt)",
+            )
+            .await
+            .unwrap();
+        let batches = df.collect().await.unwrap();
+        let arr = batches[0].column(0).as_binary::<i32>();
+        let geom = arr.geo_value(0).unwrap().unwrap();
+        let geo::Geometry::Polygon(polygon) = geom else {
+            panic!("coverage union should be a polygon, got {:?}", geom);
+        };
+        assert_eq!(polygon.unsigned_area(), 3.0);
+        assert!(polygon.interiors().is_empty());
+    }
+}
@@ -0,0 +1,74 @@
+use arrow_schema::DataType;
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+use std::any::Any;
+
+/// Whether two geometries share some but not all interior points and the intersection has a lower
+/// dimension than the geometries, e.g. two lines crossing at a point.
+#[derive(Debug)]
+pub struct CrossesUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl CrossesUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::uniform(
+                2,
+                vec![DataType::Binary, DataType::LargeBinary],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_crosses".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for CrossesUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_Crosses"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(DataType::Boolean)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        #[cfg(feature = "geos")]
+        {
+            use crate::function::args::geos_predicate;
+            use datafusion_common::{internal_datafusion_err, DataFusionError};
+            use geos::Geom;
+            geos_predicate(self.name(), args, |geom0, geom1| {
+                geom0
+                    .crosses(geom1)
+                    .map_err(|e| internal_datafusion_err!("Failed to do crosses, error: {}", e))
+            })
+        }
+        #[cfg(not(feature = "geos"))]
+        {
+            use crate::function::args::geo_predicate;
+            use geo::Relate;
+            geo_predicate(self.name(), args, |geom0, geom1| {
+                geom0.relate(geom1).is_crosses()
+            })
+        }
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for CrossesUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,105 @@
+use crate::function::args::{geos_overlay, overlay_signature};
+use crate::geo::overlay::Overlay;
+use arrow_schema::DataType;
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature};
+use std::any::Any;
+
+/// Returns the part of the first geometry not shared with the second one. An optional grid size
+/// runs the fixed precision overlay, like with `ST_Union`.
+#[derive(Debug)]
+pub struct DifferenceUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl DifferenceUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: overlay_signature(),
+            aliases: vec!["st_difference".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for DifferenceUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_Difference"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(arg_types[0].clone())
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        geos_overlay(self.name(), args, Overlay::Difference)
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for DifferenceUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{DifferenceUdf, GeomFromTextUdf};
+    use crate::geo::GeometryArray;
+    use arrow_array::cast::AsArray;
+    use arrow_array::Array;
+    use datafusion::prelude::SessionContext;
+    use datafusion_expr::ScalarUDF;
+    use geo::Area;
+
+    #[tokio::test]
+    async fn TESTST_Difference() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(DifferenceUdf::new()));
+        let df = ctx
+            .sql(
+                "select ST_Difference(ST_GeomFromText(a), ST_GeomFromText(b)) \
+                from (values \
+                ('POLYGON((0 0,2 0,2 2,0 2,0 0))', 'POLYGON((1 1,3 1,3 3,1 3,1 1))'), \
+                ('POLYGON((0 0,1 0,1 1,0 1,0 0))', null)) as t(a, b)",
+            )
+            .await
+            .unwrap();
+        let batches = df.collect().await.unwrap();
+        let results = batches[0].column(0).as_binary::<i32>();
+        assert_eq!(results.geo_value(0).unwrap().unwrap().unsigned_area(), 3.0);
+        assert!(results.is_null(1));
+    }
+
+    #[tokio::test]
+    async fn difference_grid_size() {
+        if !crate::geo::geos_capabilities().supports(crate::function::args::GRID_SIZE_GEOS) {
+            return;
+        }
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(DifferenceUdf::new()));
+        // squares overlapping by a 1e-9 sliver, which collapses on a 1e-7 grid
+        let sql = "select ST_Difference(ST_GeomFromText(a), ST_GeomFromText(b)), \
+            ST_Difference(ST_GeomFromText(a), ST_GeomFromText(b), 1e-7) \
+            from (values ('POLYGON((0 0,1 0,1 1,0 1,0 0))', \
+            'POLYGON((0.999999999 0,2 0,2 1,0.999999999 1,0.999999999 0))')) as t(a, b)";
+        let batches = ctx.sql(sql).await.unwrap().collect().await.unwrap();
+        let exact = batches[0].column(0).as_binary::<i32>();
+        assert!(exact.geo_value(0).unwrap().unwrap().unsigned_area() < 1.0);
+        let snapped = batches[0].column(1).as_binary::<i32>();
+        assert_eq!(snapped.geo_value(0).unwrap().unwrap().unsigned_area(), 1.0);
+    }
+}
@@ -0,0 +1,156 @@
+use crate::config::check_cancelled;
+use crate::function::args::{as_geometry_array, geometry_args, par_rows};
+use arrow_array::Float64Array;
+use arrow_schema::DataType;
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+use std::any::Any;
+use std::sync::Arc;
+
+/// Returns the minimum euclidean distance between two geometries, null if either is empty.
+#[derive(Debug)]
+pub struct DistanceUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl DistanceUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::uniform(
+                2,
+                vec![DataType::Binary, DataType::LargeBinary],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_distance".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for DistanceUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_Distance"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(DataType::Float64)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let (arrays, recorder) = geometry_args(self.name(), args)?;
+        let arr0 = as_geometry_array(&arrays[0])?;
+        let arr1 = as_geometry_array(&arrays[1])?;
+        let distance_vec = par_rows(arr0.geom_len(), |geom_index| {
+            check_cancelled(geom_index)?;
+            #[cfg(feature = "geos")]
+            {
+                use datafusion_common::{internal_datafusion_err, DataFusionError};
+                use geos::Geom;
+                let geoms = (arr0.geos_value(geom_index)?, arr1.geos_value(geom_index)?);
+                let (Some(geom0), Some(geom1)) = geoms else {
+                    return Ok(None);
+                };
+                recorder.compute(|| {
+                    if geom0.is_empty().unwrap_or(true) || geom1.is_empty().unwrap_or(true) {
+                        return Ok(None);
+                    }
+                    geom0.distance(&geom1).map(Some).map_err(|e| {
+                        internal_datafusion_err!("Failed to do distance, error: {}", e)
+                    })
+                })
+            }
+            #[cfg(not(feature = "geos"))]
+            {
+                use crate::geo::map::is_empty;
+                use geo::EuclideanDistance;
+                let geoms = (arr0.geo_value(geom_index)?, arr1.geo_value(geom_index)?);
+                let (Some(geom0), Some(geom1)) = geoms else {
+                    return Ok(None);
+                };
+                Ok(recorder.compute(|| {
+                    (!is_empty(&geom0) && !is_empty(&geom1))
+                        .then(|| geom0.euclidean_distance(&geom1))
+                }))
+            }
+        })?;
+        Ok(ColumnarValue::Array(Arc::new(Float64Array::from(
+            distance_vec,
+        ))))
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for DistanceUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{DistanceUdf, GeomFromTextUdf, ToLargeGeometryUdf};
+    use arrow::util::pretty::pretty_format_batches;
+    use datafusion::prelude::SessionContext;
+    use datafusion_expr::ScalarUDF;
+
+    #[tokio::test]
+    async fn distance() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(DistanceUdf::new()));
+        let df = ctx
+            .sql("select ST_Distance(ST_GeomFromText('POINT(0 0)'), ST_GeomFromText('POINT(3 4)'))")
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+--------------------------------------------------------------------------------------+
+| ST_Distance(ST_GeomFromText(Utf8(\"POINT(0 0)\")),ST_GeomFromText(Utf8(\"POINT(3 4)\"))) |
++--------------------------------------------------------------------------------------+
+| 5.0                                                                                  |
++--------------------------------------------------------------------------------------+"
+        );
+    }
+
+    #[tokio::test]
+    async fn distance_mixed_args() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(ToLargeGeometryUdf::new()));
+        ctx.register_udf(ScalarUDF::from(DistanceUdf::new()));
+        let df = ctx
+            .sql(
+                "select ST_Distance(ST_ToLargeGeometry(ST_GeomFromText(wkt)), \
+                ST_GeomFromText('LINESTRING(0 0,10 0)')) as distance \
+                from (values ('POINT(5 2)'), ('POLYGON((12 0,13 0,13 1,12 1,12 0))'), \
+                ('MULTIPOINT EMPTY'), (null)) as t(wkt)",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+----------+
+| distance |
++----------+
+| 2.0      |
+| 2.0      |
+|          |
+|          |
++----------+"
+        );
+    }
+}
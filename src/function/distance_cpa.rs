@@ -0,0 +1,60 @@
+use crate::function::closest_point_of_approach::{closest_approaches, trajectory_time_type};
+use arrow_array::Float64Array;
+use arrow_schema::DataType;
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+use std::any::Any;
+use std::sync::Arc;
+
+/// Returns the distance of two trajectories at their closest point of approach, see
+/// ST_ClosestPointOfApproach.
+#[derive(Debug)]
+pub struct DistanceCPAUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl DistanceCPAUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(4, Volatility::Immutable),
+            aliases: vec!["st_distancecpa".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for DistanceCPAUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_DistanceCPA"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        trajectory_time_type(self.name(), arg_types)?;
+        Ok(DataType::Float64)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let distances = closest_approaches(self.name(), args)?
+            .into_iter()
+            .map(|approach| approach.map(|(_, distance)| distance))
+            .collect::<Float64Array>();
+        Ok(ColumnarValue::Array(Arc::new(distances)))
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for DistanceCPAUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,190 @@
+use crate::config::default_dialect;
+use crate::function::args::geometry_args;
+use crate::geo::dialect::decode_srid;
+use crate::geo::{GeometryArray, GeometryArrayBuilder};
+use crate::DFResult;
+use arrow_array::cast::AsArray;
+use arrow_array::{Array, GenericBinaryArray, ListArray, OffsetSizeTrait};
+use arrow_buffer::{NullBuffer, OffsetBuffer};
+use arrow_schema::{DataType, Field};
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+use geo::{Line, LineString};
+use std::any::Any;
+use std::sync::Arc;
+
+/// Returns every 2 point segment of the linestrings and polygon rings of a geometry as a list of
+/// linestrings, ordered along the geometry. Rings include their closing segment.
+#[derive(Debug)]
+pub struct DumpSegmentsUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl DumpSegmentsUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::uniform(
+                1,
+                vec![DataType::Binary, DataType::LargeBinary],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_dumpsegments".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for DumpSegmentsUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_DumpSegments"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(DataType::List(Arc::new(Field::new(
+            "item",
+            arg_types[0].clone(),
+            true,
+        ))))
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let arr = &arrays[0];
+        let list = match arr.data_type() {
+            DataType::Binary => dump_segments::<i32>(arr.as_binary::<i32>())?,
+            DataType::LargeBinary => dump_segments::<i64>(arr.as_binary::<i64>())?,
+            _ => unreachable!(),
+        };
+        Ok(ColumnarValue::Array(Arc::new(list)))
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for DumpSegmentsUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn dump_segments<O: OffsetSizeTrait>(wkb_arr: &GenericBinaryArray<O>) -> DFResult<ListArray> {
+    let mut builder = GeometryArrayBuilder::<O>::new(default_dialect(), wkb_arr.geom_len());
+    let mut lengths = Vec::with_capacity(wkb_arr.geom_len());
+    let mut validity = Vec::with_capacity(wkb_arr.geom_len());
+    for i in 0..wkb_arr.geom_len() {
+        let Some(wkb) = wkb_arr.wkb(i) else {
+            lengths.push(0);
+            validity.push(false);
+            continue;
+        };
+        let srid = decode_srid(wkb)?;
+        let segments = wkb_arr
+            .geo_value(i)?
+            .map(|geom| segments(&geom))
+            .unwrap_or_default();
+        lengths.push(segments.len());
+        validity.push(true);
+        for segment in segments {
+            builder.append_geo_geometry_with_srid(&Some(LineString::from(segment).into()), srid)?;
+        }
+    }
+    let values = builder.build();
+    let field = Arc::new(Field::new("item", values.data_type().clone(), true));
+    Ok(ListArray::new(
+        field,
+        OffsetBuffer::from_lengths(lengths),
+        Arc::new(values),
+        Some(NullBuffer::from(validity)),
+    ))
+}
+
+fn segments(geom: &geo::Geometry) -> Vec<Line> {
+    match geom {
+        geo::Geometry::Point(_) | geo::Geometry::MultiPoint(_) => vec![],
+        geo::Geometry::Line(line) => vec![*line],
+        geo::Geometry::LineString(line) => line.lines().collect(),
+        geo::Geometry::MultiLineString(lines) => lines.iter().flat_map(|l| l.lines()).collect(),
+        geo::Geometry::Polygon(polygon) => polygon_segments(polygon),
+        geo::Geometry::MultiPolygon(polygons) => {
+            polygons.iter().flat_map(polygon_segments).collect()
+        }
+        geo::Geometry::Rect(rect) => polygon_segments(&rect.to_polygon()),
+        geo::Geometry::Triangle(triangle) => polygon_segments(&triangle.to_polygon()),
+        geo::Geometry::GeometryCollection(gc) => gc.iter().flat_map(segments).collect(),
+    }
+}
+
+fn polygon_segments(polygon: &geo::Polygon) -> Vec<Line> {
+    std::iter::once(polygon.exterior())
+        .chain(polygon.interiors())
+        .flat_map(|ring| ring.lines())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{DumpSegmentsUdf, GeomFromTextUdf};
+    use crate::geo::GeometryArray;
+    use arrow::util::pretty::pretty_format_batches;
+    use arrow_array::cast::AsArray;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+    use geo::line_string;
+
+    #[tokio::test]
+    async fn dump_segments() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(DumpSegmentsUdf::new()));
+        let df = ctx
+            .sql(
+                "select cardinality(ST_DumpSegments(ST_GeomFromText(wkt))) as segments from (values \
+                ('POLYGON((0 0,1 0,1 1,0 1,0 0))'), \
+                ('LINESTRING(0 0,1 1,2 0)'), \
+                ('POINT(1 1)'), \
+                (null)) as t(wkt)",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+----------+
+| segments |
++----------+
+| 4        |
+| 2        |
+| 0        |
+|          |
++----------+"
+        );
+
+        let batches = ctx
+            .sql("select ST_DumpSegments(ST_GeomFromText('LINESTRING(0 0,1 1,2 0)'))")
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        let list = batches[0].column(0).as_list::<i32>().value(0);
+        let segments = list.as_binary::<i32>();
+        assert_eq!(
+            segments.geo_value(0).unwrap(),
+            Some(line_string![(x: 0., y: 0.), (x: 1., y: 1.)].into())
+        );
+        assert_eq!(
+            segments.geo_value(1).unwrap(),
+            Some(line_string![(x: 1., y: 1.), (x: 2., y: 0.)].into())
+        );
+    }
+}
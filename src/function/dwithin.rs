@@ -0,0 +1,219 @@
+use crate::config::check_cancelled;
+use crate::function::args::{as_geometry_array, check_srids, geometry_args, par_rows};
+use arrow_array::cast::AsArray;
+use arrow_array::types::Float64Type;
+use arrow_array::{Array, BooleanArray};
+use arrow_schema::DataType;
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, TypeSignature, Volatility};
+use std::any::Any;
+use std::sync::Arc;
+
+/// Whether two geometries are within the given euclidean distance of each other, inclusive. The
+/// distance may be a column. An empty geometry is not within any distance, like in PostGIS.
+#[derive(Debug)]
+pub struct DWithinUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl DWithinUdf {
+    pub fn new() -> Self {
+        let mut signatures = vec![];
+        for type0 in [DataType::Binary, DataType::LargeBinary] {
+            for type1 in [DataType::Binary, DataType::LargeBinary] {
+                signatures.push(TypeSignature::Exact(vec![
+                    type0.clone(),
+                    type1,
+                    DataType::Float64,
+                ]));
+            }
+        }
+        Self {
+            signature: Signature::one_of(signatures, Volatility::Immutable),
+            aliases: vec!["st_dwithin".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for DWithinUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_DWithin"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(DataType::Boolean)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let (arrays, recorder) = geometry_args(self.name(), args)?;
+        let arr0 = as_geometry_array(&arrays[0])?;
+        let arr1 = as_geometry_array(&arrays[1])?;
+        let distances = arrays[2].as_primitive::<Float64Type>();
+        let bool_vec = par_rows(arr0.geom_len(), |geom_index| {
+            check_cancelled(geom_index)?;
+            if distances.is_null(geom_index) {
+                return Ok(None);
+            }
+            let max_distance = distances.value(geom_index);
+            #[cfg(feature = "geos")]
+            {
+                use datafusion_common::{internal_datafusion_err, DataFusionError};
+                use geos::Geom;
+                let geoms = (arr0.geos_value(geom_index)?, arr1.geos_value(geom_index)?);
+                let (Some(geom0), Some(geom1)) = geoms else {
+                    return Ok(None);
+                };
+                if let (Some(wkb0), Some(wkb1)) = (arr0.wkb(geom_index), arr1.wkb(geom_index)) {
+                    check_srids(geom_index, wkb0, wkb1)?;
+                }
+                recorder.compute(|| {
+                    if geom0.is_empty().unwrap_or(true) || geom1.is_empty().unwrap_or(true) {
+                        return Ok(Some(false));
+                    }
+                    geom0
+                        .distance(&geom1)
+                        .map(|distance| Some(distance <= max_distance))
+                        .map_err(|e| {
+                            internal_datafusion_err!("Failed to do distance, error: {}", e)
+                        })
+                })
+            }
+            #[cfg(not(feature = "geos"))]
+            {
+                use crate::geo::map::is_empty;
+                use geo::EuclideanDistance;
+                let geoms = (arr0.geo_value(geom_index)?, arr1.geo_value(geom_index)?);
+                let (Some(geom0), Some(geom1)) = geoms else {
+                    return Ok(None);
+                };
+                if let (Some(wkb0), Some(wkb1)) = (arr0.wkb(geom_index), arr1.wkb(geom_index)) {
+                    check_srids(geom_index, wkb0, wkb1)?;
+                }
+                Ok(Some(recorder.compute(|| {
+                    !is_empty(&geom0)
+                        && !is_empty(&geom1)
+                        && geom0.euclidean_distance(&geom1) <= max_distance
+                })))
+            }
+        })?;
+        Ok(ColumnarValue::Array(Arc::new(BooleanArray::from(bool_vec))))
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for DWithinUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{DWithinUdf, GeomFromTextUdf};
+    use arrow::util::pretty::pretty_format_batches;
+    use datafusion::prelude::SessionContext;
+    use datafusion_expr::ScalarUDF;
+
+    fn session_context() -> SessionContext {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(DWithinUdf::new()));
+        ctx
+    }
+
+    #[tokio::test]
+    async fn dwithin() {
+        let ctx = session_context();
+        let df = ctx
+            .sql(
+                "select distance, \
+                ST_DWithin(ST_GeomFromText('POINT(0 0)'), ST_GeomFromText('POINT(3 4)'), distance) as within \
+                from (values (4.9), (5.0), (null)) as t(distance)",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+----------+--------+
+| distance | within |
++----------+--------+
+| 4.9      | false  |
+| 5.0      | true   |
+|          |        |
++----------+--------+"
+        );
+    }
+
+    #[tokio::test]
+    async fn dwithin_empty() {
+        let ctx = session_context();
+        let df = ctx
+            .sql(
+                "select ST_DWithin(ST_GeomFromText(wkt), ST_GeomFromText('POINT(0 0)'), 10) as within \
+                from (values ('POINT EMPTY'), ('LINESTRING EMPTY'), ('POINT(1 1)'), (null)) as t(wkt)",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+--------+
+| within |
++--------+
+| false  |
+| false  |
+| true   |
+|        |
++--------+"
+        );
+    }
+
+    #[tokio::test]
+    async fn dwithin_filter() {
+        let ctx = session_context();
+        ctx.sql(
+            "create table points as select id, ST_GeomFromText(wkt) as geom from (values \
+            (1, 'POINT(5 1)'), \
+            (2, 'POINT(5 3)'), \
+            (3, 'POINT(12 0)'), \
+            (4, 'POINT(-1 -1)'), \
+            (5, 'POINT EMPTY'), \
+            (6, null)) as t(id, wkt)",
+        )
+        .await
+        .unwrap();
+        let df = ctx
+            .sql(
+                "select id from points \
+                where ST_DWithin(geom, ST_GeomFromText('LINESTRING(0 0,10 0)'), 2) order by id",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+----+
+| id |
++----+
+| 1  |
+| 3  |
+| 4  |
++----+"
+        );
+    }
+}
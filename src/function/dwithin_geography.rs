@@ -0,0 +1,286 @@
+use crate::config::check_cancelled;
+use crate::function::args::{as_geometry_array, geometry_args, par_rows};
+use crate::geo::crs_info;
+use crate::geo::dialect::decode_srid;
+use crate::geo::map::is_empty;
+use crate::DFResult;
+use arrow_array::cast::AsArray;
+use arrow_array::types::Float64Type;
+use arrow_array::{Array, BooleanArray};
+use arrow_schema::DataType;
+use datafusion_common::{exec_err, DataFusionError};
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, TypeSignature, Volatility};
+use geo::{
+    Closest, CoordsIter, DensifyHaversine, HaversineClosestPoint, HaversineDistance, Intersects,
+};
+use std::any::Any;
+use std::sync::Arc;
+
+/// Maximum length in meters of the segments of densified lines and rings.
+const DENSIFY_METERS: f64 = 1000.0;
+
+/// Whether two lon/lat geometries with a geographic SRID like 4326 are within the given distance
+/// in meters of each other, inclusive. Points are compared with the haversine distance. Other
+/// geometries are densified to segments of at most 1 km, then the distance from every vertex of
+/// either geometry to the closest point of the other one is checked, intersecting geometries are
+/// at distance 0. An empty geometry is not within any distance of another one, like in
+/// `ST_DWithin`.
+///
+/// The haversine formula assumes a spherical earth, the distances deviate up to 0.5% from the
+/// geodesic distances on the WGS 84 ellipsoid. The densified segments of length `L` are arcs,
+/// taking them as chords moves them by at most the sagitta `L² / 8R` on a sphere of radius `R`,
+/// i.e. (1 km)² / (8 · 6371 km) ≈ 2 cm, which adds to the 0.5%.
+#[derive(Debug)]
+pub struct DWithinGeographyUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl DWithinGeographyUdf {
+    pub fn new() -> Self {
+        let mut signatures = vec![];
+        for type0 in [DataType::Binary, DataType::LargeBinary] {
+            for type1 in [DataType::Binary, DataType::LargeBinary] {
+                signatures.push(TypeSignature::Exact(vec![
+                    type0.clone(),
+                    type1,
+                    DataType::Float64,
+                ]));
+            }
+        }
+        Self {
+            signature: Signature::one_of(signatures, Volatility::Immutable),
+            aliases: vec!["st_dwithingeography".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for DWithinGeographyUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_DWithinGeography"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(DataType::Boolean)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let (arrays, recorder) = geometry_args(self.name(), args)?;
+        let arr0 = as_geometry_array(&arrays[0])?;
+        let arr1 = as_geometry_array(&arrays[1])?;
+        let distances = arrays[2].as_primitive::<Float64Type>();
+        let bool_vec = par_rows(arr0.geom_len(), |geom_index| {
+            check_cancelled(geom_index)?;
+            let (Some(wkb0), Some(wkb1)) = (arr0.wkb(geom_index), arr1.wkb(geom_index)) else {
+                return Ok(None);
+            };
+            if distances.is_null(geom_index) {
+                return Ok(None);
+            }
+            let geoms = (arr0.geo_value(geom_index)?, arr1.geo_value(geom_index)?);
+            let (Some(geom0), Some(geom1)) = geoms else {
+                return Ok(None);
+            };
+            check_lon_lat(geom_index, wkb0)?;
+            check_lon_lat(geom_index, wkb1)?;
+            if is_empty(&geom0) || is_empty(&geom1) {
+                return Ok(Some(false));
+            }
+            let meters = distances.value(geom_index);
+            Ok(Some(
+                recorder.compute(|| within_meters(&geom0, &geom1, meters)),
+            ))
+        })?;
+        Ok(ColumnarValue::Array(Arc::new(BooleanArray::from(bool_vec))))
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for DWithinGeographyUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn check_lon_lat(row: usize, wkb: &[u8]) -> DFResult<()> {
+    let srid = decode_srid(wkb)?;
+    match srid.and_then(crs_info) {
+        Some(crs) if crs.is_geographic => Ok(()),
+        Some(crs) => exec_err!(
+            "SRID {} is projected; use planar ST_DWithin instead of ST_DWithinGeography at row {}",
+            crs.srid,
+            row
+        ),
+        None => exec_err!(
+            "ST_DWithinGeography requires a geographic SRID like 4326, got {} at row {}",
+            srid.unwrap_or(0),
+            row
+        ),
+    }
+}
+
+fn within_meters(geom0: &geo::Geometry, geom1: &geo::Geometry, meters: f64) -> bool {
+    if let (geo::Geometry::Point(point0), geo::Geometry::Point(point1)) = (geom0, geom1) {
+        return point0.haversine_distance(point1) <= meters;
+    }
+    if meters < 0.0 {
+        return false;
+    }
+    if geom0.intersects(geom1) {
+        return true;
+    }
+    let dense0 = densify(geom0);
+    let dense1 = densify(geom1);
+    any_vertex_within(&dense0, &dense1, meters) || any_vertex_within(&dense1, &dense0, meters)
+}
+
+fn any_vertex_within(from: &geo::Geometry, to: &geo::Geometry, meters: f64) -> bool {
+    from.coords_iter().any(|coord| {
+        let point = geo::Point::from(coord);
+        match to.haversine_closest_point(&point) {
+            Closest::Intersection(_) => true,
+            Closest::SinglePoint(closest) => point.haversine_distance(&closest) <= meters,
+            Closest::Indeterminate => false,
+        }
+    })
+}
+
+/// Adds vertices to the lines and rings so that no segment is longer than `DENSIFY_METERS`.
+fn densify(geom: &geo::Geometry) -> geo::Geometry {
+    match geom {
+        geo::Geometry::Point(_) | geo::Geometry::MultiPoint(_) => geom.clone(),
+        geo::Geometry::Line(line) => line.densify_haversine(DENSIFY_METERS).into(),
+        geo::Geometry::LineString(line_string) => {
+            line_string.densify_haversine(DENSIFY_METERS).into()
+        }
+        geo::Geometry::Polygon(polygon) => polygon.densify_haversine(DENSIFY_METERS).into(),
+        geo::Geometry::MultiLineString(multi_line_string) => {
+            multi_line_string.densify_haversine(DENSIFY_METERS).into()
+        }
+        geo::Geometry::MultiPolygon(multi_polygon) => {
+            multi_polygon.densify_haversine(DENSIFY_METERS).into()
+        }
+        geo::Geometry::Rect(rect) => rect.densify_haversine(DENSIFY_METERS).into(),
+        geo::Geometry::Triangle(triangle) => triangle.densify_haversine(DENSIFY_METERS).into(),
+        geo::Geometry::GeometryCollection(collection) => {
+            geo::GeometryCollection::new_from(collection.iter().map(densify).collect()).into()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{DWithinGeographyUdf, GeomFromTextUdf};
+    use arrow::util::pretty::pretty_format_batches;
+    use datafusion::prelude::SessionContext;
+    use datafusion_expr::ScalarUDF;
+
+    fn session_context() -> SessionContext {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(DWithinGeographyUdf::new()));
+        ctx
+    }
+
+    #[tokio::test]
+    async fn dwithin_geography() {
+        let ctx = session_context();
+        // paris and london are about 343.5 km apart
+        let df = ctx
+            .sql(
+                "select meters, \
+                ST_DWithinGeography(ST_GeomFromText('POINT(2.3522 48.8566)', 4326), london, meters) as paris, \
+                ST_DWithinGeography(ST_GeomFromText('POLYGON((2 48.5,2.7 48.5,2.7 49.2,2 49.2,2 48.5))', 4326), london, meters) as paris_area \
+                from (select ST_GeomFromText('POINT(-0.1276 51.5072)', 4326) as london, meters \
+                from (values (250000.0), (300000.0), (343000.0), (344000.0), (null)) as t(meters))",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+----------+-------+------------+
+| meters   | paris | paris_area |
++----------+-------+------------+
+| 250000.0 | false | false      |
+| 300000.0 | false | true       |
+| 343000.0 | false | true       |
+| 344000.0 | true  | true       |
+|          |       |            |
++----------+-------+------------+"
+        );
+    }
+
+    #[tokio::test]
+    async fn dwithin_geography_empty() {
+        let ctx = session_context();
+        let df = ctx
+            .sql(
+                "select ST_DWithinGeography(ST_GeomFromText(wkt, 4326), ST_GeomFromText('POINT(0 0)', 4326), 1000.0) as within \
+                from (values ('POINT EMPTY'), ('LINESTRING EMPTY'), ('POINT(0.001 0)'), (null)) as t(wkt)",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+--------+
+| within |
++--------+
+| false  |
+| false  |
+| true   |
+|        |
++--------+"
+        );
+    }
+
+    #[tokio::test]
+    async fn dwithin_geography_requires_lon_lat() {
+        let ctx = session_context();
+        let err = ctx
+            .sql(
+                "select ST_DWithinGeography(ST_GeomFromText('POINT(0 0)', 3857), \
+                ST_GeomFromText('POINT(1 1)', 3857), 1000.0)",
+            )
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("SRID 3857 is projected"),
+            "{}",
+            err
+        );
+
+        let err = ctx
+            .sql(
+                "select ST_DWithinGeography(ST_GeomFromText('POINT(0 0)'), \
+                ST_GeomFromText('POINT(1 1)'), 1000.0)",
+            )
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("requires a geographic SRID"),
+            "{}",
+            err
+        );
+    }
+}
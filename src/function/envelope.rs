@@ -0,0 +1,144 @@
+use crate::config::default_dialect;
+use crate::function::args::geometry_args;
+use crate::geo::dialect::decode_srid;
+use crate::geo::map::is_empty;
+use crate::geo::{GeometryArray, GeometryArrayBuilder};
+use crate::DFResult;
+use arrow_array::cast::AsArray;
+use arrow_array::{GenericBinaryArray, OffsetSizeTrait};
+use arrow_schema::DataType;
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+use geo::{line_string, point, polygon, BoundingRect, Rect};
+use std::any::Any;
+use std::sync::Arc;
+
+/// Returns the bounding box of a geometry as a polygon, or as a point or a linestring if the box
+/// has no width or no height. Empty geometries have no envelope.
+#[derive(Debug)]
+pub struct EnvelopeUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl EnvelopeUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::uniform(
+                1,
+                vec![DataType::Binary, DataType::LargeBinary],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_envelope".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for EnvelopeUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_Envelope"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(arg_types[0].clone())
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let arr = &arrays[0];
+        match arr.data_type() {
+            DataType::Binary => envelope::<i32>(arr.as_binary::<i32>()),
+            DataType::LargeBinary => envelope::<i64>(arr.as_binary::<i64>()),
+            _ => unreachable!(),
+        }
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for EnvelopeUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn envelope<O: OffsetSizeTrait>(wkb_arr: &GenericBinaryArray<O>) -> DFResult<ColumnarValue> {
+    let mut builder = GeometryArrayBuilder::<O>::new(default_dialect(), wkb_arr.geom_len());
+    for i in 0..wkb_arr.geom_len() {
+        let Some(wkb) = wkb_arr.wkb(i) else {
+            builder.append_null();
+            continue;
+        };
+        let srid = decode_srid(wkb)?;
+        let envelope = wkb_arr
+            .geo_value(i)?
+            .filter(|geom| !is_empty(geom))
+            .and_then(|geom| geom.bounding_rect())
+            .map(rect_geometry);
+        builder.append_geo_geometry_with_srid(&envelope, srid)?;
+    }
+    Ok(ColumnarValue::Array(Arc::new(builder.build())))
+}
+
+fn rect_geometry(rect: Rect) -> geo::Geometry {
+    let (min, max) = (rect.min(), rect.max());
+    match (min.x == max.x, min.y == max.y) {
+        (true, true) => point!(min).into(),
+        (true, false) | (false, true) => line_string![min, max].into(),
+        (false, false) => polygon![
+            (x: min.x, y: min.y),
+            (x: min.x, y: max.y),
+            (x: max.x, y: max.y),
+            (x: max.x, y: min.y),
+            (x: min.x, y: min.y),
+        ]
+        .into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{AsTextUdf, EnvelopeUdf, GeomFromTextUdf};
+    use arrow::util::pretty::pretty_format_batches;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+
+    #[tokio::test]
+    async fn envelope() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(EnvelopeUdf::new()));
+        let df = ctx
+            .sql(
+                "select ST_AsText(ST_Envelope(ST_GeomFromText(wkt))) as envelope \
+                from (values ('LINESTRING(1 2, 3 4)'), ('POINT(1 2)'), ('LINESTRING(1 2,1 4)'), \
+                ('MULTIPOLYGON EMPTY'), (null)) as t(wkt)",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+--------------------------------+
+| envelope                       |
++--------------------------------+
+| POLYGON((1 2,1 4,3 4,3 2,1 2)) |
+| POINT(1 2)                     |
+| LINESTRING(1 2,1 4)            |
+|                                |
+|                                |
++--------------------------------+"
+        );
+    }
+}
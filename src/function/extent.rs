@@ -1,4 +1,6 @@
+use crate::config::check_cancelled;
 use crate::geo::{Box2d, GeometryArray};
+use crate::metrics::record_call;
 use crate::DFResult;
 use arrow_array::cast::AsArray;
 use arrow_array::{Array, ArrayRef, GenericBinaryArray, OffsetSizeTrait};
@@ -78,6 +80,7 @@ impl Accumulator for ExtentAccumulator {
             return Ok(());
         }
         let arr = &values[0];
+        let _recorder = record_call("st_extent", arr.len());
         match arr.data_type() {
             DataType::Binary => {
                 let wkb_arr = arr.as_binary::<i32>();
@@ -116,12 +119,8 @@ impl Accumulator for ExtentAccumulator {
                 .iter()
                 .map(|array| ScalarValue::try_from_array(array, index))
                 .collect::<datafusion_common::Result<Vec<_>>>()?;
-            if let ScalarValue::Struct(arr) = &v[0] {
-                if let Some(box2d) = Box2d::value(arr, 0)? {
-                    self.box2d = compute_bounding_box2d(self.box2d.clone(), box2d);
-                }
-            } else {
-                unreachable!("")
+            if let Some(box2d) = Box2d::from_scalar(&v[0])? {
+                self.box2d = compute_bounding_box2d(self.box2d.clone(), box2d);
             }
             Ok(())
         })
@@ -131,6 +130,7 @@ impl Accumulator for ExtentAccumulator {
 fn compute_extent<O: OffsetSizeTrait>(arr: &GenericBinaryArray<O>) -> DFResult<Box2d> {
     let mut box2d = Box2d::new();
     for i in 0..arr.geom_len() {
+        check_cancelled(i)?;
         if let Some(value) = arr
             .geo_value(i)?
             .and_then(|geom| geom.bounding_rect().map(Box2d::from))
@@ -157,11 +157,13 @@ fn compute_bounding_box2d(b0: Box2d, b1: Box2d) -> Box2d {
 #[cfg(test)]
 mod tests {
     use crate::function::extent::ExtentUdaf;
+    use crate::function::AsTextUdf;
     use crate::geo::GeometryArrayBuilder;
     use arrow::util::pretty::pretty_format_batches;
     use arrow_array::{RecordBatch, StringArray};
     use arrow_schema::{DataType, Field, Schema};
     use datafusion::datasource::MemTable;
+    use datafusion::logical_expr::ScalarUDF;
     use datafusion::prelude::SessionContext;
     use datafusion_expr::AggregateUDF;
     use geo::line_string;
@@ -184,7 +186,7 @@ mod tests {
             ];
             linestrint_vec.push(Some(linestring));
         }
-        let builder: GeometryArrayBuilder<i32> = linestrint_vec.as_slice().into();
+        let builder: GeometryArrayBuilder<i32> = linestrint_vec.as_slice().try_into().unwrap();
 
         let record = RecordBatch::try_new(
             schema.clone(),
@@ -201,20 +203,21 @@ mod tests {
         ctx.register_table("geom_table", Arc::new(mem_table))
             .unwrap();
         ctx.register_udaf(AggregateUDF::from(ExtentUdaf::new()));
+        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
         let df = ctx
-            .sql("select ST_Extent(geom), name from geom_table group by name order by name")
+            .sql("select ST_AsText(ST_Extent(geom)) as extent, name from geom_table group by name order by name")
             .await
             .unwrap();
         assert_eq!(
             pretty_format_batches(&df.collect().await.unwrap())
                 .unwrap()
                 .to_string(),
-            "+----------------------------------------------+------+
-| st_extent(geom_table.geom)                   | name |
-+----------------------------------------------+------+
-| {xmin: 0.0, ymin: 1.0, xmax: 5.0, ymax: 6.0} | a    |
-| {xmin: 2.0, ymin: 3.0, xmax: 7.0, ymax: 8.0} | b    |
-+----------------------------------------------+------+"
+            "+--------------+------+
+| extent       | name |
++--------------+------+
+| BOX(0 1,5 6) | a    |
+| BOX(2 3,7 8) | b    |
++--------------+------+"
         );
     }
 }
@@ -0,0 +1,78 @@
+use crate::config::default_dialect;
+use crate::function::args::geometry_args;
+use crate::geo::geobuf::{decode_geobuf, GeobufData};
+use crate::geo::GeometryArrayBuilder;
+use arrow_array::cast::AsArray;
+use arrow_schema::DataType;
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+use std::any::Any;
+use std::sync::Arc;
+
+/// Decodes a geobuf into a geometry. The geometry of a feature is returned as is, the geometries
+/// of a feature collection are returned as a geometry collection.
+#[derive(Debug)]
+pub struct FromGeobufUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl FromGeobufUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::exact(vec![DataType::Binary], Volatility::Immutable),
+            aliases: vec!["st_fromgeobuf".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for FromGeobufUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_FromGeobuf"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(DataType::Binary)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let (arrays, _recorder) = geometry_args(self.name(), &args[..1])?;
+        let arr = &arrays[0];
+        let binary_arr = arr.as_binary::<i32>();
+
+        let mut builder = GeometryArrayBuilder::<i32>::new(default_dialect(), binary_arr.len());
+        for value in binary_arr.iter() {
+            let geom = match value.map(decode_geobuf).transpose()? {
+                None => None,
+                Some(GeobufData::Geometry(geom)) => Some(geom),
+                Some(GeobufData::Feature(feature)) => feature.geometry,
+                Some(GeobufData::FeatureCollection(features)) => Some(
+                    features
+                        .into_iter()
+                        .filter_map(|feature| feature.geometry)
+                        .collect::<geo::GeometryCollection>()
+                        .into(),
+                ),
+            };
+            builder.append_geo_geometry(&geom)?;
+        }
+        Ok(ColumnarValue::Array(Arc::new(builder.build())))
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for FromGeobufUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
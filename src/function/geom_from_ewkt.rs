@@ -0,0 +1,171 @@
+use crate::config::default_dialect;
+use crate::function::args::{geometry_args, ingestion_args, scalar_if_constant, IngestionArgs};
+use crate::geo::dialect::check_srid_storable;
+use crate::geo::wkt::{parse_ewkt, row_error};
+use crate::geo::GeometryArrayBuilder;
+use crate::DFResult;
+use arrow_array::cast::AsArray;
+use arrow_array::{Array, GenericStringArray, OffsetSizeTrait};
+use arrow_schema::DataType;
+use datafusion_common::{internal_datafusion_err, DataFusionError};
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+use geozero::{GeozeroGeometry, ToWkb};
+use std::any::Any;
+use std::sync::Arc;
+
+/// Parses an EWKT string like `SRID=4326;POINT(1 2)`, text without the SRID prefix is parsed like
+/// `ST_GeomFromText`.
+#[derive(Debug)]
+pub struct GeomFromEwktUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl GeomFromEwktUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::uniform(
+                1,
+                vec![DataType::Utf8, DataType::LargeUtf8],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_geomfromewkt".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for GeomFromEwktUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_GeomFromEWKT"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        match arg_types[0] {
+            DataType::Utf8 => Ok(DataType::Binary),
+            DataType::LargeUtf8 => Ok(DataType::LargeBinary),
+            _ => unreachable!(),
+        }
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let ingestion = ingestion_args(args)?;
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let arr = &arrays[0];
+        let value = match arr.data_type() {
+            DataType::Utf8 => geom_from_ewkt::<i32>(arr.as_string::<i32>(), &ingestion)?,
+            DataType::LargeUtf8 => geom_from_ewkt::<i64>(arr.as_string::<i64>(), &ingestion)?,
+            _ => unreachable!(),
+        };
+        scalar_if_constant(args, value)
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for GeomFromEwktUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn geom_from_ewkt<O: OffsetSizeTrait>(
+    string_arr: &GenericStringArray<O>,
+    ingestion: &IngestionArgs,
+) -> DFResult<ColumnarValue> {
+    let mut builder = GeometryArrayBuilder::<O>::new(default_dialect(), string_arr.len());
+    for (i, value) in string_arr.iter().enumerate() {
+        let Some(data) = value else {
+            builder.append_null();
+            continue;
+        };
+        let (srid, geometry) = parse_ewkt(data).map_err(|e| row_error(i, data, e))?;
+        check_srid_storable(default_dialect(), srid)?;
+        let wkb = geometry
+            .to_wkb_dialect(default_dialect(), geometry.dims(), srid, vec![])
+            .map_err(|e| internal_datafusion_err!("Failed to convert ewkt to wkb, error: {}", e))?;
+        builder.append_validated_wkb(&wkb, ingestion.validation, ingestion.on_invalid)?;
+    }
+    Ok(ColumnarValue::Array(Arc::new(builder.build())))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{AsTextUdf, GeomFromEwktUdf, SridUdf};
+    use arrow::util::pretty::pretty_format_batches;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+
+    fn session_context() -> SessionContext {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromEwktUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(SridUdf::new()));
+        ctx
+    }
+
+    #[tokio::test]
+    async fn geom_from_ewkt() {
+        let ctx = session_context();
+        let df = ctx
+            .sql(
+                "select ST_AsText(geom) as wkt, ST_SRID(geom) as srid \
+                from (select ST_GeomFromEWKT(ewkt) as geom from (values \
+                ('SRID=4326;POINT(1 2)'), \
+                ('POINT(3 4)'), \
+                ('SRID=3857;LINESTRING(0 0,1 1)'), \
+                (null)) as t(ewkt))",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+---------------------+------+
+| wkt                 | srid |
++---------------------+------+
+| POINT(1 2)          | 4326 |
+| POINT(3 4)          | 0    |
+| LINESTRING(0 0,1 1) | 3857 |
+|                     |      |
++---------------------+------+"
+        );
+    }
+
+    #[tokio::test]
+    async fn geom_from_invalid_ewkt() {
+        let ctx = session_context();
+        for (ewkt, message) in [
+            (
+                "SRID=abc;POINT(1 2)",
+                "Row 1 'SRID=abc;POINT(1 2)': Failed to parse ewkt",
+            ),
+            (
+                "SRID=4326;POINT(1)",
+                "Row 1 'SRID=4326;POINT(1)': Failed to parse wkt",
+            ),
+        ] {
+            let err = ctx
+                .sql(&format!(
+                    "select ST_GeomFromEWKT(ewkt) from (values ('POINT(0 0)'), ('{}')) as t(ewkt)",
+                    ewkt
+                ))
+                .await
+                .unwrap()
+                .collect()
+                .await
+                .unwrap_err();
+            assert!(err.to_string().contains(message), "{}", err);
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use crate::config::default_dialect;
+use crate::function::args::{geometry_args, scalar_if_constant};
+use crate::geo::GeometryArrayBuilder;
+use crate::DFResult;
+use arrow_array::cast::AsArray;
+use arrow_array::{Array, GenericStringArray, OffsetSizeTrait};
+use arrow_schema::DataType;
+use datafusion_common::{exec_datafusion_err, DataFusionError};
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+use geozero::geojson::GeoJson;
+use geozero::ToGeo;
+use std::any::Any;
+use std::sync::Arc;
+
+/// Parses a GeoJSON geometry object, the inverse of `ST_AsGeoJSON`.
+#[derive(Debug)]
+pub struct GeomFromGeoJsonUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl GeomFromGeoJsonUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::uniform(
+                1,
+                vec![DataType::Utf8, DataType::LargeUtf8],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_geomfromgeojson".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for GeomFromGeoJsonUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_GeomFromGeoJSON"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        match arg_types[0] {
+            DataType::Utf8 => Ok(DataType::Binary),
+            DataType::LargeUtf8 => Ok(DataType::LargeBinary),
+            _ => unreachable!(),
+        }
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let arr = &arrays[0];
+        let value = match arr.data_type() {
+            DataType::Utf8 => geom_from_geojson::<i32>(arr.as_string::<i32>())?,
+            DataType::LargeUtf8 => geom_from_geojson::<i64>(arr.as_string::<i64>())?,
+            _ => unreachable!(),
+        };
+        scalar_if_constant(args, value)
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for GeomFromGeoJsonUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn geom_from_geojson<O: OffsetSizeTrait>(
+    string_arr: &GenericStringArray<O>,
+) -> DFResult<ColumnarValue> {
+    let mut builder = GeometryArrayBuilder::<O>::new(default_dialect(), string_arr.len());
+    for (i, value) in string_arr.iter().enumerate() {
+        let geom = value
+            .map(|json| GeoJson(json).to_geo())
+            .transpose()
+            .map_err(|e| {
+                exec_datafusion_err!("Failed to parse geojson at row {}, error: {}", i, e)
+            })?;
+        builder.append_geo_geometry(&geom)?;
+    }
+    Ok(ColumnarValue::Array(Arc::new(builder.build())))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{AsGeoJsonUdf, AsTextUdf, GeomFromGeoJsonUdf, GeomFromTextUdf};
+    use arrow_array::cast::AsArray;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+
+    fn session_context() -> SessionContext {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(GeomFromGeoJsonUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsGeoJsonUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
+        ctx
+    }
+
+    #[tokio::test]
+    async fn geom_from_geojson() {
+        let ctx = session_context();
+        let df = ctx
+            .sql(
+                "select ST_AsText(ST_GeomFromGeoJSON(\
+                '{\"type\": \"LineString\", \"coordinates\": [[0,0],[1,1.5]]}'))",
+            )
+            .await
+            .unwrap();
+        let batches = df.collect().await.unwrap();
+        assert_eq!(
+            batches[0].column(0).as_string::<i32>().value(0),
+            "LINESTRING(0 0,1 1.5)"
+        );
+    }
+
+    #[tokio::test]
+    async fn geojson_round_trip() {
+        let ctx = session_context();
+        for wkt in [
+            "POINT(-71.064544 42.28787)",
+            "LINESTRING(0 0,1 1,2 0.5)",
+            "POLYGON((0 0,4 0,4 4,0 4,0 0),(1 1,2 1,2 2,1 2,1 1))",
+            "MULTIPOINT(1 1,2 2)",
+            "MULTILINESTRING((0 0,1 1),(2 2,3 3))",
+            "MULTIPOLYGON(((0 0,4 0,4 4,0 4,0 0),(1 1,2 1,2 2,1 2,1 1)),((5 5,6 5,6 6,5 5)))",
+            "GEOMETRYCOLLECTION(POINT(1 2),LINESTRING(0 0,1 1))",
+        ] {
+            let df = ctx
+                .sql(&format!(
+                    "select ST_AsText(ST_GeomFromGeoJSON(ST_AsGeoJSON(ST_GeomFromText('{}'))))",
+                    wkt
+                ))
+                .await
+                .unwrap();
+            let batches = df.collect().await.unwrap();
+            assert_eq!(batches[0].column(0).as_string::<i32>().value(0), wkt);
+        }
+    }
+
+    #[tokio::test]
+    async fn geom_from_invalid_geojson() {
+        let ctx = session_context();
+        let err = ctx
+            .sql("select ST_GeomFromGeoJSON('{\"type\": \"Point\"}')")
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("Failed to parse geojson"),
+            "{}",
+            err
+        );
+    }
+}
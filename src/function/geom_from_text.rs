@@ -1,14 +1,23 @@
+use crate::config::default_dialect;
+use crate::function::args::{
+    geometry_args, ingestion_args, ingestion_signatures, scalar_if_constant, IngestionArgs,
+};
+use crate::geo::dialect::check_srid_storable;
+use crate::geo::wkt::{parse_ewkt, row_error};
 use crate::geo::GeometryArrayBuilder;
+use crate::DFResult;
 use arrow_array::cast::AsArray;
+use arrow_array::{Array, GenericStringArray, OffsetSizeTrait};
 use arrow_schema::DataType;
-use datafusion_common::ScalarValue;
-use datafusion_common::{internal_datafusion_err, internal_err, DataFusionError};
-use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, TypeSignature, Volatility};
-use geozero::wkb::WkbDialect;
+use datafusion_common::{internal_datafusion_err, DataFusionError};
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
 use geozero::{GeozeroGeometry, ToWkb};
 use std::any::Any;
 use std::sync::Arc;
 
+/// Parses a WKT string, `ST_GeomFromText(text [, srid] [, validation [, on_invalid]])`. EWKT
+/// with an SRID prefix like `SRID=4326;POINT(1 2)` is accepted too, the srid argument overrides
+/// the prefix.
 #[derive(Debug)]
 pub struct GeomFromTextUdf {
     signature: Signature,
@@ -19,10 +28,10 @@ impl GeomFromTextUdf {
     pub fn new() -> Self {
         Self {
             signature: Signature::one_of(
-                vec![
-                    TypeSignature::Exact(vec![DataType::Utf8]),
-                    TypeSignature::Exact(vec![DataType::Utf8, DataType::Int64]),
-                ],
+                [DataType::Utf8, DataType::LargeUtf8]
+                    .into_iter()
+                    .flat_map(ingestion_signatures)
+                    .collect(),
                 Volatility::Immutable,
             ),
             aliases: vec!["st_geomfromtext".to_string()],
@@ -43,36 +52,24 @@ impl ScalarUDFImpl for GeomFromTextUdf {
         &self.signature
     }
 
-    fn return_type(&self, _arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
-        Ok(DataType::Binary)
+    fn return_type(&self, arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        match arg_types[0] {
+            DataType::Utf8 => Ok(DataType::Binary),
+            DataType::LargeUtf8 => Ok(DataType::LargeBinary),
+            _ => unreachable!(),
+        }
     }
 
     fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
-        let srid = if args.len() == 2 {
-            let ColumnarValue::Scalar(ScalarValue::Int64(Some(srid))) = &args[1] else {
-                return internal_err!("The second arg should be int64");
-            };
-            Some(*srid as i32)
-        } else {
-            None
+        let ingestion = ingestion_args(args)?;
+        let (arrays, _recorder) = geometry_args(self.name(), &args[..1])?;
+        let arr = &arrays[0];
+        let value = match arr.data_type() {
+            DataType::Utf8 => geom_from_text::<i32>(arr.as_string::<i32>(), &ingestion)?,
+            DataType::LargeUtf8 => geom_from_text::<i64>(arr.as_string::<i64>(), &ingestion)?,
+            _ => unreachable!(),
         };
-        let arr = args[0].clone().into_array(1)?;
-        let string_arr = arr.as_string::<i32>();
-
-        let mut builder = GeometryArrayBuilder::<i32>::new(WkbDialect::Ewkb, 1);
-        for value in string_arr.iter() {
-            match value {
-                None => builder.append_null(),
-                Some(data) => {
-                    let wkt = geozero::wkt::Wkt(data);
-                    let ewkb = wkt.to_ewkb(wkt.dims(), srid).map_err(|e| {
-                        internal_datafusion_err!("Failed to convert wkt to ewkb, error: {}", e)
-                    })?;
-                    builder.append_wkb(Some(&ewkb))?;
-                }
-            }
-        }
-        Ok(ColumnarValue::Array(Arc::new(builder.build())))
+        scalar_if_constant(args, value)
     }
 
     fn aliases(&self) -> &[String] {
@@ -86,9 +83,33 @@ impl Default for GeomFromTextUdf {
     }
 }
 
+fn geom_from_text<O: OffsetSizeTrait>(
+    string_arr: &GenericStringArray<O>,
+    ingestion: &IngestionArgs,
+) -> DFResult<ColumnarValue> {
+    let mut builder = GeometryArrayBuilder::<O>::new(default_dialect(), string_arr.len());
+    for (i, value) in string_arr.iter().enumerate() {
+        match value {
+            None => builder.append_null(),
+            Some(data) => {
+                let (srid, geometry) = parse_ewkt(data).map_err(|e| row_error(i, data, e))?;
+                let srid = ingestion.srid.or(srid);
+                check_srid_storable(default_dialect(), srid)?;
+                let wkb = geometry
+                    .to_wkb_dialect(default_dialect(), geometry.dims(), srid, vec![])
+                    .map_err(|e| {
+                        internal_datafusion_err!("Failed to convert wkt to wkb, error: {}", e)
+                    })?;
+                builder.append_validated_wkb(&wkb, ingestion.validation, ingestion.on_invalid)?;
+            }
+        }
+    }
+    Ok(ColumnarValue::Array(Arc::new(builder.build())))
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::function::{AsTextUdf, GeomFromTextUdf};
+    use crate::function::{AsTextUdf, GeomFromTextUdf, GeometryTypeUdf, IntersectsUdf, SridUdf};
     use arrow::util::pretty::pretty_format_batches;
     use datafusion::logical_expr::ScalarUDF;
     use datafusion::prelude::SessionContext;
@@ -114,6 +135,35 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn geom_from_large_text() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(GeometryTypeUdf::new()));
+        let df = ctx
+            .sql(
+                "select arrow_typeof(geom) as geom_type, \
+            ST_AsText(geom) as wkt, \
+            arrow_typeof(ST_AsText(geom)) as wkt_type, \
+            ST_GeometryType(geom) as geometry_type, \
+            arrow_typeof(ST_GeometryType(geom)) as geometry_type_type \
+            from (select ST_GeomFromText(arrow_cast('POINT(1 2)', 'LargeUtf8')) as geom)",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+-------------+------------+-----------+---------------+--------------------+
+| geom_type   | wkt        | wkt_type  | geometry_type | geometry_type_type |
++-------------+------------+-----------+---------------+--------------------+
+| LargeBinary | POINT(1 2) | LargeUtf8 | ST_Point      | LargeUtf8          |
++-------------+------------+-----------+---------------+--------------------+"
+        );
+    }
+
     #[cfg(feature = "geos")]
     #[tokio::test]
     async fn geom_from_text_with_srid() {
@@ -135,4 +185,252 @@ mod tests {
 +----------------------------------------------------------------------------+"
         );
     }
+
+    #[tokio::test]
+    async fn geom_from_text_constant_folding() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(IntersectsUdf::new()));
+        let df = ctx
+            .sql(
+                "explain select id from (values (1, 'POINT(1 1)'), (2, 'POINT(2 2)')) as t(id, wkt) \
+                where ST_Intersects(ST_GeomFromText(wkt), ST_GeomFromText('POINT(1 1)'))",
+            )
+            .await
+            .unwrap();
+        let plan = pretty_format_batches(&df.collect().await.unwrap())
+            .unwrap()
+            .to_string();
+        // the constant geometry is folded into a binary literal, the column one is not
+        assert!(plan.contains("), Binary(\""));
+        assert!(plan.contains("ST_Intersects(ST_GeomFromText("));
+        assert!(!plan.contains("ST_GeomFromText(Utf8"));
+    }
+
+    #[cfg(feature = "geos")]
+    #[tokio::test]
+    async fn geom_from_text_validation() {
+        use crate::geo::GeometryArray;
+        use arrow_array::cast::AsArray;
+        use geo::Area;
+
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(GeometryTypeUdf::new()));
+        let bowtie = "'POLYGON((0 0,2 2,2 0,0 2,0 0))'";
+
+        for validation in ["'none'", "'parse'"] {
+            let sql = format!(
+                "select ST_GeometryType(ST_GeomFromText({}, {}))",
+                bowtie, validation
+            );
+            let batches = ctx.sql(&sql).await.unwrap().collect().await.unwrap();
+            assert_eq!(
+                batches[0].column(0).as_string::<i32>().value(0),
+                "ST_Polygon"
+            );
+        }
+
+        let sql = format!("select ST_GeomFromText({}, 'full')", bowtie);
+        let err = ctx.sql(&sql).await.unwrap().collect().await.unwrap_err();
+        assert!(err.to_string().contains("Invalid geometry"));
+
+        let sql = format!("select ST_GeomFromText({}, 4326, 'full', 'repair')", bowtie);
+        let batches = ctx.sql(&sql).await.unwrap().collect().await.unwrap();
+        let repaired = batches[0].column(0).as_binary::<i32>();
+        let Some(geo::Geometry::MultiPolygon(polygons)) = repaired.geo_value(0).unwrap() else {
+            panic!("repaired bowtie should be a multi polygon");
+        };
+        assert_eq!(polygons.0.len(), 2);
+        assert_eq!(polygons.unsigned_area(), 2.0);
+        assert_eq!(
+            crate::geo::dialect::decode_srid(repaired.wkb(0).unwrap()).unwrap(),
+            Some(4326)
+        );
+    }
+
+    #[tokio::test]
+    async fn geom_from_tricky_text() {
+        use arrow_array::cast::AsArray;
+
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(GeometryTypeUdf::new()));
+
+        let round_trips = [
+            (
+                "GEOMETRYCOLLECTION(POINT(1 1), LINESTRING EMPTY)",
+                "GEOMETRYCOLLECTION(POINT(1 1),LINESTRING EMPTY)",
+            ),
+            ("GEOMETRYCOLLECTION EMPTY", "GEOMETRYCOLLECTION EMPTY"),
+            (
+                "GEOMETRYCOLLECTION(GEOMETRYCOLLECTION(POINT(1 2)),LINESTRING(0 0,1 1))",
+                "GEOMETRYCOLLECTION(GEOMETRYCOLLECTION(POINT(1 2)),LINESTRING(0 0,1 1))",
+            ),
+            (
+                "GEOMETRYCOLLECTION(GEOMETRYCOLLECTION EMPTY,POINT(1 1))",
+                "GEOMETRYCOLLECTION(GEOMETRYCOLLECTION EMPTY,POINT(1 1))",
+            ),
+            (
+                "MULTILINESTRING((0 0,1 1),EMPTY)",
+                "MULTILINESTRING((0 0,1 1),EMPTY)",
+            ),
+            ("MULTIPOINT EMPTY", "MULTIPOINT EMPTY"),
+            ("LINESTRING EMPTY", "LINESTRING EMPTY"),
+            ("POINT(1e3 -2.5E-2)", "POINT(1000 -0.025)"),
+            ("POINT(+.5 1.5e+2)", "POINT(0.5 150)"),
+            ("LINESTRING(1E20 0,0 1e-3)", "LINESTRING(1e+20 0,0 0.001)"),
+            ("point(1 2)", "POINT(1 2)"),
+            ("  MULTIPOINT ( (1 2) , (3 4) )  ", "MULTIPOINT(1 2,3 4)"),
+            ("POINT Z (1 2 3)", "POINT(1 2)"),
+            (
+                "MULTIPOLYGON(((0 0,1 0,1 1,0 0)),((2 2,3 2,3 3,2 2)))",
+                "MULTIPOLYGON(((0 0,1 0,1 1,0 0)),((2 2,3 2,3 3,2 2)))",
+            ),
+            (
+                "POLYGON((0 0,4 0,4 4,0 4,0 0),(1 1,2 1,2 2,1 1))",
+                "POLYGON((0 0,4 0,4 4,0 4,0 0),(1 1,2 1,2 2,1 1))",
+            ),
+        ];
+        for (wkt, expected) in round_trips {
+            let sql = format!("select ST_AsText(ST_GeomFromText('{}'))", wkt);
+            let batches = ctx.sql(&sql).await.unwrap().collect().await.unwrap();
+            assert_eq!(batches[0].column(0).as_string::<i32>().value(0), expected);
+        }
+
+        // empty points have no text representation shared by geo and geos
+        let parsed = [
+            (
+                "GEOMETRYCOLLECTION(POINT EMPTY,POINT(1 1))",
+                "ST_GeometryCollection",
+            ),
+            ("MULTIPOINT((1 2),EMPTY)", "ST_MultiPoint"),
+            ("MULTIPOLYGON(EMPTY,((0 0,1 0,1 1,0 0)))", "ST_MultiPolygon"),
+            ("POINT EMPTY", "ST_Point"),
+        ];
+        for (wkt, expected) in parsed {
+            let sql = format!("select ST_GeometryType(ST_GeomFromText('{}'))", wkt);
+            let batches = ctx.sql(&sql).await.unwrap().collect().await.unwrap();
+            assert_eq!(batches[0].column(0).as_string::<i32>().value(0), expected);
+        }
+
+        let sql =
+            "select ST_GeomFromText('GEOMETRYCOLLECTION(POINT(1 1),CIRCULARSTRING(0 0,1 1,2 0))')";
+        let err = ctx.sql(sql).await.unwrap().collect().await.unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("unsupported geometry type 'CIRCULARSTRING' at offset 31"));
+    }
+
+    #[tokio::test]
+    async fn geom_from_text_column() {
+        use arrow_array::{RecordBatch, StringArray};
+        use arrow_schema::{DataType, Field, Schema};
+        use datafusion::datasource::MemTable;
+        use std::sync::Arc;
+
+        let schema = Arc::new(Schema::new(vec![Field::new("wkt", DataType::Utf8, true)]));
+        let table = |values: Vec<Option<&str>>| {
+            let batch =
+                RecordBatch::try_new(schema.clone(), vec![Arc::new(StringArray::from(values))])
+                    .unwrap();
+            Arc::new(MemTable::try_new(schema.clone(), vec![vec![batch]]).unwrap())
+        };
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
+        ctx.register_table(
+            "valid",
+            table(vec![Some("POINT(1 2)"), None, Some("LINESTRING(0 0,1 1)")]),
+        )
+        .unwrap();
+        ctx.register_table(
+            "invalid",
+            table(vec![
+                Some("POINT(1 2)"),
+                None,
+                Some("POINT(1 2"),
+                Some("POINT(3 4)"),
+            ]),
+        )
+        .unwrap();
+
+        let df = ctx
+            .sql("select ST_AsText(ST_GeomFromText(wkt)) as geom from valid")
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+---------------------+
+| geom                |
++---------------------+
+| POINT(1 2)          |
+|                     |
+| LINESTRING(0 0,1 1) |
++---------------------+"
+        );
+
+        let err = ctx
+            .sql("select ST_GeomFromText(wkt) from invalid")
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Row 2 'POINT(1 2'"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn geom_from_text_signatures() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(SridUdf::new()));
+        ctx.sql(
+            "create table t as select * from (values \
+            ('POINT(1 2)', 'SRID=3857;POINT(1 2)')) as t(wkt, ewkt)",
+        )
+        .await
+        .unwrap();
+        let df = ctx
+            .sql(
+                "select id, ST_AsText(geom) as wkt, ST_SRID(geom) as srid from (\
+                select 1 as id, ST_GeomFromText('POINT(1 2)') as geom \
+                union all select 2, ST_GeomFromText('POINT(1 2)', 4326) \
+                union all select 3, ST_GeomFromText('POINT(1 2)', 4326, 'parse') \
+                union all select 4, ST_GeomFromText('POINT(1 2)', 4326, 'parse', 'reject') \
+                union all select 5, ST_GeomFromText('POINT(1 2)', 'none') \
+                union all select 6, ST_GeomFromText('SRID=3857;POINT(1 2)') \
+                union all select 7, ST_GeomFromText('SRID=3857;POINT(1 2)', 4326) \
+                union all select 8, ST_GeomFromText(wkt) from t \
+                union all select 9, ST_GeomFromText(wkt, 4326) from t \
+                union all select 10, ST_GeomFromText(ewkt) from t \
+                union all select 11, st_geomfromtext(ewkt) from t) order by id",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+----+------------+------+
+| id | wkt        | srid |
++----+------------+------+
+| 1  | POINT(1 2) | 0    |
+| 2  | POINT(1 2) | 4326 |
+| 3  | POINT(1 2) | 4326 |
+| 4  | POINT(1 2) | 4326 |
+| 5  | POINT(1 2) | 0    |
+| 6  | POINT(1 2) | 3857 |
+| 7  | POINT(1 2) | 4326 |
+| 8  | POINT(1 2) | 0    |
+| 9  | POINT(1 2) | 4326 |
+| 10 | POINT(1 2) | 3857 |
+| 11 | POINT(1 2) | 3857 |
++----+------------+------+"
+        );
+    }
 }
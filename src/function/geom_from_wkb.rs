@@ -1,10 +1,13 @@
+use crate::config::default_dialect;
+use crate::function::args::{
+    geometry_args, ingestion_args, ingestion_signatures, scalar_if_constant,
+};
+use crate::geo::dialect::check_srid_storable;
 use crate::geo::GeometryArrayBuilder;
 use arrow_array::cast::AsArray;
 use arrow_schema::DataType;
-use datafusion_common::ScalarValue;
-use datafusion_common::{internal_datafusion_err, internal_err, DataFusionError};
-use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, TypeSignature, Volatility};
-use geozero::wkb::WkbDialect;
+use datafusion_common::{internal_datafusion_err, DataFusionError};
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
 use geozero::{GeozeroGeometry, ToWkb};
 use std::any::Any;
 use std::sync::Arc;
@@ -19,10 +22,7 @@ impl GeomFromWkbUdf {
     pub fn new() -> Self {
         Self {
             signature: Signature::one_of(
-                vec![
-                    TypeSignature::Exact(vec![DataType::Binary]),
-                    TypeSignature::Exact(vec![DataType::Binary, DataType::Int64]),
-                ],
+                ingestion_signatures(DataType::Binary),
                 Volatility::Immutable,
             ),
             aliases: vec!["st_geomfromwkb".to_string()],
@@ -48,31 +48,32 @@ impl ScalarUDFImpl for GeomFromWkbUdf {
     }
 
     fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
-        let srid = if args.len() == 2 {
-            let ColumnarValue::Scalar(ScalarValue::Int64(Some(srid))) = &args[1] else {
-                return internal_err!("The second arg should be int32");
-            };
-            Some(*srid as i32)
-        } else {
-            None
-        };
-        let arr = args[0].clone().into_array(1)?;
+        let ingestion = ingestion_args(args)?;
+        let (arrays, _recorder) = geometry_args(self.name(), &args[..1])?;
+        let arr = &arrays[0];
         let binary_arr = arr.as_binary::<i32>();
 
-        let mut builder = GeometryArrayBuilder::<i32>::new(WkbDialect::Ewkb, 1);
+        check_srid_storable(default_dialect(), ingestion.srid)?;
+        let mut builder = GeometryArrayBuilder::<i32>::new(default_dialect(), 1);
         for value in binary_arr.iter() {
             match value {
                 None => builder.append_null(),
                 Some(data) => {
                     let wkb = geozero::wkb::Wkb(data);
-                    let ewkb = wkb.to_ewkb(wkb.dims(), srid).map_err(|e| {
-                        internal_datafusion_err!("Failed to convert wkb to ewkb, error: {}", e)
-                    })?;
-                    builder.append_wkb(Some(&ewkb))?;
+                    let wkb = wkb
+                        .to_wkb_dialect(default_dialect(), wkb.dims(), ingestion.srid, vec![])
+                        .map_err(|e| {
+                            internal_datafusion_err!("Failed to convert wkb, error: {}", e)
+                        })?;
+                    builder.append_validated_wkb(
+                        &wkb,
+                        ingestion.validation,
+                        ingestion.on_invalid,
+                    )?;
                 }
             }
         }
-        Ok(ColumnarValue::Array(Arc::new(builder.build())))
+        scalar_if_constant(args, ColumnarValue::Array(Arc::new(builder.build())))
     }
 
     fn aliases(&self) -> &[String] {
@@ -90,6 +91,7 @@ impl Default for GeomFromWkbUdf {
 mod tests {
     use crate::function::geom_from_wkb::GeomFromWkbUdf;
     use crate::function::AsTextUdf;
+    use crate::pretty::pretty_format_geometry_batches;
     use arrow::util::pretty::pretty_format_batches;
     use datafusion::logical_expr::ScalarUDF;
     use datafusion::prelude::SessionContext;
@@ -115,6 +117,27 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn geom_from_xdr_wkb() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromWkbUdf::new()));
+        let df = ctx
+            .sql("select ST_GeomFromWKB(0x00000000013ff00000000000004000000000000000) as point, \
+            ST_GeomFromWKB(0x0000000003000000010000000400000000000000000000000000000000400000000000000000000000000000004000000000000000400000000000000000000000000000000000000000000000) as polygon")
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_geometry_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+------------+----------------------------+
+| point      | polygon                    |
++------------+----------------------------+
+| POINT(1 2) | POLYGON((0 0,2 0,2 2,0 0)) |
++------------+----------------------------+"
+        );
+    }
+
     #[cfg(feature = "geos")]
     #[tokio::test]
     async fn geom_from_wkb_with_srid() {
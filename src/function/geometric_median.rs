@@ -0,0 +1,333 @@
+use crate::config::default_dialect;
+use crate::function::args::geometry_args;
+use crate::geo::dialect::decode_srid;
+use crate::geo::{GeometryArray, GeometryArrayBuilder};
+use crate::DFResult;
+use arrow_array::cast::AsArray;
+use arrow_array::{GenericBinaryArray, OffsetSizeTrait};
+use arrow_schema::DataType;
+use datafusion_common::{exec_err, DataFusionError, ScalarValue};
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, TypeSignature, Volatility};
+use std::any::Any;
+use std::sync::Arc;
+
+const DEFAULT_MAX_ITERATIONS: i64 = 10000;
+
+/// Returns the geometric median of a (multi)point, i.e. the point minimizing the sum of distances
+/// to the input points, found by Weiszfeld's algorithm. The optional args are the tolerance of
+/// the convergence and the max number of iterations, the tolerance defaults to a tiny fraction
+/// of the largest coordinate like PostGIS.
+#[derive(Debug)]
+pub struct GeometricMedianUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl GeometricMedianUdf {
+    pub fn new() -> Self {
+        let mut type_signatures = vec![];
+        for geom_type in [DataType::Binary, DataType::LargeBinary] {
+            type_signatures.push(TypeSignature::Exact(vec![geom_type.clone()]));
+            type_signatures.push(TypeSignature::Exact(vec![
+                geom_type.clone(),
+                DataType::Float64,
+            ]));
+            type_signatures.push(TypeSignature::Exact(vec![
+                geom_type,
+                DataType::Float64,
+                DataType::Int64,
+            ]));
+        }
+        Self {
+            signature: Signature::one_of(type_signatures, Volatility::Immutable),
+            aliases: vec!["st_geometricmedian".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for GeometricMedianUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_GeometricMedian"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(arg_types[0].clone())
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let tolerance = match args.get(1) {
+            None => None,
+            Some(ColumnarValue::Scalar(ScalarValue::Float64(Some(tolerance)))) => {
+                if *tolerance < 0.0 {
+                    return exec_err!("Tolerance should not be negative");
+                }
+                Some(*tolerance)
+            }
+            Some(_) => return exec_err!("The second arg should be f64 scalar"),
+        };
+        let max_iterations = match args.get(2) {
+            None => DEFAULT_MAX_ITERATIONS,
+            Some(ColumnarValue::Scalar(ScalarValue::Int64(Some(max_iterations)))) => {
+                if *max_iterations < 0 {
+                    return exec_err!("Max iterations should not be negative");
+                }
+                *max_iterations
+            }
+            Some(_) => return exec_err!("The third arg should be int64 scalar"),
+        };
+
+        let (arrays, _recorder) = geometry_args(self.name(), &args[..1])?;
+        let arr = &arrays[0];
+        match arr.data_type() {
+            DataType::Binary => {
+                geometric_median::<i32>(arr.as_binary::<i32>(), tolerance, max_iterations)
+            }
+            DataType::LargeBinary => {
+                geometric_median::<i64>(arr.as_binary::<i64>(), tolerance, max_iterations)
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for GeometricMedianUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn geometric_median<O: OffsetSizeTrait>(
+    wkb_arr: &GenericBinaryArray<O>,
+    tolerance: Option<f64>,
+    max_iterations: i64,
+) -> DFResult<ColumnarValue> {
+    let mut builder = GeometryArrayBuilder::<O>::new(default_dialect(), wkb_arr.geom_len());
+    for i in 0..wkb_arr.geom_len() {
+        let Some(wkb) = wkb_arr.wkb(i) else {
+            builder.append_null();
+            continue;
+        };
+        let points = match wkb_arr.geo_value(i)? {
+            Some(geo::Geometry::Point(point)) => vec![point],
+            Some(geo::Geometry::MultiPoint(points)) => points.0,
+            Some(geom) => {
+                return exec_err!(
+                    "ST_GeometricMedian only supports (multi)points, got {:?}",
+                    geom
+                );
+            }
+            None => vec![],
+        };
+        let median = weiszfeld(&points, tolerance, max_iterations).map(geo::Geometry::Point);
+        builder.append_geo_geometry_with_srid(&median, decode_srid(wkb)?)?;
+    }
+    Ok(ColumnarValue::Array(Arc::new(builder.build())))
+}
+
+/// Iterates the weighted mean of the points starting from their centroid. Points which coincide
+/// with the current estimate get the Vardi-Zhang correction: they hold the estimate back against
+/// the pull of the other points, so an input point which is the median is kept rather than jumped
+/// over. None for no points.
+fn weiszfeld(
+    points: &[geo::Point],
+    tolerance: Option<f64>,
+    max_iterations: i64,
+) -> Option<geo::Point> {
+    if points.is_empty() {
+        return None;
+    }
+    let tolerance = tolerance.unwrap_or_else(|| {
+        let max_coord = points
+            .iter()
+            .map(|p| p.x().abs().max(p.y().abs()))
+            .fold(0.0, f64::max);
+        1e-10 * max_coord.max(1.0)
+    });
+
+    let count = points.len() as f64;
+    let mut median = geo::Point::new(
+        points.iter().map(|p| p.x()).sum::<f64>() / count,
+        points.iter().map(|p| p.y()).sum::<f64>() / count,
+    );
+    for _ in 0..max_iterations {
+        let (mut x, mut y, mut weights, mut coincident) = (0.0, 0.0, 0.0, 0.0);
+        for point in points {
+            let distance = (point.x() - median.x()).hypot(point.y() - median.y());
+            if distance > 0.0 {
+                x += point.x() / distance;
+                y += point.y() / distance;
+                weights += 1.0 / distance;
+            } else {
+                coincident += 1.0;
+            }
+        }
+        if weights == 0.0 {
+            break;
+        }
+        let mut next = geo::Point::new(x / weights, y / weights);
+        if coincident > 0.0 {
+            // the pull of the other points, the sum of their unit vectors from the estimate
+            let pull = (x - median.x() * weights).hypot(y - median.y() * weights);
+            if pull <= coincident {
+                // the coincident points outweigh the others, the estimate is the median
+                break;
+            }
+            let stay = coincident / pull;
+            next = geo::Point::new(
+                (1.0 - stay) * next.x() + stay * median.x(),
+                (1.0 - stay) * next.y() + stay * median.y(),
+            );
+        }
+        let delta = (next.x() - median.x()).hypot(next.y() - median.y());
+        median = next;
+        if delta <= tolerance {
+            break;
+        }
+    }
+    Some(median)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{AsTextUdf, GeomFromTextUdf, GeometricMedianUdf};
+    use crate::geo::GeometryArray;
+    use arrow::util::pretty::pretty_format_batches;
+    use arrow_array::cast::AsArray;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+    use geo::Centroid;
+
+    fn context() -> SessionContext {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(GeometricMedianUdf::new()));
+        ctx
+    }
+
+    #[tokio::test]
+    async fn geometric_median_of_symmetric_points() {
+        let ctx = context();
+        let df = ctx
+            .sql(
+                "select ST_AsText(ST_GeometricMedian(ST_GeomFromText(wkt))) as median from (values \
+                ('MULTIPOINT((0 0),(2 0),(2 2),(0 2))'), ('POINT(3 4)'), (null)) as t(wkt)",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+------------+
+| median     |
++------------+
+| POINT(1 1) |
+| POINT(3 4) |
+|            |
++------------+"
+        );
+    }
+
+    #[tokio::test]
+    async fn geometric_median_of_triangle() {
+        let ctx = context();
+        let df = ctx
+            .sql(
+                "select ST_GeomFromText('MULTIPOINT((0 0),(1 0),(0 1))'), \
+                ST_GeometricMedian(ST_GeomFromText('MULTIPOINT((0 0),(1 0),(0 1))'), 1e-12, 1000)",
+            )
+            .await
+            .unwrap();
+        let batches = df.collect().await.unwrap();
+        let points = batches[0]
+            .column(0)
+            .as_binary::<i32>()
+            .geo_value(0)
+            .unwrap()
+            .unwrap();
+        let geo::Geometry::Point(median) = batches[0]
+            .column(1)
+            .as_binary::<i32>()
+            .geo_value(0)
+            .unwrap()
+            .unwrap()
+        else {
+            panic!("geometric median should be a point");
+        };
+        // the fermat point of the triangle, every side is seen at 120 degrees
+        let expected = (3.0 - 3f64.sqrt()) / 6.0;
+        assert!((median.x() - expected).abs() < 1e-9);
+        assert!((median.y() - expected).abs() < 1e-9);
+        // unlike the centroid
+        let centroid = points.centroid().unwrap();
+        assert!((centroid.x() - median.x()).abs() > 0.1);
+    }
+
+    #[tokio::test]
+    async fn geometric_median_at_an_input_point() {
+        let ctx = context();
+        // the centroid is the doubled point, which outweighs the pull of the others
+        let df = ctx
+            .sql(
+                "select ST_AsText(ST_GeometricMedian(ST_GeomFromText(\
+                'MULTIPOINT((0 0),(0 0),(4 0),(-2 3),(-2 -3))'))) as median",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+------------+
+| median     |
++------------+
+| POINT(0 0) |
++------------+"
+        );
+
+        // a single coincident point is pulled off towards the others
+        let df = ctx
+            .sql(
+                "select ST_GeometricMedian(ST_GeomFromText(\
+                'MULTIPOINT((0 0),(1 1),(1 -1),(1 0),(-3 0))'), 1e-12, 10000)",
+            )
+            .await
+            .unwrap();
+        let batches = df.collect().await.unwrap();
+        let geo::Geometry::Point(median) = batches[0]
+            .column(0)
+            .as_binary::<i32>()
+            .geo_value(0)
+            .unwrap()
+            .unwrap()
+        else {
+            panic!("geometric median should be a point");
+        };
+        // the sides to (1 1) and (1 -1) are seen at 120 degrees
+        assert!((median.x() - (1.0 - 1.0 / 3f64.sqrt())).abs() < 1e-9);
+        assert!(median.y().abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn geometric_median_of_non_points() {
+        let ctx = context();
+        let df = ctx
+            .sql("select ST_GeometricMedian(ST_GeomFromText('LINESTRING(0 0,1 1)'))")
+            .await
+            .unwrap();
+        assert!(df.collect().await.is_err());
+    }
+}
@@ -0,0 +1,144 @@
+use crate::config::default_dialect;
+use crate::function::args::geometry_args;
+use crate::geo::dialect::decode_srid;
+use crate::geo::map::is_empty;
+use crate::geo::{GeometryArray, GeometryArrayBuilder};
+use crate::DFResult;
+use arrow_array::cast::AsArray;
+use arrow_array::types::Int64Type;
+use arrow_array::{Array, GenericBinaryArray, Int64Array, OffsetSizeTrait};
+use arrow_schema::DataType;
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, TypeSignature, Volatility};
+use std::any::Any;
+use std::sync::Arc;
+
+/// Returns the 1-based nth part of a multi geometry or a geometry collection, null when out of
+/// range. A single geometry is its only part, as counted by ST_NumGeometries.
+#[derive(Debug)]
+pub struct GeometryNUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl GeometryNUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                vec![
+                    TypeSignature::Exact(vec![DataType::Binary, DataType::Int64]),
+                    TypeSignature::Exact(vec![DataType::LargeBinary, DataType::Int64]),
+                ],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_geometryn".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for GeometryNUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_GeometryN"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(arg_types[0].clone())
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let n_arr = arrays[1].as_primitive::<Int64Type>();
+        match arrays[0].data_type() {
+            DataType::Binary => geometry_n::<i32>(arrays[0].as_binary::<i32>(), n_arr),
+            DataType::LargeBinary => geometry_n::<i64>(arrays[0].as_binary::<i64>(), n_arr),
+            _ => unreachable!(),
+        }
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for GeometryNUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn geometry_n<O: OffsetSizeTrait>(
+    wkb_arr: &GenericBinaryArray<O>,
+    n_arr: &Int64Array,
+) -> DFResult<ColumnarValue> {
+    let mut builder = GeometryArrayBuilder::<O>::new(default_dialect(), wkb_arr.geom_len());
+    for i in 0..wkb_arr.geom_len() {
+        let Some(wkb) = wkb_arr.wkb(i).filter(|_| n_arr.is_valid(i)) else {
+            builder.append_null();
+            continue;
+        };
+        let part = match (wkb_arr.geo_value(i)?, usize::try_from(n_arr.value(i))) {
+            (Some(geom), Ok(n)) if n >= 1 => parts(geom).into_iter().nth(n - 1),
+            _ => None,
+        };
+        builder.append_geo_geometry_with_srid(&part, decode_srid(wkb)?)?;
+    }
+    Ok(ColumnarValue::Array(Arc::new(builder.build())))
+}
+
+/// Parts of a geometry, the members of a multi geometry or a collection, or the geometry itself.
+pub(crate) fn parts(geom: geo::Geometry) -> Vec<geo::Geometry> {
+    match geom {
+        geo::Geometry::MultiPoint(points) => points.into_iter().map(Into::into).collect(),
+        geo::Geometry::MultiLineString(lines) => lines.into_iter().map(Into::into).collect(),
+        geo::Geometry::MultiPolygon(polygons) => polygons.into_iter().map(Into::into).collect(),
+        geo::Geometry::GeometryCollection(collection) => collection.into_iter().collect(),
+        geom if is_empty(&geom) => vec![],
+        geom => vec![geom],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{AsTextUdf, GeomFromTextUdf, GeometryNUdf, NumGeometriesUdf};
+    use arrow::util::pretty::pretty_format_batches;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+
+    #[tokio::test]
+    async fn geometry_n() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(NumGeometriesUdf::new()));
+        ctx.register_udf(ScalarUDF::from(GeometryNUdf::new()));
+        let df = ctx
+            .sql(
+                "select n, ST_NumGeometries(geom) as parts, ST_AsText(ST_GeometryN(geom, n)) as part \
+                from (select ST_GeomFromText('MULTIPOLYGON(((0 0,1 0,1 1,0 0)),((5 5,6 5,6 6,5 5)))') \
+                as geom) as g \
+                cross join (values (0), (1), (2), (5)) as t(n) order by n",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+---+-------+----------------------------+
+| n | parts | part                       |
++---+-------+----------------------------+
+| 0 | 2     |                            |
+| 1 | 2     | POLYGON((0 0,1 0,1 1,0 0)) |
+| 2 | 2     | POLYGON((5 5,6 5,6 6,5 5)) |
+| 5 | 2     |                            |
++---+-------+----------------------------+"
+        );
+    }
+}
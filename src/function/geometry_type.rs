@@ -1,6 +1,9 @@
+use crate::function::args::{geometry_args, scalar_if_constant};
+use crate::geo::dialect::WkbHeader;
 use crate::geo::GeometryArray;
+use crate::DFResult;
 use arrow_array::cast::AsArray;
-use arrow_array::{Array, StringArray};
+use arrow_array::{Array, LargeStringArray, StringArray};
 use arrow_schema::DataType;
 use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
 use std::any::Any;
@@ -38,18 +41,23 @@ impl ScalarUDFImpl for GeometryTypeUdf {
         &self.signature
     }
 
-    fn return_type(&self, _arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
-        Ok(DataType::Utf8)
+    fn return_type(&self, arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        match arg_types[0] {
+            DataType::Binary => Ok(DataType::Utf8),
+            DataType::LargeBinary => Ok(DataType::LargeUtf8),
+            _ => unreachable!(),
+        }
     }
 
     fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
-        let arr = args[0].clone().into_array(1)?;
-        match arr.data_type() {
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let arr = &arrays[0];
+        let value: DFResult<ColumnarValue> = match arr.data_type() {
             DataType::Binary => {
                 let wkb_arr = arr.as_binary::<i32>();
                 let mut type_vec = vec![];
                 for i in 0..wkb_arr.geom_len() {
-                    type_vec.push(wkb_arr.geo_value(i)?.map(geometry_type));
+                    type_vec.push(wkb_arr.wkb(i).map(header_geometry_type).transpose()?);
                 }
                 Ok(ColumnarValue::Array(Arc::new(StringArray::from(type_vec))))
             }
@@ -57,12 +65,15 @@ impl ScalarUDFImpl for GeometryTypeUdf {
                 let wkb_arr = arr.as_binary::<i64>();
                 let mut type_vec = vec![];
                 for i in 0..wkb_arr.geom_len() {
-                    type_vec.push(wkb_arr.geo_value(i)?.map(geometry_type));
+                    type_vec.push(wkb_arr.wkb(i).map(header_geometry_type).transpose()?);
                 }
-                Ok(ColumnarValue::Array(Arc::new(StringArray::from(type_vec))))
+                Ok(ColumnarValue::Array(Arc::new(LargeStringArray::from(
+                    type_vec,
+                ))))
             }
             _ => unreachable!(),
-        }
+        };
+        scalar_if_constant(args, value?)
     }
 
     fn aliases(&self) -> &[String] {
@@ -76,7 +87,24 @@ impl Default for GeometryTypeUdf {
     }
 }
 
-fn geometry_type(geom: geo::Geometry) -> &'static str {
+/// Names of the wkb geometry types 1 to 7.
+pub(crate) const TYPE_NAMES: [&str; 7] = [
+    "ST_Point",
+    "ST_LineString",
+    "ST_Polygon",
+    "ST_MultiPoint",
+    "ST_MultiLineString",
+    "ST_MultiPolygon",
+    "ST_GeometryCollection",
+];
+
+/// Reads the geometry type name from the wkb header.
+fn header_geometry_type(wkb: &[u8]) -> DFResult<&'static str> {
+    let header = WkbHeader::parse(wkb)?;
+    Ok(TYPE_NAMES[header.geometry_type as usize - 1])
+}
+
+pub(crate) fn geometry_type(geom: geo::Geometry) -> &'static str {
     match geom {
         geo::Geometry::Point(_) => "ST_Point",
         geo::Geometry::Line(_) => "ST_Line",
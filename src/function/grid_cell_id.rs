@@ -0,0 +1,123 @@
+use crate::function::args::{as_geometry_array, geometry_args};
+use crate::function::snap_point_to_grid::{grid_signatures, Grid};
+use arrow_array::{ArrayRef, Int64Array, StructArray};
+use arrow_buffer::NullBuffer;
+use arrow_schema::{DataType, Field};
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+use std::any::Any;
+use std::sync::Arc;
+
+/// Returns the column and row of the grid cell of a point as a struct of ix and iy, a cheaper
+/// grouping key than the geometry of ST_SnapPointToGrid for the same grid.
+#[derive(Debug)]
+pub struct GridCellIdUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl GridCellIdUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(grid_signatures(), Volatility::Immutable),
+            aliases: vec!["st_gridcellid".to_string()],
+        }
+    }
+
+    pub fn fields() -> Vec<Field> {
+        vec![
+            Field::new("ix", DataType::Int64, false),
+            Field::new("iy", DataType::Int64, false),
+        ]
+    }
+}
+
+impl ScalarUDFImpl for GridCellIdUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_GridCellId"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(DataType::Struct(Self::fields().into()))
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let grid = Grid::try_from_args(args)?;
+        let (arrays, _recorder) = geometry_args(self.name(), &args[..1])?;
+        let wkb_arr = as_geometry_array(&arrays[0])?;
+
+        let mut cell_vec = vec![];
+        for i in 0..wkb_arr.geom_len() {
+            let cell = match wkb_arr.geo_value(i)? {
+                Some(geom) => grid.cell(&geom)?,
+                None => None,
+            };
+            cell_vec.push(cell);
+        }
+
+        let nulls: NullBuffer = cell_vec
+            .iter()
+            .map(|cell| cell.is_some())
+            .collect::<Vec<_>>()
+            .into();
+        let ix = cell_vec.iter().map(|cell| cell.map(|c| c.0));
+        let iy = cell_vec.iter().map(|cell| cell.map(|c| c.1));
+        let columns = vec![
+            Arc::new(ix.collect::<Int64Array>()) as ArrayRef,
+            Arc::new(iy.collect::<Int64Array>()) as ArrayRef,
+        ];
+        let arr = StructArray::try_new(Self::fields().into(), columns, Some(nulls))?;
+        Ok(ColumnarValue::Array(Arc::new(arr)))
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for GridCellIdUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{GeomFromTextUdf, GridCellIdUdf};
+    use arrow::util::pretty::pretty_format_batches;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+
+    #[tokio::test]
+    async fn grid_cell_id() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(GridCellIdUdf::new()));
+        let df = ctx
+            .sql(
+                "select ST_GridCellId(ST_GeomFromText(wkt), 1.0) as cell \
+                from (values ('POINT(1.2 3.7)'), ('POINT(-0.2 -1)'), (null)) as t(wkt)",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+------------------+
+| cell             |
++------------------+
+| {ix: 1, iy: 3}   |
+| {ix: -1, iy: -1} |
+|                  |
++------------------+"
+        );
+    }
+}
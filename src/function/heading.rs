@@ -0,0 +1,135 @@
+use crate::function::args::as_geometry_array;
+use crate::geo::GeometryArray;
+use arrow_array::{ArrayRef, Float64Array};
+use arrow_schema::DataType;
+use datafusion_common::{exec_err, DataFusionError};
+use datafusion_expr::{PartitionEvaluator, Signature, Volatility, WindowUDFImpl};
+use std::any::Any;
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+/// Returns the azimuth in radians from the current point to the next point of the window
+/// partition, clockwise from north. Null for the last row or when the next point is the same.
+#[derive(Debug)]
+pub struct HeadingUdwf {
+    signature: Signature,
+}
+
+impl HeadingUdwf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::uniform(
+                1,
+                vec![DataType::Binary, DataType::LargeBinary],
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl WindowUDFImpl for HeadingUdwf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        // udwf not support alias
+        "st_heading"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(DataType::Float64)
+    }
+
+    fn partition_evaluator(&self) -> datafusion_common::Result<Box<dyn PartitionEvaluator>> {
+        Ok(Box::new(HeadingEvaluator))
+    }
+}
+
+impl Default for HeadingUdwf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug)]
+struct HeadingEvaluator;
+
+impl PartitionEvaluator for HeadingEvaluator {
+    fn evaluate_all(
+        &mut self,
+        values: &[ArrayRef],
+        num_rows: usize,
+    ) -> datafusion_common::Result<ArrayRef> {
+        let wkb_arr = as_geometry_array(&values[0])?;
+        let mut points = Vec::with_capacity(num_rows);
+        for i in 0..num_rows {
+            match wkb_arr.geo_value(i)? {
+                Some(geo::Geometry::Point(point)) => points.push(Some(point)),
+                Some(geom) => {
+                    return exec_err!("ST_Heading only supports points, got {:?}", geom);
+                }
+                None => points.push(None),
+            }
+        }
+
+        let mut headings = points
+            .windows(2)
+            .map(|pair| match pair {
+                [Some(current), Some(next)] if current != next => Some(azimuth(current, next)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        headings.resize(num_rows, None);
+        Ok(Arc::new(Float64Array::from(headings)))
+    }
+}
+
+fn azimuth(from: &geo::Point, to: &geo::Point) -> f64 {
+    let azimuth = (to.x() - from.x()).atan2(to.y() - from.y());
+    if azimuth < 0.0 {
+        azimuth + 2.0 * PI
+    } else {
+        azimuth
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{GeomFromTextUdf, HeadingUdwf};
+    use arrow::util::pretty::pretty_format_batches;
+    use datafusion::logical_expr::{ScalarUDF, WindowUDF};
+    use datafusion::prelude::SessionContext;
+
+    #[tokio::test]
+    async fn heading() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udwf(WindowUDF::from(HeadingUdwf::new()));
+        let df = ctx
+            .sql(
+                "select id, ST_Heading(ST_GeomFromText(wkt)) over (order by ts) as heading \
+                from (values (1, 10, 'POINT(0 0)'), (2, 20, 'POINT(0 1)'), \
+                (3, 30, 'POINT(1 1)'), (4, 40, 'POINT(1 0)')) as t(id, ts, wkt) order by id",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+----+--------------------+
+| id | heading            |
++----+--------------------+
+| 1  | 0.0                |
+| 2  | 1.5707963267948966 |
+| 3  | 3.141592653589793  |
+| 4  |                    |
++----+--------------------+"
+        );
+    }
+}
@@ -0,0 +1,185 @@
+use crate::function::args::{geometry_args, geos_overlay};
+use crate::function::clip_by_box2d::clip_by_box2d;
+use crate::geo::overlay::Overlay;
+use crate::geo::Box2d;
+use arrow_array::cast::AsArray;
+use arrow_schema::DataType;
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, TypeSignature, Volatility};
+use std::any::Any;
+
+/// Returns the shared part of two geometries. A box2d second arg clips the geometry by the
+/// rectangle without a general overlay, see `ST_ClipByBox2D`. An optional grid size runs the fixed
+/// precision overlay, like with `ST_Union`.
+#[derive(Debug)]
+pub struct IntersectionUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl IntersectionUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                vec![
+                    TypeSignature::Exact(vec![DataType::Binary, DataType::Binary]),
+                    TypeSignature::Exact(vec![DataType::LargeBinary, DataType::LargeBinary]),
+                    TypeSignature::Exact(vec![
+                        DataType::Binary,
+                        DataType::Binary,
+                        DataType::Float64,
+                    ]),
+                    TypeSignature::Exact(vec![
+                        DataType::LargeBinary,
+                        DataType::LargeBinary,
+                        DataType::Float64,
+                    ]),
+                    TypeSignature::Exact(vec![DataType::Binary, Box2d::data_type()]),
+                    TypeSignature::Exact(vec![DataType::LargeBinary, Box2d::data_type()]),
+                ],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_intersection".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for IntersectionUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_Intersection"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(arg_types[0].clone())
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        if let DataType::Struct(_) = args[1].data_type() {
+            let (arrays, _recorder) = geometry_args(self.name(), &args[..2])?;
+            let box_arr = arrays[1].as_struct();
+            return match arrays[0].data_type() {
+                DataType::Binary => clip_by_box2d::<i32>(arrays[0].as_binary::<i32>(), box_arr),
+                DataType::LargeBinary => {
+                    clip_by_box2d::<i64>(arrays[0].as_binary::<i64>(), box_arr)
+                }
+                _ => unreachable!(),
+            };
+        }
+        geos_overlay(self.name(), args, Overlay::Intersection)
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for IntersectionUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::box2d::Box2dUdf;
+    use crate::function::{AsTextUdf, GeomFromTextUdf, IntersectionUdf};
+    use arrow::util::pretty::pretty_format_batches;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+
+    #[tokio::test]
+    async fn intersection() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(Box2dUdf::new()));
+        ctx.register_udf(ScalarUDF::from(IntersectionUdf::new()));
+        let df = ctx
+            .sql(
+                "select ST_AsText(ST_Intersection(ST_GeomFromText('LINESTRING(-1 1,3 1)'), \
+                ST_GeomFromText('POLYGON((0 0,2 0,2 2,0 2,0 0))'))) as general, \
+                ST_AsText(ST_Intersection(ST_GeomFromText('LINESTRING(-1 1,3 1)'), \
+                Box2D(ST_GeomFromText('LINESTRING(0 0,2 2)')))) as box2d",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+---------------------+----------------------------+
+| general             | box2d                      |
++---------------------+----------------------------+
+| LINESTRING(0 1,2 1) | MULTILINESTRING((0 1,2 1)) |
++---------------------+----------------------------+"
+        );
+    }
+
+    #[tokio::test]
+    async fn intersection_grid_size() {
+        use crate::geo::GeometryArray;
+        use arrow_array::cast::AsArray;
+        use geo::Area;
+
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(IntersectionUdf::new()));
+        // squares overlapping by a 1e-9 sliver, which collapses on a 1e-7 grid
+        let sql = "select ST_Intersection(ST_GeomFromText(a), ST_GeomFromText(b)), \
+            ST_Intersection(ST_GeomFromText(a), ST_GeomFromText(b), 1e-7) \
+            from (values ('POLYGON((0 0,1 0,1 1,0 1,0 0))', \
+            'POLYGON((0.999999999 0,2 0,2 1,0.999999999 1,0.999999999 0))')) as t(a, b)";
+        let batches = ctx.sql(sql).await.unwrap().collect().await.unwrap();
+        let exact = batches[0].column(0).as_binary::<i32>();
+        assert!(exact.geo_value(0).unwrap().unwrap().unsigned_area() > 0.0);
+        let snapped = batches[0].column(1).as_binary::<i32>();
+        assert_eq!(snapped.geo_value(0).unwrap().unwrap().unsigned_area(), 0.0);
+    }
+
+    #[tokio::test]
+    async fn intersection_grid_size_snaps_to_grid() {
+        use crate::geo::GeometryArray;
+        use arrow_array::cast::AsArray;
+        use geo::{Area, CoordsIter};
+
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(IntersectionUdf::new()));
+        // the left edge crosses itself in a 1e-9 wide bow-tie, which collapses on a 1e-7 grid
+        let from = "from (values ('POLYGON((0 0,10 0,10 10,0 10,0 5.000000002,\
+            0.000000001 4.999999998,0.000000001 5.000000002,0 4.999999998,0 0))', \
+            'POLYGON((-1 -1,5 -1,5 11,-1 11,-1 -1))')) as t(a, b)";
+        let batches = ctx
+            .sql(&format!(
+                "select ST_Intersection(ST_GeomFromText(a), ST_GeomFromText(b), 1e-7) {}",
+                from
+            ))
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        let snapped = batches[0].column(0).as_binary::<i32>();
+        let snapped = snapped.geo_value(0).unwrap().unwrap();
+        assert_eq!(snapped.unsigned_area(), 50.0);
+        for coord in snapped.coords_iter() {
+            assert!(
+                (coord.x * 1e7 - (coord.x * 1e7).round()).abs() < 1e-6,
+                "{:?}",
+                coord
+            );
+            assert!(
+                (coord.y * 1e7 - (coord.y * 1e7).round()).abs() < 1e-6,
+                "{:?}",
+                coord
+            );
+        }
+    }
+}
@@ -1,14 +1,10 @@
-use crate::geo::GeometryArray;
-use crate::DFResult;
-use arrow_array::cast::AsArray;
-use arrow_array::{Array, BooleanArray, GenericBinaryArray, OffsetSizeTrait};
+use crate::function::args::{box_predicate, box_predicate_signatures, point_in_polygon};
 use arrow_schema::DataType;
-use datafusion_common::{internal_err, DataFusionError};
 use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
-use rayon::prelude::*;
+use geo::Intersects;
 use std::any::Any;
-use std::sync::Arc;
 
+/// Whether two geometries share a point, either side may also be a box2d.
 #[derive(Debug)]
 pub struct IntersectsUdf {
     signature: Signature,
@@ -18,11 +14,7 @@ pub struct IntersectsUdf {
 impl IntersectsUdf {
     pub fn new() -> Self {
         Self {
-            signature: Signature::uniform(
-                2,
-                vec![DataType::Binary, DataType::LargeBinary],
-                Volatility::Immutable,
-            ),
+            signature: Signature::one_of(box_predicate_signatures(), Volatility::Immutable),
             aliases: vec!["st_intersects".to_string()],
         }
     }
@@ -46,43 +38,31 @@ impl ScalarUDFImpl for IntersectsUdf {
     }
 
     fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
-        let (arr0, arr1) = match (args[0].clone(), args[1].clone()) {
-            (ColumnarValue::Array(arr0), ColumnarValue::Array(arr1)) => (arr0, arr1),
-            (ColumnarValue::Array(arr0), ColumnarValue::Scalar(scalar)) => {
-                (arr0.clone(), scalar.to_array_of_size(arr0.len())?)
-            }
-            (ColumnarValue::Scalar(scalar), ColumnarValue::Array(arr1)) => {
-                (scalar.to_array_of_size(arr1.len())?, arr1)
-            }
-            (ColumnarValue::Scalar(scalar0), ColumnarValue::Scalar(scalar1)) => {
-                (scalar0.to_array_of_size(1)?, scalar1.to_array_of_size(1)?)
-            }
-        };
-        if arr0.len() != arr1.len() {
-            return internal_err!("Two arrays length is not same");
+        if let Some(result) =
+            box_predicate(self.name(), args, |geom0, geom1| geom0.intersects(geom1))?
+        {
+            return Ok(result);
         }
-        match (arr0.data_type(), arr1.data_type()) {
-            (DataType::Binary, DataType::Binary) => {
-                let arr0 = arr0.as_binary::<i32>();
-                let arr1 = arr1.as_binary::<i32>();
-                intersects::<i32, i32>(arr0, arr1)
-            }
-            (DataType::LargeBinary, DataType::Binary) => {
-                let arr0 = arr0.as_binary::<i64>();
-                let arr1 = arr1.as_binary::<i32>();
-                intersects::<i64, i32>(arr0, arr1)
-            }
-            (DataType::Binary, DataType::LargeBinary) => {
-                let arr0 = arr0.as_binary::<i32>();
-                let arr1 = arr1.as_binary::<i64>();
-                intersects::<i32, i64>(arr0, arr1)
-            }
-            (DataType::LargeBinary, DataType::LargeBinary) => {
-                let arr0 = arr0.as_binary::<i64>();
-                let arr1 = arr1.as_binary::<i64>();
-                intersects::<i64, i64>(arr0, arr1)
+        for polygon_index in [0, 1] {
+            if let Some(result) = point_in_polygon(self.name(), args, polygon_index)? {
+                return Ok(result);
             }
-            _ => unreachable!(),
+        }
+        #[cfg(feature = "geos")]
+        {
+            use crate::function::args::geos_predicate;
+            use datafusion_common::{internal_datafusion_err, DataFusionError};
+            use geos::Geom;
+            geos_predicate(self.name(), args, |geom0, geom1| {
+                geom0
+                    .intersects(geom1)
+                    .map_err(|e| internal_datafusion_err!("Failed to do intersects, error: {}", e))
+            })
+        }
+        #[cfg(not(feature = "geos"))]
+        {
+            use crate::function::args::geo_predicate;
+            geo_predicate(self.name(), args, |geom0, geom1| geom0.intersects(geom1))
         }
     }
 
@@ -97,42 +77,9 @@ impl Default for IntersectsUdf {
     }
 }
 
-fn intersects<O: OffsetSizeTrait, F: OffsetSizeTrait>(
-    arr0: &GenericBinaryArray<O>,
-    arr1: &GenericBinaryArray<F>,
-) -> DFResult<ColumnarValue> {
-    let bool_vec = (0..arr0.geom_len())
-        .into_par_iter()
-        .map(|geom_index| {
-            #[cfg(feature = "geos")]
-            {
-                use datafusion_common::internal_datafusion_err;
-                use geos::Geom;
-                match (arr0.geos_value(geom_index)?, arr1.geos_value(geom_index)?) {
-                    (Some(geom0), Some(geom1)) => {
-                        let result = geom0.intersects(&geom1).map_err(|e| {
-                            internal_datafusion_err!("Failed to do intersects, error: {}", e)
-                        })?;
-                        Ok(Some(result))
-                    }
-                    _ => Ok(None),
-                }
-            }
-            #[cfg(not(feature = "geos"))]
-            {
-                use geo::Intersects;
-                match (arr0.geo_value(geom_index)?, arr1.geo_value(geom_index)?) {
-                    (Some(geom0), Some(geom1)) => Ok(Some(geom0.intersects(&geom1))),
-                    _ => Ok(None),
-                }
-            }
-        })
-        .collect::<DFResult<Vec<Option<bool>>>>()?;
-    Ok(ColumnarValue::Array(Arc::new(BooleanArray::from(bool_vec))))
-}
-
 #[cfg(test)]
 mod tests {
+    use crate::function::box2d::Box2dUdf;
     use crate::function::{GeomFromTextUdf, IntersectsUdf};
     use crate::geo::GeometryArrayBuilder;
     use arrow::util::pretty::pretty_format_batches;
@@ -187,7 +134,7 @@ mod tests {
             ];
             linestrint_vec.push(Some(linestring));
         }
-        let builder: GeometryArrayBuilder<i32> = linestrint_vec.as_slice().into();
+        let builder: GeometryArrayBuilder<i32> = linestrint_vec.as_slice().try_into().unwrap();
         let record = RecordBatch::try_new(schema.clone(), vec![Arc::new(builder.build())]).unwrap();
 
         let mem_table =
@@ -215,4 +162,93 @@ mod tests {
 +--------------------------------------------------------------------+"
         );
     }
+
+    #[tokio::test]
+    async fn intersects_points_in_polygon() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(IntersectsUdf::new()));
+        let sql = |rows: &str| {
+            format!(
+                "select wkt, \
+                ST_Intersects(ST_GeomFromText('POLYGON((0 0,10 0,10 10,0 10,0 0))'), ST_GeomFromText(wkt, 4326)) as polygon_point, \
+                ST_Intersects(ST_GeomFromText(wkt, 4326), ST_GeomFromText('POLYGON((0 0,10 0,10 10,0 10,0 0))')) as point_polygon \
+                from (values {}) as t(wkt)",
+                rows
+            )
+        };
+
+        // all rows are points, the fast path applies
+        let df = ctx
+            .sql(&sql(
+                "('POINT(5 5)'), ('POINT(10 5)'), ('POINT(20 20)'), (null)",
+            ))
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+--------------+---------------+---------------+
+| wkt          | polygon_point | point_polygon |
++--------------+---------------+---------------+
+| POINT(5 5)   | true          | true          |
+| POINT(10 5)  | true          | true          |
+| POINT(20 20) | false         | false         |
+|              |               |               |
++--------------+---------------+---------------+"
+        );
+
+        // a linestring row falls back to the generic predicate
+        let df = ctx
+            .sql(&sql("('POINT(5 5)'), ('POINT(10 5)'), ('POINT(20 20)'), (null), ('LINESTRING(20 20,30 30)')"))
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+-------------------------+---------------+---------------+
+| wkt                     | polygon_point | point_polygon |
++-------------------------+---------------+---------------+
+| POINT(5 5)              | true          | true          |
+| POINT(10 5)             | true          | true          |
+| POINT(20 20)            | false         | false         |
+|                         |               |               |
+| LINESTRING(20 20,30 30) | false         | false         |
++-------------------------+---------------+---------------+"
+        );
+    }
+
+    #[tokio::test]
+    async fn intersects_box2d() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(Box2dUdf::new()));
+        ctx.register_udf(ScalarUDF::from(IntersectsUdf::new()));
+        let df = ctx
+            .sql(
+                "select wkt, ST_Intersects(b, ST_GeomFromText(wkt)) as box_geom, \
+                ST_Intersects(ST_GeomFromText(wkt), b) as geom_box, \
+                ST_Intersects(b, Box2D(ST_GeomFromText(wkt))) as box_box \
+                from (select wkt, Box2D(ST_GeomFromText('LINESTRING(0 0,2 2)')) as b \
+                from (values ('POINT(1 1)'), ('LINESTRING(3 3,4 4)'), \
+                ('LINESTRING(-1 1,1 -1)'), (null)) as t(wkt))",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+-----------------------+----------+----------+---------+
+| wkt                   | box_geom | geom_box | box_box |
++-----------------------+----------+----------+---------+
+| POINT(1 1)            | true     | true     | true    |
+| LINESTRING(3 3,4 4)   | false    | false    | false   |
+| LINESTRING(-1 1,1 -1) | true     | true     | true    |
+|                       |          |          |         |
++-----------------------+----------+----------+---------+"
+        );
+    }
 }
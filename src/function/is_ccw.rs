@@ -0,0 +1,72 @@
+use crate::function::args::{as_geometry_array, geometry_args};
+use crate::function::orientation::orientation;
+use arrow_array::BooleanArray;
+use arrow_schema::DataType;
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+use std::any::Any;
+use std::sync::Arc;
+
+/// Whether the exterior ring(s) of a polygon are counter clockwise, rings without area are not.
+/// Null in the cases ST_Orientation is null.
+#[derive(Debug)]
+pub struct IsCCWUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl IsCCWUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::uniform(
+                1,
+                vec![DataType::Binary, DataType::LargeBinary],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_isccw".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for IsCCWUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_IsCCW"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(DataType::Boolean)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let wkb_arr = as_geometry_array(&arrays[0])?;
+
+        let mut bool_vec = vec![];
+        for i in 0..wkb_arr.geom_len() {
+            bool_vec.push(
+                wkb_arr
+                    .geo_value(i)?
+                    .and_then(|geom| orientation(&geom))
+                    .map(|orientation| orientation == "CCW"),
+            );
+        }
+        Ok(ColumnarValue::Array(Arc::new(BooleanArray::from(bool_vec))))
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for IsCCWUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
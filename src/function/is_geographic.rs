@@ -0,0 +1,109 @@
+use crate::function::args::{as_geometry_array, geometry_args};
+use crate::geo::crs_info;
+use crate::geo::dialect::decode_srid;
+use arrow_array::BooleanArray;
+use arrow_schema::DataType;
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+use std::any::Any;
+use std::sync::Arc;
+
+/// Returns whether the geometry srid is a geographic coordinate reference system,
+/// null if the srid is missing or unknown.
+#[derive(Debug)]
+pub struct IsGeographicUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl IsGeographicUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::uniform(
+                1,
+                vec![DataType::Binary, DataType::LargeBinary],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_isgeographic".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for IsGeographicUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_IsGeographic"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(DataType::Boolean)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let wkb_arr = as_geometry_array(&arrays[0])?;
+
+        let mut bool_vec = vec![];
+        for i in 0..wkb_arr.geom_len() {
+            let is_geographic = match wkb_arr.wkb(i) {
+                Some(wkb) => decode_srid(wkb)?
+                    .and_then(crs_info)
+                    .map(|crs| crs.is_geographic),
+                None => None,
+            };
+            bool_vec.push(is_geographic);
+        }
+        Ok(ColumnarValue::Array(Arc::new(BooleanArray::from(bool_vec))))
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for IsGeographicUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{GeomFromTextUdf, IsGeographicUdf};
+    use arrow::util::pretty::pretty_format_batches;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+
+    #[tokio::test]
+    async fn is_geographic() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(IsGeographicUdf::new()));
+        let df = ctx
+            .sql(
+                "select ST_IsGeographic(ST_GeomFromText('POINT(1 1)', 4326)) as wgs84, \
+            ST_IsGeographic(ST_GeomFromText('POINT(1 1)', 3857)) as web_mercator, \
+            ST_IsGeographic(ST_GeomFromText('POINT(1 1)', 2154)) as lambert93, \
+            ST_IsGeographic(ST_GeomFromText('POINT(1 1)', 99999)) as unknown, \
+            ST_IsGeographic(ST_GeomFromText('POINT(1 1)')) as no_srid",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+-------+--------------+-----------+---------+---------+
+| wgs84 | web_mercator | lambert93 | unknown | no_srid |
++-------+--------------+-----------+---------+---------+
+| true  | false        | false     |         |         |
++-------+--------------+-----------+---------+---------+"
+        );
+    }
+}
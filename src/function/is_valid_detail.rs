@@ -0,0 +1,191 @@
+use crate::config::default_dialect;
+use crate::function::args::{as_geometry_array, geometry_args};
+use crate::geo::dialect::decode_srid;
+use crate::geo::{build_struct_array, GeometryArray, GeometryArrayBuilder};
+use crate::DFResult;
+use arrow_array::{ArrayRef, BooleanArray, StringArray};
+use arrow_schema::{DataType, Field};
+use datafusion_common::{internal_err, DataFusionError};
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+use geos::{AsRaw, ContextHandling};
+use std::any::Any;
+use std::ffi::CStr;
+use std::sync::Arc;
+
+/// Returns a struct of whether the geometry is valid, the reason of the invalidity and a point
+/// geometry marking where it is, reason and location are null for valid geometries.
+#[derive(Debug)]
+pub struct IsValidDetailUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl IsValidDetailUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::uniform(
+                1,
+                vec![DataType::Binary, DataType::LargeBinary],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_isvaliddetail".to_string()],
+        }
+    }
+
+    pub fn fields() -> Vec<Field> {
+        vec![
+            Field::new("valid", DataType::Boolean, false),
+            Field::new("reason", DataType::Utf8, true),
+            Field::new("location", DataType::Binary, true),
+        ]
+    }
+}
+
+impl ScalarUDFImpl for IsValidDetailUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_IsValidDetail"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(DataType::Struct(Self::fields().into()))
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let wkb_arr = as_geometry_array(&arrays[0])?;
+
+        let mut not_null_vec = vec![];
+        let mut valid_vec = vec![];
+        let mut reason_vec = vec![];
+        let mut location_builder = GeometryArrayBuilder::<i32>::new(default_dialect(), 0);
+        for i in 0..wkb_arr.geom_len() {
+            let Some(wkb) = wkb_arr.wkb(i) else {
+                not_null_vec.push(false);
+                valid_vec.push(false);
+                reason_vec.push(None);
+                location_builder.append_null();
+                continue;
+            };
+            let geom = wkb_arr.geos_value(i)?.expect("wkb is not null");
+            let (reason, location) = is_valid_detail(&geom)?;
+            not_null_vec.push(true);
+            valid_vec.push(reason.is_none());
+            reason_vec.push(reason);
+            location_builder.append_geo_geometry_with_srid(
+                &location.map(geo::Geometry::Point),
+                decode_srid(wkb)?,
+            )?;
+        }
+
+        let columns = vec![
+            Arc::new(BooleanArray::from(valid_vec)) as ArrayRef,
+            Arc::new(StringArray::from(reason_vec)) as ArrayRef,
+            Arc::new(location_builder.build()) as ArrayRef,
+        ];
+        let arr = build_struct_array(Self::fields(), columns, &not_null_vec)?;
+        Ok(ColumnarValue::Array(Arc::new(arr)))
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for IsValidDetailUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the reason and the location of the invalidity, both are none for valid geometries.
+fn is_valid_detail(geom: &geos::Geometry) -> DFResult<(Option<String>, Option<geo::Point>)> {
+    let handle = geom.get_raw_context();
+    let mut reason = std::ptr::null_mut();
+    let mut location = std::ptr::null_mut();
+    // SAFETY: the geometry is alive for the call, reason and location are owned by the caller
+    // and freed below
+    let ret = unsafe {
+        geos_sys::GEOSisValidDetail_r(handle, geom.as_raw(), 0, &mut reason, &mut location)
+    };
+    match ret {
+        1 => return Ok((None, None)),
+        0 => {}
+        _ => return internal_err!("Failed to do is valid detail"),
+    }
+    let text = if reason.is_null() {
+        None
+    } else {
+        // SAFETY: geos returns a nul terminated string allocated with its own allocator
+        unsafe {
+            let text = CStr::from_ptr(reason).to_string_lossy().into_owned();
+            geos_sys::GEOSFree_r(handle, reason.cast());
+            Some(text)
+        }
+    };
+    let point = if location.is_null() {
+        None
+    } else {
+        let (mut x, mut y) = (0.0, 0.0);
+        // SAFETY: the location is a point owned by the caller, it is destroyed after reading
+        unsafe {
+            let read = geos_sys::GEOSGeomGetX_r(handle, location, &mut x) == 1
+                && geos_sys::GEOSGeomGetY_r(handle, location, &mut y) == 1;
+            geos_sys::GEOSGeom_destroy_r(handle, location);
+            read.then(|| geo::Point::new(x, y))
+        }
+    };
+    Ok((text, point))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{GeomFromTextUdf, IsValidDetailUdf};
+    use crate::geo::GeometryArray;
+    use arrow_array::cast::AsArray;
+    use arrow_array::Array;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+
+    #[tokio::test]
+    async fn is_valid_detail() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(IsValidDetailUdf::new()));
+        let df = ctx
+            .sql(
+                "select ST_IsValidDetail(ST_GeomFromText(wkt)) from (values \
+                ('POLYGON((0 0,2 2,2 0,0 2,0 0))'), ('POLYGON((0 0,1 0,1 1,0 0))'), (null)) \
+                as t(wkt)",
+            )
+            .await
+            .unwrap();
+        let batches = df.collect().await.unwrap();
+        let arr = batches[0].column(0).as_struct();
+        let valid = arr.column(0).as_boolean();
+        let reason = arr.column(1).as_string::<i32>();
+        let location = arr.column(2).as_binary::<i32>();
+
+        // the bowtie crosses itself in the middle
+        assert!(!valid.value(0));
+        assert_eq!(reason.value(0), "Self-intersection");
+        let geo::Geometry::Point(point) = location.geo_value(0).unwrap().unwrap() else {
+            panic!("location should be a point");
+        };
+        assert!((point.x() - 1.0).abs() < 1e-6);
+        assert!((point.y() - 1.0).abs() < 1e-6);
+
+        assert!(valid.value(1));
+        assert!(reason.is_null(1));
+        assert!(location.is_null(1));
+
+        assert!(arr.is_null(2));
+    }
+}
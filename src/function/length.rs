@@ -0,0 +1,116 @@
+use crate::function::args::{as_geometry_array, geometry_args};
+use arrow_array::Float64Array;
+use arrow_schema::DataType;
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+use geo::EuclideanLength;
+use std::any::Any;
+use std::sync::Arc;
+
+/// Returns the 2d length of linear geometries, 0 for other types.
+#[derive(Debug)]
+pub struct LengthUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl LengthUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::uniform(
+                1,
+                vec![DataType::Binary, DataType::LargeBinary],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_length".to_string(), "st_length2d".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for LengthUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_Length"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(DataType::Float64)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let wkb_arr = as_geometry_array(&arrays[0])?;
+
+        let mut length_vec = vec![];
+        for i in 0..wkb_arr.geom_len() {
+            length_vec.push(wkb_arr.geo_value(i)?.map(|geom| length(&geom)));
+        }
+        Ok(ColumnarValue::Array(Arc::new(Float64Array::from(
+            length_vec,
+        ))))
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for LengthUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn length(geom: &geo::Geometry) -> f64 {
+    match geom {
+        geo::Geometry::Line(line) => line.euclidean_length(),
+        geo::Geometry::LineString(line_string) => line_string.euclidean_length(),
+        geo::Geometry::MultiLineString(multi_line_string) => multi_line_string.euclidean_length(),
+        geo::Geometry::GeometryCollection(collection) => collection.iter().map(length).sum(),
+        _ => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{GeomFromTextUdf, LengthUdf};
+    use arrow::util::pretty::pretty_format_batches;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+
+    #[tokio::test]
+    async fn length() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(LengthUdf::new()));
+        let df = ctx
+            .sql(
+                "select ST_Length2D(ST_GeomFromText(wkt)) as length from (values \
+            ('LINESTRING(0 0,3 4,3 10)'), \
+            ('MULTILINESTRING((0 0,10 0),(0 0,0 5))'), \
+            ('POLYGON((0 0,10 0,10 10,0 10,0 0))'), \
+            (null)) as t(wkt)",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+--------+
+| length |
++--------+
+| 11.0   |
+| 15.0   |
+| 0.0    |
+|        |
++--------+"
+        );
+    }
+}
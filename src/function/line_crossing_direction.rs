@@ -0,0 +1,238 @@
+use crate::function::args::{as_geometry_array, geometry_args};
+use crate::DFResult;
+use arrow_array::Int32Array;
+use arrow_schema::DataType;
+use datafusion_common::{exec_err, DataFusionError};
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+use geo::Coord;
+use std::any::Any;
+use std::sync::Arc;
+
+const LINE_NO_CROSS: i32 = 0;
+const LINE_CROSS_LEFT: i32 = -1;
+const LINE_CROSS_RIGHT: i32 = 1;
+const LINE_MULTICROSS_END_LEFT: i32 = -2;
+const LINE_MULTICROSS_END_RIGHT: i32 = 2;
+const LINE_MULTICROSS_END_SAME_FIRST_LEFT: i32 = -3;
+const LINE_MULTICROSS_END_SAME_FIRST_RIGHT: i32 = 3;
+
+/// Returns how the second linestring crosses the first one, using the PostGIS codes:
+/// 0 no cross, -1 cross left, 1 cross right, -2 multicross end left, 2 multicross end right,
+/// -3 multicross end same first left, 3 multicross end same first right.
+#[derive(Debug)]
+pub struct LineCrossingDirectionUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl LineCrossingDirectionUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::uniform(
+                2,
+                vec![DataType::Binary, DataType::LargeBinary],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_linecrossingdirection".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for LineCrossingDirectionUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_LineCrossingDirection"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(DataType::Int32)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let arr0 = as_geometry_array(&arrays[0])?;
+        let arr1 = as_geometry_array(&arrays[1])?;
+
+        let mut direction_vec = vec![];
+        for i in 0..arr0.geom_len() {
+            let direction = match (arr0.geo_value(i)?, arr1.geo_value(i)?) {
+                (Some(geom0), Some(geom1)) => Some(line_crossing_direction(
+                    &as_line_string(geom0)?,
+                    &as_line_string(geom1)?,
+                )),
+                _ => None,
+            };
+            direction_vec.push(direction);
+        }
+        Ok(ColumnarValue::Array(Arc::new(Int32Array::from(
+            direction_vec,
+        ))))
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for LineCrossingDirectionUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn as_line_string(geom: geo::Geometry) -> DFResult<geo::LineString> {
+    match geom {
+        geo::Geometry::LineString(line_string) => Ok(line_string),
+        geo::Geometry::Line(line) => Ok(line.into()),
+        _ => exec_err!("ST_LineCrossingDirection only supports linestring args"),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SegmentCross {
+    None,
+    Left,
+    Right,
+}
+
+/// Returns on which side of the segment `p1 -> p2` the point `q` is, negative for left,
+/// positive for right and 0 for collinear.
+fn segment_side(p1: Coord, p2: Coord, q: Coord) -> f64 {
+    let side = (q.x - p1.x) * (p2.y - p1.y) - (p2.x - p1.x) * (q.y - p1.y);
+    if side == 0.0 {
+        0.0
+    } else {
+        side.signum()
+    }
+}
+
+fn envelopes_interact(p1: Coord, p2: Coord, q1: Coord, q2: Coord) -> bool {
+    p1.x.max(p2.x) >= q1.x.min(q2.x)
+        && q1.x.max(q2.x) >= p1.x.min(p2.x)
+        && p1.y.max(p2.y) >= q1.y.min(q2.y)
+        && q1.y.max(q2.y) >= p1.y.min(p2.y)
+}
+
+/// Returns how the segment `q1 -> q2` crosses the segment `p1 -> p2`, an end point of `q`
+/// touching `p` and collinear overlaps are not counted as crossing.
+fn segment_cross(p1: Coord, p2: Coord, q1: Coord, q2: Coord) -> SegmentCross {
+    if !envelopes_interact(p1, p2, q1, q2) {
+        return SegmentCross::None;
+    }
+    let pq1 = segment_side(p1, p2, q1);
+    let pq2 = segment_side(p1, p2, q2);
+    if pq1 * pq2 > 0.0 {
+        return SegmentCross::None;
+    }
+    let qp1 = segment_side(q1, q2, p1);
+    let qp2 = segment_side(q1, q2, p2);
+    if qp1 * qp2 > 0.0 {
+        return SegmentCross::None;
+    }
+    if pq1 == 0.0 || pq2 == 0.0 {
+        return SegmentCross::None;
+    }
+    if pq1 < pq2 {
+        SegmentCross::Right
+    } else {
+        SegmentCross::Left
+    }
+}
+
+fn line_crossing_direction(line0: &geo::LineString, line1: &geo::LineString) -> i32 {
+    let mut cross_left = 0;
+    let mut cross_right = 0;
+    let mut first_cross = SegmentCross::None;
+    for q in line1.lines() {
+        for p in line0.lines() {
+            let cross = segment_cross(p.start, p.end, q.start, q.end);
+            match cross {
+                SegmentCross::Left => cross_left += 1,
+                SegmentCross::Right => cross_right += 1,
+                SegmentCross::None => continue,
+            }
+            if first_cross == SegmentCross::None {
+                first_cross = cross;
+            }
+        }
+    }
+
+    match (cross_left, cross_right) {
+        (0, 0) => LINE_NO_CROSS,
+        (0, 1) => LINE_CROSS_RIGHT,
+        (1, 0) => LINE_CROSS_LEFT,
+        (left, right) if left - right == 1 => LINE_MULTICROSS_END_LEFT,
+        (left, right) if left - right == -1 => LINE_MULTICROSS_END_RIGHT,
+        (left, right) if left == right && first_cross == SegmentCross::Left => {
+            LINE_MULTICROSS_END_SAME_FIRST_LEFT
+        }
+        (left, right) if left == right && first_cross == SegmentCross::Right => {
+            LINE_MULTICROSS_END_SAME_FIRST_RIGHT
+        }
+        _ => LINE_NO_CROSS,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{GeomFromTextUdf, LineCrossingDirectionUdf};
+    use arrow::util::pretty::pretty_format_batches;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+
+    #[tokio::test]
+    async fn line_crossing_direction() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(LineCrossingDirectionUdf::new()));
+        let df = ctx
+            .sql(
+                "select name, \
+            ST_LineCrossingDirection(ST_GeomFromText(a), ST_GeomFromText(b)) as a_cross_b, \
+            ST_LineCrossingDirection(ST_GeomFromText(b), ST_GeomFromText(a)) as b_cross_a \
+            from (values \
+            ('no_cross', 'LINESTRING(0 0,10 0)', 'LINESTRING(0 5,10 5)'), \
+            ('cross', 'LINESTRING(0 0,10 0)', 'LINESTRING(5 5,5 -5)'), \
+            ('multicross_end', 'LINESTRING(25 169,89 114,40 70,86 43)', 'LINESTRING(2.99 90.16,71 74,20 140,171 154)'), \
+            ('multicross_end_same', 'LINESTRING(25 169,89 114,40 70,86 43)', 'LINESTRING(171 154,20 140,71 74,161 53)'), \
+            ('null', 'LINESTRING(0 0,10 0)', null)) as t(name, a, b)",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+---------------------+-----------+-----------+
+| name                | a_cross_b | b_cross_a |
++---------------------+-----------+-----------+
+| no_cross            | 0         | 0         |
+| cross               | 1         | -1        |
+| multicross_end      | -2        | 2         |
+| multicross_end_same | 3         | -3        |
+| null                |           |           |
++---------------------+-----------+-----------+"
+        );
+    }
+
+    #[tokio::test]
+    async fn line_crossing_direction_non_lineal() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(LineCrossingDirectionUdf::new()));
+        let result = ctx
+            .sql("select ST_LineCrossingDirection(ST_GeomFromText('LINESTRING(0 0,10 0)'), ST_GeomFromText('POINT(5 5)'))")
+            .await
+            .unwrap()
+            .collect()
+            .await;
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,192 @@
+use crate::config::default_dialect;
+use crate::function::args::geometry_args;
+use crate::function::geometry_type::geometry_type;
+use crate::geo::dialect::decode_srid;
+use crate::geo::{GeometryArray, GeometryArrayBuilder};
+use crate::DFResult;
+use arrow_array::cast::AsArray;
+use arrow_array::{GenericBinaryArray, OffsetSizeTrait};
+use arrow_schema::DataType;
+use datafusion_common::{exec_err, DataFusionError, ScalarValue};
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, TypeSignature, Volatility};
+use geo::{Coord, EuclideanLength, Line};
+use std::any::Any;
+use std::sync::Arc;
+
+/// Extends a linestring beyond its end, and optionally before its start, along the direction of
+/// its terminal segments, e.g. to make sure a blade fully crosses the target of ST_Split.
+#[derive(Debug)]
+pub struct LineExtendUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl LineExtendUdf {
+    pub fn new() -> Self {
+        let mut type_signatures = vec![];
+        for geom_type in [DataType::Binary, DataType::LargeBinary] {
+            let mut arg_types = vec![geom_type];
+            for _ in 0..2 {
+                arg_types.push(DataType::Float64);
+                type_signatures.push(TypeSignature::Exact(arg_types.clone()));
+            }
+        }
+        Self {
+            signature: Signature::one_of(type_signatures, Volatility::Immutable),
+            aliases: vec!["st_lineextend".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for LineExtendUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_LineExtend"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(arg_types[0].clone())
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let ColumnarValue::Scalar(ScalarValue::Float64(Some(forward))) = args[1] else {
+            return exec_err!("The forward distance should be a f64 scalar");
+        };
+        let backward = match args.get(2) {
+            None => 0.0,
+            Some(ColumnarValue::Scalar(ScalarValue::Float64(Some(backward)))) => *backward,
+            Some(_) => return exec_err!("The backward distance should be a f64 scalar"),
+        };
+        if !(forward >= 0.0 && backward >= 0.0) {
+            return exec_err!("The distances to extend by should not be negative");
+        }
+
+        let (arrays, _recorder) = geometry_args(self.name(), &args[..1])?;
+        let arr = &arrays[0];
+        match arr.data_type() {
+            DataType::Binary => line_extend::<i32>(arr.as_binary::<i32>(), forward, backward),
+            DataType::LargeBinary => line_extend::<i64>(arr.as_binary::<i64>(), forward, backward),
+            _ => unreachable!(),
+        }
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for LineExtendUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn line_extend<O: OffsetSizeTrait>(
+    wkb_arr: &GenericBinaryArray<O>,
+    forward: f64,
+    backward: f64,
+) -> DFResult<ColumnarValue> {
+    let mut builder = GeometryArrayBuilder::<O>::new(default_dialect(), wkb_arr.geom_len());
+    for i in 0..wkb_arr.geom_len() {
+        let (Some(wkb), Some(geom)) = (wkb_arr.wkb(i), wkb_arr.geo_value(i)?) else {
+            builder.append_null();
+            continue;
+        };
+        let geo::Geometry::LineString(mut line) = geom else {
+            return exec_err!(
+                "ST_LineExtend only supports linestrings, got {}",
+                geometry_type(geom)
+            );
+        };
+        let n = line.0.len();
+        if n < 2 {
+            return exec_err!("Cannot extend a linestring of {} points", n);
+        }
+        if forward > 0.0 {
+            line.0[n - 1] = extend(line.0[n - 2], line.0[n - 1], forward)?;
+        }
+        if backward > 0.0 {
+            line.0[0] = extend(line.0[1], line.0[0], backward)?;
+        }
+        builder.append_geo_geometry_with_srid(&Some(line.into()), decode_srid(wkb)?)?;
+    }
+    Ok(ColumnarValue::Array(Arc::new(builder.build())))
+}
+
+/// Moves `end` by `distance` further along the direction from `start` to `end`.
+fn extend(start: Coord, end: Coord, distance: f64) -> DFResult<Coord> {
+    let length = Line::new(start, end).euclidean_length();
+    if length == 0.0 {
+        return exec_err!("Cannot extend a linestring along a zero length terminal segment");
+    }
+    Ok(end + (end - start) * (distance / length))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{AsTextUdf, GeomFromTextUdf, LineExtendUdf};
+    use arrow::util::pretty::pretty_format_batches;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+
+    #[tokio::test]
+    async fn line_extend() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(LineExtendUdf::new()));
+        let df = ctx
+            .sql(
+                "select ST_AsText(ST_LineExtend(ST_GeomFromText(wkt), 5.0)) as extended \
+                from (values ('LINESTRING(0 0,1 0,1 2)'), ('LINESTRING(0 0,3 4)'), (null)) as t(wkt)",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+-------------------------+
+| extended                |
++-------------------------+
+| LINESTRING(0 0,1 0,1 7) |
+| LINESTRING(0 0,6 8)     |
+|                         |
++-------------------------+"
+        );
+
+        let df = ctx
+            .sql(
+                "select ST_AsText(ST_LineExtend(ST_GeomFromText('LINESTRING(0 0,3 4)'), 5.0, 10.0)) \
+                as extended",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+-----------------------+
+| extended              |
++-----------------------+
+| LINESTRING(-6 -8,6 8) |
++-----------------------+"
+        );
+
+        for wkt in ["POINT(1 1)", "LINESTRING(0 0,1 1,1 1)"] {
+            let sql = format!(
+                "select ST_LineExtend(ST_GeomFromText(wkt), 1.0) from (values ('{}')) as t(wkt)",
+                wkt
+            );
+            let result = ctx.sql(&sql).await.unwrap().collect().await;
+            assert!(result.is_err(), "{} should not be extended", wkt);
+        }
+    }
+}
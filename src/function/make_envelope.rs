@@ -1,9 +1,11 @@
+use crate::config::default_dialect;
+use crate::function::args::scalar_if_constant;
 use crate::geo::GeometryArrayBuilder;
+use crate::metrics::record_call;
 use arrow_schema::DataType;
 use datafusion_common::{internal_datafusion_err, internal_err, DataFusionError, ScalarValue};
 use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, TypeSignature, Volatility};
 use geos::CoordSeq;
-use geozero::wkb::WkbDialect;
 use std::any::Any;
 use std::sync::Arc;
 
@@ -57,6 +59,7 @@ impl ScalarUDFImpl for MakeEnvelopeUdf {
     }
 
     fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let _recorder = record_call(self.name(), 1);
         let error = internal_err!("The arg should be float64");
         let ColumnarValue::Scalar(ScalarValue::Float64(Some(xmin))) = args[0] else {
             return error;
@@ -92,16 +95,14 @@ impl ScalarUDFImpl for MakeEnvelopeUdf {
         let mut polygon = geos::Geometry::create_polygon(exterior, vec![])
             .map_err(|_| internal_datafusion_err!("Failed to create polygon"))?;
 
-        let mut builder = if let Some(srid) = srid {
+        if let Some(srid) = srid {
             polygon.set_srid(srid as usize);
-            GeometryArrayBuilder::<i32>::new(WkbDialect::Ewkb, 1)
-        } else {
-            GeometryArrayBuilder::<i32>::new(WkbDialect::Wkb, 1)
-        };
+        }
+        let mut builder = GeometryArrayBuilder::<i32>::new(default_dialect(), 1);
         builder.append_geos_geometry(&Some(polygon))?;
 
         let wkb_arr = builder.build();
-        Ok(ColumnarValue::Array(Arc::new(wkb_arr)))
+        scalar_if_constant(args, ColumnarValue::Array(Arc::new(wkb_arr)))
     }
 
     fn aliases(&self) -> &[String] {
@@ -0,0 +1,204 @@
+use crate::function::{functions, GeoFunction};
+use crate::DFResult;
+use arrow_schema::DataType;
+use datafusion_expr::{Signature, TypeSignature};
+use serde_json::{json, Value};
+
+/// Describes every function registered by [`register_all`](crate::function::register_all) as a
+/// json array, e.g. for the completion of SQL editors. Every entry holds the kind of the function
+/// (`scalar`, `aggregate` or `window`), its name and aliases, its volatility, the cargo feature
+/// it requires and its signatures. A signature lists the argument types with the return type
+/// they produce, signatures accepting any types have a null return type.
+pub fn manifest() -> Value {
+    let entries = functions(false)
+        .into_iter()
+        .map(|(function, feature)| {
+            let (kind, name, aliases, signature) = match &function {
+                GeoFunction::Scalar(udf) => ("scalar", udf.name(), udf.aliases(), udf.signature()),
+                GeoFunction::Aggregate(udaf) => {
+                    ("aggregate", udaf.name(), &[][..], udaf.signature())
+                }
+                GeoFunction::Window(udwf) => ("window", udwf.name(), &[][..], udwf.signature()),
+            };
+            let return_type = |args: &[DataType]| -> DFResult<DataType> {
+                match &function {
+                    GeoFunction::Scalar(udf) => udf.return_type(args),
+                    GeoFunction::Aggregate(udaf) => udaf.return_type(args),
+                    GeoFunction::Window(udwf) => udwf.return_type(args),
+                }
+            };
+            json!({
+                "kind": kind,
+                "name": name,
+                "aliases": aliases,
+                "volatility": volatility(signature),
+                "feature": feature,
+                "signatures": signatures(&signature.type_signature, &return_type),
+            })
+        })
+        .collect();
+    Value::Array(entries)
+}
+
+fn volatility(signature: &Signature) -> String {
+    format!("{:?}", signature.volatility).to_lowercase()
+}
+
+fn signatures(
+    type_signature: &TypeSignature,
+    return_type: &dyn Fn(&[DataType]) -> DFResult<DataType>,
+) -> Vec<Value> {
+    let typed = |args: &[DataType]| {
+        json!({
+            "args": type_names(args),
+            // the signature admits the arguments, a failure only means the type is unknown
+            "return_type": return_type(args).ok().map(|data_type| data_type.to_string()),
+        })
+    };
+    match type_signature {
+        TypeSignature::Exact(args) => vec![typed(args)],
+        TypeSignature::Uniform(count, data_types) => uniform_args(*count, data_types)
+            .iter()
+            .map(|args| typed(args))
+            .collect(),
+        TypeSignature::OneOf(type_signatures) => type_signatures
+            .iter()
+            .flat_map(|type_signature| signatures(type_signature, return_type))
+            .collect(),
+        TypeSignature::Any(count) => vec![json!({
+            "args": vec!["Any"; *count],
+            "return_type": null,
+        })],
+        TypeSignature::Variadic(data_types) => vec![json!({
+            "variadic": type_names(data_types),
+            "return_type": null,
+        })],
+        TypeSignature::VariadicAny => vec![json!({
+            "variadic": ["Any"],
+            "return_type": null,
+        })],
+        other => vec![json!({
+            "other": format!("{:?}", other),
+            "return_type": null,
+        })],
+    }
+}
+
+fn type_names(data_types: &[DataType]) -> Vec<String> {
+    data_types
+        .iter()
+        .map(|data_type| data_type.to_string())
+        .collect()
+}
+
+/// Every combination of `count` arguments taking any of the types.
+fn uniform_args(count: usize, data_types: &[DataType]) -> Vec<Vec<DataType>> {
+    (0..count).fold(vec![vec![]], |combinations, _| {
+        combinations
+            .iter()
+            .flat_map(|args| {
+                data_types.iter().map(|data_type| {
+                    let mut args = args.clone();
+                    args.push(data_type.clone());
+                    args
+                })
+            })
+            .collect()
+    })
+}
+
+#[cfg(all(test, feature = "datasource"))]
+mod tests {
+    use crate::function::{manifest, register_all};
+    use datafusion::prelude::SessionContext;
+    use serde_json::json;
+    use std::collections::BTreeSet;
+
+    fn function_keys(ctx: &SessionContext) -> BTreeSet<(String, String)> {
+        let state = ctx.state();
+        let scalar = state.scalar_functions().keys().map(|key| ("scalar", key));
+        let aggregate = state
+            .aggregate_functions()
+            .keys()
+            .map(|key| ("aggregate", key));
+        let window = state.window_functions().keys().map(|key| ("window", key));
+        scalar
+            .chain(aggregate)
+            .chain(window)
+            .map(|(kind, key)| (kind.to_string(), key.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn manifest_matches_register_all() {
+        let builtin = function_keys(&SessionContext::new());
+        let ctx = SessionContext::new();
+        register_all(&ctx, false);
+        let registered = function_keys(&ctx)
+            .difference(&builtin)
+            .cloned()
+            .collect::<BTreeSet<_>>();
+
+        let manifest = manifest();
+        let entries = manifest.as_array().unwrap();
+        let mut keys = vec![];
+        for entry in entries {
+            let kind = entry["kind"].as_str().unwrap();
+            keys.push((
+                kind.to_string(),
+                entry["name"].as_str().unwrap().to_string(),
+            ));
+            for alias in entry["aliases"].as_array().unwrap() {
+                keys.push((kind.to_string(), alias.as_str().unwrap().to_string()));
+            }
+        }
+        let unique_keys = keys.iter().cloned().collect::<BTreeSet<_>>();
+        assert_eq!(keys.len(), unique_keys.len(), "duplicate names or aliases");
+        assert_eq!(unique_keys, registered);
+
+        // names differing only in case resolve to one function in SQL
+        let lowercase_names = entries
+            .iter()
+            .map(|entry| entry["name"].as_str().unwrap().to_lowercase())
+            .collect::<BTreeSet<_>>();
+        assert_eq!(lowercase_names.len(), entries.len());
+    }
+
+    #[test]
+    fn manifest_signatures() {
+        let manifest = manifest();
+        let entry = |name: &str| {
+            manifest
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|entry| entry["name"] == name)
+                .unwrap()
+                .clone()
+        };
+        assert_eq!(
+            entry("ST_Length"),
+            json!({
+                "kind": "scalar",
+                "name": "ST_Length",
+                "aliases": ["st_length", "st_length2d"],
+                "volatility": "immutable",
+                "feature": null,
+                "signatures": [
+                    {"args": ["Binary"], "return_type": "Float64"},
+                    {"args": ["LargeBinary"], "return_type": "Float64"},
+                ],
+            })
+        );
+        assert_eq!(
+            entry("ST_Overlaps")["signatures"][1],
+            json!({"args": ["Binary", "LargeBinary"], "return_type": "Boolean"})
+        );
+        assert_eq!(
+            entry("ST_DistanceCPA")["signatures"],
+            json!([{"args": ["Any", "Any", "Any", "Any"], "return_type": null}])
+        );
+        assert_eq!(entry("st_extent")["kind"], "aggregate");
+        assert_eq!(entry("st_heading")["kind"], "window");
+    }
+}
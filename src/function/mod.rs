@@ -1,53 +1,457 @@
+mod affine;
+mod apply_xy;
+pub(crate) mod args;
+mod as_binary;
+mod as_ewkb;
 #[cfg(feature = "geos")]
 mod as_ewkt;
+mod as_geobuf;
 mod as_geojson;
+mod as_mvt;
 mod as_mvt_geom;
 mod as_text;
+mod assert_geometry_type;
 #[cfg(feature = "geos")]
 mod boundary;
 mod box2d;
+mod box2d_intersection;
+mod box2d_union;
+mod box_distance;
 #[cfg(feature = "geos")]
 mod buffer;
+mod cancellable;
+mod centroid_xy;
+mod clean_geometry;
+mod clip_by_box2d;
+mod closest_point_of_approach;
+mod contains;
+#[cfg(feature = "geos")]
+mod contains_properly;
+#[cfg(feature = "geos")]
+mod coverage_invalid_edges;
+#[cfg(feature = "geos")]
+mod coverage_union;
 #[cfg(feature = "geos")]
 mod covered_by;
 #[cfg(feature = "geos")]
 mod covers;
+mod crosses;
+#[cfg(feature = "geos")]
+mod difference;
+mod disjoint;
+mod distance;
+mod distance_cpa;
+mod dump_segments;
+mod dwithin;
+mod dwithin_geography;
+mod envelope;
 #[cfg(feature = "geos")]
 mod equals;
 mod extent;
+mod from_geobuf;
+mod geom_from_ewkt;
+mod geom_from_geojson;
 mod geom_from_text;
 mod geom_from_wkb;
+mod geometric_median;
+mod geometry_n;
 mod geometry_type;
+mod grid_cell_id;
+mod heading;
+#[cfg(feature = "geos")]
+mod intersection;
 mod intersects;
+mod is_ccw;
+mod is_geographic;
+#[cfg(feature = "geos")]
+mod is_valid_detail;
+mod length;
+mod line_crossing_direction;
+mod line_extend;
 #[cfg(feature = "geos")]
 mod make_envelope;
+mod manifest;
+mod n_rings;
+mod normalize_for_compare;
+mod npoints;
+mod num_geometries;
+mod orientation;
+mod overlaps;
+mod perimeter;
+mod pixel_as_polygon;
+mod reduce_points;
+mod ring_n;
+mod rotate;
+mod rotate_x;
+mod rotate_y;
+mod scale;
+mod scale_to_fit;
+mod simplify;
+mod simplify_for_scale;
+mod snap_point_to_grid;
 #[cfg(feature = "geos")]
 mod split;
-#[cfg(feature = "geos")]
 mod srid;
+mod summary_stats;
+#[cfg(feature = "geos")]
+mod sym_difference;
+mod tile_envelope;
+mod to_large_geometry;
+mod to_small_geometry;
+mod touches;
+#[cfg(feature = "proj")]
+mod transform;
 mod translate;
+#[cfg(feature = "geos")]
+mod union;
+#[cfg(feature = "geos")]
+mod union_agg;
+#[cfg(feature = "geos")]
+mod union_array;
+mod within;
+mod world_to_pixel;
+mod x;
+mod y;
 
+pub use affine::*;
+pub use apply_xy::*;
+pub use as_binary::*;
+pub use as_ewkb::*;
 #[cfg(feature = "geos")]
 pub use as_ewkt::*;
+pub use as_geobuf::*;
 pub use as_geojson::*;
+pub use as_mvt::*;
 pub use as_text::*;
+pub use assert_geometry_type::*;
 #[cfg(feature = "geos")]
 pub use boundary::*;
+pub use box2d_intersection::*;
+pub use box2d_union::*;
+pub use box_distance::*;
 #[cfg(feature = "geos")]
 pub use buffer::*;
+pub use cancellable::*;
+pub use centroid_xy::*;
+pub use clean_geometry::*;
+pub use clip_by_box2d::*;
+pub use closest_point_of_approach::*;
+pub use contains::*;
+#[cfg(feature = "geos")]
+pub use contains_properly::*;
+#[cfg(feature = "geos")]
+pub use coverage_invalid_edges::*;
+#[cfg(feature = "geos")]
+pub use coverage_union::*;
 #[cfg(feature = "geos")]
 pub use covered_by::*;
 #[cfg(feature = "geos")]
 pub use covers::*;
+pub use crosses::*;
+#[cfg(feature = "geos")]
+pub use difference::*;
+pub use disjoint::*;
+pub use distance::*;
+pub use distance_cpa::*;
+pub use dump_segments::*;
+pub use dwithin::*;
+pub use dwithin_geography::*;
+pub use envelope::*;
 #[cfg(feature = "geos")]
 pub use equals::*;
+pub use from_geobuf::*;
+pub use geom_from_ewkt::*;
+pub use geom_from_geojson::*;
 pub use geom_from_text::*;
+pub use geometric_median::*;
+pub use geometry_n::*;
 pub use geometry_type::*;
+pub use grid_cell_id::*;
+pub use heading::*;
+#[cfg(feature = "geos")]
+pub use intersection::*;
 pub use intersects::*;
+pub use is_ccw::*;
+pub use is_geographic::*;
+#[cfg(feature = "geos")]
+pub use is_valid_detail::*;
+pub use length::*;
+pub use line_crossing_direction::*;
+pub use line_extend::*;
 #[cfg(feature = "geos")]
 pub use make_envelope::*;
+pub use manifest::*;
+pub use n_rings::*;
+pub use normalize_for_compare::*;
+pub use npoints::*;
+pub use num_geometries::*;
+pub use orientation::*;
+pub use overlaps::*;
+pub use perimeter::*;
+pub use pixel_as_polygon::*;
+pub use reduce_points::*;
+pub use ring_n::*;
+pub use rotate::*;
+pub use rotate_x::*;
+pub use rotate_y::*;
+pub use scale::*;
+pub use scale_to_fit::*;
+pub use simplify::*;
+pub use simplify_for_scale::*;
+pub use snap_point_to_grid::*;
 #[cfg(feature = "geos")]
 pub use split::*;
-#[cfg(feature = "geos")]
 pub use srid::*;
+pub use summary_stats::*;
+#[cfg(feature = "geos")]
+pub use sym_difference::*;
+pub use tile_envelope::*;
+pub use to_large_geometry::*;
+pub use to_small_geometry::*;
+pub use touches::*;
+#[cfg(feature = "proj")]
+pub use transform::*;
 pub use translate::*;
+#[cfg(feature = "geos")]
+pub use union::*;
+#[cfg(feature = "geos")]
+pub use union_agg::*;
+#[cfg(feature = "geos")]
+pub use union_array::*;
+pub use within::*;
+pub use world_to_pixel::*;
+pub use x::*;
+pub use y::*;
+
+#[cfg(feature = "datasource")]
+use datafusion::prelude::SessionContext;
+use datafusion_expr::{AggregateUDF, ScalarUDF, WindowUDF};
+
+/// A function of the crate, registered by [`register_all`] and described by [`manifest`].
+pub(crate) enum GeoFunction {
+    Scalar(ScalarUDF),
+    Aggregate(AggregateUDF),
+    Window(WindowUDF),
+}
+
+/// Registers all the functions of the crate. With `skip_unsupported` the functions the linked
+/// GEOS library is too old for are left out instead of failing when invoked. The scalar and
+/// aggregate functions check the [`CancellationFlag`](crate::config::CancellationFlag) extension
+/// of the session config, if any.
+#[cfg(feature = "datasource")]
+pub fn register_all(ctx: &SessionContext, skip_unsupported: bool) {
+    let cancellation = ctx
+        .copied_config()
+        .get_extension::<crate::config::CancellationFlag>();
+    for (function, _) in functions(skip_unsupported) {
+        match function {
+            GeoFunction::Scalar(udf) => match &cancellation {
+                Some(flag) => ctx.register_udf(CancellableUdf::new(udf, flag.clone()).into()),
+                None => ctx.register_udf(udf),
+            },
+            GeoFunction::Aggregate(udaf) => match &cancellation {
+                Some(flag) => ctx.register_udaf(CancellableUdaf::new(udaf, flag.clone()).into()),
+                None => ctx.register_udaf(udaf),
+            },
+            GeoFunction::Window(udwf) => ctx.register_udwf(udwf),
+        }
+    }
+}
+
+/// All the functions of the crate with the cargo feature they require.
+#[cfg_attr(not(feature = "geos"), allow(unused_variables))]
+pub(crate) fn functions(skip_unsupported: bool) -> Vec<(GeoFunction, Option<&'static str>)> {
+    let scalar_udfs: Vec<ScalarUDF> = vec![
+        AffineUdf::new().into(),
+        ApplyXYUdf::new().into(),
+        AsBinaryUdf::new().into(),
+        AsEwkbUdf::new().into(),
+        AsGeoJsonUdf::new().into(),
+        as_mvt_geom::AsMVTGeomUdf::new().into(),
+        AsTextUdf::new().into(),
+        AssertGeometryTypeUdf::new().into(),
+        box2d::Box2dUdf::new().into(),
+        Box2dIntersectionUdf::new().into(),
+        Box2dUnionUdf::new().into(),
+        BoxDistanceUdf::new().into(),
+        CentroidXYUdf::new().into(),
+        CleanGeometryUdf::new().into(),
+        ClipByBox2dUdf::new().into(),
+        ClosestPointOfApproachUdf::new().into(),
+        ContainsUdf::new().into(),
+        CrossesUdf::new().into(),
+        DisjointUdf::new().into(),
+        DistanceUdf::new().into(),
+        DistanceCPAUdf::new().into(),
+        DumpSegmentsUdf::new().into(),
+        DWithinUdf::new().into(),
+        DWithinGeographyUdf::new().into(),
+        EnvelopeUdf::new().into(),
+        FromGeobufUdf::new().into(),
+        GeomFromEwktUdf::new().into(),
+        GeomFromGeoJsonUdf::new().into(),
+        GeomFromTextUdf::new().into(),
+        geom_from_wkb::GeomFromWkbUdf::new().into(),
+        GeometricMedianUdf::new().into(),
+        GeometryNUdf::new().into(),
+        GeometryTypeUdf::new().into(),
+        GridCellIdUdf::new().into(),
+        IntersectsUdf::new().into(),
+        IsCCWUdf::new().into(),
+        IsGeographicUdf::new().into(),
+        LengthUdf::new().into(),
+        LineCrossingDirectionUdf::new().into(),
+        LineExtendUdf::new().into(),
+        NRingsUdf::new().into(),
+        NormalizeForCompareUdf::new().into(),
+        NPointsUdf::new().into(),
+        NumGeometriesUdf::new().into(),
+        OrientationUdf::new().into(),
+        OverlapsUdf::new().into(),
+        PerimeterUdf::new().into(),
+        PixelAsPolygonUdf::new().into(),
+        ReducePointsUdf::new().into(),
+        RingNUdf::new().into(),
+        RotateUdf::new().into(),
+        RotateXUdf::new().into(),
+        RotateYUdf::new().into(),
+        ScaleUdf::new().into(),
+        ScaleToFitUdf::new().into(),
+        SimplifyUdf::new().into(),
+        SimplifyForScaleUdf::new().into(),
+        SnapPointToGridUdf::new().into(),
+        SridUdf::new().into(),
+        TileEnvelopeUdf::new().into(),
+        ToLargeGeometryUdf::new().into(),
+        ToSmallGeometryUdf::new().into(),
+        TouchesUdf::new().into(),
+        TranslateUdf::new().into(),
+        WithinUdf::new().into(),
+        WorldToPixelUdf::new().into(),
+        XUdf::new().into(),
+        YUdf::new().into(),
+    ];
+    let mut functions = scalar_udfs
+        .into_iter()
+        .map(|udf| (GeoFunction::Scalar(udf), None))
+        .collect::<Vec<_>>();
+    functions.push((GeoFunction::Aggregate(AsGeobufUdaf::new().into()), None));
+    functions.push((GeoFunction::Aggregate(AsMvtUdaf::new().into()), None));
+    functions.push((
+        GeoFunction::Aggregate(extent::ExtentUdaf::new().into()),
+        None,
+    ));
+    functions.push((GeoFunction::Aggregate(SummaryStatsUdaf::new().into()), None));
+    functions.push((GeoFunction::Window(HeadingUdwf::new().into()), None));
+
+    #[cfg(feature = "geos")]
+    {
+        let geos_udfs: Vec<ScalarUDF> = vec![
+            AsEwktUdf::new().into(),
+            BoundaryUdf::new().into(),
+            BufferUdf::new().into(),
+            ContainsProperlyUdf::new().into(),
+            CoveredByUdf::new().into(),
+            CoversUdf::new().into(),
+            DifferenceUdf::new().into(),
+            EqualsUdf::new().into(),
+            IntersectionUdf::new().into(),
+            IsValidDetailUdf::new().into(),
+            MakeEnvelopeUdf::new().into(),
+            SplitUdf::new().into(),
+            SymDifferenceUdf::new().into(),
+            UnionUdf::new().into(),
+            UnionArrayUdf::new().into(),
+        ];
+        functions.extend(
+            geos_udfs
+                .into_iter()
+                .map(|udf| (GeoFunction::Scalar(udf), Some("geos"))),
+        );
+        functions.push((
+            GeoFunction::Aggregate(CoverageInvalidEdgesUdaf::new().into()),
+            Some("geos"),
+        ));
+        functions.push((
+            GeoFunction::Aggregate(UnionUdaf::new().into()),
+            Some("geos"),
+        ));
+        let capabilities = crate::geo::geos_capabilities();
+        if !skip_unsupported || capabilities.supports(coverage_union::COVERAGE_UNION_GEOS) {
+            functions.push((
+                GeoFunction::Aggregate(CoverageUnionUdaf::new().into()),
+                Some("geos"),
+            ));
+        }
+    }
+
+    #[cfg(feature = "proj")]
+    functions.push((
+        GeoFunction::Scalar(TransformUdf::new().into()),
+        Some("proj"),
+    ));
+    functions
+}
+
+#[cfg(all(test, feature = "datasource"))]
+mod tests {
+    use crate::function::register_all;
+    use arrow::util::pretty::pretty_format_batches;
+    use datafusion::prelude::SessionContext;
+
+    #[tokio::test]
+    async fn register_all_functions() {
+        let ctx = SessionContext::new();
+        register_all(&ctx, true);
+        let df = ctx
+            .sql("select ST_AsText(ST_Translate(ST_GeomFromText('POINT(1 1)'), 1.0, 2.0)) as point")
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+------------+
+| point      |
++------------+
+| POINT(2 3) |
++------------+"
+        );
+        let state = ctx.state();
+        assert!(state.aggregate_functions().contains_key("st_extent"));
+        assert!(state.window_functions().contains_key("st_heading"));
+    }
+
+    #[tokio::test]
+    async fn predicate_matrix() {
+        let ctx = SessionContext::new();
+        register_all(&ctx, true);
+        let df = ctx
+            .sql(
+                "select pair, st_touches(a, b) as touches, st_crosses(a, b) as crosses, \
+                st_overlaps(a, b) as overlaps, st_disjoint(a, b) as disjoint \
+                from (select id, pair, ST_GeomFromText(a) as a, ST_GeomFromText(b) as b from (values \
+                (1, 'touching polygons', 'POLYGON((0 0,1 0,1 1,0 1,0 0))', 'POLYGON((1 0,2 0,2 1,1 1,1 0))'), \
+                (2, 'crossing lines', 'LINESTRING(0 0,2 2)', 'LINESTRING(0 2,2 0)'), \
+                (3, 'overlapping polygons', 'POLYGON((0 0,2 0,2 2,0 2,0 0))', 'POLYGON((1 1,3 1,3 3,1 3,1 1))'), \
+                (4, 'disjoint points', 'POINT(0 0)', 'POINT(1 1)'), \
+                (5, 'null', 'POINT(0 0)', null)) as t(id, pair, a, b)) order by id",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+----------------------+---------+---------+----------+----------+
+| pair                 | touches | crosses | overlaps | disjoint |
++----------------------+---------+---------+----------+----------+
+| touching polygons    | true    | false   | false    | false    |
+| crossing lines       | false   | true    | false    | false    |
+| overlapping polygons | false   | false   | true     | false    |
+| disjoint points      | false   | false   | false    | true     |
+| null                 |         |         |          |          |
++----------------------+---------+---------+----------+----------+"
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use crate::function::args::{as_geometry_array, geometry_args};
+use crate::function::ring_n::rings;
+use arrow_array::Int64Array;
+use arrow_schema::DataType;
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+use std::any::Any;
+use std::sync::Arc;
+
+/// Returns the number of rings of a (multi)polygon, interiors included, 0 for other types.
+/// The rings are counted in the order used by ST_RingN.
+#[derive(Debug)]
+pub struct NRingsUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl NRingsUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::uniform(
+                1,
+                vec![DataType::Binary, DataType::LargeBinary],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_nrings".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for NRingsUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_NRings"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(DataType::Int64)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let wkb_arr = as_geometry_array(&arrays[0])?;
+
+        let mut count_vec = vec![];
+        for i in 0..wkb_arr.geom_len() {
+            count_vec.push(wkb_arr.geo_value(i)?.map(|geom| rings(&geom).len() as i64));
+        }
+        Ok(ColumnarValue::Array(Arc::new(Int64Array::from(count_vec))))
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for NRingsUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{GeomFromTextUdf, NRingsUdf};
+    use arrow::util::pretty::pretty_format_batches;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+
+    #[tokio::test]
+    async fn n_rings() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(NRingsUdf::new()));
+        let df = ctx
+            .sql(
+                "select ST_NRings(ST_GeomFromText(wkt)) as rings from (values \
+                ('POLYGON((0 0,10 0,10 10,0 10,0 0),(1 1,2 1,2 2,1 1))'), \
+                ('MULTIPOLYGON(((0 0,10 0,10 10,0 0),(1 1,2 1,2 2,1 1)),((20 0,30 0,30 10,20 0)))'), \
+                ('LINESTRING(0 0,1 1)'), \
+                (null)) as t(wkt)",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+-------+
+| rings |
++-------+
+| 2     |
+| 3     |
+| 0     |
+|       |
++-------+"
+        );
+    }
+}
@@ -0,0 +1,261 @@
+use crate::config::default_dialect;
+use crate::function::args::geometry_args;
+use crate::geo::map::map_geometry_recursive;
+use crate::geo::{GeometryArray, GeometryArrayBuilder};
+use crate::DFResult;
+use arrow_array::cast::AsArray;
+use arrow_array::{GenericBinaryArray, OffsetSizeTrait};
+use arrow_schema::DataType;
+use datafusion_expr::{ColumnarValue, Expr, ScalarUDF, ScalarUDFImpl, Signature, Volatility};
+use geo::{Coord, CoordsIter};
+use geozero::wkb::WkbDialect;
+use std::any::Any;
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+/// Outputs a canonical wkb for each geometry so that the same geometry always has the same bytes,
+/// regardless of its byte order, wkb dialect or the order of its multi members.
+/// The result is meant to be used as a `GROUP BY` / `DISTINCT` key.
+#[derive(Debug)]
+pub struct NormalizeForCompareUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl NormalizeForCompareUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::uniform(
+                1,
+                vec![DataType::Binary, DataType::LargeBinary],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_normalizeforcompare".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for NormalizeForCompareUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_NormalizeForCompare"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(arg_types[0].clone())
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let arr = &arrays[0];
+        match arr.data_type() {
+            DataType::Binary => normalize_for_compare::<i32>(arr.as_binary::<i32>()),
+            DataType::LargeBinary => normalize_for_compare::<i64>(arr.as_binary::<i64>()),
+            _ => unreachable!(),
+        }
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for NormalizeForCompareUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Wraps a geometry expression with `ST_NormalizeForCompare`, e.g.
+/// `df.aggregate(vec![st_distinct_on_geometry(col("geom"))], vec![])` keeps one row per distinct geometry.
+pub fn st_distinct_on_geometry(expr: Expr) -> Expr {
+    ScalarUDF::from(NormalizeForCompareUdf::new()).call(vec![expr])
+}
+
+fn normalize_for_compare<O: OffsetSizeTrait>(
+    wkb_arr: &GenericBinaryArray<O>,
+) -> DFResult<ColumnarValue> {
+    let mut builder = GeometryArrayBuilder::<O>::new(default_dialect(), wkb_arr.geom_len());
+    for i in 0..wkb_arr.geom_len() {
+        builder.append_geo_geometry(&wkb_arr.geo_value(i)?.map(normalize))?;
+    }
+    Ok(ColumnarValue::Array(Arc::new(builder.build())))
+}
+
+fn normalize(geom: geo::Geometry) -> geo::Geometry {
+    map_geometry_recursive(geom, &mut |geom| match geom {
+        geo::Geometry::MultiPoint(mut mp) => {
+            mp.0.sort_by(cmp_coords);
+            geo::Geometry::MultiPoint(mp)
+        }
+        geo::Geometry::MultiLineString(mut mls) => {
+            mls.0.sort_by(cmp_coords);
+            geo::Geometry::MultiLineString(mls)
+        }
+        geo::Geometry::MultiPolygon(mut mp) => {
+            mp.0.sort_by(cmp_coords);
+            geo::Geometry::MultiPolygon(mp)
+        }
+        geom => geom,
+    })
+}
+
+fn cmp_coords<G: CoordsIter<Scalar = f64>>(a: &G, b: &G) -> Ordering {
+    let mut a_iter = a.coords_iter();
+    let mut b_iter = b.coords_iter();
+    loop {
+        match (a_iter.next(), b_iter.next()) {
+            (Some(a), Some(b)) => {
+                let ordering = cmp_coord(&a, &b);
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(_), None) => return Ordering::Greater,
+            (None, Some(_)) => return Ordering::Less,
+            (None, None) => return Ordering::Equal,
+        }
+    }
+}
+
+fn cmp_coord(a: &Coord, b: &Coord) -> Ordering {
+    a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{AsTextUdf, GeomFromTextUdf, NormalizeForCompareUdf};
+    use crate::geo::GeometryArrayBuilder;
+    use arrow::util::pretty::pretty_format_batches;
+    use arrow_array::{BinaryArray, RecordBatch};
+    use arrow_schema::{DataType, Field, Schema};
+    use datafusion::datasource::MemTable;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+    use geozero::wkb::WkbDialect;
+    use std::sync::Arc;
+
+    const POLYGON: [(f64, f64); 4] = [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 0.0)];
+
+    fn polygon_wkb(big_endian: bool, srid: Option<u32>) -> Vec<u8> {
+        let u32_bytes = |v: u32| {
+            if big_endian {
+                v.to_be_bytes()
+            } else {
+                v.to_le_bytes()
+            }
+        };
+        let f64_bytes = |v: f64| {
+            if big_endian {
+                v.to_be_bytes()
+            } else {
+                v.to_le_bytes()
+            }
+        };
+        let mut wkb = vec![if big_endian { 0 } else { 1 }];
+        match srid {
+            Some(srid) => {
+                wkb.extend_from_slice(&u32_bytes(3 | 0x20000000));
+                wkb.extend_from_slice(&u32_bytes(srid));
+            }
+            None => wkb.extend_from_slice(&u32_bytes(3)),
+        }
+        wkb.extend_from_slice(&u32_bytes(1));
+        wkb.extend_from_slice(&u32_bytes(POLYGON.len() as u32));
+        for (x, y) in POLYGON {
+            wkb.extend_from_slice(&f64_bytes(x));
+            wkb.extend_from_slice(&f64_bytes(y));
+        }
+        wkb
+    }
+
+    fn polygon_array(dialect: WkbDialect, big_endian: bool, srid: Option<u32>) -> BinaryArray {
+        let mut builder = GeometryArrayBuilder::<i32>::new(dialect, 1);
+        builder
+            .append_wkb(Some(&polygon_wkb(big_endian, srid)))
+            .unwrap();
+        builder.build()
+    }
+
+    #[tokio::test]
+    async fn group_by_normalized_geometry() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(NormalizeForCompareUdf::new()));
+
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "geom",
+            DataType::Binary,
+            true,
+        )]));
+        let batches = vec![
+            polygon_array(WkbDialect::Wkb, false, None),
+            polygon_array(WkbDialect::Wkb, true, None),
+            polygon_array(WkbDialect::Ewkb, false, Some(4326)),
+        ]
+        .into_iter()
+        .map(|arr| RecordBatch::try_new(schema.clone(), vec![Arc::new(arr)]).unwrap())
+        .collect::<Vec<_>>();
+        let mem_table = MemTable::try_new(schema.clone(), vec![batches]).unwrap();
+        ctx.register_table("geom_table", Arc::new(mem_table))
+            .unwrap();
+
+        let df = ctx
+            .sql("select count(*) as raw_groups from (select geom from geom_table group by geom)")
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+------------+
+| raw_groups |
++------------+
+| 3          |
++------------+"
+        );
+
+        let df = ctx
+            .sql("select count(*) as cnt from geom_table group by ST_NormalizeForCompare(geom)")
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+-----+
+| cnt |
++-----+
+| 3   |
++-----+"
+        );
+    }
+
+    #[tokio::test]
+    async fn normalize_multi_members() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(NormalizeForCompareUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
+        let df = ctx
+            .sql("select ST_AsText(ST_NormalizeForCompare(ST_GeomFromText('MULTIPOINT(3 4,1 2)')))")
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+---------------------------------------------------------------------------------+
+| ST_AsText(ST_NormalizeForCompare(ST_GeomFromText(Utf8(\"MULTIPOINT(3 4,1 2)\")))) |
++---------------------------------------------------------------------------------+
+| MULTIPOINT(1 2,3 4)                                                             |
++---------------------------------------------------------------------------------+"
+        );
+    }
+}
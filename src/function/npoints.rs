@@ -0,0 +1,157 @@
+use crate::function::args::{as_geometry_array, geometry_args};
+use crate::geo::map::is_empty;
+use arrow_array::Int64Array;
+use arrow_schema::DataType;
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+use geo::CoordsIter;
+use std::any::Any;
+use std::sync::Arc;
+
+/// Returns the number of coordinates of a geometry, counting all rings and parts.
+#[derive(Debug)]
+pub struct NPointsUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl NPointsUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::uniform(
+                1,
+                vec![DataType::Binary, DataType::LargeBinary],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_npoints".to_string(), "st_numpoints".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for NPointsUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_NPoints"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(DataType::Int64)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let wkb_arr = as_geometry_array(&arrays[0])?;
+        let mut counts = Vec::with_capacity(wkb_arr.geom_len());
+        for i in 0..wkb_arr.geom_len() {
+            counts.push(wkb_arr.geo_value(i)?.map(|geom| {
+                // an empty point is decoded with NaN coordinates
+                if is_empty(&geom) {
+                    0
+                } else {
+                    geom.coords_count() as i64
+                }
+            }));
+        }
+        Ok(ColumnarValue::Array(Arc::new(Int64Array::from(counts))))
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for NPointsUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{GeomFromTextUdf, NPointsUdf};
+    use crate::geo::GeometryArrayBuilder;
+    use arrow::util::pretty::pretty_format_batches;
+    use arrow_array::RecordBatch;
+    use arrow_schema::{DataType, Field, Schema};
+    use datafusion::datasource::MemTable;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+    use geo::line_string;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn npoints() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(NPointsUdf::new()));
+        let df = ctx
+            .sql(
+                "select wkt, ST_NPoints(ST_GeomFromText(wkt)) as npoints \
+                from (values ('POINT(1 2)'), \
+                ('POLYGON((0 0,4 0,4 4,0 4,0 0),(1 1,2 1,2 2,1 1))'), \
+                ('MULTILINESTRING((0 0,1 1),(2 2,3 3,4 4))'), \
+                ('MULTIPOLYGON EMPTY'), (null)) as t(wkt)",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+--------------------------------------------------+---------+
+| wkt                                              | npoints |
++--------------------------------------------------+---------+
+| POINT(1 2)                                       | 1       |
+| POLYGON((0 0,4 0,4 4,0 4,0 0),(1 1,2 1,2 2,1 1)) | 9       |
+| MULTILINESTRING((0 0,1 1),(2 2,3 3,4 4))         | 5       |
+| MULTIPOLYGON EMPTY                               | 0       |
+|                                                  |         |
++--------------------------------------------------+---------+"
+        );
+    }
+
+    #[tokio::test]
+    async fn numpoints_table() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(NPointsUdf::new()));
+
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "geom",
+            DataType::Binary,
+            true,
+        )]));
+        let lines = vec![
+            Some(line_string![(x: 0., y: 0.), (x: 1., y: 1.)]),
+            None,
+            Some(line_string![(x: 0., y: 0.), (x: 1., y: 1.), (x: 2., y: 0.)]),
+        ];
+        let builder: GeometryArrayBuilder<i32> = lines.as_slice().try_into().unwrap();
+        let record = RecordBatch::try_new(schema.clone(), vec![Arc::new(builder.build())]).unwrap();
+        let mem_table = MemTable::try_new(schema, vec![vec![record]]).unwrap();
+        ctx.register_table("geom_table", Arc::new(mem_table))
+            .unwrap();
+
+        let df = ctx
+            .sql("select st_numpoints(geom) as npoints from geom_table")
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+---------+
+| npoints |
++---------+
+| 2       |
+|         |
+| 3       |
++---------+"
+        );
+    }
+}
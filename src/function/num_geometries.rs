@@ -0,0 +1,116 @@
+use crate::function::args::{as_geometry_array, geometry_args};
+use crate::geo::dialect::WkbHeader;
+use crate::DFResult;
+use arrow_array::Int32Array;
+use arrow_schema::DataType;
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+use std::any::Any;
+use std::sync::Arc;
+
+/// Returns the number of parts of a multi geometry or a geometry collection, 1 for a single
+/// geometry and 0 for an empty one, see ST_GeometryN.
+#[derive(Debug)]
+pub struct NumGeometriesUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl NumGeometriesUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::uniform(
+                1,
+                vec![DataType::Binary, DataType::LargeBinary],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_numgeometries".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for NumGeometriesUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_NumGeometries"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(DataType::Int32)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let wkb_arr = as_geometry_array(&arrays[0])?;
+
+        let mut count_vec = vec![];
+        for i in 0..wkb_arr.geom_len() {
+            count_vec.push(wkb_arr.wkb(i).map(num_geometries).transpose()?);
+        }
+        Ok(ColumnarValue::Array(Arc::new(Int32Array::from(count_vec))))
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for NumGeometriesUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Reads the number of parts from the wkb header.
+fn num_geometries(wkb: &[u8]) -> DFResult<i32> {
+    let header = WkbHeader::parse(wkb)?;
+    Ok(match header.geometry_type {
+        4..=7 => header.num_elements as i32,
+        _ => i32::from(header.num_elements > 0),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{AsTextUdf, GeomFromTextUdf, GeometryNUdf, NumGeometriesUdf};
+    use arrow::util::pretty::pretty_format_batches;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+
+    #[tokio::test]
+    async fn num_geometries() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(NumGeometriesUdf::new()));
+        ctx.register_udf(ScalarUDF::from(GeometryNUdf::new()));
+        let df = ctx
+            .sql(
+                "select ST_NumGeometries(ST_GeomFromText(wkt)) as parts, \
+                ST_AsText(ST_GeometryN(ST_GeomFromText(wkt), 1)) as first \
+                from (values ('POINT(1 2)'), ('GEOMETRYCOLLECTION(POINT(0 0),LINESTRING(0 0,1 1))'), \
+                ('MULTIPOLYGON EMPTY'), (null)) as t(wkt)",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+-------+------------+
+| parts | first      |
++-------+------------+
+| 1     | POINT(1 2) |
+| 2     | POINT(0 0) |
+| 0     |            |
+|       |            |
++-------+------------+"
+        );
+    }
+}
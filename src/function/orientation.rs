@@ -0,0 +1,141 @@
+use crate::function::args::{as_geometry_array, geometry_args};
+use arrow_array::StringArray;
+use arrow_schema::DataType;
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+use geo::winding_order::WindingOrder;
+use geo::{LineString, Winding};
+use std::any::Any;
+use std::sync::Arc;
+
+/// Returns the orientation of the exterior ring of a polygon, 'CW', 'CCW' or 'COLLINEAR' when the
+/// ring has no area. The exteriors of a multipolygon must all agree, otherwise the result is null
+/// like for empty and non polygonal geometries. A closed linestring is treated as a ring.
+#[derive(Debug)]
+pub struct OrientationUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl OrientationUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::uniform(
+                1,
+                vec![DataType::Binary, DataType::LargeBinary],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_orientation".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for OrientationUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_Orientation"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(DataType::Utf8)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let wkb_arr = as_geometry_array(&arrays[0])?;
+
+        let mut orientation_vec = vec![];
+        for i in 0..wkb_arr.geom_len() {
+            orientation_vec.push(wkb_arr.geo_value(i)?.and_then(|geom| orientation(&geom)));
+        }
+        Ok(ColumnarValue::Array(Arc::new(StringArray::from(
+            orientation_vec,
+        ))))
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for OrientationUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Orientation of the exterior ring(s) of a geometry, see [`OrientationUdf`].
+pub(crate) fn orientation(geom: &geo::Geometry) -> Option<&'static str> {
+    let exteriors: Vec<&LineString> = match geom {
+        geo::Geometry::Polygon(polygon) => vec![polygon.exterior()],
+        geo::Geometry::MultiPolygon(polygons) => polygons.iter().map(|p| p.exterior()).collect(),
+        geo::Geometry::LineString(line) if line.is_closed() => vec![line],
+        _ => vec![],
+    };
+    let mut orientations = exteriors
+        .into_iter()
+        .filter(|ring| !ring.0.is_empty())
+        .map(|ring| match ring.winding_order() {
+            Some(WindingOrder::Clockwise) => "CW",
+            Some(WindingOrder::CounterClockwise) => "CCW",
+            None => "COLLINEAR",
+        });
+    let first = orientations.next()?;
+    orientations
+        .all(|orientation| orientation == first)
+        .then_some(first)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{GeomFromTextUdf, IsCCWUdf, OrientationUdf};
+    use arrow::util::pretty::pretty_format_batches;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+
+    #[tokio::test]
+    async fn orientation() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(OrientationUdf::new()));
+        ctx.register_udf(ScalarUDF::from(IsCCWUdf::new()));
+        let df = ctx
+            .sql(
+                "select ST_Orientation(ST_GeomFromText(wkt)) as orientation, \
+                ST_IsCCW(ST_GeomFromText(wkt)) as is_ccw from (values \
+                ('POLYGON((0 0,1 0,1 1,0 1,0 0))'), \
+                ('POLYGON((0 0,0 1,1 1,1 0,0 0))'), \
+                ('POLYGON((0 0,1 1,2 2,0 0))'), \
+                ('MULTIPOLYGON(((0 0,1 0,1 1,0 0)),((5 5,6 5,6 6,5 5)))'), \
+                ('MULTIPOLYGON(((0 0,1 0,1 1,0 0)),((5 5,6 6,6 5,5 5)))'), \
+                ('MULTIPOLYGON EMPTY'), \
+                ('POINT(1 1)'), \
+                (null)) as t(wkt)",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+-------------+--------+
+| orientation | is_ccw |
++-------------+--------+
+| CCW         | true   |
+| CW          | false  |
+| COLLINEAR   | false  |
+| CCW         | true   |
+|             |        |
+|             |        |
+|             |        |
+|             |        |
++-------------+--------+"
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use arrow_schema::DataType;
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+use std::any::Any;
+
+/// Whether two geometries of the same dimension share some but not all interior points and the
+/// intersection has the same dimension.
+#[derive(Debug)]
+pub struct OverlapsUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl OverlapsUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::uniform(
+                2,
+                vec![DataType::Binary, DataType::LargeBinary],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_overlaps".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for OverlapsUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_Overlaps"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(DataType::Boolean)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        #[cfg(feature = "geos")]
+        {
+            use crate::function::args::geos_predicate;
+            use datafusion_common::{internal_datafusion_err, DataFusionError};
+            use geos::Geom;
+            geos_predicate(self.name(), args, |geom0, geom1| {
+                geom0
+                    .overlaps(geom1)
+                    .map_err(|e| internal_datafusion_err!("Failed to do overlaps, error: {}", e))
+            })
+        }
+        #[cfg(not(feature = "geos"))]
+        {
+            use crate::function::args::geo_predicate;
+            use geo::Relate;
+            geo_predicate(self.name(), args, |geom0, geom1| {
+                geom0.relate(geom1).is_overlaps()
+            })
+        }
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for OverlapsUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,128 @@
+use crate::function::args::{as_geometry_array, geometry_args};
+use arrow_array::Float64Array;
+use arrow_schema::DataType;
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+use geo::EuclideanLength;
+use std::any::Any;
+use std::sync::Arc;
+
+/// Returns the 2d perimeter of polygonal geometries including interior rings, 0 for other types.
+#[derive(Debug)]
+pub struct PerimeterUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl PerimeterUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::uniform(
+                1,
+                vec![DataType::Binary, DataType::LargeBinary],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_perimeter".to_string(), "st_perimeter2d".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for PerimeterUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_Perimeter"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(DataType::Float64)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let wkb_arr = as_geometry_array(&arrays[0])?;
+
+        let mut perimeter_vec = vec![];
+        for i in 0..wkb_arr.geom_len() {
+            perimeter_vec.push(wkb_arr.geo_value(i)?.map(|geom| perimeter(&geom)));
+        }
+        Ok(ColumnarValue::Array(Arc::new(Float64Array::from(
+            perimeter_vec,
+        ))))
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for PerimeterUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn polygon_perimeter(polygon: &geo::Polygon) -> f64 {
+    polygon.exterior().euclidean_length()
+        + polygon
+            .interiors()
+            .iter()
+            .map(|ring| ring.euclidean_length())
+            .sum::<f64>()
+}
+
+fn perimeter(geom: &geo::Geometry) -> f64 {
+    match geom {
+        geo::Geometry::Polygon(polygon) => polygon_perimeter(polygon),
+        geo::Geometry::MultiPolygon(multi_polygon) => {
+            multi_polygon.iter().map(polygon_perimeter).sum()
+        }
+        geo::Geometry::Rect(rect) => polygon_perimeter(&rect.to_polygon()),
+        geo::Geometry::Triangle(triangle) => polygon_perimeter(&triangle.to_polygon()),
+        geo::Geometry::GeometryCollection(collection) => collection.iter().map(perimeter).sum(),
+        _ => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{GeomFromTextUdf, PerimeterUdf};
+    use arrow::util::pretty::pretty_format_batches;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+
+    #[tokio::test]
+    async fn perimeter() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(PerimeterUdf::new()));
+        let df = ctx
+            .sql(
+                "select ST_Perimeter2D(ST_GeomFromText(wkt)) as perimeter from (values \
+            ('POLYGON((0 0,10 0,10 10,0 10,0 0),(2 2,4 2,4 4,2 4,2 2))'), \
+            ('MULTIPOLYGON(((0 0,10 0,10 10,0 10,0 0),(2 2,4 2,4 4,2 4,2 2)),((20 20,21 20,21 21,20 21,20 20)))'), \
+            ('LINESTRING(0 0,10 0)'), \
+            (null)) as t(wkt)",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+-----------+
+| perimeter |
++-----------+
+| 48.0      |
+| 52.0      |
+| 0.0       |
+|           |
++-----------+"
+        );
+    }
+}
@@ -0,0 +1,199 @@
+use crate::config::default_dialect;
+use crate::function::args::geometry_args;
+use crate::geo::{Box2d, GeometryArrayBuilder};
+use crate::DFResult;
+use arrow_array::cast::AsArray;
+use arrow_array::types::Int64Type;
+use arrow_array::{Array, ArrayRef};
+use arrow_schema::DataType;
+use datafusion_common::{exec_err, DataFusionError};
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+use geo::polygon;
+use std::any::Any;
+use std::sync::Arc;
+
+/// Returns the polygon of the cell at (col, row) of a regular grid of width x height cells over
+/// a box2d extent, row 0 is at the top like in rasters. Cells outside the grid are null.
+#[derive(Debug)]
+pub struct PixelAsPolygonUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl PixelAsPolygonUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::exact(
+                vec![
+                    Box2d::data_type(),
+                    DataType::Int64,
+                    DataType::Int64,
+                    DataType::Int64,
+                    DataType::Int64,
+                ],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_pixelaspolygon".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for PixelAsPolygonUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_PixelAsPolygon"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(DataType::Binary)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let box_arr = arrays[0].as_struct();
+        let mut builder = GeometryArrayBuilder::<i32>::new(default_dialect(), box_arr.len());
+        for i in 0..box_arr.len() {
+            let Some(grid) = PixelGrid::value(&arrays, i)? else {
+                builder.append_null();
+                continue;
+            };
+            let (col, row) = (int_value(&arrays[3], i), int_value(&arrays[4], i));
+            let cell = match (col, row) {
+                (Some(col), Some(row)) => grid.cell_polygon(col, row),
+                _ => None,
+            };
+            builder.append_geo_geometry(&cell)?;
+        }
+        Ok(ColumnarValue::Array(Arc::new(builder.build())))
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for PixelAsPolygonUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A regular grid over a box2d, read from the `(extent, width, height, ..)` args.
+pub(crate) struct PixelGrid {
+    extent: Box2d,
+    width: i64,
+    height: i64,
+}
+
+impl PixelGrid {
+    /// The grid of a row, None if any of its args is null.
+    pub(crate) fn value(arrays: &[ArrayRef], index: usize) -> DFResult<Option<Self>> {
+        let extent = Box2d::value(arrays[0].as_struct(), index)?;
+        let (Some(extent), Some(width), Some(height)) = (
+            extent,
+            int_value(&arrays[1], index),
+            int_value(&arrays[2], index),
+        ) else {
+            return Ok(None);
+        };
+        if width <= 0 || height <= 0 {
+            return exec_err!("The grid size should be positive, got {}x{}", width, height);
+        }
+        if !(extent.xmax > extent.xmin && extent.ymax > extent.ymin) {
+            return exec_err!("The grid extent should not be empty, got {:?}", extent);
+        }
+        Ok(Some(Self {
+            extent,
+            width,
+            height,
+        }))
+    }
+
+    fn cell_size(&self) -> (f64, f64) {
+        (
+            (self.extent.xmax - self.extent.xmin) / self.width as f64,
+            (self.extent.ymax - self.extent.ymin) / self.height as f64,
+        )
+    }
+
+    fn cell_polygon(&self, col: i64, row: i64) -> Option<geo::Geometry> {
+        if !(0..self.width).contains(&col) || !(0..self.height).contains(&row) {
+            return None;
+        }
+        let (cell_width, cell_height) = self.cell_size();
+        let x0 = self.extent.xmin + col as f64 * cell_width;
+        let x1 = self.extent.xmin + (col + 1) as f64 * cell_width;
+        let y0 = self.extent.ymax - (row + 1) as f64 * cell_height;
+        let y1 = self.extent.ymax - row as f64 * cell_height;
+        Some(polygon![(x: x0, y: y0), (x: x1, y: y0), (x: x1, y: y1), (x: x0, y: y1)].into())
+    }
+
+    /// Column and row of the cell containing the coordinate, points on the right and bottom
+    /// edges of the extent belong to the last cells.
+    pub(crate) fn pixel(&self, coord: geo::Coord) -> Option<(i64, i64)> {
+        let extent = &self.extent;
+        if !(coord.x >= extent.xmin
+            && coord.x <= extent.xmax
+            && coord.y >= extent.ymin
+            && coord.y <= extent.ymax)
+        {
+            return None;
+        }
+        let (cell_width, cell_height) = self.cell_size();
+        let col = ((coord.x - extent.xmin) / cell_width).floor() as i64;
+        let row = ((extent.ymax - coord.y) / cell_height).floor() as i64;
+        Some((col.min(self.width - 1), row.min(self.height - 1)))
+    }
+}
+
+pub(crate) fn int_value(arr: &ArrayRef, index: usize) -> Option<i64> {
+    let arr = arr.as_primitive::<Int64Type>();
+    arr.is_valid(index).then(|| arr.value(index))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::box2d::Box2dUdf;
+    use crate::function::{AsTextUdf, GeomFromTextUdf, PixelAsPolygonUdf};
+    use arrow::util::pretty::pretty_format_batches;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+
+    #[tokio::test]
+    async fn pixel_as_polygon() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(Box2dUdf::new()));
+        ctx.register_udf(ScalarUDF::from(PixelAsPolygonUdf::new()));
+        let df = ctx
+            .sql(
+                "select col, row, ST_AsText(ST_PixelAsPolygon(\
+                Box2D(ST_GeomFromText('LINESTRING(0 0,10 5)')), 10, 5, col, row)) as pixel \
+                from (values (0, 0), (9, 4), (10, 0), (0, -1), (null, 0)) as t(col, row)",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+-----+-----+----------------------------------+
+| col | row | pixel                            |
++-----+-----+----------------------------------+
+| 0   | 0   | POLYGON((0 4,1 4,1 5,0 5,0 4))   |
+| 9   | 4   | POLYGON((9 0,10 0,10 1,9 1,9 0)) |
+| 10  | 0   |                                  |
+| 0   | -1  |                                  |
+|     | 0   |                                  |
++-----+-----+----------------------------------+"
+        );
+    }
+}
@@ -0,0 +1,226 @@
+use crate::config::default_dialect;
+use crate::function::args::geometry_args;
+use crate::function::simplify_for_scale::simplify;
+use crate::geo::dialect::decode_srid;
+use crate::geo::{GeometryArray, GeometryArrayBuilder};
+use crate::DFResult;
+use arrow_array::cast::AsArray;
+use arrow_array::{GenericBinaryArray, OffsetSizeTrait};
+use arrow_schema::DataType;
+use datafusion_common::{exec_err, DataFusionError, ScalarValue};
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, TypeSignature, Volatility};
+use geo::{BoundingRect, CoordsIter};
+use std::any::Any;
+use std::sync::Arc;
+
+/// Number of halvings of the tolerance range, enough to get below a billionth of the extent.
+const MAX_ITERATIONS: usize = 32;
+
+/// Simplifies a geometry to at most the given number of vertices, the smallest topology preserving
+/// Visvalingam-Whyatt tolerance meeting the budget is found by binary search. Rings are never
+/// reduced below their minimal valid size, so the budget may be exceeded for very small budgets.
+#[derive(Debug)]
+pub struct ReducePointsUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl ReducePointsUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                vec![
+                    TypeSignature::Exact(vec![DataType::Binary, DataType::Int64]),
+                    TypeSignature::Exact(vec![DataType::LargeBinary, DataType::Int64]),
+                ],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_reducepoints".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for ReducePointsUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_ReducePoints"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(arg_types[0].clone())
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let ColumnarValue::Scalar(ScalarValue::Int64(Some(max_points))) = args[1] else {
+            return exec_err!("The second arg should be int64 scalar");
+        };
+        if max_points < 0 {
+            return exec_err!("Max points should not be negative");
+        }
+
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let arr = &arrays[0];
+        match arr.data_type() {
+            DataType::Binary => reduce_points::<i32>(arr.as_binary::<i32>(), max_points as usize),
+            DataType::LargeBinary => {
+                reduce_points::<i64>(arr.as_binary::<i64>(), max_points as usize)
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for ReducePointsUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn reduce_points<O: OffsetSizeTrait>(
+    wkb_arr: &GenericBinaryArray<O>,
+    max_points: usize,
+) -> DFResult<ColumnarValue> {
+    let mut builder = GeometryArrayBuilder::<O>::new(default_dialect(), wkb_arr.geom_len());
+    for i in 0..wkb_arr.geom_len() {
+        let Some(wkb) = wkb_arr.wkb(i) else {
+            builder.append_null();
+            continue;
+        };
+        let srid = decode_srid(wkb)?;
+        let geom = wkb_arr
+            .geo_value(i)?
+            .map(|geom| reduce_geometry(geom, max_points));
+        builder.append_geo_geometry_with_srid(&geom, srid)?;
+    }
+    Ok(ColumnarValue::Array(Arc::new(builder.build())))
+}
+
+fn reduce_geometry(geom: geo::Geometry, max_points: usize) -> geo::Geometry {
+    if geom.coords_count() <= max_points {
+        return geom;
+    }
+    let Some(rect) = geom.bounding_rect() else {
+        return geom;
+    };
+    // a tolerance of the largest extent removes every vertex which can be removed
+    let mut low = 0.0;
+    let mut high = rect.width().max(rect.height());
+    let mut reduced = simplify(geom.clone(), high);
+    if reduced.coords_count() > max_points {
+        return reduced;
+    }
+    for _ in 0..MAX_ITERATIONS {
+        let tolerance = (low + high) / 2.0;
+        let candidate = simplify(geom.clone(), tolerance);
+        if candidate.coords_count() <= max_points {
+            high = tolerance;
+            reduced = candidate;
+        } else {
+            low = tolerance;
+        }
+    }
+    reduced
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{GeomFromTextUdf, ReducePointsUdf};
+    use crate::geo::GeometryArray;
+    use arrow_array::cast::AsArray;
+    #[cfg(feature = "geos")]
+    use arrow_array::BinaryArray;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+    use geo::{Area, CoordsIter};
+    use std::f64::consts::PI;
+
+    #[tokio::test]
+    async fn reduce_points() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(ReducePointsUdf::new()));
+
+        // a wiggly coastline like ring around an island
+        let mut coords = (0..10_000)
+            .map(|i| {
+                let angle = 2.0 * PI * i as f64 / 10_000.0;
+                let radius = 1000.0 + 50.0 * (angle * 40.0).sin() + 5.0 * (angle * 900.0).sin();
+                format!("{} {}", radius * angle.cos(), radius * angle.sin())
+            })
+            .collect::<Vec<_>>();
+        coords.push(coords[0].clone());
+        let wkt = format!("POLYGON(({}))", coords.join(","));
+
+        let sql = format!(
+            "select ST_GeomFromText('{}'), ST_ReducePoints(ST_GeomFromText('{}'), 500)",
+            wkt, wkt
+        );
+        let batches = ctx.sql(&sql).await.unwrap().collect().await.unwrap();
+        let original = batches[0]
+            .column(0)
+            .as_binary::<i32>()
+            .geo_value(0)
+            .unwrap()
+            .unwrap();
+        let reduced = batches[0]
+            .column(1)
+            .as_binary::<i32>()
+            .geo_value(0)
+            .unwrap()
+            .unwrap();
+        assert_eq!(original.coords_count(), 10_001);
+        assert!(reduced.coords_count() <= 500);
+        // the reduced budget should be used rather than collapsing the ring
+        assert!(reduced.coords_count() > 250);
+
+        let geo::Geometry::Polygon(polygon) = &reduced else {
+            panic!("reduced geometry should be a polygon");
+        };
+        assert!(polygon.exterior().is_closed());
+        assert!(polygon.exterior().0.len() >= 4);
+        let ratio = reduced.unsigned_area() / original.unsigned_area();
+        assert!((ratio - 1.0).abs() < 0.01);
+        #[cfg(feature = "geos")]
+        assert_valid(batches[0].column(1).as_binary::<i32>());
+
+        // a hole close to the coastline stays inside the reduced exterior
+        let hole = "880 -30,940 -30,940 30,880 30,880 -30";
+        let sql = format!(
+            "select ST_ReducePoints(ST_GeomFromText('POLYGON(({}),({}))'), 100)",
+            coords.join(","),
+            hole
+        );
+        let batches = ctx.sql(&sql).await.unwrap().collect().await.unwrap();
+        let arr = batches[0].column(0).as_binary::<i32>();
+        let geo::Geometry::Polygon(polygon) = arr.geo_value(0).unwrap().unwrap() else {
+            panic!("reduced geometry should be a polygon");
+        };
+        assert_eq!(polygon.interiors().len(), 1);
+        let vertices = polygon.coords_count();
+        assert!(vertices <= 100, "{}", vertices);
+        #[cfg(feature = "geos")]
+        assert_valid(arr);
+    }
+
+    #[cfg(feature = "geos")]
+    fn assert_valid(arr: &BinaryArray) {
+        use geos::Geom;
+        let geom = arr.geos_value(0).unwrap().unwrap();
+        assert!(
+            geom.is_valid(),
+            "{}",
+            geom.is_valid_reason().unwrap_or_default()
+        );
+    }
+}
@@ -0,0 +1,178 @@
+use crate::config::default_dialect;
+use crate::function::args::geometry_args;
+use crate::geo::dialect::decode_srid;
+use crate::geo::{GeometryArray, GeometryArrayBuilder};
+use crate::DFResult;
+use arrow_array::cast::AsArray;
+use arrow_array::types::Int64Type;
+use arrow_array::{Array, GenericBinaryArray, Int64Array, OffsetSizeTrait};
+use arrow_schema::DataType;
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, TypeSignature, Volatility};
+use std::any::Any;
+use std::sync::Arc;
+
+/// Returns the 1-based nth ring of a (multi)polygon as a closed linestring, null when out of range.
+/// Rings are numbered member by member, the exterior before the interiors, as counted by ST_NRings.
+#[derive(Debug)]
+pub struct RingNUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl RingNUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                vec![
+                    TypeSignature::Exact(vec![DataType::Binary, DataType::Int64]),
+                    TypeSignature::Exact(vec![DataType::LargeBinary, DataType::Int64]),
+                ],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_ringn".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for RingNUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_RingN"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(arg_types[0].clone())
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let n_arr = arrays[1].as_primitive::<Int64Type>();
+        match arrays[0].data_type() {
+            DataType::Binary => ring_n::<i32>(arrays[0].as_binary::<i32>(), n_arr),
+            DataType::LargeBinary => ring_n::<i64>(arrays[0].as_binary::<i64>(), n_arr),
+            _ => unreachable!(),
+        }
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for RingNUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn ring_n<O: OffsetSizeTrait>(
+    wkb_arr: &GenericBinaryArray<O>,
+    n_arr: &Int64Array,
+) -> DFResult<ColumnarValue> {
+    let mut builder = GeometryArrayBuilder::<O>::new(default_dialect(), wkb_arr.geom_len());
+    for i in 0..wkb_arr.geom_len() {
+        let Some(wkb) = wkb_arr.wkb(i).filter(|_| n_arr.is_valid(i)) else {
+            builder.append_null();
+            continue;
+        };
+        let ring = match (wkb_arr.geo_value(i)?, usize::try_from(n_arr.value(i))) {
+            (Some(geom), Ok(n)) if n >= 1 => rings(&geom).into_iter().nth(n - 1),
+            _ => None,
+        };
+        match ring {
+            Some(ring) => {
+                builder.append_geo_geometry_with_srid(&Some(ring.into()), decode_srid(wkb)?)?
+            }
+            None => builder.append_null(),
+        }
+    }
+    Ok(ColumnarValue::Array(Arc::new(builder.build())))
+}
+
+/// All rings of a (multi)polygon, member by member with the exterior before the interiors.
+pub(crate) fn rings(geom: &geo::Geometry) -> Vec<geo::LineString> {
+    let polygon_rings = |polygon: &geo::Polygon| {
+        std::iter::once(polygon.exterior().clone())
+            .chain(polygon.interiors().iter().cloned())
+            .collect::<Vec<_>>()
+    };
+    match geom {
+        geo::Geometry::Polygon(polygon) => polygon_rings(polygon),
+        geo::Geometry::MultiPolygon(polygons) => polygons.iter().flat_map(polygon_rings).collect(),
+        geo::Geometry::Rect(rect) => polygon_rings(&rect.to_polygon()),
+        geo::Geometry::Triangle(triangle) => polygon_rings(&triangle.to_polygon()),
+        _ => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{AsTextUdf, GeomFromTextUdf, NRingsUdf, RingNUdf};
+    use arrow::util::pretty::pretty_format_batches;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+
+    #[tokio::test]
+    async fn ring_n() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(NRingsUdf::new()));
+        ctx.register_udf(ScalarUDF::from(RingNUdf::new()));
+        let df = ctx
+            .sql(
+                "select n, ST_AsText(ST_RingN(geom, n)) as ring \
+                from (select ST_GeomFromText('MULTIPOLYGON(((0 0,10 0,10 10,0 10,0 0),\
+                (1 1,2 1,2 2,1 1),(3 3,4 3,4 4,3 3)),((20 0,30 0,30 10,20 0),(21 1,22 1,22 2,21 1)))') \
+                as geom) as g \
+                cross join (values (0), (1), (2), (3), (4), (5), (6)) as t(n) order by n",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+---+-------------------------------------+
+| n | ring                                |
++---+-------------------------------------+
+| 0 |                                     |
+| 1 | LINESTRING(0 0,10 0,10 10,0 10,0 0) |
+| 2 | LINESTRING(1 1,2 1,2 2,1 1)         |
+| 3 | LINESTRING(3 3,4 3,4 4,3 3)         |
+| 4 | LINESTRING(20 0,30 0,30 10,20 0)    |
+| 5 | LINESTRING(21 1,22 1,22 2,21 1)     |
+| 6 |                                     |
++---+-------------------------------------+"
+        );
+
+        // iterating up to ST_NRings visits every ring once
+        let df = ctx
+            .sql(
+                "select count(ST_RingN(geom, n)) as rings \
+                from (select ST_GeomFromText('MULTIPOLYGON(((0 0,10 0,10 10,0 10,0 0),\
+                (1 1,2 1,2 2,1 1)),((20 0,30 0,30 10,20 0)))') as geom) as g \
+                cross join (values (1), (2), (3), (4)) as t(n) \
+                where n <= ST_NRings(geom)",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+-------+
+| rings |
++-------+
+| 3     |
++-------+"
+        );
+    }
+}
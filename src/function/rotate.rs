@@ -0,0 +1,134 @@
+use crate::expr::AffineBuilder;
+use crate::function::affine::affine_transform;
+use arrow_schema::DataType;
+use datafusion_common::{exec_err, DataFusionError, ScalarValue};
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, TypeSignature, Volatility};
+use std::any::Any;
+
+/// Rotates a geometry counter-clockwise by the angle in radians, around the origin or around the
+/// point given by `ST_Rotate(geom, angle, x, y)`.
+#[derive(Debug)]
+pub struct RotateUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl RotateUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                vec![
+                    TypeSignature::Exact(vec![DataType::Binary, DataType::Float64]),
+                    TypeSignature::Exact(vec![DataType::LargeBinary, DataType::Float64]),
+                    TypeSignature::Exact(vec![
+                        DataType::Binary,
+                        DataType::Float64,
+                        DataType::Float64,
+                        DataType::Float64,
+                    ]),
+                    TypeSignature::Exact(vec![
+                        DataType::LargeBinary,
+                        DataType::Float64,
+                        DataType::Float64,
+                        DataType::Float64,
+                    ]),
+                ],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_rotate".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for RotateUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_Rotate"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(arg_types[0].clone())
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let ColumnarValue::Scalar(ScalarValue::Float64(Some(angle))) = args[1] else {
+            return exec_err!("The second arg should be f64 scalar");
+        };
+        let builder = AffineBuilder::new();
+        let builder = if args.len() == 4 {
+            let ColumnarValue::Scalar(ScalarValue::Float64(Some(x))) = args[2] else {
+                return exec_err!("The third arg should be f64 scalar");
+            };
+            let ColumnarValue::Scalar(ScalarValue::Float64(Some(y))) = args[3] else {
+                return exec_err!("The fourth arg should be f64 scalar");
+            };
+            builder.rotate_around(angle, x, y)
+        } else {
+            builder.rotate(angle)
+        };
+        let transform = builder.transform();
+        affine_transform(self.name(), args, &transform)
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for RotateUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{GeomFromTextUdf, RotateUdf};
+    use crate::test_utils::assert_geometry_array_eq;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+
+    #[tokio::test]
+    async fn rotate() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(RotateUdf::new()));
+        let df = ctx
+            .sql("select ST_Rotate(ST_GeomFromText('LINESTRING(1 0,2 1)'), pi() / 2), ST_GeomFromText('LINESTRING(0 1,-1 2)')")
+            .await
+            .unwrap();
+        let batches = df.collect().await.unwrap();
+        assert_geometry_array_eq(batches[0].column(0), batches[0].column(1), 1e-9);
+    }
+
+    #[tokio::test]
+    async fn rotate_around_point() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(RotateUdf::new()));
+        let df = ctx
+            .sql("select ST_Rotate(ST_GeomFromText('POINT(2 1)'), pi() / 2, 1.0, 1.0), ST_GeomFromText('POINT(1 2)')")
+            .await
+            .unwrap();
+        let batches = df.collect().await.unwrap();
+        assert_geometry_array_eq(batches[0].column(0), batches[0].column(1), 1e-9);
+
+        let err = ctx
+            .sql("select ST_Rotate(ST_GeomFromText('POINT(2 1)'), pi() / 2, x, 1.0) from (values (1.0)) as t(x)")
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("The third arg should be f64 scalar"));
+    }
+}
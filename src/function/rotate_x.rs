@@ -0,0 +1,166 @@
+use crate::config::default_dialect;
+use crate::function::args::geometry_args;
+use crate::geo::xyz::map_coords_xyz;
+use crate::geo::{GeometryArray, GeometryArrayBuilder};
+use crate::DFResult;
+use arrow_array::cast::AsArray;
+use arrow_array::{GenericBinaryArray, OffsetSizeTrait};
+use arrow_schema::DataType;
+use datafusion_common::{exec_err, DataFusionError, ScalarValue};
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, TypeSignature, Volatility};
+use std::any::Any;
+use std::sync::Arc;
+
+/// Rotates a geometry around the x axis by the angle in radians, the result is always 3d and 2d
+/// geometries are rotated as if their z is 0.
+#[derive(Debug)]
+pub struct RotateXUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl RotateXUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                vec![
+                    TypeSignature::Exact(vec![DataType::Binary, DataType::Float64]),
+                    TypeSignature::Exact(vec![DataType::LargeBinary, DataType::Float64]),
+                ],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_rotatex".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for RotateXUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_RotateX"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(arg_types[0].clone())
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let ColumnarValue::Scalar(ScalarValue::Float64(Some(angle))) = args[1] else {
+            return exec_err!("The second arg should be f64 scalar");
+        };
+        let (sin, cos) = angle.sin_cos();
+        xyz_transform(self.name(), args, move |x, y, z| {
+            (x, y * cos - z * sin, y * sin + z * cos)
+        })
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for RotateXUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Maps the xyz coordinates of the geometry arg, the other args are ignored.
+pub(crate) fn xyz_transform(
+    name: &str,
+    args: &[ColumnarValue],
+    map: impl Fn(f64, f64, f64) -> (f64, f64, f64) + Copy,
+) -> DFResult<ColumnarValue> {
+    let (arrays, _recorder) = geometry_args(name, &args[..1])?;
+    let arr = &arrays[0];
+    match arr.data_type() {
+        DataType::Binary => xyz_transform_array::<i32>(arr.as_binary::<i32>(), map),
+        DataType::LargeBinary => xyz_transform_array::<i64>(arr.as_binary::<i64>(), map),
+        _ => unreachable!(),
+    }
+}
+
+fn xyz_transform_array<O: OffsetSizeTrait>(
+    wkb_arr: &GenericBinaryArray<O>,
+    map: impl Fn(f64, f64, f64) -> (f64, f64, f64) + Copy,
+) -> DFResult<ColumnarValue> {
+    let dialect = default_dialect();
+    let mut builder = GeometryArrayBuilder::<O>::new(dialect, wkb_arr.geom_len());
+    for i in 0..wkb_arr.geom_len() {
+        match wkb_arr.wkb(i) {
+            Some(wkb) => builder.append_wkb(Some(&map_coords_xyz(wkb, dialect, map)?))?,
+            None => builder.append_null(),
+        }
+    }
+    Ok(ColumnarValue::Array(Arc::new(builder.build())))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{GeomFromTextUdf, RotateXUdf, RotateYUdf};
+    use crate::geo::dialect::decode_srid;
+    use crate::geo::xyz::map_coords_xyz;
+    use crate::geo::GeometryArray;
+    use arrow_array::cast::AsArray;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+    use geozero::wkb::WkbDialect;
+
+    fn coords(wkb: &[u8]) -> Vec<(f64, f64, f64)> {
+        let mut coords = vec![];
+        map_coords_xyz(wkb, WkbDialect::Wkb, |x, y, z| {
+            coords.push((x, y, z));
+            (x, y, z)
+        })
+        .unwrap();
+        coords
+    }
+
+    fn assert_coords_eq(left: &[(f64, f64, f64)], right: &[(f64, f64, f64)]) {
+        assert_eq!(left.len(), right.len());
+        for (l, r) in left.iter().zip(right) {
+            assert!(
+                (l.0 - r.0).abs() < 1e-9 && (l.1 - r.1).abs() < 1e-9 && (l.2 - r.2).abs() < 1e-9,
+                "{:?} != {:?}",
+                left,
+                right
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn rotate_x_and_y() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(RotateXUdf::new()));
+        ctx.register_udf(ScalarUDF::from(RotateYUdf::new()));
+        // a segment along the y axis is stood up along the z axis, then laid down along the x axis
+        let df = ctx
+            .sql(
+                "select ST_RotateX(geom, pi() / 2), \
+                ST_RotateY(ST_RotateX(geom, pi() / 2), pi() / 2) \
+                from (select ST_GeomFromText('LINESTRING(0 0,0 1)', 4326) as geom)",
+            )
+            .await
+            .unwrap();
+        let batches = df.collect().await.unwrap();
+        let vertical = batches[0].column(0).as_binary::<i32>();
+        assert_coords_eq(
+            &coords(vertical.wkb(0).unwrap()),
+            &[(0.0, 0.0, 0.0), (0.0, 0.0, 1.0)],
+        );
+        let laid_down = batches[0].column(1).as_binary::<i32>();
+        assert_coords_eq(
+            &coords(laid_down.wkb(0).unwrap()),
+            &[(0.0, 0.0, 0.0), (1.0, 0.0, 0.0)],
+        );
+        assert_eq!(decode_srid(laid_down.wkb(0).unwrap()).unwrap(), Some(4326));
+    }
+}
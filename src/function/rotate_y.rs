@@ -0,0 +1,66 @@
+use crate::function::rotate_x::xyz_transform;
+use arrow_schema::DataType;
+use datafusion_common::{exec_err, DataFusionError, ScalarValue};
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, TypeSignature, Volatility};
+use std::any::Any;
+
+/// Rotates a geometry around the y axis by the angle in radians, the result is always 3d and 2d
+/// geometries are rotated as if their z is 0.
+#[derive(Debug)]
+pub struct RotateYUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl RotateYUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                vec![
+                    TypeSignature::Exact(vec![DataType::Binary, DataType::Float64]),
+                    TypeSignature::Exact(vec![DataType::LargeBinary, DataType::Float64]),
+                ],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_rotatey".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for RotateYUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_RotateY"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(arg_types[0].clone())
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let ColumnarValue::Scalar(ScalarValue::Float64(Some(angle))) = args[1] else {
+            return exec_err!("The second arg should be f64 scalar");
+        };
+        let (sin, cos) = angle.sin_cos();
+        xyz_transform(self.name(), args, move |x, y, z| {
+            (x * cos + z * sin, y, z * cos - x * sin)
+        })
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for RotateYUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
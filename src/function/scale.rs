@@ -0,0 +1,135 @@
+use crate::expr::AffineBuilder;
+use crate::function::affine::affine_transform;
+use arrow_schema::DataType;
+use datafusion_common::{exec_err, DataFusionError, ScalarValue};
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, TypeSignature, Volatility};
+use std::any::Any;
+
+/// Scales a geometry relative to the origin by the x and y factors.
+#[derive(Debug)]
+pub struct ScaleUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl ScaleUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                vec![
+                    TypeSignature::Exact(vec![
+                        DataType::Binary,
+                        DataType::Float64,
+                        DataType::Float64,
+                    ]),
+                    TypeSignature::Exact(vec![
+                        DataType::LargeBinary,
+                        DataType::Float64,
+                        DataType::Float64,
+                    ]),
+                ],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_scale".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for ScaleUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_Scale"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(arg_types[0].clone())
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let ColumnarValue::Scalar(ScalarValue::Float64(Some(x_factor))) = args[1] else {
+            return exec_err!("The second arg should be f64 scalar");
+        };
+        let ColumnarValue::Scalar(ScalarValue::Float64(Some(y_factor))) = args[2] else {
+            return exec_err!("The third arg should be f64 scalar");
+        };
+        let transform = AffineBuilder::new().scale(x_factor, y_factor).transform();
+        affine_transform(self.name(), args, &transform)
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for ScaleUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{AsTextUdf, GeomFromTextUdf, ScaleUdf};
+    use arrow::util::pretty::pretty_format_batches;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+
+    #[tokio::test]
+    async fn scale() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(ScaleUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
+        let df = ctx
+            .sql("select ST_AsText(ST_Scale(ST_GeomFromText('LINESTRING(1 2,3 4)'), 0.5, 3)) as geom")
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+--------------------------+
+| geom                     |
++--------------------------+
+| LINESTRING(0.5 6,1.5 12) |
++--------------------------+"
+        );
+    }
+
+    #[tokio::test]
+    async fn scale_polygon_area() {
+        use crate::geo::GeometryArray;
+        use arrow_array::cast::AsArray;
+        use geo::Area;
+
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(ScaleUdf::new()));
+        let df = ctx
+            .sql(
+                "select ST_GeomFromText(wkt), ST_Scale(ST_GeomFromText(wkt), 2.0, 2.0) \
+            from (values ('POLYGON((0 0,3 0,3 1,0 1,0 0))')) as t(wkt)",
+            )
+            .await
+            .unwrap();
+        let batches = df.collect().await.unwrap();
+        let area = |i: usize| {
+            batches[0]
+                .column(i)
+                .as_binary::<i32>()
+                .geo_value(0)
+                .unwrap()
+                .unwrap()
+                .unsigned_area()
+        };
+        assert_eq!(area(0), 3.0);
+        assert_eq!(area(1), 4.0 * area(0));
+    }
+}
@@ -0,0 +1,158 @@
+use crate::config::default_dialect;
+use crate::function::args::geometry_args;
+use crate::geo::dialect::decode_srid;
+use crate::geo::{Box2d, GeometryArray, GeometryArrayBuilder};
+use crate::DFResult;
+use arrow_array::cast::AsArray;
+use arrow_array::{GenericBinaryArray, OffsetSizeTrait, StructArray};
+use arrow_schema::DataType;
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, TypeSignature, Volatility};
+use geo::AffineOps;
+use std::any::Any;
+use std::sync::Arc;
+
+/// Scales and translates a geometry so the source box fits the target box, e.g. to fit a data
+/// extent into a pixel extent.
+#[derive(Debug)]
+pub struct ScaleToFitUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl ScaleToFitUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                vec![
+                    TypeSignature::Exact(vec![
+                        DataType::Binary,
+                        Box2d::data_type(),
+                        Box2d::data_type(),
+                    ]),
+                    TypeSignature::Exact(vec![
+                        DataType::LargeBinary,
+                        Box2d::data_type(),
+                        Box2d::data_type(),
+                    ]),
+                ],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_scaletofit".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for ScaleToFitUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_ScaleToFit"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(arg_types[0].clone())
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let source_arr = arrays[1].as_struct();
+        let target_arr = arrays[2].as_struct();
+        match arrays[0].data_type() {
+            DataType::Binary => {
+                scale_to_fit::<i32>(arrays[0].as_binary::<i32>(), source_arr, target_arr)
+            }
+            DataType::LargeBinary => {
+                scale_to_fit::<i64>(arrays[0].as_binary::<i64>(), source_arr, target_arr)
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for ScaleToFitUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn scale_to_fit<O: OffsetSizeTrait>(
+    wkb_arr: &GenericBinaryArray<O>,
+    source_arr: &StructArray,
+    target_arr: &StructArray,
+) -> DFResult<ColumnarValue> {
+    let mut builder = GeometryArrayBuilder::<O>::new(default_dialect(), wkb_arr.geom_len());
+    for i in 0..wkb_arr.geom_len() {
+        let (Some(wkb), Some(source), Some(target)) = (
+            wkb_arr.wkb(i),
+            Box2d::value(source_arr, i)?,
+            Box2d::value(target_arr, i)?,
+        ) else {
+            builder.append_null();
+            continue;
+        };
+        let transform = source.transform_to(&target, false)?;
+        let geom = wkb_arr
+            .geo_value(i)?
+            .map(|geom| geom.affine_transform(&transform));
+        builder.append_geo_geometry_with_srid(&geom, decode_srid(wkb)?)?;
+    }
+    Ok(ColumnarValue::Array(Arc::new(builder.build())))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::box2d::Box2dUdf;
+    use crate::function::{AsTextUdf, GeomFromTextUdf, ScaleToFitUdf};
+    use arrow::util::pretty::pretty_format_batches;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+
+    #[tokio::test]
+    async fn scale_to_fit() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(Box2dUdf::new()));
+        ctx.register_udf(ScalarUDF::from(ScaleToFitUdf::new()));
+        let df = ctx
+            .sql(
+                "select ST_AsText(ST_ScaleToFit(ST_GeomFromText(wkt), \
+                Box2D(ST_GeomFromText('LINESTRING(10 20,20 40)')), \
+                Box2D(ST_GeomFromText('LINESTRING(0 0,100 100)')))) as fitted \
+                from (values ('LINESTRING(10 20,15 30,20 40)'), (null)) as t(wkt)",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+-------------------------------+
+| fitted                        |
++-------------------------------+
+| LINESTRING(0 0,50 50,100 100) |
+|                               |
++-------------------------------+"
+        );
+
+        let df = ctx
+            .sql(
+                "select ST_ScaleToFit(ST_GeomFromText('POINT(1 1)'), \
+                Box2D(ST_GeomFromText('LINESTRING(10 20,10 40)')), \
+                Box2D(ST_GeomFromText('LINESTRING(0 0,100 100)')))",
+            )
+            .await
+            .unwrap();
+        assert!(df.collect().await.is_err());
+    }
+}
@@ -0,0 +1,214 @@
+use crate::config::default_dialect;
+use crate::function::args::geometry_args;
+use crate::geo::dialect::decode_srid;
+use crate::geo::{GeometryArray, GeometryArrayBuilder};
+use crate::DFResult;
+use arrow_array::cast::AsArray;
+use arrow_array::{GenericBinaryArray, OffsetSizeTrait};
+use arrow_schema::DataType;
+use datafusion_common::{exec_err, DataFusionError, ScalarValue};
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, TypeSignature, Volatility};
+use std::any::Any;
+use std::sync::Arc;
+
+/// Simplifies a geometry with the Douglas-Peucker algorithm, vertices closer than the tolerance
+/// to the simplified line are removed. A zero tolerance returns the geometry unchanged.
+#[derive(Debug)]
+pub struct SimplifyUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl SimplifyUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                vec![
+                    TypeSignature::Exact(vec![DataType::Binary, DataType::Float64]),
+                    TypeSignature::Exact(vec![DataType::LargeBinary, DataType::Float64]),
+                ],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_simplify".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for SimplifyUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_Simplify"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(arg_types[0].clone())
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let ColumnarValue::Scalar(ScalarValue::Float64(Some(tolerance))) = args[1] else {
+            return exec_err!("The second arg should be f64 scalar");
+        };
+        if tolerance.is_nan() || tolerance < 0.0 {
+            return exec_err!("Tolerance should not be negative, got {}", tolerance);
+        }
+
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let arr = &arrays[0];
+        match arr.data_type() {
+            DataType::Binary => simplify::<i32>(arr.as_binary::<i32>(), tolerance),
+            DataType::LargeBinary => simplify::<i64>(arr.as_binary::<i64>(), tolerance),
+            _ => unreachable!(),
+        }
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for SimplifyUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn simplify<O: OffsetSizeTrait>(
+    wkb_arr: &GenericBinaryArray<O>,
+    tolerance: f64,
+) -> DFResult<ColumnarValue> {
+    let mut builder = GeometryArrayBuilder::<O>::new(default_dialect(), wkb_arr.geom_len());
+    for i in 0..wkb_arr.geom_len() {
+        let Some(wkb) = wkb_arr.wkb(i) else {
+            builder.append_null();
+            continue;
+        };
+        let srid = decode_srid(wkb)?;
+        if tolerance == 0.0 {
+            builder.append_geo_geometry_with_srid(&wkb_arr.geo_value(i)?, srid)?;
+            continue;
+        }
+        #[cfg(feature = "geos")]
+        {
+            use datafusion_common::internal_datafusion_err;
+            use geos::Geom;
+            let geom = wkb_arr
+                .geos_value(i)?
+                .map(|geom| {
+                    let mut simplified = geom.simplify(tolerance).map_err(|e| {
+                        internal_datafusion_err!("Failed to do simplify, error: {}", e)
+                    })?;
+                    if let Some(srid) = srid {
+                        simplified.set_srid(srid as usize);
+                    }
+                    Ok::<_, DataFusionError>(simplified)
+                })
+                .transpose()?;
+            builder.append_geos_geometry(&geom)?;
+        }
+        #[cfg(not(feature = "geos"))]
+        {
+            use crate::geo::map::map_geometry_recursive;
+            use geo::Simplify;
+            let geom = wkb_arr.geo_value(i)?.map(|geom| {
+                map_geometry_recursive(geom, &mut |geom| match geom {
+                    geo::Geometry::LineString(ls) => ls.simplify(&tolerance).into(),
+                    geo::Geometry::MultiLineString(mls) => mls.simplify(&tolerance).into(),
+                    geo::Geometry::Polygon(p) => p.simplify(&tolerance).into(),
+                    geo::Geometry::MultiPolygon(mp) => mp.simplify(&tolerance).into(),
+                    geom => geom,
+                })
+            });
+            builder.append_geo_geometry_with_srid(&geom, srid)?;
+        }
+    }
+    Ok(ColumnarValue::Array(Arc::new(builder.build())))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{AsTextUdf, GeomFromTextUdf, SimplifyUdf};
+    use crate::geo::dialect::decode_srid;
+    use crate::geo::GeometryArray;
+    use arrow::util::pretty::pretty_format_batches;
+    use arrow_array::cast::AsArray;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+    use geo::CoordsIter;
+
+    #[tokio::test]
+    async fn simplify_dense_linestring() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(SimplifyUdf::new()));
+
+        // a straight line with a 0.01 zigzag
+        let coords = (0..100)
+            .map(|i| format!("{} {}", i, (i % 2) as f64 * 0.01))
+            .collect::<Vec<_>>();
+        let sql = format!(
+            "select ST_Simplify(ST_GeomFromText('LINESTRING({})', 4326), 0.5)",
+            coords.join(",")
+        );
+        let batches = ctx.sql(&sql).await.unwrap().collect().await.unwrap();
+        let arr = batches[0].column(0).as_binary::<i32>();
+        assert_eq!(decode_srid(arr.wkb(0).unwrap()).unwrap(), Some(4326));
+        let geom = arr.geo_value(0).unwrap().unwrap();
+        assert_eq!(geom.coords_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn simplify_zero_tolerance() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(SimplifyUdf::new()));
+        let df = ctx
+            .sql(
+                "select ST_AsText(ST_Simplify(ST_GeomFromText(wkt), 0.0)) as simplified \
+                from (values ('LINESTRING(0 0,1 0,2 0,2 1)'), (null)) as t(wkt)",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+-----------------------------+
+| simplified                  |
++-----------------------------+
+| LINESTRING(0 0,1 0,2 0,2 1) |
+|                             |
++-----------------------------+"
+        );
+    }
+
+    #[tokio::test]
+    async fn simplify_array_tolerance() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(SimplifyUdf::new()));
+        let err = ctx
+            .sql(
+                "select ST_Simplify(ST_GeomFromText(wkt), tolerance) \
+                from (values ('LINESTRING(0 0,1 0,2 0)', 0.5)) as t(wkt, tolerance)",
+            )
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("The second arg should be f64 scalar"),
+            "{}",
+            err
+        );
+    }
+}
@@ -0,0 +1,173 @@
+use crate::config::default_dialect;
+use crate::function::args::geometry_args;
+use crate::geo::dialect::decode_srid;
+use crate::geo::map::map_geometry_recursive;
+use crate::geo::{crs_info, GeometryArray, GeometryArrayBuilder};
+use crate::DFResult;
+use arrow_array::cast::AsArray;
+use arrow_array::{GenericBinaryArray, OffsetSizeTrait};
+use arrow_schema::DataType;
+use datafusion_common::{exec_err, DataFusionError, ScalarValue};
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, TypeSignature, Volatility};
+use geo::{BoundingRect, SimplifyVwPreserve};
+use std::any::Any;
+use std::sync::Arc;
+
+/// Web mercator ground resolution in metres per pixel at zoom level 0 for 256 pixel tiles.
+const ZOOM_0_RESOLUTION: f64 = 156_543.033_928_040_97;
+const METRES_PER_DEGREE: f64 = 111_320.0;
+const MAX_ZOOM: i64 = 30;
+
+/// Simplifies a geometry with a tolerance of one pixel at the given web mercator zoom level.
+/// Geometries in a geographic srid get the tolerance converted to degrees at their latitude,
+/// all others are assumed to be in metres.
+#[derive(Debug)]
+pub struct SimplifyForScaleUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl SimplifyForScaleUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                vec![
+                    TypeSignature::Exact(vec![DataType::Binary, DataType::Int64]),
+                    TypeSignature::Exact(vec![DataType::LargeBinary, DataType::Int64]),
+                ],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_simplifyforscale".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for SimplifyForScaleUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_SimplifyForScale"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(arg_types[0].clone())
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let ColumnarValue::Scalar(ScalarValue::Int64(Some(zoom))) = args[1] else {
+            return exec_err!("The second arg should be int64 scalar");
+        };
+        if !(0..=MAX_ZOOM).contains(&zoom) {
+            return exec_err!("Zoom level should be between 0 and {}", MAX_ZOOM);
+        }
+        let resolution = ZOOM_0_RESOLUTION / 2f64.powi(zoom as i32);
+
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let arr = &arrays[0];
+        match arr.data_type() {
+            DataType::Binary => simplify_for_scale::<i32>(arr.as_binary::<i32>(), resolution),
+            DataType::LargeBinary => simplify_for_scale::<i64>(arr.as_binary::<i64>(), resolution),
+            _ => unreachable!(),
+        }
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for SimplifyForScaleUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn simplify_for_scale<O: OffsetSizeTrait>(
+    wkb_arr: &GenericBinaryArray<O>,
+    resolution: f64,
+) -> DFResult<ColumnarValue> {
+    let mut builder = GeometryArrayBuilder::<O>::new(default_dialect(), wkb_arr.geom_len());
+    for i in 0..wkb_arr.geom_len() {
+        let Some(wkb) = wkb_arr.wkb(i) else {
+            builder.append_null();
+            continue;
+        };
+        let srid = decode_srid(wkb)?;
+        let is_geographic = srid.and_then(crs_info).is_some_and(|crs| crs.is_geographic);
+        let geom = wkb_arr.geo_value(i)?.map(|geom| {
+            let tolerance = if is_geographic {
+                let latitude = geom
+                    .bounding_rect()
+                    .map(|rect| rect.center().y)
+                    .unwrap_or_default();
+                resolution * latitude.to_radians().cos() / METRES_PER_DEGREE
+            } else {
+                resolution
+            };
+            simplify(geom, tolerance)
+        });
+        builder.append_geo_geometry_with_srid(&geom, srid)?;
+    }
+    Ok(ColumnarValue::Array(Arc::new(builder.build())))
+}
+
+/// Topology preserving simplification, the area threshold of Visvalingam-Whyatt is the squared tolerance.
+pub(crate) fn simplify(geom: geo::Geometry, tolerance: f64) -> geo::Geometry {
+    let epsilon = tolerance * tolerance;
+    map_geometry_recursive(geom, &mut |geom| match geom {
+        geo::Geometry::LineString(ls) => ls.simplify_vw_preserve(&epsilon).into(),
+        geo::Geometry::MultiLineString(mls) => mls.simplify_vw_preserve(&epsilon).into(),
+        geo::Geometry::Polygon(p) => p.simplify_vw_preserve(&epsilon).into(),
+        geo::Geometry::MultiPolygon(mp) => mp.simplify_vw_preserve(&epsilon).into(),
+        geom => geom,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{GeomFromTextUdf, SimplifyForScaleUdf};
+    use crate::geo::dialect::decode_srid;
+    use crate::geo::GeometryArray;
+    use arrow_array::cast::AsArray;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+    use geo::CoordsIter;
+
+    #[tokio::test]
+    async fn simplify_for_scale() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(SimplifyForScaleUdf::new()));
+
+        // a 100 metres zigzag with 5 kilometres bumps every 5 kilometres
+        let coords = (0..400)
+            .map(|i| {
+                let y = (i % 2) as f64 * 20.0 + if (i / 50) % 2 == 1 { 5000.0 } else { 0.0 };
+                format!("{} {}", i * 100, y)
+            })
+            .collect::<Vec<_>>();
+        let wkt = format!("LINESTRING({})", coords.join(","));
+
+        let mut vertex_counts = vec![];
+        for zoom in [2, 8, 14] {
+            let sql = format!(
+                "select ST_SimplifyForScale(ST_GeomFromText('{}', 3857), {})",
+                wkt, zoom
+            );
+            let batches = ctx.sql(&sql).await.unwrap().collect().await.unwrap();
+            let arr = batches[0].column(0).as_binary::<i32>();
+            assert_eq!(decode_srid(arr.wkb(0).unwrap()).unwrap(), Some(3857));
+            let geom = arr.geo_value(0).unwrap().unwrap();
+            vertex_counts.push(geom.coords_count());
+        }
+        assert!(vertex_counts[0] < vertex_counts[1]);
+        assert!(vertex_counts[1] < vertex_counts[2]);
+        assert_eq!(vertex_counts[2], 400);
+    }
+}
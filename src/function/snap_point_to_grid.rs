@@ -0,0 +1,258 @@
+use crate::config::default_dialect;
+use crate::function::args::geometry_args;
+use crate::function::geometry_type::geometry_type;
+use crate::geo::dialect::decode_srid;
+use crate::geo::{GeometryArray, GeometryArrayBuilder};
+use crate::DFResult;
+use arrow_array::cast::AsArray;
+use arrow_array::{GenericBinaryArray, OffsetSizeTrait};
+use arrow_schema::DataType;
+use datafusion_common::{exec_err, DataFusionError, ScalarValue};
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, TypeSignature, Volatility};
+use std::any::Any;
+use std::sync::Arc;
+
+/// Moves a point to the center of its grid cell, so the points of a cell have byte identical wkb
+/// and can be grouped by. The grid has square cells of the given size starting at the origin,
+/// which defaults to (0 0).
+#[derive(Debug)]
+pub struct SnapPointToGridUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl SnapPointToGridUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(grid_signatures(), Volatility::Immutable),
+            aliases: vec!["st_snappointtogrid".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for SnapPointToGridUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_SnapPointToGrid"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(arg_types[0].clone())
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let grid = Grid::try_from_args(args)?;
+        let (arrays, _recorder) = geometry_args(self.name(), &args[..1])?;
+        let arr = &arrays[0];
+        match arr.data_type() {
+            DataType::Binary => snap_point_to_grid::<i32>(arr.as_binary::<i32>(), &grid),
+            DataType::LargeBinary => snap_point_to_grid::<i64>(arr.as_binary::<i64>(), &grid),
+            _ => unreachable!(),
+        }
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for SnapPointToGridUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Signatures of `(point, cell_size [, origin_x, origin_y])`.
+pub(crate) fn grid_signatures() -> Vec<TypeSignature> {
+    let mut type_signatures = vec![];
+    for geom_type in [DataType::Binary, DataType::LargeBinary] {
+        type_signatures.push(TypeSignature::Exact(vec![
+            geom_type.clone(),
+            DataType::Float64,
+        ]));
+        type_signatures.push(TypeSignature::Exact(vec![
+            geom_type,
+            DataType::Float64,
+            DataType::Float64,
+            DataType::Float64,
+        ]));
+    }
+    type_signatures
+}
+
+pub(crate) struct Grid {
+    cell_size: f64,
+    origin: geo::Coord,
+}
+
+impl Grid {
+    pub(crate) fn try_from_args(args: &[ColumnarValue]) -> DFResult<Self> {
+        let mut values = vec![];
+        for arg in &args[1..] {
+            let ColumnarValue::Scalar(ScalarValue::Float64(Some(value))) = arg else {
+                return exec_err!("The grid args should be f64 scalars");
+            };
+            values.push(*value);
+        }
+        let cell_size = values[0];
+        if !(cell_size > 0.0 && cell_size.is_finite()) {
+            return exec_err!("The grid cell size should be positive, got {}", cell_size);
+        }
+        let origin = match values[1..] {
+            [x, y] => geo::coord! { x: x, y: y },
+            _ => geo::coord! { x: 0.0, y: 0.0 },
+        };
+        Ok(Self { cell_size, origin })
+    }
+
+    /// Column and row of the cell of a point, the coordinates of empty points are NaN.
+    pub(crate) fn cell(&self, geom: &geo::Geometry) -> DFResult<Option<(i64, i64)>> {
+        let geo::Geometry::Point(point) = geom else {
+            return exec_err!(
+                "Only points can be snapped to a grid, got {}",
+                geometry_type(geom.clone())
+            );
+        };
+        if point.x().is_nan() || point.y().is_nan() {
+            return Ok(None);
+        }
+        let ix = ((point.x() - self.origin.x) / self.cell_size).floor() as i64;
+        let iy = ((point.y() - self.origin.y) / self.cell_size).floor() as i64;
+        Ok(Some((ix, iy)))
+    }
+
+    fn center(&self, (ix, iy): (i64, i64)) -> geo::Point {
+        geo::Point::new(
+            self.origin.x + (ix as f64 + 0.5) * self.cell_size,
+            self.origin.y + (iy as f64 + 0.5) * self.cell_size,
+        )
+    }
+}
+
+fn snap_point_to_grid<O: OffsetSizeTrait>(
+    wkb_arr: &GenericBinaryArray<O>,
+    grid: &Grid,
+) -> DFResult<ColumnarValue> {
+    let mut builder = GeometryArrayBuilder::<O>::new(default_dialect(), wkb_arr.geom_len());
+    for i in 0..wkb_arr.geom_len() {
+        let (Some(wkb), Some(geom)) = (wkb_arr.wkb(i), wkb_arr.geo_value(i)?) else {
+            builder.append_null();
+            continue;
+        };
+        let snapped = grid
+            .cell(&geom)?
+            .map(|cell| geo::Geometry::Point(grid.center(cell)));
+        builder.append_geo_geometry_with_srid(&snapped, decode_srid(wkb)?)?;
+    }
+    Ok(ColumnarValue::Array(Arc::new(builder.build())))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{AsTextUdf, GeomFromTextUdf, GridCellIdUdf, SnapPointToGridUdf};
+    use crate::geo::GeometryArrayBuilder;
+    use arrow::util::pretty::pretty_format_batches;
+    use arrow_array::RecordBatch;
+    use arrow_schema::{DataType, Field, Schema};
+    use datafusion::datasource::MemTable;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn snap_point_to_grid() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(SnapPointToGridUdf::new()));
+        let df = ctx
+            .sql(
+                "select ST_AsText(ST_SnapPointToGrid(ST_GeomFromText(wkt), 1.0)) as cell, \
+                ST_AsText(ST_SnapPointToGrid(ST_GeomFromText(wkt), 1.0, 0.5, 0.5)) as shifted_cell \
+                from (values ('POINT(1.2 3.7)'), ('POINT(-0.2 -1)'), (null)) as t(wkt)",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+------------------+--------------+
+| cell             | shifted_cell |
++------------------+--------------+
+| POINT(1.5 3.5)   | POINT(1 4)   |
+| POINT(-0.5 -0.5) | POINT(0 -1)  |
+|                  |              |
++------------------+--------------+"
+        );
+
+        let result = ctx
+            .sql(
+                "select ST_SnapPointToGrid(ST_GeomFromText(wkt), 1.0) \
+                from (values ('LINESTRING(0 0,1 1)')) as t(wkt)",
+            )
+            .await
+            .unwrap()
+            .collect()
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn group_points_by_cell() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(SnapPointToGridUdf::new()));
+        ctx.register_udf(ScalarUDF::from(GridCellIdUdf::new()));
+
+        // 1000 pseudo random points, 10 in each cell of a 10 x 10 grid
+        let mut seed: u64 = 42;
+        let mut random = || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 11) as f64 / (1u64 << 53) as f64
+        };
+        let points = (0..1000)
+            .map(|i| {
+                let x = (i % 10) as f64 + random();
+                let y = ((i / 10) % 10) as f64 + random();
+                Some(geo::Point::new(x * 2.0, y * 2.0))
+            })
+            .collect::<Vec<_>>();
+        let builder: GeometryArrayBuilder<i32> = points.as_slice().try_into().unwrap();
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "geom",
+            DataType::Binary,
+            true,
+        )]));
+        let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(builder.build())]).unwrap();
+        let table = MemTable::try_new(schema, vec![vec![batch]]).unwrap();
+        ctx.register_table("points", Arc::new(table)).unwrap();
+
+        for key in ["ST_SnapPointToGrid(geom, 2.0)", "ST_GridCellId(geom, 2.0)"] {
+            let sql = format!(
+                "select count(*) as cells, min(n) as min_points, max(n) as max_points from \
+                (select {} as cell, count(*) as n from points group by cell)",
+                key
+            );
+            let df = ctx.sql(&sql).await.unwrap();
+            assert_eq!(
+                pretty_format_batches(&df.collect().await.unwrap())
+                    .unwrap()
+                    .to_string(),
+                "+-------+------------+------------+
+| cells | min_points | max_points |
++-------+------------+------------+
+| 100   | 10         | 10         |
++-------+------------+------------+"
+            );
+        }
+    }
+}
@@ -1,16 +1,28 @@
+use crate::config::{check_cancelled, default_dialect};
+use crate::function::args::{geometry_args, par_rows};
+use crate::function::geometry_type::geometry_type;
+use crate::geo::dialect::decode_srid;
 use crate::geo::{GeometryArray, GeometryArrayBuilder};
+use crate::metrics::Recorder;
 use crate::DFResult;
 use arrow_array::cast::AsArray;
 use arrow_array::{GenericBinaryArray, OffsetSizeTrait};
 use arrow_schema::DataType;
-use datafusion_common::{internal_datafusion_err, internal_err, DataFusionError};
+use datafusion_common::{exec_err, internal_datafusion_err, DataFusionError};
 use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+use geo::line_intersection::{line_intersection, LineIntersection};
+use geo::{Contains, EuclideanDistance, InteriorPoint};
 use geos::Geom;
-use rayon::iter::IntoParallelIterator;
-use rayon::prelude::*;
+use geozero::geos::ToGeos;
+use geozero::ToGeo;
 use std::any::Any;
 use std::sync::Arc;
 
+/// Distance within which a blade point is on a line.
+const TOLERANCE: f64 = 1e-9;
+
+/// Splits a geometry by a blade into a collection of pieces: lines by points or lines and
+/// polygons by lines. Multi geometry blades cut with every member.
 #[derive(Debug)]
 pub struct SplitUdf {
     signature: Signature,
@@ -48,42 +60,29 @@ impl ScalarUDFImpl for SplitUdf {
     }
 
     fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
-        let (arr0, arr1) = match (args[0].clone(), args[1].clone()) {
-            (ColumnarValue::Array(arr0), ColumnarValue::Array(arr1)) => (arr0, arr1),
-            (ColumnarValue::Array(arr0), ColumnarValue::Scalar(scalar)) => {
-                (arr0.clone(), scalar.to_array_of_size(arr0.len())?)
-            }
-            (ColumnarValue::Scalar(scalar), ColumnarValue::Array(arr1)) => {
-                (scalar.to_array_of_size(arr1.len())?, arr1)
-            }
-            (ColumnarValue::Scalar(scalar0), ColumnarValue::Scalar(scalar1)) => {
-                (scalar0.to_array_of_size(1)?, scalar1.to_array_of_size(1)?)
-            }
-        };
-        if arr0.len() != arr1.len() {
-            return internal_err!("Two arrays length is not same");
-        }
+        let (arrays, recorder) = geometry_args(self.name(), args)?;
+        let (arr0, arr1) = (&arrays[0], &arrays[1]);
 
         match (arr0.data_type(), arr1.data_type()) {
             (DataType::Binary, DataType::Binary) => {
                 let arr0 = arr0.as_binary::<i32>();
                 let arr1 = arr1.as_binary::<i32>();
-                split::<i32, i32>(arr0, arr1)
+                split::<i32, i32>(arr0, arr1, &recorder)
             }
             (DataType::LargeBinary, DataType::Binary) => {
                 let arr0 = arr0.as_binary::<i64>();
                 let arr1 = arr1.as_binary::<i32>();
-                split::<i64, i32>(arr0, arr1)
+                split::<i64, i32>(arr0, arr1, &recorder)
             }
             (DataType::Binary, DataType::LargeBinary) => {
                 let arr0 = arr0.as_binary::<i32>();
                 let arr1 = arr1.as_binary::<i64>();
-                split::<i32, i64>(arr0, arr1)
+                split::<i32, i64>(arr0, arr1, &recorder)
             }
             (DataType::LargeBinary, DataType::LargeBinary) => {
                 let arr0 = arr0.as_binary::<i64>();
                 let arr1 = arr1.as_binary::<i64>();
-                split::<i64, i64>(arr0, arr1)
+                split::<i64, i64>(arr0, arr1, &recorder)
             }
             _ => unreachable!(),
         }
@@ -103,54 +102,267 @@ impl Default for SplitUdf {
 fn split<O: OffsetSizeTrait, F: OffsetSizeTrait>(
     arr0: &GenericBinaryArray<O>,
     arr1: &GenericBinaryArray<F>,
+    recorder: &Recorder,
 ) -> DFResult<ColumnarValue> {
-    let geom_vec = (0..arr0.geom_len())
-        .into_par_iter()
-        .map(
-            |geom_index| match (arr0.geos_value(geom_index)?, arr1.geos_value(geom_index)?) {
-                (Some(geom0), Some(geom1)) => {
-                    let boundary = geom0.boundary().map_err(|e| {
-                        internal_datafusion_err!("Failed to do boundary, error: {}", e)
-                    })?;
-                    let union = boundary.union(&geom1).map_err(|e| {
-                        internal_datafusion_err!("Failed to do union, error: {}", e)
-                    })?;
-                    let (result, ..) = union.polygonize_full().map_err(|e| {
-                        internal_datafusion_err!("Failed to do polygonize_full, error: {}", e)
-                    })?;
-
-                    Ok(Some(result))
-                }
-                _ => Ok(None),
-            },
-        )
-        .collect::<DFResult<Vec<Option<geos::Geometry>>>>()?;
-    let builder = GeometryArrayBuilder::<O>::from(geom_vec.as_slice());
+    let geom_vec = par_rows(arr0.geom_len(), |geom_index| {
+        check_cancelled(geom_index)?;
+        match (arr0.geo_value(geom_index)?, arr1.geo_value(geom_index)?) {
+            (Some(geom0), Some(geom1)) => {
+                let pieces = recorder.compute(|| split_geometry(geom0, &geom1))?;
+                Ok(Some(geo::GeometryCollection::new_from(pieces).into()))
+            }
+            _ => Ok(None),
+        }
+    })?;
+    let mut builder = GeometryArrayBuilder::<O>::new(default_dialect(), arr0.geom_len());
+    for (geom_index, geom) in geom_vec.iter().enumerate() {
+        let srid = match arr0.wkb(geom_index) {
+            Some(wkb) => decode_srid(wkb)?,
+            None => None,
+        };
+        builder.append_geo_geometry_with_srid(geom, srid)?;
+    }
     Ok(ColumnarValue::Array(Arc::new(builder.build())))
 }
 
+/// Splits the geometry by every member of the blade in turn, each member cuts the pieces left by
+/// the previous ones. Members which don't cross a piece leave it as is.
+fn split_geometry(geom: geo::Geometry, blade: &geo::Geometry) -> DFResult<Vec<geo::Geometry>> {
+    let mut pieces = vec![];
+    collect_parts(geom, &mut pieces);
+    let mut blades = vec![];
+    collect_parts(blade.clone(), &mut blades);
+    for blade in blades.iter() {
+        let mut cut_pieces = vec![];
+        for piece in pieces {
+            cut_pieces.extend(split_part(piece, blade)?);
+        }
+        pieces = cut_pieces;
+    }
+    Ok(pieces)
+}
+
+/// Collects the points, lines and polygons of a geometry, multi geometries are split up.
+fn collect_parts(geom: geo::Geometry, parts: &mut Vec<geo::Geometry>) {
+    match geom {
+        geo::Geometry::MultiPoint(multi_point) => {
+            parts.extend(multi_point.into_iter().map(geo::Geometry::Point))
+        }
+        geo::Geometry::MultiLineString(multi_line_string) => {
+            parts.extend(multi_line_string.into_iter().map(geo::Geometry::LineString))
+        }
+        geo::Geometry::MultiPolygon(multi_polygon) => {
+            parts.extend(multi_polygon.into_iter().map(geo::Geometry::Polygon))
+        }
+        geo::Geometry::GeometryCollection(collection) => {
+            for geom in collection {
+                collect_parts(geom, parts);
+            }
+        }
+        geo::Geometry::Line(line) => parts.push(geo::Geometry::LineString(line.into())),
+        geo::Geometry::Rect(rect) => parts.push(geo::Geometry::Polygon(rect.to_polygon())),
+        geo::Geometry::Triangle(triangle) => {
+            parts.push(geo::Geometry::Polygon(triangle.to_polygon()))
+        }
+        geom => parts.push(geom),
+    }
+}
+
+fn split_part(piece: geo::Geometry, blade: &geo::Geometry) -> DFResult<Vec<geo::Geometry>> {
+    match (piece, blade) {
+        (geo::Geometry::LineString(line), geo::Geometry::Point(point)) => {
+            Ok(split_line_by_coord(line, point.0)
+                .into_iter()
+                .map(geo::Geometry::LineString)
+                .collect())
+        }
+        (geo::Geometry::LineString(line), geo::Geometry::LineString(blade)) => {
+            let mut lines = vec![line];
+            for coord in crossings(&lines[0], blade)? {
+                lines = lines
+                    .into_iter()
+                    .flat_map(|line| split_line_by_coord(line, coord))
+                    .collect();
+            }
+            Ok(lines.into_iter().map(geo::Geometry::LineString).collect())
+        }
+        (geo::Geometry::Polygon(polygon), geo::Geometry::LineString(blade)) => {
+            split_polygon_by_line(polygon, blade)
+        }
+        (piece @ geo::Geometry::Point(_), _) => Ok(vec![piece]),
+        (piece, blade) => exec_err!(
+            "ST_Split: splitting a {} by a {} is unsupported",
+            geometry_type(piece),
+            geometry_type(blade.clone())
+        ),
+    }
+}
+
+/// Cuts the line where it passes the coord, a coord at an end or off the line cuts nothing.
+fn split_line_by_coord(line: geo::LineString, coord: geo::Coord) -> Vec<geo::LineString> {
+    let point = geo::Point::from(coord);
+    let Some(segment_index) = line
+        .lines()
+        .position(|segment| segment.euclidean_distance(&point) <= TOLERANCE)
+    else {
+        return vec![line];
+    };
+    let coords = &line.0;
+    let (first, last) = (coords[0], coords[coords.len() - 1]);
+    if coord == first || coord == last {
+        return vec![line];
+    }
+    let mut head = coords[..=segment_index].to_vec();
+    if head.last() != Some(&coord) {
+        head.push(coord);
+    }
+    let mut tail = vec![coord];
+    tail.extend(
+        coords[segment_index + 1..]
+            .iter()
+            .skip_while(|c| **c == coord)
+            .copied(),
+    );
+    if head.len() < 2 || tail.len() < 2 {
+        return vec![line];
+    }
+    vec![head.into(), tail.into()]
+}
+
+/// The points where the blade crosses the line, fails if they share a segment.
+fn crossings(line: &geo::LineString, blade: &geo::LineString) -> DFResult<Vec<geo::Coord>> {
+    let mut coords = vec![];
+    for segment in line.lines() {
+        for blade_segment in blade.lines() {
+            match line_intersection(segment, blade_segment) {
+                Some(LineIntersection::SinglePoint { intersection, .. }) => {
+                    coords.push(intersection)
+                }
+                Some(LineIntersection::Collinear { .. }) => {
+                    return exec_err!("ST_Split: the blade shares a segment with the line")
+                }
+                None => {}
+            }
+        }
+    }
+    Ok(coords)
+}
+
+/// Polygonizes the polygon boundary noded with the blade and keeps the faces inside the polygon.
+fn split_polygon_by_line(
+    polygon: geo::Polygon,
+    blade: &geo::LineString,
+) -> DFResult<Vec<geo::Geometry>> {
+    let geos_polygon = geo::Geometry::Polygon(polygon.clone())
+        .to_geos()
+        .map_err(|e| internal_datafusion_err!("Failed to convert to geos, error: {}", e))?;
+    let geos_blade = geo::Geometry::LineString(blade.clone())
+        .to_geos()
+        .map_err(|e| internal_datafusion_err!("Failed to convert to geos, error: {}", e))?;
+    let boundary = geos_polygon
+        .boundary()
+        .map_err(|e| internal_datafusion_err!("Failed to do boundary, error: {}", e))?;
+    let union = boundary
+        .union(&geos_blade)
+        .map_err(|e| internal_datafusion_err!("Failed to do union, error: {}", e))?;
+    let (faces, ..) = union
+        .polygonize_full()
+        .map_err(|e| internal_datafusion_err!("Failed to do polygonize_full, error: {}", e))?;
+    let faces = faces
+        .to_geo()
+        .map_err(|e| internal_datafusion_err!("Failed to convert to geo, error: {}", e))?;
+    let mut pieces = vec![];
+    collect_parts(faces, &mut pieces);
+    pieces.retain(|face| {
+        face.interior_point()
+            .is_some_and(|point| polygon.contains(&point))
+    });
+    if pieces.len() < 2 {
+        return Ok(vec![polygon.into()]);
+    }
+    Ok(pieces)
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::function::{GeomFromTextUdf, SplitUdf};
-    // use arrow::util::pretty::pretty_format_batches;
+    use crate::function::{AsTextUdf, GeomFromTextUdf, SplitUdf};
+    use crate::geo::GeometryArray;
+    use arrow_array::cast::AsArray;
     use datafusion::prelude::SessionContext;
     use datafusion_expr::ScalarUDF;
+    use geo::Area;
 
-    #[tokio::test]
-    async fn split() {
+    fn session_context() -> SessionContext {
         let ctx = SessionContext::new();
         ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
         ctx.register_udf(ScalarUDF::from(SplitUdf::new()));
-        let _df = ctx
-            .sql("select ST_Split(ST_GeomFromText('LINESTRING ( 0 0, 1 1, 2 2 )'), ST_GeomFromText('POINT(1 1)'))")
+        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
+        ctx
+    }
+
+    #[tokio::test]
+    async fn split_line() {
+        let ctx = session_context();
+        for (wkt, blade, expected) in [
+            (
+                "LINESTRING(0 0,1 1,2 2)",
+                "POINT(1 1)",
+                "GEOMETRYCOLLECTION(LINESTRING(0 0,1 1),LINESTRING(1 1,2 2))",
+            ),
+            (
+                "LINESTRING(0 0,4 0,10 0)",
+                "MULTIPOINT(2 0,5 0,8 0)",
+                "GEOMETRYCOLLECTION(LINESTRING(0 0,2 0),LINESTRING(2 0,4 0,5 0),\
+                LINESTRING(5 0,8 0),LINESTRING(8 0,10 0))",
+            ),
+            (
+                "LINESTRING(0 0,10 0)",
+                "MULTILINESTRING((3 -1,3 1),(7 -1,7 1))",
+                "GEOMETRYCOLLECTION(LINESTRING(0 0,3 0),LINESTRING(3 0,7 0),LINESTRING(7 0,10 0))",
+            ),
+            (
+                "LINESTRING(0 0,1 0)",
+                "MULTIPOINT(5 5,0 0)",
+                "GEOMETRYCOLLECTION(LINESTRING(0 0,1 0))",
+            ),
+        ] {
+            let df = ctx
+                .sql(&format!(
+                    "select ST_AsText(ST_Split(ST_GeomFromText('{}'), ST_GeomFromText('{}')))",
+                    wkt, blade
+                ))
+                .await
+                .unwrap();
+            let batches = df.collect().await.unwrap();
+            assert_eq!(batches[0].column(0).as_string::<i32>().value(0), expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn split_polygon() {
+        let ctx = session_context();
+        let df = ctx
+            .sql(
+                "select ST_Split(ST_GeomFromText('POLYGON((0 0,4 0,4 4,0 4,0 0))'), \
+                ST_GeomFromText('MULTILINESTRING((2 -1,2 5),(-1 2,1 2))'))",
+            )
             .await
             .unwrap();
-        // FIXME fix split implementation
-        // assert_eq!(
-        //     pretty_format_batches(&df.collect().await.unwrap())
-        //         .unwrap()
-        //         .to_string(),
-        //     ""
-        // );
+        let batches = df.collect().await.unwrap();
+        let Some(geo::Geometry::GeometryCollection(pieces)) = batches[0]
+            .column(0)
+            .as_binary::<i32>()
+            .geo_value(0)
+            .unwrap()
+        else {
+            panic!("split should return a collection");
+        };
+        // the second blade only ends inside the polygon and cuts nothing
+        let mut areas = pieces
+            .iter()
+            .map(|piece| piece.unsigned_area())
+            .collect::<Vec<_>>();
+        areas.sort_by(f64::total_cmp);
+        assert_eq!(areas, vec![8.0, 8.0]);
     }
 }
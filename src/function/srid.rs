@@ -1,12 +1,15 @@
+use crate::function::args::{geometry_args, scalar_if_constant};
+use crate::geo::dialect::WkbHeader;
 use crate::geo::GeometryArray;
+use crate::DFResult;
 use arrow_array::cast::AsArray;
 use arrow_array::{Array, Int32Array};
 use arrow_schema::DataType;
 use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
-use geozero::GeozeroGeometry;
 use std::any::Any;
 use std::sync::Arc;
 
+/// Returns the srid of a geometry, 0 if it has none.
 #[derive(Debug)]
 pub struct SridUdf {
     signature: Signature,
@@ -44,13 +47,14 @@ impl ScalarUDFImpl for SridUdf {
     }
 
     fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
-        let arr = args[0].clone().into_array(1)?;
-        match arr.data_type() {
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let arr = &arrays[0];
+        let value: DFResult<ColumnarValue> = match arr.data_type() {
             DataType::Binary => {
                 let wkb_arr = arr.as_binary::<i32>();
                 let mut srid_vec = vec![];
                 for i in 0..wkb_arr.geom_len() {
-                    srid_vec.push(wkb_arr.geos_value(i)?.and_then(|geom| geom.srid()));
+                    srid_vec.push(wkb_arr.wkb(i).map(header_srid).transpose()?);
                 }
                 Ok(ColumnarValue::Array(Arc::new(Int32Array::from(srid_vec))))
             }
@@ -58,12 +62,13 @@ impl ScalarUDFImpl for SridUdf {
                 let wkb_arr = arr.as_binary::<i64>();
                 let mut srid_vec = vec![];
                 for i in 0..wkb_arr.geom_len() {
-                    srid_vec.push(wkb_arr.geos_value(i)?.and_then(|geom| geom.srid()));
+                    srid_vec.push(wkb_arr.wkb(i).map(header_srid).transpose()?);
                 }
                 Ok(ColumnarValue::Array(Arc::new(Int32Array::from(srid_vec))))
             }
             _ => unreachable!(),
-        }
+        };
+        scalar_if_constant(args, value?)
     }
 
     fn aliases(&self) -> &[String] {
@@ -77,6 +82,11 @@ impl Default for SridUdf {
     }
 }
 
+/// Reads the srid from the wkb header, 0 for geometries without srid.
+fn header_srid(wkb: &[u8]) -> DFResult<i32> {
+    Ok(WkbHeader::parse(wkb)?.srid.unwrap_or(0))
+}
+
 #[cfg(test)]
 mod tests {
     use crate::function::{GeomFromTextUdf, SridUdf};
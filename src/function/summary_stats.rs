@@ -0,0 +1,277 @@
+use crate::config::check_cancelled;
+use crate::function::geometry_type::{geometry_type, TYPE_NAMES};
+use crate::geo::dialect::scan_wkb;
+use crate::geo::{build_box2d_array, Box2d, GeometryArray};
+use crate::metrics::record_call;
+use crate::DFResult;
+use arrow_array::cast::AsArray;
+use arrow_array::types::Int64Type;
+use arrow_array::{
+    Array, ArrayRef, Float64Array, GenericBinaryArray, Int64Array, OffsetSizeTrait, StringArray,
+    StructArray,
+};
+use arrow_schema::{DataType, Field, Fields};
+use datafusion_common::ScalarValue;
+use datafusion_expr::{Accumulator, AggregateUDFImpl, Signature, Volatility};
+use geo::{BoundingRect, CoordsIter};
+use std::any::Any;
+use std::sync::Arc;
+
+/// Profiles a geometry column: row and null counts, the number of geometries per type as a json
+/// object, the total and average vertex count and the extent. The facts are read by scanning the
+/// wkb, only the dialects without a cheap header are decoded.
+#[derive(Debug)]
+pub struct SummaryStatsUdaf {
+    signature: Signature,
+}
+
+impl SummaryStatsUdaf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::uniform(
+                1,
+                vec![DataType::Binary, DataType::LargeBinary],
+                Volatility::Immutable,
+            ),
+        }
+    }
+
+    fn fields() -> Fields {
+        vec![
+            Field::new("count", DataType::Int64, false),
+            Field::new("null_count", DataType::Int64, false),
+            Field::new("type_counts", DataType::Utf8, false),
+            Field::new("total_vertices", DataType::Int64, false),
+            Field::new("extent", Box2d::data_type(), true),
+            Field::new("avg_vertices", DataType::Float64, true),
+        ]
+        .into()
+    }
+}
+
+impl AggregateUDFImpl for SummaryStatsUdaf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        // uadf not support alias
+        "st_summarystats"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(DataType::Struct(Self::fields()))
+    }
+
+    fn accumulator(&self, _arg: &DataType) -> datafusion_common::Result<Box<dyn Accumulator>> {
+        Ok(Box::new(SummaryStatsAccumulator::new()))
+    }
+
+    fn state_type(&self, _return_type: &DataType) -> datafusion_common::Result<Vec<DataType>> {
+        // count, null count, total vertices, extent and the count of every type
+        let mut state_type = vec![DataType::Int64; 3];
+        state_type.push(Box2d::data_type());
+        state_type.extend(std::iter::repeat(DataType::Int64).take(TYPE_NAMES.len()));
+        Ok(state_type)
+    }
+}
+
+impl Default for SummaryStatsUdaf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug)]
+pub struct SummaryStatsAccumulator {
+    count: i64,
+    null_count: i64,
+    total_vertices: i64,
+    box2d: Box2d,
+    type_counts: [i64; TYPE_NAMES.len()],
+}
+
+impl SummaryStatsAccumulator {
+    pub fn new() -> Self {
+        Self {
+            count: 0,
+            null_count: 0,
+            total_vertices: 0,
+            box2d: Box2d::new(),
+            type_counts: [0; TYPE_NAMES.len()],
+        }
+    }
+
+    fn add_rect(&mut self, rect: geo::Rect) {
+        self.box2d = Box2d {
+            xmin: self.box2d.xmin.min(rect.min().x),
+            ymin: self.box2d.ymin.min(rect.min().y),
+            xmax: self.box2d.xmax.max(rect.max().x),
+            ymax: self.box2d.ymax.max(rect.max().y),
+        };
+    }
+
+    fn update<O: OffsetSizeTrait>(&mut self, arr: &GenericBinaryArray<O>) -> DFResult<()> {
+        for i in 0..arr.geom_len() {
+            check_cancelled(i)?;
+            let Some(wkb) = arr.wkb(i) else {
+                self.null_count += 1;
+                continue;
+            };
+            self.count += 1;
+            if let Some(scan) = scan_wkb(wkb)? {
+                if let Some(count) = self.type_counts.get_mut(scan.geometry_type as usize - 1) {
+                    *count += 1;
+                }
+                self.total_vertices += scan.vertices as i64;
+                if let Some(rect) = scan.rect {
+                    self.add_rect(rect);
+                }
+            } else if let Some(geom) = arr.geo_value(i)? {
+                self.total_vertices += geom.coords_count() as i64;
+                if let Some(rect) = geom.bounding_rect() {
+                    self.add_rect(rect);
+                }
+                let name = geometry_type(geom);
+                if let Some(index) = TYPE_NAMES.iter().position(|type_name| *type_name == name) {
+                    self.type_counts[index] += 1;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The type counts as a json object, types without geometries are left out.
+    fn type_counts_json(&self) -> String {
+        let counts = TYPE_NAMES
+            .iter()
+            .zip(self.type_counts)
+            .filter(|(_, count)| *count > 0)
+            .map(|(name, count)| format!("\"{}\":{}", name, count))
+            .collect::<Vec<_>>();
+        format!("{{{}}}", counts.join(","))
+    }
+}
+
+impl Accumulator for SummaryStatsAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> datafusion_common::Result<()> {
+        if values.is_empty() {
+            return Ok(());
+        }
+        let arr = &values[0];
+        let _recorder = record_call("st_summarystats", arr.len());
+        match arr.data_type() {
+            DataType::Binary => self.update::<i32>(arr.as_binary::<i32>()),
+            DataType::LargeBinary => self.update::<i64>(arr.as_binary::<i64>()),
+            _ => unreachable!(),
+        }
+    }
+
+    fn evaluate(&mut self) -> datafusion_common::Result<ScalarValue> {
+        let extent = (self.box2d.xmin <= self.box2d.xmax).then(|| self.box2d.clone());
+        let avg_vertices = (self.count > 0).then(|| self.total_vertices as f64 / self.count as f64);
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(Int64Array::from(vec![self.count])),
+            Arc::new(Int64Array::from(vec![self.null_count])),
+            Arc::new(StringArray::from(vec![self.type_counts_json()])),
+            Arc::new(Int64Array::from(vec![self.total_vertices])),
+            Arc::new(build_box2d_array(vec![extent])),
+            Arc::new(Float64Array::from(vec![avg_vertices])),
+        ];
+        let arr = StructArray::try_new(SummaryStatsUdaf::fields(), columns, None)?;
+        Ok(ScalarValue::Struct(Arc::new(arr)))
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self)
+    }
+
+    fn state(&mut self) -> datafusion_common::Result<Vec<ScalarValue>> {
+        let mut state = vec![
+            ScalarValue::Int64(Some(self.count)),
+            ScalarValue::Int64(Some(self.null_count)),
+            ScalarValue::Int64(Some(self.total_vertices)),
+            self.box2d.clone().into(),
+        ];
+        state.extend(
+            self.type_counts
+                .iter()
+                .map(|count| ScalarValue::Int64(Some(*count))),
+        );
+        Ok(state)
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> datafusion_common::Result<()> {
+        if states.is_empty() {
+            return Ok(());
+        }
+        let counts = |index: usize| states[index].as_primitive::<Int64Type>();
+        for row in 0..states[0].len() {
+            self.count += counts(0).value(row);
+            self.null_count += counts(1).value(row);
+            self.total_vertices += counts(2).value(row);
+            if let Some(box2d) = Box2d::value(states[3].as_struct(), row)? {
+                if box2d.xmin <= box2d.xmax {
+                    self.add_rect(box2d.into());
+                }
+            }
+            for (i, count) in self.type_counts.iter_mut().enumerate() {
+                *count += counts(4 + i).value(row);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::summary_stats::SummaryStatsUdaf;
+    use crate::function::{AsTextUdf, GeomFromTextUdf};
+    use arrow::util::pretty::pretty_format_batches;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+    use datafusion_expr::AggregateUDF;
+
+    #[tokio::test]
+    async fn summary_stats() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udaf(AggregateUDF::from(SummaryStatsUdaf::new()));
+        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
+        ctx.sql(
+            "create table geoms as select ST_GeomFromText(wkt) as geom from (values \
+            ('POINT(1 2)'), \
+            ('POINT(-3 0)'), \
+            ('LINESTRING(0 0,4 4,8 0)'), \
+            ('POLYGON((0 0,2 0,2 2,0 2,0 0))'), \
+            ('MULTIPOINT(10 10,11 11)'), \
+            ('GEOMETRYCOLLECTION(POINT(5 -1),LINESTRING EMPTY)'), \
+            (null)) as t(wkt)",
+        )
+        .await
+        .unwrap();
+        let df = ctx
+            .sql(
+                "select s['count'] as count, s['null_count'] as null_count, \
+                s['type_counts'] as type_counts, s['total_vertices'] as total_vertices, \
+                ST_AsText(s['extent']) as extent, s['avg_vertices'] as avg_vertices \
+                from (select ST_SummaryStats(geom) as s from geoms)",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+-------+------------+---------------------------------------------------------------------------------------------+----------------+------------------+--------------------+
+| count | null_count | type_counts                                                                                 | total_vertices | extent           | avg_vertices       |
++-------+------------+---------------------------------------------------------------------------------------------+----------------+------------------+--------------------+
+| 6     | 1          | {\"ST_Point\":2,\"ST_LineString\":1,\"ST_Polygon\":1,\"ST_MultiPoint\":1,\"ST_GeometryCollection\":1} | 13             | BOX(-3 -1,11 11) | 2.1666666666666665 |
++-------+------------+---------------------------------------------------------------------------------------------+----------------+------------------+--------------------+"
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use crate::config::default_dialect;
+use crate::function::args::{geometry_args, scalar_if_constant};
+use crate::function::pixel_as_polygon::int_value;
+use crate::geo::GeometryArrayBuilder;
+use crate::DFResult;
+use arrow_schema::DataType;
+use datafusion_common::{exec_err, DataFusionError};
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+use geo::polygon;
+use std::any::Any;
+use std::sync::Arc;
+
+/// Half the width of the web mercator world.
+const WORLD: f64 = 20037508.342789244;
+const WEB_MERCATOR: i32 = 3857;
+
+/// Returns the web mercator polygon of the tile z/x/y of an xyz tile pyramid, tile 0/0/0 covers
+/// the whole world and y grows southwards. The polygon has the srid 3857.
+#[derive(Debug)]
+pub struct TileEnvelopeUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl TileEnvelopeUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::exact(
+                vec![DataType::Int64, DataType::Int64, DataType::Int64],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_tileenvelope".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for TileEnvelopeUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_TileEnvelope"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(DataType::Binary)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let len = arrays[0].len();
+        let mut builder = GeometryArrayBuilder::<i32>::new(default_dialect(), len);
+        for i in 0..len {
+            let tile = match (
+                int_value(&arrays[0], i),
+                int_value(&arrays[1], i),
+                int_value(&arrays[2], i),
+            ) {
+                (Some(z), Some(x), Some(y)) => Some(tile_envelope(z, x, y)?.into()),
+                _ => None,
+            };
+            builder.append_geo_geometry_with_srid(&tile, Some(WEB_MERCATOR))?;
+        }
+        scalar_if_constant(args, ColumnarValue::Array(Arc::new(builder.build())))
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for TileEnvelopeUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn tile_envelope(z: i64, x: i64, y: i64) -> DFResult<geo::Polygon> {
+    if !(0..=31).contains(&z) {
+        return exec_err!("The tile zoom should be between 0 and 31, got {}", z);
+    }
+    let tiles = 1i64 << z;
+    if !(0..tiles).contains(&x) || !(0..tiles).contains(&y) {
+        return exec_err!("Tile {}/{}/{} is outside the tile pyramid", z, x, y);
+    }
+    let size = 2.0 * WORLD / tiles as f64;
+    let (x0, y1) = (-WORLD + x as f64 * size, WORLD - y as f64 * size);
+    let (x1, y0) = (x0 + size, y1 - size);
+    Ok(polygon![(x: x0, y: y0), (x: x1, y: y0), (x: x1, y: y1), (x: x0, y: y1)])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::tile_envelope::WORLD;
+    use crate::function::{SridUdf, TileEnvelopeUdf};
+    use crate::geo::GeometryArray;
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::Int32Type;
+    use arrow_array::Array;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+    use geo::polygon;
+
+    #[tokio::test]
+    async fn tile_envelope() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(TileEnvelopeUdf::new()));
+        ctx.register_udf(ScalarUDF::from(SridUdf::new()));
+        let df = ctx
+            .sql(
+                "select ST_TileEnvelope(z, x, y), ST_SRID(ST_TileEnvelope(z, x, y)) \
+                from (values (0, 0, 0), (1, 1, 0), (null, 0, 0)) as t(z, x, y)",
+            )
+            .await
+            .unwrap();
+        let batches = df.collect().await.unwrap();
+        let tiles = batches[0].column(0).as_binary::<i32>();
+        assert_eq!(
+            tiles.geo_value(0).unwrap().unwrap(),
+            polygon![(x: -WORLD, y: -WORLD), (x: WORLD, y: -WORLD), (x: WORLD, y: WORLD), (x: -WORLD, y: WORLD)].into()
+        );
+        assert_eq!(
+            tiles.geo_value(1).unwrap().unwrap(),
+            polygon![(x: 0., y: 0.), (x: WORLD, y: 0.), (x: WORLD, y: WORLD), (x: 0., y: WORLD)]
+                .into()
+        );
+        assert!(tiles.is_null(2));
+        let srids = batches[0].column(1).as_primitive::<Int32Type>();
+        assert_eq!(srids.value(0), 3857);
+        assert_eq!(srids.value(1), 3857);
+
+        let err = ctx
+            .sql("select ST_TileEnvelope(1, 2, 0)")
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Tile 1/2/0 is outside the tile pyramid"),
+            "{}",
+            err
+        );
+    }
+}
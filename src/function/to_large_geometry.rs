@@ -0,0 +1,149 @@
+use crate::function::args::geometry_args;
+use arrow_array::cast::AsArray;
+use arrow_array::{Array, BinaryArray, LargeBinaryArray};
+use arrow_buffer::{OffsetBuffer, ScalarBuffer};
+use arrow_schema::DataType;
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+use std::any::Any;
+use std::sync::Arc;
+
+/// Converts a Binary geometry column into a LargeBinary one, only the offsets are rebuilt and
+/// the wkb values are shared.
+#[derive(Debug)]
+pub struct ToLargeGeometryUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl ToLargeGeometryUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::exact(vec![DataType::Binary], Volatility::Immutable),
+            aliases: vec!["st_tolargegeometry".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for ToLargeGeometryUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_ToLargeGeometry"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(DataType::LargeBinary)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let large_arr = to_large(arrays[0].as_binary::<i32>());
+        Ok(ColumnarValue::Array(Arc::new(large_arr)))
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for ToLargeGeometryUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn to_large(arr: &BinaryArray) -> LargeBinaryArray {
+    let offsets = arr
+        .offsets()
+        .iter()
+        .map(|offset| *offset as i64)
+        .collect::<ScalarBuffer<i64>>();
+    LargeBinaryArray::new(
+        OffsetBuffer::new(offsets),
+        arr.values().clone(),
+        arr.nulls().cloned(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::ToLargeGeometryUdf;
+    use crate::geo::GeometryArrayBuilder;
+    use arrow::util::pretty::pretty_format_batches;
+    use arrow_array::{Int32Array, RecordBatch};
+    use arrow_schema::{DataType, Field, Schema};
+    use datafusion::datasource::MemTable;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+    use geo::point;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn join_small_and_large_geometry() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(ToLargeGeometryUdf::new()));
+
+        let points = vec![
+            Some(point!(x: 1.0, y: 1.0)),
+            None,
+            Some(point!(x: 2.0, y: 2.0)),
+        ];
+        let builder: GeometryArrayBuilder<i32> = points.as_slice().try_into().unwrap();
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("geom", DataType::Binary, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2, 3])),
+                Arc::new(builder.build()),
+            ],
+        )
+        .unwrap();
+        let small = MemTable::try_new(schema, vec![vec![batch]]).unwrap();
+        ctx.register_table("small", Arc::new(small)).unwrap();
+
+        let points = vec![Some(point!(x: 2.0, y: 2.0)), Some(point!(x: 1.0, y: 1.0))];
+        let builder: GeometryArrayBuilder<i64> = points.as_slice().try_into().unwrap();
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("geom", DataType::LargeBinary, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![20, 10])),
+                Arc::new(builder.build()),
+            ],
+        )
+        .unwrap();
+        let large = MemTable::try_new(schema, vec![vec![batch]]).unwrap();
+        ctx.register_table("large", Arc::new(large)).unwrap();
+
+        let df = ctx
+            .sql(
+                "select small.id, large.id from small join large \
+                on ST_ToLargeGeometry(small.geom) = large.geom order by small.id",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+----+----+
+| id | id |
++----+----+
+| 1  | 10 |
+| 3  | 20 |
++----+----+"
+        );
+    }
+}
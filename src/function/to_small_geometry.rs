@@ -0,0 +1,113 @@
+use crate::function::args::geometry_args;
+use crate::DFResult;
+use arrow_array::cast::AsArray;
+use arrow_array::{Array, BinaryArray, LargeBinaryArray};
+use arrow_buffer::{OffsetBuffer, ScalarBuffer};
+use arrow_schema::DataType;
+use datafusion_common::{exec_err, DataFusionError};
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+use std::any::Any;
+use std::sync::Arc;
+
+/// Converts a LargeBinary geometry column into a Binary one, only the offsets are rebuilt and
+/// the wkb values are shared. Fails if the values do not fit in i32 offsets.
+#[derive(Debug)]
+pub struct ToSmallGeometryUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl ToSmallGeometryUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::exact(vec![DataType::LargeBinary], Volatility::Immutable),
+            aliases: vec!["st_tosmallgeometry".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for ToSmallGeometryUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_ToSmallGeometry"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(DataType::Binary)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let small_arr = to_small(arrays[0].as_binary::<i64>())?;
+        Ok(ColumnarValue::Array(Arc::new(small_arr)))
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for ToSmallGeometryUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn to_small(arr: &LargeBinaryArray) -> DFResult<BinaryArray> {
+    // a sliced array may start in the middle of the values, only the referenced part is kept
+    let start = arr.offsets()[0];
+    let end = arr.offsets()[arr.len()];
+    if end - start > i32::MAX as i64 {
+        return exec_err!(
+            "Geometry values of {} bytes do not fit in Binary, use LargeBinary",
+            end - start
+        );
+    }
+    let offsets = arr
+        .offsets()
+        .iter()
+        .map(|offset| (offset - start) as i32)
+        .collect::<ScalarBuffer<i32>>();
+    let values = arr
+        .values()
+        .slice_with_length(start as usize, (end - start) as usize);
+    Ok(BinaryArray::new(
+        OffsetBuffer::new(offsets),
+        values,
+        arr.nulls().cloned(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::to_small_geometry::to_small;
+    use crate::geo::GeometryArrayBuilder;
+    use arrow_array::Array;
+    use geo::point;
+
+    #[test]
+    fn sliced_large_geometry_to_small() {
+        let points = vec![
+            Some(point!(x: 1.0, y: 1.0)),
+            None,
+            Some(point!(x: 2.0, y: 2.0)),
+            Some(point!(x: 3.0, y: 3.0)),
+        ];
+        let large: GeometryArrayBuilder<i64> = points.as_slice().try_into().unwrap();
+        let large = large.build().slice(1, 2);
+        let small: GeometryArrayBuilder<i32> = points[1..3].to_vec().as_slice().try_into().unwrap();
+        let small = small.build();
+
+        let converted = to_small(&large).unwrap();
+        assert_eq!(converted, small);
+        assert_eq!(converted.value_offsets()[0], 0);
+        assert!(converted.is_null(0));
+    }
+}
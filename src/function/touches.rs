@@ -0,0 +1,73 @@
+use arrow_schema::DataType;
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+use std::any::Any;
+
+/// Whether two geometries share boundary points but no interior points.
+#[derive(Debug)]
+pub struct TouchesUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl TouchesUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::uniform(
+                2,
+                vec![DataType::Binary, DataType::LargeBinary],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_touches".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for TouchesUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_Touches"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(DataType::Boolean)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        #[cfg(feature = "geos")]
+        {
+            use crate::function::args::geos_predicate;
+            use datafusion_common::{internal_datafusion_err, DataFusionError};
+            use geos::Geom;
+            geos_predicate(self.name(), args, |geom0, geom1| {
+                geom0
+                    .touches(geom1)
+                    .map_err(|e| internal_datafusion_err!("Failed to do touches, error: {}", e))
+            })
+        }
+        #[cfg(not(feature = "geos"))]
+        {
+            use crate::function::args::geo_predicate;
+            use geo::Relate;
+            geo_predicate(self.name(), args, |geom0, geom1| {
+                geom0.relate(geom1).is_touches()
+            })
+        }
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for TouchesUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,275 @@
+use crate::config::default_dialect;
+use crate::function::args::geometry_args;
+use crate::geo::dialect::decode_srid;
+use crate::geo::proj_cache::{transformation, Transformation};
+use crate::geo::{crs_info, GeometryArray, GeometryArrayBuilder};
+use crate::DFResult;
+use arrow_array::cast::AsArray;
+use arrow_array::types::Int64Type;
+use arrow_array::{Array, GenericBinaryArray, Int64Array, OffsetSizeTrait};
+use arrow_schema::DataType;
+use datafusion_common::{exec_datafusion_err, exec_err, internal_datafusion_err, DataFusionError};
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, TypeSignature, Volatility};
+use geo::{BoundingRect, MapCoords};
+use std::any::Any;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Reprojects a geometry to the target srid with proj, `ST_Transform(geom, target_srid)` reads
+/// the source srid from the geometry, `ST_Transform(geom, source_srid, target_srid)` overrides it.
+#[derive(Debug)]
+pub struct TransformUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl TransformUdf {
+    pub fn new() -> Self {
+        let mut signatures = vec![];
+        for geometry_type in [DataType::Binary, DataType::LargeBinary] {
+            signatures.push(TypeSignature::Exact(vec![
+                geometry_type.clone(),
+                DataType::Int64,
+            ]));
+            signatures.push(TypeSignature::Exact(vec![
+                geometry_type,
+                DataType::Int64,
+                DataType::Int64,
+            ]));
+        }
+        Self {
+            signature: Signature::one_of(signatures, Volatility::Immutable),
+            aliases: vec!["st_transform".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for TransformUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_Transform"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(arg_types[0].clone())
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let (source_srids, target_srids) = match arrays.len() {
+            2 => (None, arrays[1].as_primitive::<Int64Type>()),
+            _ => (
+                Some(arrays[1].as_primitive::<Int64Type>()),
+                arrays[2].as_primitive::<Int64Type>(),
+            ),
+        };
+        let arr = &arrays[0];
+        match arr.data_type() {
+            DataType::Binary => {
+                transform::<i32>(arr.as_binary::<i32>(), source_srids, target_srids)
+            }
+            DataType::LargeBinary => {
+                transform::<i64>(arr.as_binary::<i64>(), source_srids, target_srids)
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for TransformUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn transform<O: OffsetSizeTrait>(
+    wkb_arr: &GenericBinaryArray<O>,
+    source_srids: Option<&Int64Array>,
+    target_srids: &Int64Array,
+) -> DFResult<ColumnarValue> {
+    // the transformations are looked up in the process wide cache once per invocation and pair
+    // of srids
+    let mut transformations = HashMap::<(i64, i64), Transformation>::new();
+    let mut builder = GeometryArrayBuilder::<O>::new(default_dialect(), wkb_arr.geom_len());
+    for i in 0..wkb_arr.geom_len() {
+        let Some(wkb) = wkb_arr.wkb(i) else {
+            builder.append_null();
+            continue;
+        };
+        if target_srids.is_null(i) || source_srids.is_some_and(|srids| srids.is_null(i)) {
+            builder.append_null();
+            continue;
+        }
+        let target = target_srids.value(i);
+        let source = match source_srids {
+            Some(srids) => srids.value(i),
+            None => match decode_srid(wkb)? {
+                Some(srid) if srid != 0 => srid as i64,
+                _ => {
+                    return exec_err!(
+                        "ST_Transform: geometry at row {} has no SRID, use ST_Transform(geom, source_srid, target_srid)",
+                        i
+                    )
+                }
+            },
+        };
+        let proj = match transformations.entry((source, target)) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(transformation(source, target)?),
+        };
+        let geom = wkb_arr.geo_value(i)?;
+        if let Some(geom) = &geom {
+            check_lon_lat(i, source, geom)?;
+        }
+        let proj = proj
+            .lock()
+            .map_err(|e| internal_datafusion_err!("Failed to lock the transformation, e: {}", e))?;
+        let geom = geom
+            .map(|geom| {
+                geom.try_map_coords(|coord| {
+                    proj.convert((coord.x, coord.y))
+                        .map(|(x, y)| geo::coord! { x: x, y: y })
+                })
+            })
+            .transpose()
+            .map_err(|e| {
+                exec_datafusion_err!("Failed to transform geometry at row {}, error: {}", i, e)
+            })?;
+        builder.append_geo_geometry_with_srid(&geom, Some(target as i32))?;
+    }
+    Ok(ColumnarValue::Array(Arc::new(builder.build())))
+}
+
+/// Catches projected coordinates labelled with a geographic srid, which proj would transform into
+/// garbage or fail on with an obscure error.
+fn check_lon_lat(row: usize, source: i64, geom: &geo::Geometry) -> DFResult<()> {
+    let geographic = i32::try_from(source)
+        .ok()
+        .and_then(crs_info)
+        .is_some_and(|crs| crs.is_geographic);
+    let Some(rect) = geom.bounding_rect() else {
+        return Ok(());
+    };
+    if geographic
+        && (rect.min().x < -180.0
+            || rect.max().x > 180.0
+            || rect.min().y < -90.0
+            || rect.max().y > 90.0)
+    {
+        return exec_err!(
+            "ST_Transform: SRID {} is geographic but the geometry at row {} is outside of the lon/lat range, it may be projected",
+            source,
+            row
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{GeomFromTextUdf, SridUdf, TransformUdf};
+    use crate::geo::dialect::decode_srid;
+    use crate::geo::GeometryArray;
+    use arrow_array::cast::AsArray;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+
+    #[tokio::test]
+    async fn transform_to_web_mercator() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(TransformUdf::new()));
+        let df = ctx
+            .sql(
+                "select ST_Transform(ST_GeomFromText('POINT(0 0)', 4326), 3857) \
+                union all \
+                select ST_Transform(ST_GeomFromText('POINT(-71 42)'), 4326, 3857)",
+            )
+            .await
+            .unwrap();
+        let mut points = vec![];
+        for batch in df.collect().await.unwrap() {
+            let arr = batch.column(0).as_binary::<i32>();
+            for i in 0..arr.geom_len() {
+                assert_eq!(decode_srid(arr.wkb(i).unwrap()).unwrap(), Some(3857));
+                let Some(geo::Geometry::Point(point)) = arr.geo_value(i).unwrap() else {
+                    panic!("transformed geometry should be a point");
+                };
+                points.push(point);
+            }
+        }
+        points.sort_by(|a, b| a.x().total_cmp(&b.x()));
+        let expected = [(-7903683.846322424, 5160979.444049783), (0.0, 0.0)];
+        assert_eq!(points.len(), expected.len());
+        for (point, (x, y)) in points.iter().zip(expected) {
+            assert!((point.x() - x).abs() < 1e-3, "{:?}", point);
+            assert!((point.y() - y).abs() < 1e-3, "{:?}", point);
+        }
+    }
+
+    #[tokio::test]
+    async fn transform_sets_target_srid() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(TransformUdf::new()));
+        ctx.register_udf(ScalarUDF::from(SridUdf::new()));
+        let df = ctx
+            .sql("select ST_SRID(ST_Transform(ST_GeomFromText('POINT(-71 42)', 4326), 3857))")
+            .await
+            .unwrap();
+        let batches = df.collect().await.unwrap();
+        assert_eq!(
+            batches[0]
+                .column(0)
+                .as_primitive::<arrow_array::types::Int32Type>()
+                .value(0),
+            3857
+        );
+    }
+
+    #[tokio::test]
+    async fn transform_without_srid() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(TransformUdf::new()));
+        let err = ctx
+            .sql("select ST_Transform(ST_GeomFromText('POINT(-71 42)'), 3857)")
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("has no SRID"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn transform_projected_coordinates_with_geographic_srid() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(TransformUdf::new()));
+        let err = ctx
+            .sql("select ST_Transform(ST_GeomFromText('POINT(-7903683 5160979)', 4326), 3857)")
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("SRID 4326 is geographic"),
+            "{}",
+            err
+        );
+    }
+}
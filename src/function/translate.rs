@@ -1,7 +1,14 @@
+use crate::config::default_dialect;
+use crate::function::args::geometry_args;
+use crate::geo::dialect::decode_srid;
+use crate::geo::map::map_geometry_recursive;
 use crate::geo::{GeometryArray, GeometryArrayBuilder};
+use crate::DFResult;
 use arrow_array::cast::AsArray;
+use arrow_array::types::Float64Type;
+use arrow_array::{Array, ArrayRef, GenericBinaryArray, OffsetSizeTrait};
 use arrow_schema::DataType;
-use datafusion_common::{internal_err, DataFusionError, ScalarValue};
+use datafusion_common::ScalarValue;
 use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, TypeSignature, Volatility};
 use geo::Translate;
 use std::any::Any;
@@ -54,46 +61,25 @@ impl ScalarUDFImpl for TranslateUdf {
     }
 
     fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
-        let ColumnarValue::Scalar(ScalarValue::Float64(Some(x_offset))) = args[1] else {
-            return internal_err!("The second arg should be f64 scalar");
-        };
-        let ColumnarValue::Scalar(ScalarValue::Float64(Some(y_offset))) = args[2] else {
-            return internal_err!("The third arg should be f64 scalar");
-        };
-
-        match args[0].data_type() {
-            DataType::Binary => {
-                let arr = args[0].clone().into_array(1)?;
-                let wkb_arr = arr.as_binary::<i32>();
-
-                let mut geom_vec = vec![];
-                for i in 0..wkb_arr.geom_len() {
-                    geom_vec.push(
-                        wkb_arr
-                            .geo_value(i)?
-                            .map(|geom| geom.translate(x_offset, y_offset)),
-                    );
-                }
-
-                let builder: GeometryArrayBuilder<i32> = geom_vec.as_slice().into();
-                Ok(ColumnarValue::Array(Arc::new(builder.build())))
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let arr = &arrays[0];
+        match (&args[1], &args[2]) {
+            // scalar offsets are read once instead of per row
+            (
+                ColumnarValue::Scalar(ScalarValue::Float64(x_offset)),
+                ColumnarValue::Scalar(ScalarValue::Float64(y_offset)),
+            ) => {
+                let offset = x_offset.zip(*y_offset);
+                translate_array(arr, |_| offset)
             }
-            DataType::LargeBinary => {
-                let arr = args[0].clone().into_array(0)?;
-                let wkb_arr = arr.as_binary::<i64>();
-
-                let mut geom_vec = vec![];
-                for i in 0..wkb_arr.geom_len() {
-                    geom_vec.push(
-                        wkb_arr
-                            .geo_value(i)?
-                            .map(|geom| geom.translate(x_offset, y_offset)),
-                    );
-                }
-                let builder: GeometryArrayBuilder<i64> = geom_vec.as_slice().into();
-                Ok(ColumnarValue::Array(Arc::new(builder.build())))
+            _ => {
+                let x_offsets = arrays[1].as_primitive::<Float64Type>();
+                let y_offsets = arrays[2].as_primitive::<Float64Type>();
+                translate_array(arr, |i| {
+                    (x_offsets.is_valid(i) && y_offsets.is_valid(i))
+                        .then(|| (x_offsets.value(i), y_offsets.value(i)))
+                })
             }
-            _ => unreachable!(),
         }
     }
 
@@ -108,32 +94,205 @@ impl Default for TranslateUdf {
     }
 }
 
+/// Translates every geometry by the offsets of its row keeping its srid, rows with null offsets
+/// are null.
+fn translate_array(
+    arr: &ArrayRef,
+    offset: impl Fn(usize) -> Option<(f64, f64)>,
+) -> DFResult<ColumnarValue> {
+    let arr: ArrayRef = match arr.data_type() {
+        DataType::Binary => Arc::new(translate_geometries(arr.as_binary::<i32>(), offset)?),
+        DataType::LargeBinary => Arc::new(translate_geometries(arr.as_binary::<i64>(), offset)?),
+        _ => unreachable!(),
+    };
+    Ok(ColumnarValue::Array(arr))
+}
+
+fn translate_geometries<O: OffsetSizeTrait>(
+    wkb_arr: &GenericBinaryArray<O>,
+    offset: impl Fn(usize) -> Option<(f64, f64)>,
+) -> DFResult<GenericBinaryArray<O>> {
+    let mut builder = GeometryArrayBuilder::<O>::new(default_dialect(), wkb_arr.geom_len());
+    for i in 0..wkb_arr.geom_len() {
+        let (Some(wkb), Some((x_offset, y_offset))) = (wkb_arr.wkb(i), offset(i)) else {
+            builder.append_null();
+            continue;
+        };
+        let geom = wkb_arr
+            .geo_value(i)?
+            .map(|geom| translate(geom, x_offset, y_offset));
+        builder.append_geo_geometry_with_srid(&geom, decode_srid(wkb)?)?;
+    }
+    Ok(builder.build())
+}
+
+fn translate(geom: geo::Geometry, x_offset: f64, y_offset: f64) -> geo::Geometry {
+    map_geometry_recursive(geom, &mut |geom| geom.translate(x_offset, y_offset))
+}
+
 #[cfg(test)]
 mod tests {
     use crate::function::{AsTextUdf, GeomFromTextUdf, TranslateUdf};
+    use crate::test_utils::assert_geometry_array_eq;
     use arrow::util::pretty::pretty_format_batches;
+    use arrow_schema::DataType;
     use datafusion::logical_expr::ScalarUDF;
     use datafusion::prelude::SessionContext;
 
     #[tokio::test]
     async fn translate() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(TranslateUdf::new()));
+        let df = ctx
+            .sql(
+                "select ST_Translate(ST_GeomFromText('POINT(-71.064544 42.28787)'), 1.0, 2.0), \
+                ST_GeomFromText('POINT(-70.064544 44.28787)')",
+            )
+            .await
+            .unwrap();
+        let batches = df.collect().await.unwrap();
+        assert_geometry_array_eq(batches[0].column(0), batches[0].column(1), 1e-9);
+    }
+
+    #[tokio::test]
+    async fn translate_by_columns() {
+        use arrow_array::{Float64Array, RecordBatch, StringArray};
+        use arrow_schema::{Field, Schema};
+        use datafusion::datasource::MemTable;
+        use std::sync::Arc;
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("wkt", DataType::Utf8, true),
+            Field::new("dx", DataType::Float64, true),
+            Field::new("dy", DataType::Float64, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(StringArray::from(vec![
+                    Some("POINT(1 1)"),
+                    Some("LINESTRING(0 0,1 1)"),
+                    Some("POINT(5 5)"),
+                    None,
+                ])),
+                Arc::new(Float64Array::from(vec![
+                    Some(1.0),
+                    Some(-2.0),
+                    None,
+                    Some(1.0),
+                ])),
+                Arc::new(Float64Array::from(vec![
+                    Some(2.0),
+                    Some(0.5),
+                    Some(1.0),
+                    Some(1.0),
+                ])),
+            ],
+        )
+        .unwrap();
+        let table = MemTable::try_new(schema, vec![vec![batch]]).unwrap();
+
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(TranslateUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
+        ctx.register_table("t", Arc::new(table)).unwrap();
+        let df = ctx
+            .sql(
+                "select ST_AsText(ST_Translate(ST_GeomFromText(wkt), dx, dy)) as geom, \
+                ST_AsText(ST_Translate(ST_GeomFromText('POINT(0 0)'), dx, 1.0)) as origin from t",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+---------------------------+-------------+
+| geom                      | origin      |
++---------------------------+-------------+
+| POINT(2 3)                | POINT(1 1)  |
+| LINESTRING(-2 0.5,-1 1.5) | POINT(-2 1) |
+|                           |             |
+|                           | POINT(1 1)  |
++---------------------------+-------------+"
+        );
+    }
+
+    #[tokio::test]
+    async fn translate_large_geometry() {
+        use crate::function::ToLargeGeometryUdf;
+
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(ToLargeGeometryUdf::new()));
+        ctx.register_udf(ScalarUDF::from(TranslateUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
+        let df = ctx
+            .sql(
+                "select ST_AsText(ST_Translate(ST_ToLargeGeometry(ST_GeomFromText(wkt)), 1.0, dy)) as geom, \
+                ST_AsText(ST_Translate(ST_ToLargeGeometry(ST_GeomFromText('POINT(0 0)')), 1.0, 2.0)) as scalar \
+                from (values ('POINT(1 1)', 1.0), ('LINESTRING(0 0,1 1)', 2.0), (null, 3.0)) as t(wkt, dy)",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+---------------------+------------+
+| geom                | scalar     |
++---------------------+------------+
+| POINT(2 2)          | POINT(1 2) |
+| LINESTRING(1 2,2 3) | POINT(1 2) |
+|                     | POINT(1 2) |
++---------------------+------------+"
+        );
+
+        let df = ctx
+            .sql("select ST_AsText(ST_Translate(ST_ToLargeGeometry(ST_GeomFromText('POINT(0 0)')), 1.0, 2.0)) as geom")
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+------------+
+| geom       |
++------------+
+| POINT(1 2) |
++------------+"
+        );
+    }
+
+    #[tokio::test]
+    async fn translate_keeps_srid() {
+        use crate::function::SridUdf;
+
         let ctx = SessionContext::new();
         ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
         ctx.register_udf(ScalarUDF::from(TranslateUdf::new()));
         ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(SridUdf::new()));
         let df = ctx
-            .sql("select ST_AsText(ST_Translate(ST_GeomFromText('POINT(-71.064544 42.28787)'), 1.0, 2.0))")
+            .sql(
+                "select ST_AsText(geom) as geom, ST_SRID(geom) as srid from (\
+                select ST_Translate(ST_GeomFromText(wkt, 4326), 1.0, 2.0) as geom \
+                from (values ('POINT(1 1)'), ('LINESTRING(0 0,1 1)')) as t(wkt))",
+            )
             .await
             .unwrap();
         assert_eq!(
             pretty_format_batches(&df.collect().await.unwrap())
                 .unwrap()
                 .to_string(),
-            "+----------------------------------------------------------------------------------------------------+
-| ST_AsText(ST_Translate(ST_GeomFromText(Utf8(\"POINT(-71.064544 42.28787)\")),Float64(1),Float64(2))) |
-+----------------------------------------------------------------------------------------------------+
-| POINT(-70.064544 44.28787)                                                                         |
-+----------------------------------------------------------------------------------------------------+"
+            "+---------------------+------+
+| geom                | srid |
++---------------------+------+
+| POINT(2 3)          | 4326 |
+| LINESTRING(1 2,2 3) | 4326 |
++---------------------+------+"
         );
     }
 }
@@ -0,0 +1,136 @@
+use crate::function::args::{geos_overlay, overlay_signature};
+use crate::geo::overlay::Overlay;
+use arrow_schema::DataType;
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature};
+use std::any::Any;
+
+/// Returns the point set union of two geometries. An optional grid size runs the fixed precision
+/// overlay of OverlayNG, which snap rounds to the grid and avoids topology errors on nearly
+/// coincident edges. The grid size requires GEOS 3.9 or newer.
+#[derive(Debug)]
+pub struct UnionUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl UnionUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: overlay_signature(),
+            aliases: vec!["st_union".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for UnionUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_Union"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(arg_types[0].clone())
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        geos_overlay(self.name(), args, Overlay::Union)
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for UnionUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{AsTextUdf, GeomFromTextUdf, UnionUdf};
+    use crate::geo::GeometryArray;
+    use arrow_array::cast::AsArray;
+    use arrow_array::Array;
+    use datafusion::prelude::SessionContext;
+    use datafusion_expr::ScalarUDF;
+    use geo::Area;
+
+    #[tokio::test]
+    async fn union() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(UnionUdf::new()));
+        let df = ctx
+            .sql(
+                "select ST_Union(ST_GeomFromText(a), ST_GeomFromText(b)), \
+                ST_AsText(ST_Union(ST_GeomFromText(a), ST_GeomFromText(b))) \
+                from (values \
+                ('POLYGON((0 0,2 0,2 2,0 2,0 0))', 'POLYGON((1 1,3 1,3 3,1 3,1 1))'), \
+                ('POLYGON((0 0,1 0,1 1,0 1,0 0))', 'POLYGON((5 5,6 5,6 6,5 6,5 5))'), \
+                ('POLYGON((0 0,1 0,1 1,0 1,0 0))', null)) as t(a, b)",
+            )
+            .await
+            .unwrap();
+        let batches = df.collect().await.unwrap();
+        let unions = batches[0].column(0).as_binary::<i32>();
+        let texts = batches[0].column(1).as_string::<i32>();
+
+        let overlapping = unions.geo_value(0).unwrap().unwrap();
+        assert!(matches!(overlapping, geo::Geometry::Polygon(_)));
+        assert_eq!(overlapping.unsigned_area(), 7.0);
+        assert!(texts.value(0).starts_with("POLYGON"));
+
+        let disjoint = unions.geo_value(1).unwrap().unwrap();
+        assert_eq!(disjoint.unsigned_area(), 2.0);
+        assert!(texts.value(1).starts_with("MULTIPOLYGON"));
+
+        assert!(unions.is_null(2));
+        assert!(texts.is_null(2));
+    }
+
+    #[tokio::test]
+    async fn union_grid_size() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(UnionUdf::new()));
+        // squares apart by 1e-9, closed by snap rounding to a 1e-7 grid
+        let sql = "select ST_Union(ST_GeomFromText(a), ST_GeomFromText(b)), \
+            ST_Union(ST_GeomFromText(a), ST_GeomFromText(b), 1e-7) \
+            from (values ('POLYGON((0 0,1 0,1 1,0 1,0 0))', \
+            'POLYGON((1.000000001 0,2 0,2 1,1.000000001 1,1.000000001 0))')) as t(a, b)";
+        let batches = ctx.sql(sql).await.unwrap().collect().await.unwrap();
+
+        let exact = batches[0].column(0).as_binary::<i32>();
+        let exact = exact.geo_value(0).unwrap().unwrap();
+        assert!(matches!(exact, geo::Geometry::MultiPolygon(_)));
+
+        let snapped = batches[0].column(1).as_binary::<i32>();
+        let snapped = snapped.geo_value(0).unwrap().unwrap();
+        assert!(matches!(snapped, geo::Geometry::Polygon(_)));
+        assert_eq!(snapped.unsigned_area(), 2.0);
+
+        let err = ctx
+            .sql("select ST_Union(ST_GeomFromText(a), ST_GeomFromText(a), -1.0) from (values ('POINT(0 0)')) as t(a)")
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("The grid size should be a positive f64 scalar"),
+            "{}",
+            err
+        );
+    }
+}
@@ -0,0 +1,324 @@
+use crate::config::{check_cancelled, default_dialect};
+use crate::function::args::GRID_SIZE_GEOS;
+use crate::function::coverage_union::CoverageAccumulator;
+use crate::geo::dialect::decode_srid;
+use crate::geo::overlay::unary_union;
+use crate::geo::{geos_capabilities, GeometryArray, GeometryArrayBuilder, GeosVersion};
+use crate::metrics::record_call;
+use crate::DFResult;
+use arrow_array::cast::AsArray;
+use arrow_array::{Array, ArrayRef, BinaryArray, BooleanArray};
+use arrow_schema::DataType;
+use datafusion_common::{exec_datafusion_err, exec_err, DataFusionError, ScalarValue};
+use datafusion_expr::{Accumulator, AggregateUDFImpl, Signature, TypeSignature, Volatility};
+use geos::Geom;
+use std::any::Any;
+
+const NAME: &str = "st_union_agg";
+
+/// Unions all geometries of a group into one geometry. Invalid geometries fail the query unless
+/// the optional second argument is true, then they are repaired with make valid first, which
+/// requires geos 3.8 or newer. The repaired inputs are counted in the metrics. An optional third
+/// argument is the grid size of a fixed precision union, like with `ST_Union`.
+#[derive(Debug)]
+pub struct UnionUdaf {
+    signature: Signature,
+}
+
+impl UnionUdaf {
+    pub fn new() -> Self {
+        let mut signatures = vec![];
+        for geometry_type in [DataType::Binary, DataType::LargeBinary] {
+            signatures.push(TypeSignature::Exact(vec![geometry_type.clone()]));
+            signatures.push(TypeSignature::Exact(vec![
+                geometry_type.clone(),
+                DataType::Boolean,
+            ]));
+            signatures.push(TypeSignature::Exact(vec![
+                geometry_type,
+                DataType::Boolean,
+                DataType::Float64,
+            ]));
+        }
+        Self {
+            signature: Signature::one_of(signatures, Volatility::Immutable),
+        }
+    }
+}
+
+impl AggregateUDFImpl for UnionUdaf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        // uadf not support alias
+        NAME
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(DataType::Binary)
+    }
+
+    fn accumulator(&self, _arg: &DataType) -> datafusion_common::Result<Box<dyn Accumulator>> {
+        Ok(Box::new(UnionAccumulator {
+            inner: CoverageAccumulator::new(NAME, |geoms| unary_union(geoms, None)),
+            grid_size: None,
+        }))
+    }
+
+    fn state_type(&self, _return_type: &DataType) -> datafusion_common::Result<Vec<DataType>> {
+        Ok(vec![
+            CoverageAccumulator::state_data_type(),
+            DataType::Float64,
+        ])
+    }
+}
+
+impl Default for UnionUdaf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Checks the validity of the inputs and collects them, the union is evaluated on the whole group.
+#[derive(Debug)]
+struct UnionAccumulator {
+    inner: CoverageAccumulator,
+    grid_size: Option<f64>,
+}
+
+impl UnionAccumulator {
+    /// Takes the grid size of the group, it has to be the same positive value in every row.
+    fn set_grid_size(&mut self, arr: &ArrayRef) -> DFResult<()> {
+        for i in 0..arr.len() {
+            let grid_size = match ScalarValue::try_from_array(arr, i)? {
+                ScalarValue::Float64(None) => continue,
+                ScalarValue::Float64(Some(grid_size)) if grid_size > 0.0 => grid_size,
+                _ => return exec_err!("The grid size should be a positive f64 scalar"),
+            };
+            match self.grid_size {
+                None => {
+                    geos_capabilities()
+                        .check(&format!("{} with a grid size", NAME), GRID_SIZE_GEOS)?;
+                    self.grid_size = Some(grid_size);
+                }
+                Some(current) if current != grid_size => {
+                    return exec_err!(
+                        "{} got the grid sizes {} and {} in one group",
+                        NAME,
+                        current,
+                        grid_size
+                    );
+                }
+                Some(_) => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the geometries of the array with the invalid ones repaired where make valid is set,
+/// and the number of repaired geometries.
+fn checked_inputs(
+    arr: &dyn GeometryArray,
+    make_valid: Option<&BooleanArray>,
+) -> DFResult<(BinaryArray, u64)> {
+    let mut wkbs = Vec::with_capacity(arr.geom_len());
+    let mut repaired = 0;
+    for i in 0..arr.geom_len() {
+        check_cancelled(i)?;
+        let Some(geom) = arr.geos_value(i)? else {
+            wkbs.push(None);
+            continue;
+        };
+        if geom.is_valid() {
+            wkbs.push(arr.wkb(i).map(|wkb| wkb.to_vec()));
+            continue;
+        }
+        if !make_valid.is_some_and(|make_valid| make_valid.is_valid(i) && make_valid.value(i)) {
+            return exec_err!(
+                "{}: invalid geometry at row {}: {}, pass true as the second argument to repair it",
+                NAME,
+                i,
+                geom.is_valid_reason().unwrap_or_default()
+            );
+        }
+        geos_capabilities().check("Repairing invalid geometries", GeosVersion::new(3, 8, 0))?;
+        let mut valid = geom
+            .make_valid()
+            .map_err(|e| exec_datafusion_err!("Failed to repair geometry, e: {}", e))?;
+        if let Some(srid) = arr.wkb(i).map(decode_srid).transpose()?.flatten() {
+            valid.set_srid(srid as usize);
+        }
+        let mut builder = GeometryArrayBuilder::<i32>::new(default_dialect(), 1);
+        builder.append_geos_geometry(&Some(valid))?;
+        wkbs.push(Some(builder.build().value(0).to_vec()));
+        repaired += 1;
+    }
+    Ok((BinaryArray::from_iter(wkbs), repaired))
+}
+
+impl Accumulator for UnionAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> datafusion_common::Result<()> {
+        if values.is_empty() {
+            return Ok(());
+        }
+        let arr = &values[0];
+        let make_valid = values.get(1).map(|arr| arr.as_boolean());
+        let recorder = record_call(NAME, arr.len());
+        let (wkb_arr, repaired) = match arr.data_type() {
+            DataType::Binary => checked_inputs(arr.as_binary::<i32>(), make_valid)?,
+            DataType::LargeBinary => checked_inputs(arr.as_binary::<i64>(), make_valid)?,
+            _ => unreachable!(),
+        };
+        recorder.repaired(repaired);
+        if let Some(grid_size) = values.get(2) {
+            self.set_grid_size(grid_size)?;
+        }
+        self.inner.append(&wkb_arr)
+    }
+
+    fn evaluate(&mut self) -> datafusion_common::Result<ScalarValue> {
+        let grid_size = self.grid_size;
+        self.inner
+            .evaluate_with(|geoms| unary_union(geoms, grid_size))
+    }
+
+    fn size(&self) -> usize {
+        self.inner.size()
+    }
+
+    fn state(&mut self) -> datafusion_common::Result<Vec<ScalarValue>> {
+        let mut state = self.inner.state()?;
+        state.push(ScalarValue::Float64(self.grid_size));
+        Ok(state)
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> datafusion_common::Result<()> {
+        if let Some(grid_size) = states.get(1) {
+            self.set_grid_size(grid_size)?;
+        }
+        self.inner.merge_batch(states)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{GeomFromTextUdf, UnionUdaf};
+    use crate::geo::GeometryArray;
+    use crate::metrics;
+    use arrow_array::cast::AsArray;
+    use datafusion::prelude::SessionContext;
+    use datafusion_expr::{AggregateUDF, ScalarUDF};
+    use geo::Area;
+
+    fn session_context() -> SessionContext {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udaf(AggregateUDF::from(UnionUdaf::new()));
+        ctx
+    }
+
+    const BOWTIE_GROUP: &str = "(values \
+        ('POLYGON((0 0,2 2,2 0,0 2,0 0))'), \
+        ('POLYGON((2 0,3 0,3 2,2 2,2 0))')) as t(wkt)";
+
+    #[tokio::test]
+    async fn union_agg() {
+        let ctx = session_context();
+        let df = ctx
+            .sql(
+                "select st_union_agg(ST_GeomFromText(wkt)) from (values \
+                ('POLYGON((0 0,2 0,2 2,0 2,0 0))'), \
+                ('POLYGON((1 1,3 1,3 3,1 3,1 1))'), \
+                (null)) as t(wkt)",
+            )
+            .await
+            .unwrap();
+        let batches = df.collect().await.unwrap();
+        let union = batches[0]
+            .column(0)
+            .as_binary::<i32>()
+            .geo_value(0)
+            .unwrap()
+            .unwrap();
+        assert_eq!(union.unsigned_area(), 7.0);
+    }
+
+    #[tokio::test]
+    async fn union_agg_rejects_invalid_input() {
+        let ctx = session_context();
+        let err = ctx
+            .sql(&format!(
+                "select st_union_agg(ST_GeomFromText(wkt)) from {}",
+                BOWTIE_GROUP
+            ))
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("invalid geometry"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn union_agg_make_valid() {
+        if !crate::geo::geos_capabilities().supports(crate::geo::GeosVersion::new(3, 8, 0)) {
+            return;
+        }
+        metrics::enable();
+        let ctx = session_context();
+        let df = ctx
+            .sql(&format!(
+                "select st_union_agg(ST_GeomFromText(wkt), true) from {}",
+                BOWTIE_GROUP
+            ))
+            .await
+            .unwrap();
+        let batches = df.collect().await.unwrap();
+        let union = batches[0]
+            .column(0)
+            .as_binary::<i32>()
+            .geo_value(0)
+            .unwrap()
+            .unwrap();
+        // the two triangles of the bow-tie and the square
+        assert_eq!(union.unsigned_area(), 4.0);
+        let repaired = metrics::snapshot()
+            .function("st_union_agg")
+            .unwrap()
+            .repaired;
+        assert!(repaired >= 1, "{}", repaired);
+    }
+
+    #[tokio::test]
+    async fn union_agg_grid_size() {
+        if !crate::geo::geos_capabilities().supports(crate::function::args::GRID_SIZE_GEOS) {
+            return;
+        }
+        let ctx = session_context();
+        // squares apart by 1e-9, closed by snap rounding to a 1e-7 grid
+        let df = ctx
+            .sql(
+                "select st_union_agg(ST_GeomFromText(wkt), false, 1e-7) from (values \
+                ('POLYGON((0 0,1 0,1 1,0 1,0 0))'), \
+                ('POLYGON((1.000000001 0,2 0,2 1,1.000000001 1,1.000000001 0))')) as t(wkt)",
+            )
+            .await
+            .unwrap();
+        let batches = df.collect().await.unwrap();
+        let union = batches[0]
+            .column(0)
+            .as_binary::<i32>()
+            .geo_value(0)
+            .unwrap()
+            .unwrap();
+        assert!(matches!(union, geo::Geometry::Polygon(_)));
+        assert_eq!(union.unsigned_area(), 2.0);
+    }
+}
@@ -0,0 +1,141 @@
+use crate::config::{check_cancelled, default_dialect};
+use crate::function::args::{as_geometry_array, geometry_args, grid_size_arg};
+use crate::geo::overlay::unary_union;
+use crate::geo::{GeometryArray, GeometryArrayBuilder};
+use arrow_array::cast::AsArray;
+use arrow_schema::{DataType, Field};
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, TypeSignature, Volatility};
+use std::any::Any;
+use std::sync::Arc;
+
+/// Unions all geometries of a list into one geometry per row, e.g. the result of `array_agg`.
+/// Null elements are skipped, an empty list returns null. An optional grid size runs the fixed
+/// precision unary union, like with `ST_Union`.
+#[derive(Debug)]
+pub struct UnionArrayUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl UnionArrayUdf {
+    pub fn new() -> Self {
+        let list_of = |data_type| DataType::List(Arc::new(Field::new("item", data_type, true)));
+        Self {
+            signature: Signature::one_of(
+                vec![
+                    TypeSignature::Exact(vec![list_of(DataType::Binary)]),
+                    TypeSignature::Exact(vec![list_of(DataType::LargeBinary)]),
+                    TypeSignature::Exact(vec![list_of(DataType::Binary), DataType::Float64]),
+                    TypeSignature::Exact(vec![list_of(DataType::LargeBinary), DataType::Float64]),
+                ],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_unionarray".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for UnionArrayUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_UnionArray"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(DataType::Binary)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let grid_size = grid_size_arg(self.name(), args, 1)?;
+        let (arrays, _recorder) = geometry_args(self.name(), &args[..1])?;
+        let list_arr = arrays[0].as_list::<i32>();
+
+        let mut builder = GeometryArrayBuilder::<i32>::new(default_dialect(), list_arr.len());
+        for (index, list) in list_arr.iter().enumerate() {
+            check_cancelled(index)?;
+            let Some(list) = list else {
+                builder.append_null();
+                continue;
+            };
+            let wkb_arr = as_geometry_array(&list)?;
+            let mut geoms = vec![];
+            for i in 0..wkb_arr.geom_len() {
+                if let Some(geom) = wkb_arr.geos_value(i)? {
+                    geoms.push(geom);
+                }
+            }
+            let union = unary_union(geoms, grid_size)?;
+            builder.append_geos_geometry(&union)?;
+        }
+        Ok(ColumnarValue::Array(Arc::new(builder.build())))
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for UnionArrayUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::UnionArrayUdf;
+    use crate::geo::{GeometryArray, GeometryArrayBuilder};
+    use arrow_array::cast::AsArray;
+    use arrow_array::{Array, ListArray, RecordBatch};
+    use arrow_buffer::{NullBuffer, OffsetBuffer};
+    use arrow_schema::{DataType, Field, Schema};
+    use datafusion::datasource::MemTable;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+    use geo::{polygon, Area};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn union_array() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(UnionArrayUdf::new()));
+
+        // two overlapping squares with a null in between, an empty list and a null list
+        let polygons = vec![
+            Some(polygon![(x: 0., y: 0.), (x: 2., y: 0.), (x: 2., y: 2.), (x: 0., y: 2.)]),
+            None,
+            Some(polygon![(x: 1., y: 1.), (x: 3., y: 1.), (x: 3., y: 3.), (x: 1., y: 3.)]),
+        ];
+        let builder: GeometryArrayBuilder<i32> = polygons.as_slice().try_into().unwrap();
+        let field = Arc::new(Field::new("item", DataType::Binary, true));
+        let list = ListArray::new(
+            field.clone(),
+            OffsetBuffer::from_lengths([3, 0, 0]),
+            Arc::new(builder.build()),
+            Some(NullBuffer::from(vec![true, true, false])),
+        );
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "geoms",
+            DataType::List(field),
+            true,
+        )]));
+        let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(list)]).unwrap();
+        let table = MemTable::try_new(schema, vec![vec![batch]]).unwrap();
+        ctx.register_table("t", Arc::new(table)).unwrap();
+
+        let df = ctx.sql("select ST_UnionArray(geoms) from t").await.unwrap();
+        let batches = df.collect().await.unwrap();
+        let arr = batches[0].column(0).as_binary::<i32>();
+        let union = arr.geo_value(0).unwrap().unwrap();
+        assert_eq!(union.unsigned_area(), 7.0);
+        assert!(arr.is_null(1));
+        assert!(arr.is_null(2));
+    }
+}
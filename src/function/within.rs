@@ -0,0 +1,232 @@
+use crate::function::args::{box_predicate, box_predicate_signatures, point_in_polygon_interior};
+use arrow_schema::DataType;
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+use geo::Within;
+use std::any::Any;
+
+/// Whether the first geometry lies in the second one and touches its interior, either side may
+/// also be a box2d.
+#[derive(Debug)]
+pub struct WithinUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl WithinUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(box_predicate_signatures(), Volatility::Immutable),
+            aliases: vec!["st_within".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for WithinUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_Within"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(DataType::Boolean)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        if let Some(result) =
+            box_predicate(self.name(), args, |geom0, geom1| geom0.is_within(geom1))?
+        {
+            return Ok(result);
+        }
+        if let Some(result) = point_in_polygon_interior(self.name(), args, 1)? {
+            return Ok(result);
+        }
+        #[cfg(feature = "geos")]
+        {
+            use crate::function::args::geos_predicate;
+            use datafusion_common::{internal_datafusion_err, DataFusionError};
+            use geos::Geom;
+            geos_predicate(self.name(), args, |geom0, geom1| {
+                geom0
+                    .within(geom1)
+                    .map_err(|e| internal_datafusion_err!("Failed to do within, error: {}", e))
+            })
+        }
+        #[cfg(not(feature = "geos"))]
+        {
+            use crate::function::args::geo_predicate;
+            geo_predicate(self.name(), args, |geom0, geom1| geom0.is_within(geom1))
+        }
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for WithinUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::box2d::Box2dUdf;
+    use crate::function::{GeomFromTextUdf, WithinUdf};
+    use crate::geo::GeometryArrayBuilder;
+    use arrow::util::pretty::pretty_format_batches;
+    use arrow_array::RecordBatch;
+    use arrow_schema::{DataType, Field, Schema};
+    use datafusion::datasource::MemTable;
+    use datafusion::prelude::SessionContext;
+    use datafusion_expr::ScalarUDF;
+    use geo::polygon;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn within() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(WithinUdf::new()));
+        let df = ctx
+            .sql(
+                "select ST_Within(ST_GeomFromText(a), ST_GeomFromText(b)) as within \
+                from (values ('POINT(1 1)', 'POLYGON((0 0,2 0,2 2,0 2,0 0))'), \
+                ('POINT(2 1)', 'POLYGON((0 0,2 0,2 2,0 2,0 0))'), \
+                ('POLYGON((0 0,2 0,2 2,0 2,0 0))', 'POLYGON((0 0,2 0,2 2,0 2,0 0))'), \
+                (null, 'POLYGON((0 0,2 0,2 2,0 2,0 0))')) as t(a, b)",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+--------+
+| within |
++--------+
+| true   |
+| false  |
+| true   |
+|        |
++--------+"
+        );
+    }
+
+    #[tokio::test]
+    async fn within_table() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(WithinUdf::new()));
+
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "geom",
+            DataType::Binary,
+            true,
+        )]));
+        let polygons = (0..3)
+            .map(|i| {
+                let i = i as f64;
+                Some(polygon![
+                    (x: i, y: i),
+                    (x: i + 2.0, y: i),
+                    (x: i + 2.0, y: i + 2.0),
+                    (x: i, y: i + 2.0),
+                ])
+            })
+            .collect::<Vec<_>>();
+        let builder: GeometryArrayBuilder<i32> = polygons.as_slice().try_into().unwrap();
+        let record = RecordBatch::try_new(schema.clone(), vec![Arc::new(builder.build())]).unwrap();
+        let mem_table = MemTable::try_new(schema, vec![vec![record]]).unwrap();
+        ctx.register_table("geom_table", Arc::new(mem_table))
+            .unwrap();
+
+        let df = ctx
+            .sql("select ST_Within(geom, ST_GeomFromText('POLYGON((0 0,3 0,3 3,0 3,0 0))')) from geom_table")
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+------------------------------------------------------------------------------------+
+| ST_Within(geom_table.geom,ST_GeomFromText(Utf8(\"POLYGON((0 0,3 0,3 3,0 3,0 0))\"))) |
++------------------------------------------------------------------------------------+
+| true                                                                               |
+| true                                                                               |
+| false                                                                              |
++------------------------------------------------------------------------------------+"
+        );
+    }
+
+    #[tokio::test]
+    async fn within_box2d() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(Box2dUdf::new()));
+        ctx.register_udf(ScalarUDF::from(WithinUdf::new()));
+        let df = ctx
+            .sql(
+                "select point, ST_Within(ST_GeomFromText(point), b) as point_box, \
+                ST_Within(b, ST_GeomFromText(polygon)) as box_polygon \
+                from (select point, polygon, Box2D(ST_GeomFromText('LINESTRING(0 0,2 2)')) as b \
+                from (values ('POINT(1 1)', 'POLYGON((-1 -1,3 -1,3 3,-1 3,-1 -1))'), \
+                ('POINT(2 1)', 'POLYGON((0 0,2 0,2 2,0 2,0 0))'), \
+                ('POINT(3 3)', 'POLYGON((1 1,3 1,3 3,1 3,1 1))'), \
+                (null, null)) as t(point, polygon))",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+------------+-----------+-------------+
+| point      | point_box | box_polygon |
++------------+-----------+-------------+
+| POINT(1 1) | true      | true        |
+| POINT(2 1) | false     | true        |
+| POINT(3 3) | false     | false       |
+|            |           |             |
++------------+-----------+-------------+"
+        );
+    }
+
+    #[tokio::test]
+    async fn within_polygon_points() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(WithinUdf::new()));
+        // a polygon literal and a column of points take the point in polygon fast path, the
+        // points on an edge or a vertex are on the boundary
+        let df = ctx
+            .sql(
+                "select id, ST_Within(ST_GeomFromText(point), ST_GeomFromText('POLYGON((0 0,2 0,2 2,0 2,0 0))')) as within \
+                from (values (1, 'POINT(1 1)'), (2, 'POINT(2 1)'), (3, 'POINT(0 0)'), \
+                (4, 'POINT(3 1)'), (5, null)) as t(id, point)",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+----+--------+
+| id | within |
++----+--------+
+| 1  | true   |
+| 2  | false  |
+| 3  | false  |
+| 4  | false  |
+| 5  |        |
++----+--------+"
+        );
+    }
+}
@@ -0,0 +1,144 @@
+use crate::function::args::{as_geometry_array, geometry_args};
+use crate::function::geometry_type::geometry_type;
+use crate::function::pixel_as_polygon::PixelGrid;
+use crate::geo::Box2d;
+use arrow_array::{ArrayRef, Int64Array, StructArray};
+use arrow_buffer::NullBuffer;
+use arrow_schema::{DataType, Field};
+use datafusion_common::{exec_err, DataFusionError};
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, TypeSignature, Volatility};
+use std::any::Any;
+use std::sync::Arc;
+
+/// Returns the column and row of the cell of a regular grid of width x height cells over a box2d
+/// extent containing a point, see ST_PixelAsPolygon. Points outside the extent are null.
+#[derive(Debug)]
+pub struct WorldToPixelUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl WorldToPixelUdf {
+    pub fn new() -> Self {
+        let mut type_signatures = vec![];
+        for geom_type in [DataType::Binary, DataType::LargeBinary] {
+            type_signatures.push(TypeSignature::Exact(vec![
+                Box2d::data_type(),
+                DataType::Int64,
+                DataType::Int64,
+                geom_type,
+            ]));
+        }
+        Self {
+            signature: Signature::one_of(type_signatures, Volatility::Immutable),
+            aliases: vec!["st_worldtopixel".to_string()],
+        }
+    }
+
+    pub fn fields() -> Vec<Field> {
+        vec![
+            Field::new("col", DataType::Int64, false),
+            Field::new("row", DataType::Int64, false),
+        ]
+    }
+}
+
+impl ScalarUDFImpl for WorldToPixelUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_WorldToPixel"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(DataType::Struct(Self::fields().into()))
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        let (arrays, _recorder) = geometry_args(self.name(), args)?;
+        let wkb_arr = as_geometry_array(&arrays[3])?;
+
+        let mut pixel_vec = vec![];
+        for i in 0..wkb_arr.geom_len() {
+            let (Some(grid), Some(geom)) = (PixelGrid::value(&arrays, i)?, wkb_arr.geo_value(i)?)
+            else {
+                pixel_vec.push(None);
+                continue;
+            };
+            let geo::Geometry::Point(point) = geom else {
+                return exec_err!("Only points have a pixel, got {}", geometry_type(geom));
+            };
+            pixel_vec.push(grid.pixel(point.0));
+        }
+
+        let nulls: NullBuffer = pixel_vec
+            .iter()
+            .map(|pixel| pixel.is_some())
+            .collect::<Vec<_>>()
+            .into();
+        let col = pixel_vec.iter().map(|pixel| pixel.map(|p| p.0));
+        let row = pixel_vec.iter().map(|pixel| pixel.map(|p| p.1));
+        let columns = vec![
+            Arc::new(col.collect::<Int64Array>()) as ArrayRef,
+            Arc::new(row.collect::<Int64Array>()) as ArrayRef,
+        ];
+        let arr = StructArray::try_new(Self::fields().into(), columns, Some(nulls))?;
+        Ok(ColumnarValue::Array(Arc::new(arr)))
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for WorldToPixelUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::box2d::Box2dUdf;
+    use crate::function::{GeomFromTextUdf, WorldToPixelUdf};
+    use arrow::util::pretty::pretty_format_batches;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+
+    #[tokio::test]
+    async fn world_to_pixel() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(Box2dUdf::new()));
+        ctx.register_udf(ScalarUDF::from(WorldToPixelUdf::new()));
+        let df = ctx
+            .sql(
+                "select wkt, ST_WorldToPixel(Box2D(ST_GeomFromText('LINESTRING(0 0,10 5)')), \
+                10, 5, ST_GeomFromText(wkt)) as pixel \
+                from (values ('POINT(0.5 4.5)'), ('POINT(9.5 0.5)'), ('POINT(10 0)'), \
+                ('POINT(11 1)'), (null)) as t(wkt)",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+----------------+------------------+
+| wkt            | pixel            |
++----------------+------------------+
+| POINT(0.5 4.5) | {col: 0, row: 0} |
+| POINT(9.5 0.5) | {col: 9, row: 4} |
+| POINT(10 0)    | {col: 9, row: 4} |
+| POINT(11 1)    |                  |
+|                |                  |
++----------------+------------------+"
+        );
+    }
+}
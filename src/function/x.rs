@@ -0,0 +1,135 @@
+use crate::function::args::{as_geometry_array, geometry_args};
+use crate::function::geometry_type::geometry_type;
+use crate::DFResult;
+use arrow_array::Float64Array;
+use arrow_schema::DataType;
+use datafusion_common::{exec_err, DataFusionError};
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+use std::any::Any;
+use std::sync::Arc;
+
+/// Returns the x coordinate of a point, null for an empty point. Other geometries are an error
+/// like in PostGIS.
+#[derive(Debug)]
+pub struct XUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl XUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::uniform(
+                1,
+                vec![DataType::Binary, DataType::LargeBinary],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_x".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for XUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_X"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(DataType::Float64)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        point_ordinate(self.name(), args, |point| point.x())
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for XUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Reads an ordinate of the point of every row, an empty point has no ordinate.
+pub(crate) fn point_ordinate(
+    name: &str,
+    args: &[ColumnarValue],
+    ordinate: impl Fn(geo::Point) -> f64,
+) -> DFResult<ColumnarValue> {
+    let (arrays, _recorder) = geometry_args(name, args)?;
+    let wkb_arr = as_geometry_array(&arrays[0])?;
+    let mut values = Vec::with_capacity(wkb_arr.geom_len());
+    for i in 0..wkb_arr.geom_len() {
+        match wkb_arr.geo_value(i)? {
+            Some(geo::Geometry::Point(point)) => {
+                values.push(Some(ordinate(point)).filter(|value| !value.is_nan()))
+            }
+            Some(geom) => {
+                return exec_err!("{} expects a point, got {}", name, geometry_type(geom));
+            }
+            None => values.push(None),
+        }
+    }
+    Ok(ColumnarValue::Array(Arc::new(Float64Array::from(values))))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::{GeomFromTextUdf, XUdf, YUdf};
+    use arrow::util::pretty::pretty_format_batches;
+    use datafusion::logical_expr::ScalarUDF;
+    use datafusion::prelude::SessionContext;
+
+    #[tokio::test]
+    async fn x_and_y() {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+        ctx.register_udf(ScalarUDF::from(XUdf::new()));
+        ctx.register_udf(ScalarUDF::from(YUdf::new()));
+        let df = ctx
+            .sql(
+                "select ST_X(ST_GeomFromText(wkt)) as x, ST_Y(ST_GeomFromText(wkt)) as y \
+                from (values ('POINT(1.5 -2)'), (null)) as t(wkt)",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+-----+------+
+| x   | y    |
++-----+------+
+| 1.5 | -2.0 |
+|     |      |
++-----+------+"
+        );
+
+        let err = ctx
+            .sql(
+                "select ST_X(ST_GeomFromText(wkt)) from (values ('LINESTRING(0 0,1 1)')) as t(wkt)",
+            )
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("ST_X expects a point, got ST_LineString"),
+            "{}",
+            err
+        );
+    }
+}
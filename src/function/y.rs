@@ -0,0 +1,57 @@
+use crate::function::x::point_ordinate;
+use arrow_schema::DataType;
+use datafusion_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+use std::any::Any;
+
+/// Returns the y coordinate of a point, null for an empty point. Other geometries are an error
+/// like in PostGIS.
+#[derive(Debug)]
+pub struct YUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl YUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::uniform(
+                1,
+                vec![DataType::Binary, DataType::LargeBinary],
+                Volatility::Immutable,
+            ),
+            aliases: vec!["st_y".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for YUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ST_Y"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(DataType::Float64)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> datafusion_common::Result<ColumnarValue> {
+        point_ordinate(self.name(), args, |point| point.y())
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+impl Default for YUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,9 +1,10 @@
 use crate::geo::dialect::decode_wkb_dialect;
+use crate::metrics;
 use crate::DFResult;
 use arrow_array::types::GenericBinaryType;
 use arrow_array::{Array, GenericByteArray, OffsetSizeTrait};
-use datafusion_common::{internal_datafusion_err, DataFusionError};
-use geozero::wkb::FromWkb;
+use datafusion_common::{internal_datafusion_err, internal_err, DataFusionError};
+use geozero::wkb::{FromWkb, WkbDialect};
 
 pub trait GeometryArray {
     fn geom_len(&self) -> usize;
@@ -12,10 +13,16 @@ pub trait GeometryArray {
 
     fn geo_value(&self, geom_index: usize) -> DFResult<Option<geo::Geometry>> {
         if let Some(wkb) = self.wkb(geom_index) {
-            let dialect = decode_wkb_dialect(wkb[0])?;
-            let mut rdr = std::io::Cursor::new(&wkb[1..]);
-            let value = geo::Geometry::from_wkb(&mut rdr, dialect)
-                .map_err(|e| internal_datafusion_err!("Failed to parse wkb, error: {}", e))?;
+            let (dialect, wkb) = split_dialect(wkb, geom_index)?;
+            let mut rdr = std::io::Cursor::new(wkb);
+            let value =
+                metrics::decode(|| geo::Geometry::from_wkb(&mut rdr, dialect)).map_err(|e| {
+                    internal_datafusion_err!(
+                        "Failed to parse wkb at row {}, error: {}",
+                        geom_index,
+                        e
+                    )
+                })?;
             Ok(Some(value))
         } else {
             Ok(None)
@@ -25,15 +32,146 @@ pub trait GeometryArray {
     #[cfg(feature = "geos")]
     fn geos_value(&self, geom_index: usize) -> DFResult<Option<geos::Geometry>> {
         if let Some(wkb) = self.wkb(geom_index) {
-            let dialect = decode_wkb_dialect(wkb[0])?;
-            let mut rdr = std::io::Cursor::new(&wkb[1..]);
-            let value = geos::Geometry::from_wkb(&mut rdr, dialect)
-                .map_err(|e| internal_datafusion_err!("Failed to parse wkb, error: {}", e))?;
+            let (dialect, wkb) = split_dialect(wkb, geom_index)?;
+            let mut rdr = std::io::Cursor::new(wkb);
+            let value =
+                metrics::decode(|| geos::Geometry::from_wkb(&mut rdr, dialect)).map_err(|e| {
+                    internal_datafusion_err!(
+                        "Failed to parse wkb at row {}, error: {}",
+                        geom_index,
+                        e
+                    )
+                })?;
             Ok(Some(value))
         } else {
             Ok(None)
         }
     }
+
+    /// Iterates over the geometries of all rows, nulls included. A row which fails to decode
+    /// yields an error instead of ending the iteration, the following rows can still be read.
+    fn iter_geo(&self) -> GeoIter<'_, Self>
+    where
+        Self: Sized,
+    {
+        GeoIter {
+            arr: self,
+            front: 0,
+            back: self.geom_len(),
+        }
+    }
+
+    /// Iterates over the non-null geometries with their row index, null rows are skipped without
+    /// being decoded.
+    fn iter_geo_values(&self) -> GeoValues<'_, Self>
+    where
+        Self: Sized,
+    {
+        GeoValues(self.iter_geo())
+    }
+}
+
+/// Iterator over the geometries of a [`GeometryArray`], see [`GeometryArray::iter_geo`].
+#[derive(Debug)]
+pub struct GeoIter<'a, A> {
+    arr: &'a A,
+    front: usize,
+    back: usize,
+}
+
+impl<A: GeometryArray> Iterator for GeoIter<'_, A> {
+    type Item = DFResult<Option<geo::Geometry>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        Some(self.arr.geo_value(self.front - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl<A: GeometryArray> DoubleEndedIterator for GeoIter<'_, A> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.arr.geo_value(self.back))
+    }
+}
+
+impl<A: GeometryArray> ExactSizeIterator for GeoIter<'_, A> {}
+
+/// Iterator over the non-null geometries of a [`GeometryArray`] and their row index, see
+/// [`GeometryArray::iter_geo_values`].
+#[derive(Debug)]
+pub struct GeoValues<'a, A>(GeoIter<'a, A>);
+
+impl<A: GeometryArray> Iterator for GeoValues<'_, A> {
+    type Item = DFResult<(usize, geo::Geometry)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let iter = &mut self.0;
+        while iter.front < iter.back {
+            let index = iter.front;
+            iter.front += 1;
+            if let Some(value) = non_null_value(iter.arr, index) {
+                return Some(value);
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.0.len()))
+    }
+}
+
+impl<A: GeometryArray> DoubleEndedIterator for GeoValues<'_, A> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let iter = &mut self.0;
+        while iter.front < iter.back {
+            iter.back -= 1;
+            if let Some(value) = non_null_value(iter.arr, iter.back) {
+                return Some(value);
+            }
+        }
+        None
+    }
+}
+
+fn non_null_value<A: GeometryArray>(
+    arr: &A,
+    geom_index: usize,
+) -> Option<DFResult<(usize, geo::Geometry)>> {
+    arr.wkb(geom_index)?;
+    arr.geo_value(geom_index)
+        .map(|geom| geom.map(|geom| (geom_index, geom)))
+        .transpose()
+}
+
+/// Splits a stored geometry into its dialect and its wkb.
+fn split_dialect(wkb: &[u8], geom_index: usize) -> DFResult<(WkbDialect, &[u8])> {
+    let Some((type_id, wkb)) = wkb.split_first() else {
+        return internal_err!(
+            "Failed to parse wkb at row {}, error: empty value",
+            geom_index
+        );
+    };
+    let dialect = decode_wkb_dialect(*type_id).map_err(|_| {
+        internal_datafusion_err!(
+            "Failed to parse wkb at row {}, error: unknown dialect {}",
+            geom_index,
+            type_id
+        )
+    })?;
+    Ok((dialect, wkb))
 }
 
 impl<O: OffsetSizeTrait> GeometryArray for GenericByteArray<GenericBinaryType<O>> {
@@ -51,20 +189,32 @@ impl<O: OffsetSizeTrait> GeometryArray for GenericByteArray<GenericBinaryType<O>
 
 #[cfg(test)]
 mod tests {
+    use crate::geo::dialect::decode_srid;
     use crate::geo::{GeometryArray, GeometryArrayBuilder};
+    use crate::DFResult;
+    use arrow_array::BinaryArray;
     use geo::{line_string, point, polygon};
+    use geozero::wkb::WkbDialect;
 
     #[test]
     fn point_array() {
         let p0 = point!(x: 0f64, y: 1f64);
         let p2 = point!(x: 2f64, y: 3f64);
-        let builder: GeometryArrayBuilder<i32> = vec![Some(p0), None, Some(p2)].as_slice().into();
+        let builder: GeometryArrayBuilder<i32> = vec![Some(p0), None, Some(p2)]
+            .as_slice()
+            .try_into()
+            .unwrap();
         let arr = builder.build();
         assert_eq!(arr.geom_len(), 3);
 
-        assert_eq!(arr.geo_value(0).unwrap(), Some(geo::Geometry::Point(p0)));
-        assert_eq!(arr.geo_value(1).unwrap(), None);
-        assert_eq!(arr.geo_value(2).unwrap(), Some(geo::Geometry::Point(p2)));
+        assert_eq!(
+            arr.iter_geo().collect::<DFResult<Vec<_>>>().unwrap(),
+            vec![
+                Some(geo::Geometry::Point(p0)),
+                None,
+                Some(geo::Geometry::Point(p2))
+            ]
+        );
         assert_eq!(arr.geo_value(3).unwrap(), None);
     }
 
@@ -80,18 +230,18 @@ mod tests {
         ];
         let builder: GeometryArrayBuilder<i32> = vec![Some(ls0.clone()), None, Some(ls2.clone())]
             .as_slice()
-            .into();
+            .try_into()
+            .unwrap();
         let arr = builder.build();
         assert_eq!(arr.geom_len(), 3);
 
         assert_eq!(
-            arr.geo_value(0).unwrap(),
-            Some(geo::Geometry::LineString(ls0))
-        );
-        assert_eq!(arr.geo_value(1).unwrap(), None);
-        assert_eq!(
-            arr.geo_value(2).unwrap(),
-            Some(geo::Geometry::LineString(ls2))
+            arr.iter_geo().collect::<DFResult<Vec<_>>>().unwrap(),
+            vec![
+                Some(geo::Geometry::LineString(ls0)),
+                None,
+                Some(geo::Geometry::LineString(ls2))
+            ]
         );
         assert_eq!(arr.geo_value(3).unwrap(), None);
     }
@@ -122,13 +272,19 @@ mod tests {
         );
         let builder: GeometryArrayBuilder<i32> = vec![Some(p0.clone()), None, Some(p2.clone())]
             .as_slice()
-            .into();
+            .try_into()
+            .unwrap();
         let arr = builder.build();
         assert_eq!(arr.geom_len(), 3);
 
-        assert_eq!(arr.geo_value(0).unwrap(), Some(geo::Geometry::Polygon(p0)));
-        assert_eq!(arr.geo_value(1).unwrap(), None);
-        assert_eq!(arr.geo_value(2).unwrap(), Some(geo::Geometry::Polygon(p2)));
+        assert_eq!(
+            arr.iter_geo().collect::<DFResult<Vec<_>>>().unwrap(),
+            vec![
+                Some(geo::Geometry::Polygon(p0)),
+                None,
+                Some(geo::Geometry::Polygon(p2))
+            ]
+        );
         assert_eq!(arr.geo_value(3).unwrap(), None);
     }
 
@@ -152,18 +308,18 @@ mod tests {
         ]);
         let builder: GeometryArrayBuilder<i32> = vec![Some(mp0.clone()), None, Some(mp2.clone())]
             .as_slice()
-            .into();
+            .try_into()
+            .unwrap();
         let arr = builder.build();
         assert_eq!(arr.geom_len(), 3);
 
         assert_eq!(
-            arr.geo_value(0).unwrap(),
-            Some(geo::Geometry::MultiPoint(mp0))
-        );
-        assert_eq!(arr.geo_value(1).unwrap(), None);
-        assert_eq!(
-            arr.geo_value(2).unwrap(),
-            Some(geo::Geometry::MultiPoint(mp2))
+            arr.iter_geo().collect::<DFResult<Vec<_>>>().unwrap(),
+            vec![
+                Some(geo::Geometry::MultiPoint(mp0)),
+                None,
+                Some(geo::Geometry::MultiPoint(mp2))
+            ]
         );
         assert_eq!(arr.geo_value(3).unwrap(), None);
     }
@@ -193,18 +349,18 @@ mod tests {
 
         let builder: GeometryArrayBuilder<i32> = vec![Some(ml0.clone()), None, Some(ml2.clone())]
             .as_slice()
-            .into();
+            .try_into()
+            .unwrap();
         let arr = builder.build();
         assert_eq!(arr.geom_len(), 3);
 
         assert_eq!(
-            arr.geo_value(0).unwrap(),
-            Some(geo::Geometry::MultiLineString(ml0))
-        );
-        assert_eq!(arr.geo_value(1).unwrap(), None);
-        assert_eq!(
-            arr.geo_value(2).unwrap(),
-            Some(geo::Geometry::MultiLineString(ml2))
+            arr.iter_geo().collect::<DFResult<Vec<_>>>().unwrap(),
+            vec![
+                Some(geo::Geometry::MultiLineString(ml0)),
+                None,
+                Some(geo::Geometry::MultiLineString(ml2))
+            ]
         );
         assert_eq!(arr.geo_value(3).unwrap(), None);
     }
@@ -252,19 +408,113 @@ mod tests {
 
         let builder: GeometryArrayBuilder<i32> = vec![Some(mp0.clone()), None, Some(mp2.clone())]
             .as_slice()
-            .into();
+            .try_into()
+            .unwrap();
         let arr = builder.build();
         assert_eq!(arr.geom_len(), 3);
 
+        assert_eq!(
+            arr.iter_geo().collect::<DFResult<Vec<_>>>().unwrap(),
+            vec![
+                Some(geo::Geometry::MultiPolygon(mp0)),
+                None,
+                Some(geo::Geometry::MultiPolygon(mp2))
+            ]
+        );
+        assert_eq!(arr.geo_value(3).unwrap(), None);
+    }
+
+    #[test]
+    fn xdr_wkb_array() {
+        let mut xdr_point = vec![0x00, 0x00, 0x00, 0x00, 0x01];
+        xdr_point.extend_from_slice(&1f64.to_be_bytes());
+        xdr_point.extend_from_slice(&2f64.to_be_bytes());
+
+        let mut xdr_ewkb_point = vec![0x00, 0x20, 0x00, 0x00, 0x01];
+        xdr_ewkb_point.extend_from_slice(&4326u32.to_be_bytes());
+        xdr_ewkb_point.extend_from_slice(&1f64.to_be_bytes());
+        xdr_ewkb_point.extend_from_slice(&2f64.to_be_bytes());
+
+        let mut builder = GeometryArrayBuilder::<i32>::new(WkbDialect::Wkb, 1);
+        builder.append_wkb(Some(&xdr_point)).unwrap();
+        let arr = builder.build();
         assert_eq!(
             arr.geo_value(0).unwrap(),
-            Some(geo::Geometry::MultiPolygon(mp0))
+            Some(geo::Geometry::Point(point!(x: 1., y: 2.)))
         );
-        assert_eq!(arr.geo_value(1).unwrap(), None);
+
+        let mut builder = GeometryArrayBuilder::<i32>::new(WkbDialect::Ewkb, 1);
+        builder.append_wkb(Some(&xdr_ewkb_point)).unwrap();
+        let arr = builder.build();
         assert_eq!(
-            arr.geo_value(2).unwrap(),
-            Some(geo::Geometry::MultiPolygon(mp2))
+            arr.geo_value(0).unwrap(),
+            Some(geo::Geometry::Point(point!(x: 1., y: 2.)))
         );
-        assert_eq!(arr.geo_value(3).unwrap(), None);
+        assert_eq!(decode_srid(arr.wkb(0).unwrap()).unwrap(), Some(4326));
+    }
+
+    #[test]
+    fn iter_geo_values() {
+        let p0 = point!(x: 0f64, y: 1f64);
+        let p3 = point!(x: 2f64, y: 3f64);
+        let builder: GeometryArrayBuilder<i32> = vec![Some(p0), None, None, Some(p3), None]
+            .as_slice()
+            .try_into()
+            .unwrap();
+        let arr = builder.build();
+
+        let iter = arr.iter_geo();
+        assert_eq!(iter.len(), 5);
+        let values = arr.iter_geo_values().collect::<DFResult<Vec<_>>>().unwrap();
+        assert_eq!(values, vec![(0, p0.into()), (3, p3.into())]);
+        let reversed = arr
+            .iter_geo_values()
+            .rev()
+            .collect::<DFResult<Vec<_>>>()
+            .unwrap();
+        assert_eq!(reversed, vec![(3, p3.into()), (0, p0.into())]);
+
+        // both ends meet in the middle
+        let mut iter = arr.iter_geo();
+        assert_eq!(iter.next_back().unwrap().unwrap(), None);
+        assert_eq!(iter.next().unwrap().unwrap(), Some(p0.into()));
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.rev().count(), 3);
+    }
+
+    #[test]
+    fn iter_geo_corrupt_row() {
+        let builder: GeometryArrayBuilder<i32> = vec![Some(point!(x: 0f64, y: 1f64))]
+            .as_slice()
+            .try_into()
+            .unwrap();
+        let point = builder.build();
+        // a truncated point and an unknown dialect between two valid points
+        let arr = BinaryArray::from_opt_vec(vec![
+            Some(point.value(0)),
+            Some(&point.value(0)[..10]),
+            Some([9u8, 1, 2].as_slice()),
+            None,
+            Some(point.value(0)),
+        ]);
+
+        let rows = arr.iter_geo().collect::<Vec<_>>();
+        assert_eq!(rows.len(), 5);
+        assert!(rows[0].is_ok());
+        assert!(rows[1]
+            .as_ref()
+            .unwrap_err()
+            .to_string()
+            .contains("Failed to parse wkb at row 1"));
+        assert!(rows[2]
+            .as_ref()
+            .unwrap_err()
+            .to_string()
+            .contains("unknown dialect 9"));
+        assert!(rows[4].is_ok());
+
+        let values = arr.iter_geo_values().collect::<Vec<_>>();
+        assert_eq!(values.len(), 4);
+        assert!(arr.iter_geo_values().collect::<DFResult<Vec<_>>>().is_err());
     }
 }
@@ -1,10 +1,11 @@
 use crate::DFResult;
 use arrow_array::cast::AsArray;
 use arrow_array::types::Float64Type;
-use arrow_array::{Array, Float64Array, StructArray};
+use arrow_array::{Array, ArrayRef, Float64Array, StructArray};
 use arrow_buffer::NullBuffer;
 use arrow_schema::{DataType, Field};
-use datafusion_common::{internal_err, DataFusionError, ScalarValue};
+use datafusion_common::{exec_err, internal_err, DataFusionError, ScalarValue};
+use geo::AffineTransform;
 use std::sync::Arc;
 
 #[derive(Debug, Clone)]
@@ -47,6 +48,62 @@ impl Box2d {
         let box2d: Box2d = (&scalar).try_into()?;
         Ok(Some(box2d))
     }
+
+    /// Converts a struct scalar into box2d, a null scalar produces None.
+    pub fn from_scalar(value: &ScalarValue) -> DFResult<Option<Box2d>> {
+        match value {
+            ScalarValue::Struct(arr) => Box2d::value(arr, 0),
+            _ => internal_err!("ScalarValue is not struct"),
+        }
+    }
+
+    /// Whether the boxes share at least a point, an empty box intersects nothing.
+    pub fn intersects(&self, other: &Box2d) -> bool {
+        self.xmin <= other.xmax
+            && other.xmin <= self.xmax
+            && self.ymin <= other.ymax
+            && other.ymin <= self.ymax
+    }
+
+    /// The smallest box containing both boxes.
+    pub fn union(&self, other: &Box2d) -> Box2d {
+        Box2d {
+            xmin: self.xmin.min(other.xmin),
+            ymin: self.ymin.min(other.ymin),
+            xmax: self.xmax.max(other.xmax),
+            ymax: self.ymax.max(other.ymax),
+        }
+    }
+
+    /// The box shared by both boxes, None if they don't intersect.
+    pub fn intersection(&self, other: &Box2d) -> Option<Box2d> {
+        self.intersects(other).then(|| Box2d {
+            xmin: self.xmin.max(other.xmin),
+            ymin: self.ymin.max(other.ymin),
+            xmax: self.xmax.min(other.xmax),
+            ymax: self.ymax.min(other.ymax),
+        })
+    }
+
+    /// Returns the transform fitting this box into the target box, e.g. a data extent into a pixel
+    /// extent. With `flip_y` the y axis is flipped, so the top of this box maps to the bottom of
+    /// the target box like in screen coordinates.
+    pub fn transform_to(&self, target: &Box2d, flip_y: bool) -> DFResult<AffineTransform> {
+        let width = self.xmax - self.xmin;
+        let height = self.ymax - self.ymin;
+        if !(width > 0.0 && height > 0.0 && width.is_finite() && height.is_finite()) {
+            return exec_err!("Cannot transform from a degenerate box {:?}", self);
+        }
+        let fx = (target.xmax - target.xmin) / width;
+        let fy = (target.ymax - target.ymin) / height;
+        let xoff = target.xmin - self.xmin * fx;
+        let transform = if flip_y {
+            AffineTransform::new(fx, 0.0, xoff, 0.0, -fy, target.ymin + self.ymax * fy)
+        } else {
+            AffineTransform::new(fx, 0.0, xoff, 0.0, fy, target.ymin - self.ymin * fy)
+        };
+        Ok(transform)
+    }
 }
 
 impl Default for Box2d {
@@ -63,6 +120,9 @@ impl TryFrom<&ScalarValue> for Box2d {
             if arr.data_type() != &Box2d::data_type() {
                 return internal_err!("ScalarValue data type is not matched");
             }
+            if arr.is_empty() || arr.is_null(0) {
+                return internal_err!("ScalarValue is null");
+            }
             let xmin = arr.column(0).as_primitive::<Float64Type>().value(0);
             let ymin = arr.column(1).as_primitive::<Float64Type>().value(0);
             let xmax = arr.column(2).as_primitive::<Float64Type>().value(0);
@@ -97,6 +157,15 @@ impl From<geo::Rect> for Box2d {
     }
 }
 
+impl From<Box2d> for geo::Rect {
+    fn from(value: Box2d) -> Self {
+        geo::Rect::new(
+            geo::coord! { x: value.xmin, y: value.ymin },
+            geo::coord! { x: value.xmax, y: value.ymax },
+        )
+    }
+}
+
 #[cfg(feature = "geos")]
 impl TryFrom<geos::Geometry<'_>> for Box2d {
     type Error = DataFusionError;
@@ -126,39 +195,81 @@ impl TryFrom<geos::Geometry<'_>> for Box2d {
 }
 
 pub fn build_box2d_array(data: Vec<Option<Box2d>>) -> StructArray {
-    let xmin_arr = Arc::new(Float64Array::from(
-        data.iter()
-            .map(|b| b.clone().map(|b| b.xmin))
-            .collect::<Vec<_>>(),
-    ));
-    let ymin_arr = Arc::new(Float64Array::from(
-        data.iter()
-            .map(|b| b.clone().map(|b| b.ymin))
-            .collect::<Vec<_>>(),
-    ));
-    let xmax_arr = Arc::new(Float64Array::from(
-        data.iter()
-            .map(|b| b.clone().map(|b| b.xmax))
-            .collect::<Vec<_>>(),
-    ));
-    let ymax_arr = Arc::new(Float64Array::from(
-        data.iter()
-            .map(|b| b.clone().map(|b| b.ymax))
-            .collect::<Vec<_>>(),
-    ));
-    let nulls: NullBuffer = data.iter().map(|b| b.is_some()).collect::<Vec<_>>().into();
-    StructArray::try_new(
-        Box2d::fields().into(),
-        vec![xmin_arr, ymin_arr, xmax_arr, ymax_arr],
-        Some(nulls),
-    )
-    .expect("data is valid")
+    let values = data
+        .iter()
+        .map(|b| b.as_ref().map(|b| [b.xmin, b.ymin, b.xmax, b.ymax]))
+        .collect::<Vec<_>>();
+    build_f64_struct_array(Box2d::fields(), &values)
+}
+
+/// Combines two box2d arrays row by row straight on their float columns, a row is null if either
+/// side is null or `f` returns None.
+pub(crate) fn combine_box2d_arrays(
+    left: &StructArray,
+    right: &StructArray,
+    f: impl Fn(&Box2d, &Box2d) -> Option<Box2d>,
+) -> DFResult<StructArray> {
+    if left.data_type() != &Box2d::data_type() || right.data_type() != &Box2d::data_type() {
+        return internal_err!("StructArray data type is not matched");
+    }
+    if left.len() != right.len() {
+        return internal_err!("Box2d arrays length is not same");
+    }
+    let columns = |arr: &StructArray| {
+        let column = |i: usize| arr.column(i).as_primitive::<Float64Type>().clone();
+        [column(0), column(1), column(2), column(3)]
+    };
+    let (left_columns, right_columns) = (columns(left), columns(right));
+    let row = |columns: &[Float64Array; 4], i: usize| Box2d {
+        xmin: columns[0].value(i),
+        ymin: columns[1].value(i),
+        xmax: columns[2].value(i),
+        ymax: columns[3].value(i),
+    };
+    let boxes = (0..left.len())
+        .map(|i| {
+            if left.is_null(i) || right.is_null(i) {
+                return None;
+            }
+            f(&row(&left_columns, i), &row(&right_columns, i))
+        })
+        .collect::<Vec<_>>();
+    Ok(build_box2d_array(boxes))
+}
+
+/// Builds a struct array whose fields are all float64, a null entry produces a null struct.
+pub fn build_f64_struct_array<const N: usize>(
+    fields: Vec<Field>,
+    data: &[Option<[f64; N]>],
+) -> StructArray {
+    let columns = (0..N)
+        .map(|i| {
+            Arc::new(Float64Array::from(
+                data.iter().map(|v| v.map(|v| v[i])).collect::<Vec<_>>(),
+            )) as ArrayRef
+        })
+        .collect::<Vec<_>>();
+    let valid = data.iter().map(|v| v.is_some()).collect::<Vec<_>>();
+    build_struct_array(fields, columns, &valid).expect("data is valid")
+}
+
+/// Builds a struct array of the columns, the rows which are not valid are null structs.
+pub(crate) fn build_struct_array(
+    fields: Vec<Field>,
+    columns: Vec<ArrayRef>,
+    valid: &[bool],
+) -> DFResult<StructArray> {
+    let nulls: NullBuffer = valid.to_vec().into();
+    Ok(StructArray::try_new(fields.into(), columns, Some(nulls))?)
 }
 
 #[cfg(test)]
 mod tests {
     use crate::geo::r#box::{build_box2d_array, Box2d};
     use arrow_array::{Array, StructArray};
+    use datafusion_common::ScalarValue;
+    use geo::coord;
+    use std::sync::Arc;
 
     #[test]
     fn box2d_array() {
@@ -189,4 +300,69 @@ mod tests {
         );
         assert_eq!(format!("{:?}", Box2d::value(&arr, 3).unwrap()), "None");
     }
+
+    #[test]
+    fn null_box2d_scalar() {
+        let arr: StructArray = build_box2d_array(vec![None]);
+        let scalar = ScalarValue::Struct(Arc::new(arr));
+        assert!(Box2d::from_scalar(&scalar).unwrap().is_none());
+        assert!(Box2d::try_from(&scalar).is_err());
+
+        let scalar: ScalarValue = Box2d {
+            xmin: 1.0,
+            ymin: 2.0,
+            xmax: 3.0,
+            ymax: 4.0,
+        }
+        .into();
+        assert_eq!(
+            format!("{:?}", Box2d::from_scalar(&scalar).unwrap()),
+            "Some(Box2d { xmin: 1.0, ymin: 2.0, xmax: 3.0, ymax: 4.0 })"
+        );
+    }
+
+    #[test]
+    fn box2d_transform_to() {
+        let source = Box2d {
+            xmin: 10.0,
+            ymin: 20.0,
+            xmax: 20.0,
+            ymax: 40.0,
+        };
+        let target = Box2d {
+            xmin: 0.0,
+            ymin: 0.0,
+            xmax: 100.0,
+            ymax: 100.0,
+        };
+        let transform = source.transform_to(&target, false).unwrap();
+        assert_eq!(
+            transform.apply(coord! { x: 10.0, y: 20.0 }),
+            coord! { x: 0.0, y: 0.0 }
+        );
+        assert_eq!(
+            transform.apply(coord! { x: 20.0, y: 40.0 }),
+            coord! { x: 100.0, y: 100.0 }
+        );
+
+        // the top left corner of the source maps to the origin of the target
+        let transform = source.transform_to(&target, true).unwrap();
+        assert_eq!(
+            transform.apply(coord! { x: 10.0, y: 40.0 }),
+            coord! { x: 0.0, y: 0.0 }
+        );
+        assert_eq!(
+            transform.apply(coord! { x: 20.0, y: 20.0 }),
+            coord! { x: 100.0, y: 100.0 }
+        );
+
+        let degenerate = Box2d {
+            xmin: 10.0,
+            ymin: 20.0,
+            xmax: 10.0,
+            ymax: 40.0,
+        };
+        assert!(degenerate.transform_to(&target, false).is_err());
+        assert!(Box2d::new().transform_to(&target, true).is_err());
+    }
 }
@@ -1,12 +1,18 @@
-use crate::geo::dialect::wkb_type_id;
+use crate::config::{default_dialect, GeoConfig, InvalidGeometryAction, ValidationMode};
+use crate::geo::dialect::{check_srid_storable, decode_srid, wkb_type_id};
+use crate::geo::scalar_to_geometry;
 use crate::DFResult;
 use arrow_array::builder::UInt8BufferBuilder;
 use arrow_array::types::GenericBinaryType;
 use arrow_array::{GenericByteArray, OffsetSizeTrait};
 use arrow_buffer::{BufferBuilder, NullBufferBuilder, OffsetBuffer};
-use datafusion_common::{internal_datafusion_err, DataFusionError};
+use datafusion_common::{
+    exec_err, internal_datafusion_err, internal_err, DataFusionError, ScalarValue,
+};
+use geo::CoordsIter;
 use geozero::wkb::{FromWkb, WkbDialect};
 use geozero::{GeozeroGeometry, ToWkb};
+use rayon::prelude::*;
 
 pub struct GeometryArrayBuilder<O: OffsetSizeTrait> {
     dialect: WkbDialect,
@@ -32,20 +38,99 @@ impl<O: OffsetSizeTrait> GeometryArrayBuilder<O> {
     pub fn append_wkb(&mut self, wkb: Option<&[u8]>) -> DFResult<()> {
         if let Some(wkb) = wkb {
             check_wkb(wkb, self.dialect)?;
-            self.internal_append_wkb(wkb);
+            self.internal_append_wkb(wkb)?;
         } else {
             self.append_null();
         }
         Ok(())
     }
 
+    /// Appends a wkb in the builder dialect checked according to the validation mode, invalid
+    /// geometries are rejected or repaired with full validation.
+    pub fn append_validated_wkb(
+        &mut self,
+        wkb: &[u8],
+        validation: ValidationMode,
+        on_invalid: InvalidGeometryAction,
+    ) -> DFResult<()> {
+        match validation {
+            ValidationMode::None => self.internal_append_wkb(wkb),
+            ValidationMode::Parse => self.append_wkb(Some(wkb)),
+            ValidationMode::Full => self.append_valid_wkb(wkb, on_invalid),
+        }
+    }
+
+    #[cfg(feature = "geos")]
+    fn append_valid_wkb(&mut self, wkb: &[u8], on_invalid: InvalidGeometryAction) -> DFResult<()> {
+        use crate::geo::{geos_capabilities, GeosVersion};
+        use datafusion_common::exec_datafusion_err;
+        use geos::Geom;
+
+        let mut rdr = std::io::Cursor::new(wkb);
+        let geom = geos::Geometry::from_wkb(&mut rdr, self.dialect)
+            .map_err(|e| exec_datafusion_err!("Failed to parse wkb, error: {}", e))?;
+        if geom.is_valid() {
+            return self.internal_append_wkb(wkb);
+        }
+        match on_invalid {
+            InvalidGeometryAction::Reject => exec_err!(
+                "Invalid geometry: {}",
+                geom.is_valid_reason().unwrap_or_default()
+            ),
+            InvalidGeometryAction::Repair => {
+                geos_capabilities()
+                    .check("Repairing invalid geometries", GeosVersion::new(3, 8, 0))?;
+                let mut repaired = geom
+                    .make_valid()
+                    .map_err(|e| exec_datafusion_err!("Failed to repair geometry, e: {}", e))?;
+                let mut prefixed = vec![wkb_type_id(self.dialect)];
+                prefixed.extend_from_slice(wkb);
+                if let Some(srid) = decode_srid(&prefixed)? {
+                    repaired.set_srid(srid as usize);
+                }
+                self.append_geos_geometry(&Some(repaired))
+            }
+        }
+    }
+
+    #[cfg(not(feature = "geos"))]
+    fn append_valid_wkb(
+        &mut self,
+        _wkb: &[u8],
+        _on_invalid: InvalidGeometryAction,
+    ) -> DFResult<()> {
+        exec_err!("Full geometry validation requires the geos feature")
+    }
+
     #[inline]
     pub fn append_geo_geometry(&mut self, geom: &Option<geo::Geometry>) -> DFResult<()> {
         if let Some(geom) = geom {
+            check_vertex_limit(self.len(), geom.coords_count())?;
             let wkb = geom
                 .to_wkb_dialect(self.dialect, geom.dims(), geom.srid(), vec![])
                 .map_err(|e| internal_datafusion_err!("Failed to convert to wkb, error: {}", e))?;
-            self.internal_append_wkb(&wkb);
+            self.internal_append_wkb(&wkb)?;
+        } else {
+            self.append_null();
+        }
+        Ok(())
+    }
+
+    /// Appends a geo geometry with the given srid, fails if the srid is non-zero and the dialect
+    /// has no srid.
+    #[inline]
+    pub fn append_geo_geometry_with_srid(
+        &mut self,
+        geom: &Option<geo::Geometry>,
+        srid: Option<i32>,
+    ) -> DFResult<()> {
+        if let Some(geom) = geom {
+            check_vertex_limit(self.len(), geom.coords_count())?;
+            check_srid_storable(self.dialect, srid)?;
+            let wkb = geom
+                .to_wkb_dialect(self.dialect, geom.dims(), srid, vec![])
+                .map_err(|e| internal_datafusion_err!("Failed to convert to wkb, error: {}", e))?;
+            self.internal_append_wkb(&wkb)?;
         } else {
             self.append_null();
         }
@@ -55,29 +140,84 @@ impl<O: OffsetSizeTrait> GeometryArrayBuilder<O> {
     #[cfg(feature = "geos")]
     #[inline]
     pub fn append_geos_geometry(&mut self, geom: &Option<geos::Geometry>) -> DFResult<()> {
+        use geos::Geom;
         if let Some(geom) = geom {
+            let vertices = geom.get_num_coordinates().map_err(|e| {
+                internal_datafusion_err!("Failed to count coordinates, error: {}", e)
+            })?;
+            check_vertex_limit(self.len(), vertices)?;
+            check_srid_storable(self.dialect, geom.srid())?;
             let wkb = geom
                 .to_wkb_dialect(self.dialect, geom.dims(), geom.srid(), vec![])
                 .map_err(|e| internal_datafusion_err!("Failed to convert to wkb, error: {}", e))?;
-            self.internal_append_wkb(&wkb);
+            self.internal_append_wkb(&wkb)?;
         } else {
             self.append_null();
         }
         Ok(())
     }
 
+    /// Appends a binary or large binary geometry scalar, the geometry is re-encoded
+    /// if its dialect differs from the builder dialect.
+    pub fn append_scalar(&mut self, value: &ScalarValue) -> DFResult<()> {
+        let wkb = match value {
+            ScalarValue::Binary(wkb) | ScalarValue::LargeBinary(wkb) => wkb,
+            _ => return internal_err!("ScalarValue is not binary"),
+        };
+        let Some(wkb) = wkb else {
+            self.append_null();
+            return Ok(());
+        };
+        if wkb.first() == Some(&wkb_type_id(self.dialect)) {
+            self.internal_append_wkb(&wkb[1..])
+        } else {
+            let srid = decode_srid(wkb)?;
+            self.append_geo_geometry_with_srid(&scalar_to_geometry(value)?, srid)
+        }
+    }
+
     #[inline]
     pub fn append_null(&mut self) {
         self.null_buffer_builder.append_null();
         self.offsets_builder.append(self.next_offset());
     }
 
-    fn internal_append_wkb(&mut self, wkb: &[u8]) {
+    /// Number of values appended so far, i.e. the row of the next value.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.offsets_builder.len() - 1
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn internal_append_wkb(&mut self, wkb: &[u8]) -> DFResult<()> {
+        check_wkb_bytes(self.len(), wkb.len() + 1)?;
         let mut bytes = vec![wkb_type_id(self.dialect)];
         bytes.extend_from_slice(wkb);
         self.value_builder.append_slice(&bytes);
         self.null_buffer_builder.append(true);
         self.offsets_builder.append(self.next_offset());
+        Ok(())
+    }
+
+    /// Appends the rows of a chunk encoded by [`encode_chunks`].
+    fn append_encoded_chunk(&mut self, chunk: EncodedChunk) {
+        let mut offset = self.value_builder.len();
+        self.value_builder.append_slice(&chunk.values);
+        for length in chunk.lengths {
+            match length {
+                Some(length) => {
+                    self.null_buffer_builder.append(true);
+                    offset += length;
+                }
+                None => self.null_buffer_builder.append_null(),
+            }
+            self.offsets_builder
+                .append(O::from_usize(offset).expect("array offset overflow"));
+        }
     }
 
     #[inline]
@@ -94,6 +234,135 @@ impl<O: OffsetSizeTrait> GeometryArrayBuilder<O> {
     }
 }
 
+impl<O: OffsetSizeTrait> GeometryArrayBuilder<O> {
+    /// Encodes the geometries to wkb in parallel, the array is byte-identical to appending the
+    /// geometries one by one and fails with the error of the first failing row.
+    pub fn from_geo_parallel(
+        geoms: &[Option<geo::Geometry>],
+        dialect: WkbDialect,
+    ) -> DFResult<Self> {
+        Self::from_encoded_chunks(
+            geoms.len(),
+            dialect,
+            encode_chunks(geoms, dialect, |geom| {
+                let wkb = geom
+                    .to_wkb_dialect(dialect, geom.dims(), geom.srid(), vec![])
+                    .map_err(|e| {
+                        internal_datafusion_err!("Failed to convert to wkb, error: {}", e)
+                    })?;
+                Ok((geom.coords_count(), wkb))
+            }),
+        )
+    }
+
+    /// Encodes the geos geometries to wkb in parallel, see [`Self::from_geo_parallel`].
+    #[cfg(feature = "geos")]
+    pub fn from_geos_parallel(
+        geoms: &[Option<geos::Geometry>],
+        dialect: WkbDialect,
+    ) -> DFResult<Self> {
+        use geos::Geom;
+        Self::from_encoded_chunks(
+            geoms.len(),
+            dialect,
+            encode_chunks(geoms, dialect, |geom| {
+                let vertices = geom.get_num_coordinates().map_err(|e| {
+                    internal_datafusion_err!("Failed to count coordinates, error: {}", e)
+                })?;
+                check_srid_storable(dialect, geom.srid())?;
+                let wkb = geom
+                    .to_wkb_dialect(dialect, geom.dims(), geom.srid(), vec![])
+                    .map_err(|e| {
+                        internal_datafusion_err!("Failed to convert to wkb, error: {}", e)
+                    })?;
+                Ok((vertices, wkb))
+            }),
+        )
+    }
+
+    fn from_encoded_chunks(
+        len: usize,
+        dialect: WkbDialect,
+        chunks: Vec<DFResult<EncodedChunk>>,
+    ) -> DFResult<Self> {
+        let mut builder = Self::new(dialect, len);
+        for chunk in chunks {
+            builder.append_encoded_chunk(chunk?);
+        }
+        Ok(builder)
+    }
+}
+
+/// Rows encoded per parallel task.
+const PARALLEL_CHUNK_ROWS: usize = 4096;
+
+/// The prefixed wkb of consecutive rows and the byte length of each row, None for a null row.
+struct EncodedChunk {
+    values: Vec<u8>,
+    lengths: Vec<Option<usize>>,
+}
+
+/// Encodes the geometries chunk by chunk in parallel, `encode` returns the vertex count and the
+/// wkb of a geometry. A chunk stops at its first failing row, the chunks are in row order.
+fn encode_chunks<G: Sync>(
+    geoms: &[Option<G>],
+    dialect: WkbDialect,
+    encode: impl Fn(&G) -> DFResult<(usize, Vec<u8>)> + Sync,
+) -> Vec<DFResult<EncodedChunk>> {
+    geoms
+        .par_chunks(PARALLEL_CHUNK_ROWS)
+        .enumerate()
+        .map(|(chunk_index, geoms)| {
+            let mut chunk = EncodedChunk {
+                values: vec![],
+                lengths: Vec::with_capacity(geoms.len()),
+            };
+            for (i, geom) in geoms.iter().enumerate() {
+                let Some(geom) = geom else {
+                    chunk.lengths.push(None);
+                    continue;
+                };
+                let row = chunk_index * PARALLEL_CHUNK_ROWS + i;
+                let (vertices, wkb) = encode(geom)?;
+                check_vertex_limit(row, vertices)?;
+                check_wkb_bytes(row, wkb.len() + 1)?;
+                chunk.values.push(wkb_type_id(dialect));
+                chunk.values.extend_from_slice(&wkb);
+                chunk.lengths.push(Some(wkb.len() + 1));
+            }
+            Ok(chunk)
+        })
+        .collect()
+}
+
+/// Fails if a geometry at the row has more vertices than the configured limit.
+pub(crate) fn check_vertex_limit(row: usize, vertices: usize) -> DFResult<()> {
+    let max_vertices = GeoConfig::get().max_vertices;
+    if vertices > max_vertices {
+        return exec_err!(
+            "Geometry at row {} has {} vertices, exceeding the limit of {}",
+            row,
+            vertices,
+            max_vertices
+        );
+    }
+    Ok(())
+}
+
+/// Fails if a geometry at the row has more prefixed wkb bytes than the configured limit.
+fn check_wkb_bytes(row: usize, bytes: usize) -> DFResult<()> {
+    let max_wkb_bytes = GeoConfig::get().max_wkb_bytes;
+    if bytes > max_wkb_bytes {
+        return exec_err!(
+            "Geometry at row {} has {} wkb bytes, exceeding the limit of {}",
+            row,
+            bytes,
+            max_wkb_bytes
+        );
+    }
+    Ok(())
+}
+
 fn check_wkb(wkb: &[u8], dialect: WkbDialect) -> DFResult<()> {
     let mut rdr = std::io::Cursor::new(wkb);
     #[cfg(feature = "geos")]
@@ -109,97 +378,179 @@ fn check_wkb(wkb: &[u8], dialect: WkbDialect) -> DFResult<()> {
     Ok(())
 }
 
-impl<O: OffsetSizeTrait> From<&[Option<geo::Geometry>]> for GeometryArrayBuilder<O> {
-    fn from(value: &[Option<geo::Geometry>]) -> Self {
-        let mut builder = GeometryArrayBuilder::<O>::new(WkbDialect::Ewkb, value.len());
+/// Fails like [`GeometryArrayBuilder::append_geo_geometry`], e.g. if a geometry exceeds the
+/// configured limits.
+impl<O: OffsetSizeTrait> TryFrom<&[Option<geo::Geometry>]> for GeometryArrayBuilder<O> {
+    type Error = DataFusionError;
+
+    fn try_from(value: &[Option<geo::Geometry>]) -> DFResult<Self> {
+        let mut builder = GeometryArrayBuilder::<O>::new(default_dialect(), value.len());
         for geom in value {
-            builder
-                .append_geo_geometry(geom)
-                .expect("geometry data is valid");
+            builder.append_geo_geometry(geom)?;
         }
-        builder
+        Ok(builder)
     }
 }
 
-impl<O: OffsetSizeTrait> From<&[Option<geo::Point>]> for GeometryArrayBuilder<O> {
-    fn from(value: &[Option<geo::Point>]) -> Self {
+impl<O: OffsetSizeTrait> TryFrom<&[Option<geo::Point>]> for GeometryArrayBuilder<O> {
+    type Error = DataFusionError;
+
+    fn try_from(value: &[Option<geo::Point>]) -> DFResult<Self> {
         let geo_vec = value
             .iter()
             .map(|p| p.map(geo::Geometry::Point))
             .collect::<Vec<_>>();
-        geo_vec.as_slice().into()
+        geo_vec.as_slice().try_into()
     }
 }
 
-impl<O: OffsetSizeTrait> From<&[Option<geo::LineString>]> for GeometryArrayBuilder<O> {
-    fn from(value: &[Option<geo::LineString>]) -> Self {
+impl<O: OffsetSizeTrait> TryFrom<&[Option<geo::LineString>]> for GeometryArrayBuilder<O> {
+    type Error = DataFusionError;
+
+    fn try_from(value: &[Option<geo::LineString>]) -> DFResult<Self> {
         let geo_vec = value
             .iter()
             .map(|ls| ls.clone().map(geo::Geometry::LineString))
             .collect::<Vec<_>>();
-        geo_vec.as_slice().into()
+        geo_vec.as_slice().try_into()
     }
 }
 
-impl<O: OffsetSizeTrait> From<&[Option<geo::Polygon>]> for GeometryArrayBuilder<O> {
-    fn from(value: &[Option<geo::Polygon>]) -> Self {
+impl<O: OffsetSizeTrait> TryFrom<&[Option<geo::Polygon>]> for GeometryArrayBuilder<O> {
+    type Error = DataFusionError;
+
+    fn try_from(value: &[Option<geo::Polygon>]) -> DFResult<Self> {
         let geo_vec = value
             .iter()
             .map(|p| p.clone().map(geo::Geometry::Polygon))
             .collect::<Vec<_>>();
-        geo_vec.as_slice().into()
+        geo_vec.as_slice().try_into()
     }
 }
 
-impl<O: OffsetSizeTrait> From<&[Option<geo::MultiPoint>]> for GeometryArrayBuilder<O> {
-    fn from(value: &[Option<geo::MultiPoint>]) -> Self {
+impl<O: OffsetSizeTrait> TryFrom<&[Option<geo::MultiPoint>]> for GeometryArrayBuilder<O> {
+    type Error = DataFusionError;
+
+    fn try_from(value: &[Option<geo::MultiPoint>]) -> DFResult<Self> {
         let geo_vec = value
             .iter()
             .map(|mp| mp.clone().map(geo::Geometry::MultiPoint))
             .collect::<Vec<_>>();
-        geo_vec.as_slice().into()
+        geo_vec.as_slice().try_into()
     }
 }
 
-impl<O: OffsetSizeTrait> From<&[Option<geo::MultiLineString>]> for GeometryArrayBuilder<O> {
-    fn from(value: &[Option<geo::MultiLineString>]) -> Self {
+impl<O: OffsetSizeTrait> TryFrom<&[Option<geo::MultiLineString>]> for GeometryArrayBuilder<O> {
+    type Error = DataFusionError;
+
+    fn try_from(value: &[Option<geo::MultiLineString>]) -> DFResult<Self> {
         let geo_vec = value
             .iter()
             .map(|ml| ml.clone().map(geo::Geometry::MultiLineString))
             .collect::<Vec<_>>();
-        geo_vec.as_slice().into()
+        geo_vec.as_slice().try_into()
     }
 }
 
-impl<O: OffsetSizeTrait> From<&[Option<geo::MultiPolygon>]> for GeometryArrayBuilder<O> {
-    fn from(value: &[Option<geo::MultiPolygon>]) -> Self {
+impl<O: OffsetSizeTrait> TryFrom<&[Option<geo::MultiPolygon>]> for GeometryArrayBuilder<O> {
+    type Error = DataFusionError;
+
+    fn try_from(value: &[Option<geo::MultiPolygon>]) -> DFResult<Self> {
         let geo_vec = value
             .iter()
             .map(|mp| mp.clone().map(geo::Geometry::MultiPolygon))
             .collect::<Vec<_>>();
-        geo_vec.as_slice().into()
+        geo_vec.as_slice().try_into()
     }
 }
 
-impl<O: OffsetSizeTrait> From<&[Option<geo::GeometryCollection>]> for GeometryArrayBuilder<O> {
-    fn from(value: &[Option<geo::GeometryCollection>]) -> Self {
+impl<O: OffsetSizeTrait> TryFrom<&[Option<geo::GeometryCollection>]> for GeometryArrayBuilder<O> {
+    type Error = DataFusionError;
+
+    fn try_from(value: &[Option<geo::GeometryCollection>]) -> DFResult<Self> {
         let geo_vec = value
             .iter()
             .map(|gc| gc.clone().map(geo::Geometry::GeometryCollection))
             .collect::<Vec<_>>();
-        geo_vec.as_slice().into()
+        geo_vec.as_slice().try_into()
     }
 }
 
 #[cfg(feature = "geos")]
-impl<O: OffsetSizeTrait> From<&[Option<geos::Geometry<'_>>]> for GeometryArrayBuilder<O> {
-    fn from(value: &[Option<geos::Geometry>]) -> Self {
-        let mut builder = GeometryArrayBuilder::<O>::new(WkbDialect::Ewkb, value.len());
+impl<O: OffsetSizeTrait> TryFrom<&[Option<geos::Geometry<'_>>]> for GeometryArrayBuilder<O> {
+    type Error = DataFusionError;
+
+    fn try_from(value: &[Option<geos::Geometry>]) -> DFResult<Self> {
+        let mut builder = GeometryArrayBuilder::<O>::new(default_dialect(), value.len());
         for geom in value {
-            builder
-                .append_geos_geometry(geom)
-                .expect("geometry data is valid");
+            builder.append_geos_geometry(geom)?;
         }
-        builder
+        Ok(builder)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::default_dialect;
+    use crate::geo::GeometryArrayBuilder;
+
+    /// Polygons with pseudo random vertices, every tenth row is null.
+    fn random_polygons(len: usize) -> Vec<Option<geo::Geometry>> {
+        let mut state = 42u64;
+        let mut next = move || {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 11) as f64 / (1u64 << 53) as f64 * 1000.0
+        };
+        (0..len)
+            .map(|i| {
+                if i % 10 == 0 {
+                    return None;
+                }
+                let (x, y) = (next(), next());
+                let mut coords = (0..3 + i % 5)
+                    .map(|_| geo::coord! { x: x + next() / 100.0, y: y + next() / 100.0 })
+                    .collect::<Vec<_>>();
+                coords.push(coords[0]);
+                Some(geo::Polygon::new(coords.into(), vec![]).into())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parallel_is_byte_identical() {
+        let geoms = random_polygons(50_000);
+        let serial = GeometryArrayBuilder::<i32>::try_from(geoms.as_slice())
+            .unwrap()
+            .build();
+        let parallel = GeometryArrayBuilder::<i32>::from_geo_parallel(&geoms, default_dialect())
+            .unwrap()
+            .build();
+        assert_eq!(serial.offsets(), parallel.offsets());
+        assert_eq!(serial.values(), parallel.values());
+        assert_eq!(serial.nulls(), parallel.nulls());
+    }
+
+    #[cfg(feature = "geos")]
+    #[test]
+    fn parallel_geos_is_byte_identical() {
+        use crate::geo::GeometryArray;
+
+        let geo_arr = GeometryArrayBuilder::<i64>::try_from(random_polygons(5_000).as_slice())
+            .unwrap()
+            .build();
+        let geoms = (0..geo_arr.geom_len())
+            .map(|i| geo_arr.geos_value(i).unwrap())
+            .collect::<Vec<_>>();
+        let serial = GeometryArrayBuilder::<i64>::try_from(geoms.as_slice())
+            .unwrap()
+            .build();
+        let parallel = GeometryArrayBuilder::<i64>::from_geos_parallel(&geoms, default_dialect())
+            .unwrap()
+            .build();
+        assert_eq!(serial.offsets(), parallel.offsets());
+        assert_eq!(serial.values(), parallel.values());
+        assert_eq!(serial.nulls(), parallel.nulls());
     }
 }
@@ -0,0 +1,135 @@
+//! Capabilities of the GEOS library linked at runtime.
+//!
+//! Some functions need a newer GEOS than the oldest one the crate links against, they check
+//! [`geos_capabilities`] when invoked instead of failing on a missing symbol.
+use crate::DFResult;
+use datafusion_common::{exec_datafusion_err, exec_err, DataFusionError};
+use std::fmt::{Display, Formatter};
+use std::sync::OnceLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct GeosVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl GeosVersion {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Parses a GEOS version like `3.12.1-CAPI-1.18.1` or `3.13.0dev`.
+    pub fn parse(version: &str) -> Option<Self> {
+        let mut parts = version.split(|c: char| !c.is_ascii_digit());
+        let major = parts.next()?.parse().ok()?;
+        let minor = parts.next()?.parse().ok()?;
+        let patch = parts.next().and_then(|p| p.parse().ok()).unwrap_or(0);
+        Some(Self::new(major, minor, patch))
+    }
+}
+
+impl Display for GeosVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeosCapabilities {
+    /// Linked GEOS version, None if it could not be determined.
+    pub version: Option<GeosVersion>,
+}
+
+impl GeosCapabilities {
+    pub fn supports(&self, required: GeosVersion) -> bool {
+        self.version.is_some_and(|version| version >= required)
+    }
+
+    /// Fails with an execution error naming the required version if the function is unsupported.
+    pub fn check(&self, function: &str, required: GeosVersion) -> DFResult<()> {
+        if self.supports(required) {
+            return Ok(());
+        }
+        match self.version {
+            Some(version) => exec_err!(
+                "{} requires GEOS >= {}, the linked GEOS is {}",
+                function,
+                required,
+                version
+            ),
+            None => exec_err!(
+                "{} requires GEOS >= {}, the linked GEOS version is unknown",
+                function,
+                required
+            ),
+        }
+    }
+}
+
+static CAPABILITIES: OnceLock<GeosCapabilities> = OnceLock::new();
+
+impl GeosCapabilities {
+    /// Overrides the probed capabilities, e.g. to check how the functions degrade on an older
+    /// GEOS. Fails once the capabilities are probed or set.
+    pub fn set(capabilities: GeosCapabilities) -> DFResult<()> {
+        CAPABILITIES
+            .set(capabilities)
+            .map_err(|_| exec_datafusion_err!("GeosCapabilities are already set"))
+    }
+
+    fn probe() -> Self {
+        Self {
+            version: geos::version()
+                .ok()
+                .and_then(|version| GeosVersion::parse(&version)),
+        }
+    }
+}
+
+/// Probes the linked GEOS library, the result is computed once.
+pub fn geos_capabilities() -> &'static GeosCapabilities {
+    CAPABILITIES.get_or_init(GeosCapabilities::probe)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geo::{geos_capabilities, GeosCapabilities, GeosVersion};
+
+    #[test]
+    fn geos_version() {
+        assert_eq!(
+            GeosVersion::parse("3.12.1-CAPI-1.18.1"),
+            Some(GeosVersion::new(3, 12, 1))
+        );
+        assert_eq!(
+            GeosVersion::parse("3.13.0dev"),
+            Some(GeosVersion::new(3, 13, 0))
+        );
+        assert_eq!(GeosVersion::parse("unknown"), None);
+        // the crate is built against the GEOS 3.10 api
+        assert!(geos_capabilities().supports(GeosVersion::new(3, 10, 0)));
+    }
+
+    #[test]
+    fn unsupported_geos() {
+        let old = GeosCapabilities {
+            version: Some(GeosVersion::new(3, 7, 2)),
+        };
+        let err = old
+            .check("st_coverageunion", GeosVersion::new(3, 8, 0))
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("st_coverageunion requires GEOS >= 3.8.0, the linked GEOS is 3.7.2"));
+
+        let unknown = GeosCapabilities { version: None };
+        assert!(unknown
+            .check("st_coverageunion", GeosVersion::new(3, 8, 0))
+            .is_err());
+    }
+}
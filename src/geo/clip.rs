@@ -0,0 +1,382 @@
+//! Fast clipping of geometries by an axis aligned rectangle, without a general overlay.
+
+use geo::orient::Direction;
+use geo::{
+    Area, BoundingRect, Contains, Coord, InteriorPoint, Intersects, LineString, Orient, Rect,
+};
+
+/// Clips a geometry by a rectangle, the boundary of the rectangle is included.
+///
+/// The rings of polygons are clipped like lines and the pieces are joined along the boundary of
+/// the rectangle, which splits concave polygons leaving and re-entering the rectangle into
+/// separate polygons and opens the shell at holes crossing the boundary. Lines are clipped segment
+/// by segment, parts only touching the rectangle in a point are dropped.
+pub fn clip_by_rect(geom: &geo::Geometry, rect: &Rect) -> geo::Geometry {
+    match geom.bounding_rect() {
+        Some(bbox) if rect.contains(&bbox) => return geom.clone(),
+        Some(bbox) if bbox.intersects(rect) => {}
+        _ => return empty_of(geom),
+    }
+    match geom {
+        geo::Geometry::Point(point) if rect.intersects(point) => geom.clone(),
+        geo::Geometry::Point(_) => geo::MultiPoint::new(vec![]).into(),
+        geo::Geometry::MultiPoint(points) => points
+            .iter()
+            .filter(|point| rect.intersects(*point))
+            .cloned()
+            .collect::<geo::MultiPoint>()
+            .into(),
+        geo::Geometry::Line(line) => clip_lines([LineString::from(*line)].iter(), rect).into(),
+        geo::Geometry::LineString(line) => clip_lines([line].into_iter(), rect).into(),
+        geo::Geometry::MultiLineString(lines) => clip_lines(lines.iter(), rect).into(),
+        geo::Geometry::Polygon(polygon) => clip_polygons([polygon].into_iter(), rect),
+        geo::Geometry::MultiPolygon(polygons) => clip_polygons(polygons.iter(), rect),
+        geo::Geometry::Rect(r) => clip_polygons([&r.to_polygon()].into_iter(), rect),
+        geo::Geometry::Triangle(t) => clip_polygons([&t.to_polygon()].into_iter(), rect),
+        geo::Geometry::GeometryCollection(gc) => gc
+            .iter()
+            .map(|geom| clip_by_rect(geom, rect))
+            .collect::<geo::GeometryCollection>()
+            .into(),
+    }
+}
+
+fn empty_of(geom: &geo::Geometry) -> geo::Geometry {
+    match geom {
+        geo::Geometry::Point(_) | geo::Geometry::MultiPoint(_) => {
+            geo::MultiPoint::new(vec![]).into()
+        }
+        geo::Geometry::Line(_)
+        | geo::Geometry::LineString(_)
+        | geo::Geometry::MultiLineString(_) => geo::MultiLineString::new(vec![]).into(),
+        geo::Geometry::GeometryCollection(_) => geo::GeometryCollection::new_from(vec![]).into(),
+        _ => geo::MultiPolygon::new(vec![]).into(),
+    }
+}
+
+fn clip_lines<'a>(
+    lines: impl Iterator<Item = &'a LineString>,
+    rect: &Rect,
+) -> geo::MultiLineString {
+    let mut clipped = vec![];
+    for line in lines {
+        let mut current: Vec<Coord> = vec![];
+        for segment in line.lines() {
+            match clip_segment(segment.start, segment.end, rect) {
+                Some((start, end)) if start != end => {
+                    if current.last() != Some(&start) {
+                        if current.len() >= 2 {
+                            clipped.push(LineString::new(std::mem::take(&mut current)));
+                        }
+                        current = vec![start];
+                    }
+                    current.push(end);
+                }
+                _ => {
+                    if current.len() >= 2 {
+                        clipped.push(LineString::new(std::mem::take(&mut current)));
+                    }
+                    current.clear();
+                }
+            }
+        }
+        if current.len() >= 2 {
+            clipped.push(LineString::new(current));
+        }
+    }
+    geo::MultiLineString::new(clipped)
+}
+
+/// Liang-Barsky clipping of a segment, unclipped ends are returned as is so the pieces of
+/// consecutive segments can be joined by comparing coordinates.
+fn clip_segment(start: Coord, end: Coord, rect: &Rect) -> Option<(Coord, Coord)> {
+    let delta = end - start;
+    let mut t0: f64 = 0.0;
+    let mut t1: f64 = 1.0;
+    let checks = [
+        (-delta.x, start.x - rect.min().x),
+        (delta.x, rect.max().x - start.x),
+        (-delta.y, start.y - rect.min().y),
+        (delta.y, rect.max().y - start.y),
+    ];
+    for (p, q) in checks {
+        if p == 0.0 {
+            if q < 0.0 {
+                return None;
+            }
+            continue;
+        }
+        let t = q / p;
+        if p < 0.0 {
+            t0 = t0.max(t);
+        } else {
+            t1 = t1.min(t);
+        }
+        if t0 > t1 {
+            return None;
+        }
+    }
+    let point_at = |t: f64| match t {
+        t if t == 0.0 => start,
+        t if t == 1.0 => end,
+        t => start + delta * t,
+    };
+    Some((point_at(t0), point_at(t1)))
+}
+
+/// Intersects the polygons with the rectangle, a single polygon result is returned as a polygon.
+/// The exteriors of the result are counter clockwise and the interiors clockwise.
+fn clip_polygons<'a>(
+    polygons: impl Iterator<Item = &'a geo::Polygon>,
+    rect: &Rect,
+) -> geo::Geometry {
+    let mut clipped = if rect.width() > 0.0 && rect.height() > 0.0 {
+        polygons
+            .flat_map(|polygon| clip_polygon(&polygon.orient(Direction::Default), rect))
+            .collect()
+    } else {
+        vec![]
+    };
+    match clipped.len() {
+        1 => clipped.remove(0).into(),
+        _ => geo::MultiPolygon::new(clipped).into(),
+    }
+}
+
+/// Clips an oriented polygon, the pieces of the rings inside the rectangle keep the polygon on
+/// their left, so walking counter clockwise along the boundary from the end of a piece to the
+/// nearest start of a piece closes the rings of the result.
+fn clip_polygon(polygon: &geo::Polygon, rect: &Rect) -> Vec<geo::Polygon> {
+    let center = geo::Point::from(rect.center());
+    let mut pieces = vec![];
+    let mut shells = vec![];
+    let mut holes = vec![];
+    for (i, ring) in std::iter::once(polygon.exterior())
+        .chain(polygon.interiors())
+        .enumerate()
+    {
+        let inside = ring
+            .bounding_rect()
+            .is_some_and(|bbox| rect.contains(&bbox));
+        if inside {
+            if i == 0 {
+                shells.push(ring.clone());
+            } else {
+                holes.push(ring.clone());
+            }
+            continue;
+        }
+        let ring_pieces = clip_ring(ring, rect);
+        if !ring_pieces.is_empty() {
+            pieces.extend(ring_pieces);
+            continue;
+        }
+        // a ring outside the rectangle either surrounds it or is disjoint from it, nothing is
+        // left when the exterior is disjoint or a hole surrounds it
+        let surrounds = geo::Polygon::new(ring.clone(), vec![]).contains(&center);
+        if surrounds == (i > 0) {
+            return vec![];
+        }
+    }
+    if pieces.is_empty() && shells.is_empty() {
+        shells.push(rect_ring(rect));
+    }
+    shells.extend(join_pieces(pieces, rect));
+
+    let mut clipped = shells
+        .into_iter()
+        .map(|shell| geo::Polygon::new(shell, vec![]))
+        .filter(|shell| shell.unsigned_area() > 0.0)
+        .collect::<Vec<_>>();
+    for hole in holes {
+        let Some(point) = geo::Polygon::new(hole.clone(), vec![]).interior_point() else {
+            continue;
+        };
+        if let Some(shell) = clipped.iter_mut().find(|shell| shell.contains(&point)) {
+            shell.interiors_push(hole);
+        }
+    }
+    clipped
+}
+
+/// Clips a closed ring crossing the boundary of the rectangle into pieces starting and ending
+/// on the boundary.
+fn clip_ring(ring: &LineString, rect: &Rect) -> Vec<LineString> {
+    let mut pieces = clip_lines([ring].into_iter(), rect).0;
+    // the ring starts inside the rectangle, the last piece continues with the first one
+    if pieces.len() >= 2
+        && pieces[0].0.first() == ring.0.first()
+        && pieces[pieces.len() - 1].0.last() == ring.0.last()
+    {
+        let first = pieces.remove(0);
+        let last = pieces.last_mut().unwrap();
+        last.0.extend(first.0.into_iter().skip(1));
+    }
+    pieces
+}
+
+/// Joins the pieces into closed rings, the corners of the rectangle passed on the boundary
+/// between two pieces are inserted.
+fn join_pieces(pieces: Vec<LineString>, rect: &Rect) -> Vec<LineString> {
+    let perimeter = 2.0 * (rect.width() + rect.height());
+    let corners = [
+        rect.min(),
+        Coord {
+            x: rect.max().x,
+            y: rect.min().y,
+        },
+        rect.max(),
+        Coord {
+            x: rect.min().x,
+            y: rect.max().y,
+        },
+    ];
+    let starts = pieces
+        .iter()
+        .map(|piece| boundary_position(piece.0[0], rect))
+        .collect::<Vec<_>>();
+    let mut used = vec![false; pieces.len()];
+    let mut rings = vec![];
+    for first in 0..pieces.len() {
+        if used[first] {
+            continue;
+        }
+        let mut coords: Vec<Coord> = vec![];
+        let mut current = first;
+        loop {
+            used[current] = true;
+            coords.extend(pieces[current].0.iter().copied());
+            let end = boundary_position(*coords.last().unwrap(), rect);
+            let distance_to = |start: f64| (start - end).rem_euclid(perimeter);
+            let next = (0..pieces.len())
+                .filter(|&i| !used[i] || i == first)
+                .min_by(|&a, &b| distance_to(starts[a]).total_cmp(&distance_to(starts[b])))
+                .unwrap();
+            let mut passed = corners
+                .iter()
+                .map(|corner| (distance_to(boundary_position(*corner, rect)), *corner))
+                .filter(|(distance, _)| *distance > 0.0 && *distance < distance_to(starts[next]))
+                .collect::<Vec<_>>();
+            passed.sort_by(|a, b| a.0.total_cmp(&b.0));
+            coords.extend(passed.into_iter().map(|(_, corner)| corner));
+            if next == first {
+                coords.push(coords[0]);
+                break;
+            }
+            current = next;
+        }
+        rings.push(LineString::new(coords));
+    }
+    rings
+}
+
+/// The counter clockwise distance along the boundary from the min corner to a coordinate on
+/// the boundary, the coordinate is assigned to its nearest side.
+fn boundary_position(coord: Coord, rect: &Rect) -> f64 {
+    let (min, max) = (rect.min(), rect.max());
+    let sides = [
+        ((coord.y - min.y).abs(), coord.x - min.x),
+        ((coord.x - max.x).abs(), rect.width() + coord.y - min.y),
+        (
+            (coord.y - max.y).abs(),
+            rect.width() + rect.height() + max.x - coord.x,
+        ),
+        (
+            (coord.x - min.x).abs(),
+            2.0 * rect.width() + rect.height() + max.y - coord.y,
+        ),
+    ];
+    sides
+        .into_iter()
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, position)| position)
+        .unwrap()
+}
+
+fn rect_ring(rect: &Rect) -> LineString {
+    let (min, max) = (rect.min(), rect.max());
+    LineString::from(vec![
+        (min.x, min.y),
+        (max.x, min.y),
+        (max.x, max.y),
+        (min.x, max.y),
+        (min.x, min.y),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geo::clip_by_rect;
+    use geo::{coord, line_string, point, polygon, Area, EuclideanLength, Rect};
+
+    #[test]
+    fn clip_geometries_by_rect() {
+        let rect = Rect::new(coord! { x: 0., y: 0. }, coord! { x: 2., y: 2. });
+
+        let inside = point!(x: 1., y: 1.).into();
+        assert_eq!(clip_by_rect(&inside, &rect), inside);
+        let outside = point!(x: 3., y: 1.).into();
+        assert_eq!(
+            clip_by_rect(&outside, &rect),
+            geo::Geometry::from(geo::MultiPoint::new(vec![]))
+        );
+
+        // leaves and re-enters the rectangle
+        let line = line_string![
+            (x: -1., y: 1.), (x: 1., y: 1.), (x: 1., y: 3.), (x: 1.5, y: 3.), (x: 1.5, y: 1.),
+        ]
+        .into();
+        let geo::Geometry::MultiLineString(clipped) = clip_by_rect(&line, &rect) else {
+            panic!("clipped line should be a multi linestring");
+        };
+        assert_eq!(clipped.0.len(), 2);
+        assert_eq!(clipped.euclidean_length(), 3.0);
+
+        // a U shape whose opening is cut by the rectangle
+        let polygon: geo::Geometry = polygon![
+            (x: -1., y: -1.), (x: 3., y: -1.), (x: 3., y: 3.), (x: 1.5, y: 3.),
+            (x: 1.5, y: 0.5), (x: 0.5, y: 0.5), (x: 0.5, y: 3.), (x: -1., y: 3.),
+        ]
+        .into();
+        let clipped = clip_by_rect(&polygon, &rect);
+        assert!(
+            matches!(clipped, geo::Geometry::Polygon(_)),
+            "{:?}",
+            clipped
+        );
+        assert_eq!(clipped.unsigned_area(), 4.0 - 1.5);
+
+        // the legs of the U are separate polygons, not bridged along the rectangle boundary
+        let legs = Rect::new(coord! { x: 0., y: 1. }, coord! { x: 2., y: 2. });
+        let geo::Geometry::MultiPolygon(clipped) = clip_by_rect(&polygon, &legs) else {
+            panic!("clipped legs should be a multi polygon");
+        };
+        assert_eq!(clipped.0.len(), 2);
+        assert_eq!(clipped.unsigned_area(), 1.0);
+
+        // a hole crossing the rectangle boundary opens the shell instead of touching it
+        let polygon = polygon!(
+            exterior: [(x: -1., y: -1.), (x: 3., y: -1.), (x: 3., y: 3.), (x: -1., y: 3.)],
+            interiors: [[(x: 1., y: 0.5), (x: 2.5, y: 0.5), (x: 2.5, y: 1.5), (x: 1., y: 1.5)]],
+        )
+        .into();
+        let geo::Geometry::Polygon(clipped) = clip_by_rect(&polygon, &rect) else {
+            panic!("clipped polygon should be a polygon");
+        };
+        assert!(clipped.interiors().is_empty());
+        assert_eq!(clipped.unsigned_area(), 3.0);
+
+        // the rectangle inside the polygon, and inside its hole
+        let polygon = polygon!(
+            exterior: [(x: -5., y: -5.), (x: 5., y: -5.), (x: 5., y: 5.), (x: -5., y: 5.)],
+            interiors: [[(x: -3., y: -3.), (x: 3., y: -3.), (x: 3., y: 3.), (x: -3., y: 3.)]],
+        )
+        .into();
+        let far = Rect::new(coord! { x: 3.5, y: -1. }, coord! { x: 4.5, y: 1. });
+        assert_eq!(clip_by_rect(&polygon, &far).unsigned_area(), 2.0);
+        assert_eq!(
+            clip_by_rect(&polygon, &rect),
+            geo::Geometry::from(geo::MultiPolygon::new(vec![]))
+        );
+    }
+}
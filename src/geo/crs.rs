@@ -0,0 +1,177 @@
+//! A small embedded registry of common EPSG coordinate reference systems.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrsUnit {
+    Degree,
+    Metre,
+    Foot,
+    UsSurveyFoot,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AxisOrder {
+    /// x is easting / longitude, y is northing / latitude
+    EastNorth,
+    /// x is northing / latitude, y is easting / longitude
+    NorthEast,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrsInfo {
+    pub srid: i32,
+    pub is_geographic: bool,
+    pub unit: CrsUnit,
+    pub axis_order: AxisOrder,
+}
+
+impl CrsInfo {
+    const fn geographic(srid: i32) -> Self {
+        Self {
+            srid,
+            is_geographic: true,
+            unit: CrsUnit::Degree,
+            axis_order: AxisOrder::NorthEast,
+        }
+    }
+
+    const fn projected(srid: i32, unit: CrsUnit, axis_order: AxisOrder) -> Self {
+        Self {
+            srid,
+            is_geographic: false,
+            unit,
+            axis_order,
+        }
+    }
+}
+
+/// Geographic 2D systems, e.g. WGS 84, NAD83, ETRS89, GDA94/2020, CGCS2000, SIRGAS 2000.
+const GEOGRAPHIC: &[i32] = &[
+    4326, 4269, 4267, 4258, 4283, 7844, 4167, 4230, 4612, 6668, 4490, 4674, 4617, 4148, 4152, 4171,
+    4214, 4236, 4277, 4301, 4313, 4322, 4324, 4619, 4624, 4640, 4670, 4680, 4686, 4755, 4759, 4760,
+    4979,
+];
+
+/// Projected systems in metre with easting/northing axis.
+const PROJECTED_METRE_EN: &[i32] = &[
+    3857, 900913, 3395, 3349, 3832, 2154, 27700, 5070, 3577, 3112, 3308, 3310, 3347, 3400, 3401,
+    3402, 3403, 3405, 3414, 3765, 3794, 3812, 3978, 3979, 5514, 6933, 8857, 21781, 2056, 31370,
+    28992, 23700, 2100, 5179, 5186, 5187, 3826, 3828, 4527, 6870, 3763, 3116, 5641, 6372, 22275,
+];
+
+/// Projected systems in metre with northing/easting axis, e.g. Gauss-Kruger zones, ETRS89 LAEA
+/// and LCC, SWEREF99 TM, ETRS-TM35FIN, NZTM2000 and the Polish CS92 and Stereo70.
+const PROJECTED_METRE_NE: &[i32] = &[
+    31466, 31467, 31468, 31469, 2176, 2177, 2178, 2179, 3120, 3035, 3034, 3006, 3067, 2193, 2180,
+    3844,
+];
+
+/// US state plane systems in US survey foot.
+const PROJECTED_US_FOOT: &[i32] = &[
+    2225, 2226, 2227, 2228, 2229, 2230, 2231, 2232, 2233, 2234, 2235, 2236, 2237, 2238, 2239, 2240,
+    2241, 2242, 2243, 2244, 2245, 2246, 2247, 2248, 2249, 2250, 2254, 2255, 2257, 2258, 2259, 2260,
+    2261, 2262, 2263, 2264, 2267, 2268, 2271, 2272, 2274, 2275, 2276, 2277, 2278, 2279, 2283, 2284,
+    2285, 2286, 2287, 2288, 2289, 3089, 3090, 3359, 3361, 3419, 3420, 3421, 3433, 3434, 3435, 3436,
+    3437, 3438, 3451, 3452, 3453, 3454, 3455, 6420, 6423, 6426, 6434, 6436, 6438, 6442, 6447, 6448,
+    6450, 6452, 6454, 6456, 6458, 6460, 6463, 6464, 6465, 6466, 6467, 6469, 6488, 6494, 6496, 6500,
+    6504, 6506, 6509, 6511, 6515, 6517, 6524, 6536, 6538, 6539,
+];
+
+/// Systems in international foot, e.g. the state plane systems of the states which adopted it.
+const PROJECTED_FOOT: &[i32] = &[
+    2222, 2223, 2224, 2251, 2252, 2253, 2256, 2265, 2266, 2269, 2270, 2273, 2280, 2281, 2282, 2867,
+    2868, 2869,
+];
+
+/// Ranges of UTM like systems in metre with easting/northing axis.
+const PROJECTED_METRE_EN_RANGES: &[(i32, i32)] = &[
+    // WGS 84 / UTM north and south
+    (32601, 32660),
+    (32701, 32760),
+    // NAD83 / UTM
+    (26901, 26923),
+    // NAD27 / UTM
+    (26701, 26722),
+    // ETRS89 / UTM
+    (25828, 25838),
+    // GDA94 and GDA2020 / MGA
+    (28348, 28358),
+    (7846, 7859),
+    // SIRGAS 2000 / UTM
+    (31965, 31985),
+];
+
+/// Ranges of systems in metre with northing/easting axis.
+const PROJECTED_METRE_NE_RANGES: &[(i32, i32)] = &[
+    // CGCS2000 / 3-degree Gauss-Kruger CM
+    (4534, 4554),
+    // JGD2011 / Japan plane rectangular
+    (6669, 6687),
+];
+
+fn in_ranges(ranges: &[(i32, i32)], srid: i32) -> bool {
+    ranges
+        .iter()
+        .any(|(start, end)| (*start..=*end).contains(&srid))
+}
+
+/// Returns the CRS information of an EPSG code, None if the code is unknown.
+pub fn crs_info(srid: i32) -> Option<CrsInfo> {
+    if GEOGRAPHIC.contains(&srid) {
+        return Some(CrsInfo::geographic(srid));
+    }
+    let projected = |unit, axis_order| Some(CrsInfo::projected(srid, unit, axis_order));
+    if PROJECTED_METRE_EN.contains(&srid) || in_ranges(PROJECTED_METRE_EN_RANGES, srid) {
+        return projected(CrsUnit::Metre, AxisOrder::EastNorth);
+    }
+    if PROJECTED_METRE_NE.contains(&srid) || in_ranges(PROJECTED_METRE_NE_RANGES, srid) {
+        return projected(CrsUnit::Metre, AxisOrder::NorthEast);
+    }
+    if PROJECTED_US_FOOT.contains(&srid) {
+        return projected(CrsUnit::UsSurveyFoot, AxisOrder::EastNorth);
+    }
+    if PROJECTED_FOOT.contains(&srid) {
+        return projected(CrsUnit::Foot, AxisOrder::EastNorth);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geo::{crs_info, AxisOrder, CrsUnit};
+
+    #[test]
+    fn lookup_crs() {
+        let wgs84 = crs_info(4326).unwrap();
+        assert!(wgs84.is_geographic);
+        assert_eq!(wgs84.unit, CrsUnit::Degree);
+        assert_eq!(wgs84.axis_order, AxisOrder::NorthEast);
+
+        let web_mercator = crs_info(3857).unwrap();
+        assert!(!web_mercator.is_geographic);
+        assert_eq!(web_mercator.unit, CrsUnit::Metre);
+        assert_eq!(web_mercator.axis_order, AxisOrder::EastNorth);
+
+        let lambert93 = crs_info(2154).unwrap();
+        assert!(!lambert93.is_geographic);
+        assert_eq!(lambert93.unit, CrsUnit::Metre);
+
+        let utm = crs_info(32633).unwrap();
+        assert!(!utm.is_geographic);
+        assert_eq!(utm.unit, CrsUnit::Metre);
+        assert_eq!(utm.axis_order, AxisOrder::EastNorth);
+
+        for srid in [
+            3035, 3034, 3006, 3067, 2193, 2180, 3844, 4534, 4554, 6669, 6687,
+        ] {
+            let crs = crs_info(srid).unwrap();
+            assert_eq!(crs.unit, CrsUnit::Metre);
+            assert_eq!(crs.axis_order, AxisOrder::NorthEast, "{}", srid);
+        }
+
+        assert!(crs_info(2263).is_some_and(|crs| crs.unit == CrsUnit::UsSurveyFoot));
+        // NAD83 / Michigan Central (ft)
+        assert!(crs_info(2252).is_some_and(|crs| crs.unit == CrsUnit::Foot));
+        assert_eq!(crs_info(99999), None);
+        assert_eq!(crs_info(0), None);
+    }
+}
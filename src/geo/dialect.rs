@@ -1,5 +1,5 @@
 use crate::DFResult;
-use datafusion_common::{internal_err, DataFusionError};
+use datafusion_common::{exec_err, internal_datafusion_err, internal_err, DataFusionError};
 use geozero::wkb::WkbDialect;
 
 pub(crate) fn wkb_type_id(dialect: WkbDialect) -> u8 {
@@ -12,6 +12,19 @@ pub(crate) fn wkb_type_id(dialect: WkbDialect) -> u8 {
     }
 }
 
+/// Fails if a non-zero srid is requested for a geometry of a dialect which can't store it, so the
+/// srid isn't silently dropped.
+pub(crate) fn check_srid_storable(dialect: WkbDialect, srid: Option<i32>) -> DFResult<()> {
+    match srid {
+        Some(srid) if srid != 0 && dialect == WkbDialect::Wkb => exec_err!(
+            "Srid {} can't be stored in the {:?} dialect, configure a dialect with srids like Ewkb",
+            srid,
+            dialect
+        ),
+        _ => Ok(()),
+    }
+}
+
 pub(crate) fn decode_wkb_dialect(type_id: u8) -> DFResult<WkbDialect> {
     if type_id == wkb_type_id(WkbDialect::Wkb) {
         Ok(WkbDialect::Wkb)
@@ -27,3 +40,407 @@ pub(crate) fn decode_wkb_dialect(type_id: u8) -> DFResult<WkbDialect> {
         internal_err!("Cannot decode WkbDialect from {}", type_id)
     }
 }
+
+/// Reads the srid from the header of a wkb prefixed by its dialect type id without decoding the geometry.
+pub(crate) fn decode_srid(wkb: &[u8]) -> DFResult<Option<i32>> {
+    Ok(WkbHeader::parse(wkb)?.srid)
+}
+
+/// Header of a wkb prefixed by its dialect type id, read without walking the coordinates.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct WkbHeader {
+    pub(crate) dialect: WkbDialect,
+    pub(crate) little_endian: bool,
+    /// Wkb type code of the outermost geometry without dimension flags, 1 (point) to 7
+    /// (geometry collection).
+    pub(crate) geometry_type: u32,
+    pub(crate) has_z: bool,
+    pub(crate) has_m: bool,
+    pub(crate) srid: Option<i32>,
+    /// Number of points of a line string, rings of a polygon or parts of a multi geometry or
+    /// collection, 1 for a point and 0 for an empty one.
+    pub(crate) num_elements: u32,
+}
+
+impl WkbHeader {
+    pub(crate) fn parse(wkb: &[u8]) -> DFResult<WkbHeader> {
+        let Some((type_id, data)) = wkb.split_first() else {
+            return internal_err!("Wkb is empty");
+        };
+        let dialect = decode_wkb_dialect(*type_id)?;
+        if dialect == WkbDialect::SpatiaLite {
+            return Self::parse_spatialite(data);
+        }
+        let (offset, srid) = match dialect {
+            WkbDialect::Geopackage => {
+                let little_endian = read_byte(data, 3)? & 0x01 == 1;
+                (
+                    geopackage_offset(data)?,
+                    Some(read_u32(data, 4, little_endian)? as i32),
+                )
+            }
+            WkbDialect::MySQL => (4, Some(read_u32(data, 0, true)? as i32)),
+            _ => (0, None),
+        };
+        let little_endian = read_byte(data, offset)? == 1;
+        let geom_type = read_u32(data, offset + 1, little_endian)?;
+        let mut pos = offset + 5;
+        let mut srid = srid;
+        if dialect == WkbDialect::Ewkb && geom_type & 0x2000_0000 != 0 {
+            srid = Some(read_u32(data, pos, little_endian)? as i32);
+        }
+        // other dialects don't carry the ewkb srid flag, but skip a srid written anyway
+        if geom_type & 0x2000_0000 != 0 {
+            pos += 4;
+        }
+        // ewkb z and m flags, iso dimension offsets
+        let code = geom_type & 0x0fff_ffff;
+        let has_z = geom_type & 0x8000_0000 != 0 || matches!(code / 1000, 1 | 3);
+        let has_m = geom_type & 0x4000_0000 != 0 || matches!(code / 1000, 2 | 3);
+        let geometry_type = code % 1000;
+        let num_elements = match geometry_type {
+            1 => {
+                let x = read_f64(data, pos, little_endian)?;
+                let y = read_f64(data, pos + 8, little_endian)?;
+                u32::from(!(x.is_nan() && y.is_nan()))
+            }
+            2..=7 => read_u32(data, pos, little_endian)?,
+            _ => return internal_err!("Unsupported wkb geometry type {}", geom_type),
+        };
+        Ok(WkbHeader {
+            dialect,
+            little_endian,
+            geometry_type,
+            has_z,
+            has_m,
+            srid,
+            num_elements,
+        })
+    }
+
+    /// Spatialite blobs start with `0x00`, the byte order, the srid, the mbr and `0x7c`, followed
+    /// by the class type and the geometry body.
+    fn parse_spatialite(data: &[u8]) -> DFResult<WkbHeader> {
+        if read_byte(data, 0)? != 0x00 || read_byte(data, 38)? != 0x7c {
+            return internal_err!("Invalid spatialite blob header");
+        }
+        let little_endian = read_byte(data, 1)? == 1;
+        let srid = read_u32(data, 2, little_endian)? as i32;
+        let class_type = read_u32(data, 39, little_endian)?;
+        // dimension offsets of 1000 (z), 2000 (m) and 3000 (zm), compressed classes add 1000000
+        let dims = (class_type % 1_000_000) / 1000;
+        let geometry_type = class_type % 1000;
+        let num_elements = match geometry_type {
+            1 => {
+                let x = read_f64(data, 43, little_endian)?;
+                let y = read_f64(data, 51, little_endian)?;
+                u32::from(!(x.is_nan() && y.is_nan()))
+            }
+            2..=7 => read_u32(data, 43, little_endian)?,
+            _ => return internal_err!("Unsupported spatialite class type {}", class_type),
+        };
+        Ok(WkbHeader {
+            dialect: WkbDialect::SpatiaLite,
+            little_endian,
+            geometry_type,
+            has_z: matches!(dims, 1 | 3),
+            has_m: matches!(dims, 2 | 3),
+            srid: Some(srid),
+            num_elements,
+        })
+    }
+}
+
+/// Reads the coordinate of a point geometry straight from a wkb prefixed by its dialect type id,
+/// None if the geometry is not a point or the dialect is not supported.
+pub(crate) fn decode_point(wkb: &[u8]) -> DFResult<Option<geo::Coord>> {
+    let Some((type_id, data)) = wkb.split_first() else {
+        return internal_err!("Wkb is empty");
+    };
+    let Some(offset) = geometry_offset(*type_id, data)? else {
+        return Ok(None);
+    };
+    let little_endian = read_byte(data, offset)? == 1;
+    let geom_type = read_u32(data, offset + 1, little_endian)?;
+    // strip ewkb flags and iso dimension offsets
+    if (geom_type & 0x0fff_ffff) % 1000 != 1 {
+        return Ok(None);
+    }
+    let coord_offset = if geom_type & 0x2000_0000 != 0 {
+        offset + 9
+    } else {
+        offset + 5
+    };
+    let x = read_f64(data, coord_offset, little_endian)?;
+    let y = read_f64(data, coord_offset + 8, little_endian)?;
+    Ok(Some(geo::Coord { x, y }))
+}
+
+/// Offset of the wkb geometry after the dialect specific header, None for an empty geopackage
+/// geometry and for the unsupported spatialite dialect.
+fn geometry_offset(type_id: u8, data: &[u8]) -> DFResult<Option<usize>> {
+    let offset = match decode_wkb_dialect(type_id)? {
+        WkbDialect::Wkb | WkbDialect::Ewkb => 0,
+        WkbDialect::MySQL => 4,
+        WkbDialect::Geopackage => {
+            // empty geometry
+            if read_byte(data, 3)? & 0x10 != 0 {
+                return Ok(None);
+            }
+            geopackage_offset(data)?
+        }
+        WkbDialect::SpatiaLite => return Ok(None),
+    };
+    Ok(Some(offset))
+}
+
+/// Offset of the wkb geometry after the geopackage header and envelope.
+fn geopackage_offset(data: &[u8]) -> DFResult<usize> {
+    let envelope_len = match (read_byte(data, 3)? >> 1) & 0x07 {
+        0 => 0,
+        1 => 32,
+        2 | 3 => 48,
+        4 => 64,
+        _ => return internal_err!("Invalid geopackage envelope indicator"),
+    };
+    Ok(8 + envelope_len)
+}
+
+/// Facts of a geometry read by scanning its wkb, without building the geometry.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct WkbScan {
+    /// Wkb type code of the outermost geometry, 1 (point) to 7 (geometry collection).
+    pub(crate) geometry_type: u32,
+    /// Number of vertices, empty points have none.
+    pub(crate) vertices: usize,
+    /// Bounding box of the vertices, None without vertices.
+    pub(crate) rect: Option<geo::Rect>,
+}
+
+/// Scans a wkb prefixed by its dialect type id for its type, vertex count and bounding box.
+/// Returns None where the header can't be skipped cheaply, see [`geometry_offset`].
+pub(crate) fn scan_wkb(wkb: &[u8]) -> DFResult<Option<WkbScan>> {
+    let Some((type_id, data)) = wkb.split_first() else {
+        return internal_err!("Wkb is empty");
+    };
+    let Some(offset) = geometry_offset(*type_id, data)? else {
+        return Ok(None);
+    };
+    let mut scan = WkbScan {
+        geometry_type: 0,
+        vertices: 0,
+        rect: None,
+    };
+    let (geometry_type, _) = scan_geometry(data, offset, &mut scan)?;
+    scan.geometry_type = geometry_type;
+    Ok(Some(scan))
+}
+
+/// Scans the geometry at `offset`, returns its type code and the offset following it.
+fn scan_geometry(data: &[u8], offset: usize, scan: &mut WkbScan) -> DFResult<(u32, usize)> {
+    let little_endian = read_byte(data, offset)? == 1;
+    let geom_type = read_u32(data, offset + 1, little_endian)?;
+    let mut pos = offset + 5;
+    // ewkb srid
+    if geom_type & 0x2000_0000 != 0 {
+        pos += 4;
+    }
+    // ewkb z and m flags, iso dimension offsets
+    let mut dims = 2 + (geom_type >> 31) as usize + ((geom_type >> 30) & 1) as usize;
+    let code = geom_type & 0x0fff_ffff;
+    dims += match code / 1000 {
+        1 | 2 => 1,
+        3 => 2,
+        _ => 0,
+    };
+    let read_coords = |pos: usize, count: usize, scan: &mut WkbScan| -> DFResult<usize> {
+        for i in 0..count {
+            let coord_pos = pos + i * dims * 8;
+            let x = read_f64(data, coord_pos, little_endian)?;
+            let y = read_f64(data, coord_pos + 8, little_endian)?;
+            if x.is_nan() && y.is_nan() {
+                continue;
+            }
+            scan.vertices += 1;
+            let coord = geo::coord! { x: x, y: y };
+            scan.rect = Some(match scan.rect {
+                Some(rect) => geo::Rect::new(
+                    geo::coord! { x: rect.min().x.min(x), y: rect.min().y.min(y) },
+                    geo::coord! { x: rect.max().x.max(x), y: rect.max().y.max(y) },
+                ),
+                None => geo::Rect::new(coord, coord),
+            });
+        }
+        Ok(pos + count * dims * 8)
+    };
+    let kind = code % 1000;
+    match kind {
+        1 => pos = read_coords(pos, 1, scan)?,
+        2 => {
+            let count = read_u32(data, pos, little_endian)? as usize;
+            pos = read_coords(pos + 4, count, scan)?;
+        }
+        3 => {
+            let rings = read_u32(data, pos, little_endian)?;
+            pos += 4;
+            for _ in 0..rings {
+                let count = read_u32(data, pos, little_endian)? as usize;
+                pos = read_coords(pos + 4, count, scan)?;
+            }
+        }
+        4..=7 => {
+            let parts = read_u32(data, pos, little_endian)?;
+            pos += 4;
+            for _ in 0..parts {
+                pos = scan_geometry(data, pos, scan)?.1;
+            }
+        }
+        _ => return internal_err!("Unsupported wkb geometry type {}", geom_type),
+    }
+    Ok((kind, pos))
+}
+
+fn read_byte(data: &[u8], offset: usize) -> DFResult<u8> {
+    data.get(offset)
+        .copied()
+        .ok_or_else(|| internal_datafusion_err!("Wkb header is truncated"))
+}
+
+fn read_u32(data: &[u8], offset: usize, little_endian: bool) -> DFResult<u32> {
+    let bytes: [u8; 4] = data
+        .get(offset..offset + 4)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| internal_datafusion_err!("Wkb header is truncated"))?;
+    if little_endian {
+        Ok(u32::from_le_bytes(bytes))
+    } else {
+        Ok(u32::from_be_bytes(bytes))
+    }
+}
+
+fn read_f64(data: &[u8], offset: usize, little_endian: bool) -> DFResult<f64> {
+    let bytes: [u8; 8] = data
+        .get(offset..offset + 8)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| internal_datafusion_err!("Wkb is truncated"))?;
+    if little_endian {
+        Ok(f64::from_le_bytes(bytes))
+    } else {
+        Ok(f64::from_be_bytes(bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geo::dialect::{wkb_type_id, WkbHeader};
+    use crate::geo::wkt::parse_wkt;
+    use geozero::wkb::WkbDialect;
+    use geozero::{GeozeroGeometry, ToWkb};
+    use proptest::prelude::*;
+
+    const DIALECTS: [WkbDialect; 5] = [
+        WkbDialect::Wkb,
+        WkbDialect::Ewkb,
+        WkbDialect::Geopackage,
+        WkbDialect::MySQL,
+        WkbDialect::SpatiaLite,
+    ];
+
+    fn wkb(wkt: &str, dialect: WkbDialect, srid: Option<i32>) -> Vec<u8> {
+        let geometry = parse_wkt(wkt).unwrap();
+        let mut wkb = vec![wkb_type_id(dialect)];
+        wkb.extend(
+            geometry
+                .to_wkb_dialect(dialect, geometry.dims(), srid, vec![])
+                .unwrap(),
+        );
+        wkb
+    }
+
+    #[test]
+    fn header() {
+        for dialect in DIALECTS {
+            let expected_srid = (dialect != WkbDialect::Wkb).then_some(4326);
+            let header = WkbHeader::parse(&wkb(
+                "POLYGON((0 0,4 0,4 4,0 0),(1 1,2 1,2 2,1 1))",
+                dialect,
+                Some(4326),
+            ))
+            .unwrap();
+            assert_eq!(header.dialect, dialect);
+            assert_eq!(
+                (header.geometry_type, header.num_elements, header.srid),
+                (3, 2, expected_srid)
+            );
+            assert!(!header.has_z && !header.has_m);
+
+            for (wkt, geometry_type, num_elements, has_z, has_m) in [
+                ("POINT Z(1 2 3)", 1, 1, true, false),
+                ("POINT EMPTY", 1, 0, false, false),
+                ("LINESTRING M(0 0 1,1 1 2,2 2 3)", 2, 3, false, true),
+                ("MULTIPOINT ZM((0 0 1 2),(1 1 2 3))", 4, 2, true, true),
+                (
+                    "GEOMETRYCOLLECTION(POINT(1 1),LINESTRING EMPTY)",
+                    7,
+                    2,
+                    false,
+                    false,
+                ),
+                ("MULTIPOLYGON EMPTY", 6, 0, false, false),
+            ] {
+                let header = WkbHeader::parse(&wkb(wkt, dialect, None)).unwrap();
+                assert_eq!(
+                    (
+                        header.geometry_type,
+                        header.num_elements,
+                        header.has_z,
+                        header.has_m
+                    ),
+                    (geometry_type, num_elements, has_z, has_m),
+                    "{} as {:?}",
+                    wkt,
+                    dialect
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn truncated_header() {
+        for dialect in DIALECTS {
+            let wkb = wkb("POINT(1 2)", dialect, Some(4326));
+            // the header of a point ends with its coordinate, spatialite adds an end marker
+            let header_len = match dialect {
+                WkbDialect::SpatiaLite => wkb.len() - 1,
+                _ => wkb.len(),
+            };
+            for len in 0..header_len {
+                assert!(
+                    WkbHeader::parse(&wkb[..len]).is_err(),
+                    "{} bytes of {:?}",
+                    len,
+                    dialect
+                );
+            }
+        }
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(256))]
+
+        #[test]
+        fn garbage_header(type_id in 0u8..=6, bytes in proptest::collection::vec(any::<u8>(), 0..64)) {
+            let mut wkb = vec![type_id];
+            wkb.extend(bytes);
+            let _ = WkbHeader::parse(&wkb);
+        }
+
+        #[test]
+        fn corrupted_header(dialect in 0..DIALECTS.len(), index in 0usize..64, value in any::<u8>()) {
+            let mut wkb = wkb("MULTIPOINT((0 0),(1 1))", DIALECTS[dialect], Some(4326));
+            let index = index % wkb.len();
+            wkb[index] = value;
+            let _ = WkbHeader::parse(&wkb);
+        }
+    }
+}
@@ -0,0 +1,160 @@
+//! Number formatting of the text writers (WKT, EWKT and GeoJSON).
+//!
+//! The writers print coordinates with the shortest round trip representation of Rust. With the
+//! PostGIS number format the printed numbers are rewritten the way PostGIS prints them: at most
+//! 15 decimals with trailing zeros trimmed, values within 1e-12 of zero as `0` and scientific
+//! notation from 1e15 on.
+use crate::config::{GeoConfig, NumberFormat};
+
+/// Largest magnitude printed in fixed notation.
+const MAX_FIXED: f64 = 1e15;
+/// Magnitude below which values are printed as zero.
+const ZERO_TOLERANCE: f64 = 1e-12;
+/// Maximum number of decimals.
+const PRECISION: usize = 15;
+
+/// Rewrites the numbers of a writer output according to the configured number format.
+pub(crate) fn format_numbers(text: String) -> String {
+    match GeoConfig::get().number_format {
+        NumberFormat::Rust => text,
+        NumberFormat::Postgis => postgis_numbers(&text),
+    }
+}
+
+/// Rounds the numbers of a writer output to the given number of decimals, at least 15 decimals
+/// keep the numbers as they are.
+pub(crate) fn round_numbers(text: &str, decimals: u32) -> String {
+    if decimals as usize >= PRECISION {
+        return text.to_string();
+    }
+    let factor = 10f64.powi(decimals as i32);
+    // adding zero turns a negative zero into zero
+    map_numbers(text, |value| {
+        ((value * factor).round() / factor + 0.0).to_string()
+    })
+}
+
+fn postgis_numbers(text: &str) -> String {
+    map_numbers(text, format_number)
+}
+
+/// Replaces every number of the text with its formatted value, digits inside words are kept.
+fn map_numbers(text: &str, format: impl Fn(f64) -> String) -> String {
+    let mut formatted = String::with_capacity(text.len());
+    let mut chars = text.char_indices().peekable();
+    let mut previous = None;
+    while let Some((start, c)) = chars.next() {
+        let starts_number = c.is_ascii_digit()
+            || (c == '-' && chars.peek().is_some_and(|(_, next)| next.is_ascii_digit()));
+        // digits inside words, e.g. in a json key, are not numbers
+        let in_word = previous.is_some_and(|p: char| p.is_alphanumeric() || p == '_');
+        previous = Some(c);
+        if !starts_number || in_word {
+            formatted.push(c);
+            continue;
+        }
+        let mut end = start + c.len_utf8();
+        while let Some(&(i, next)) = chars.peek() {
+            if !(next.is_ascii_digit() || next == '.') {
+                break;
+            }
+            end = i + next.len_utf8();
+            previous = Some(next);
+            chars.next();
+        }
+        let number = &text[start..end];
+        match number.parse::<f64>() {
+            Ok(value) => formatted.push_str(&format(value)),
+            Err(_) => formatted.push_str(number),
+        }
+    }
+    formatted
+}
+
+/// Formats a number the way PostGIS prints coordinates by default.
+pub(crate) fn format_number(value: f64) -> String {
+    if !value.is_finite() {
+        return value.to_string();
+    }
+    if value.abs() <= ZERO_TOLERANCE {
+        return "0".to_string();
+    }
+    if value.abs() < MAX_FIXED {
+        let shortest = value.to_string();
+        return match shortest.split_once('.') {
+            Some((_, decimals)) if decimals.len() > PRECISION => {
+                trim_zeros(&format!("{:.*}", PRECISION, value)).to_string()
+            }
+            _ => shortest,
+        };
+    }
+    let shortest = format!("{:e}", value);
+    let (mantissa, exponent) = shortest.split_once('e').unwrap_or((&shortest, "0"));
+    let mantissa = match mantissa.split_once('.') {
+        Some((_, decimals)) if decimals.len() > PRECISION => {
+            let rounded = format!("{:.*e}", PRECISION, value);
+            let (mantissa, _) = rounded.split_once('e').unwrap_or((&rounded, "0"));
+            trim_zeros(mantissa).to_string()
+        }
+        _ => mantissa.to_string(),
+    };
+    match exponent.strip_prefix('-') {
+        Some(exponent) => format!("{}e-{}", mantissa, exponent),
+        None => format!("{}e+{}", mantissa, exponent),
+    }
+}
+
+fn trim_zeros(number: &str) -> &str {
+    if number.contains('.') {
+        number.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        number
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geo::format::{format_number, postgis_numbers, round_numbers};
+
+    #[test]
+    fn postgis_number_format() {
+        assert_eq!(format_number(1.0), "1");
+        assert_eq!(format_number(-71.064544), "-71.064544");
+        assert_eq!(format_number(0.1 + 0.2), "0.3");
+        assert_eq!(format_number(99.99999999999999), "99.99999999999999");
+        assert_eq!(format_number(-0.0), "0");
+        assert_eq!(format_number(6.123233995736766e-17), "0");
+        assert_eq!(format_number(123456789012345.6), "123456789012345.6");
+        assert_eq!(format_number(1e20), "1e+20");
+        assert_eq!(format_number(-1.5e15), "-1.5e+15");
+        assert_eq!(format_number(f64::NAN), "NaN");
+    }
+
+    #[test]
+    fn postgis_numbers_in_text() {
+        assert_eq!(
+            postgis_numbers("SRID=4326;POINT(0.30000000000000004 -0)"),
+            "SRID=4326;POINT(0.3 0)"
+        );
+        assert_eq!(
+            postgis_numbers("LINESTRING Z(1 2 3,100000000000000000000 -1 0.5)"),
+            "LINESTRING Z(1 2 3,1e+20 -1 0.5)"
+        );
+        assert_eq!(
+            postgis_numbers("{\"type\": \"Point\", \"coordinates\": [0.30000000000000004,-2]}"),
+            "{\"type\": \"Point\", \"coordinates\": [0.3,-2]}"
+        );
+    }
+
+    #[test]
+    fn round_numbers_in_text() {
+        assert_eq!(
+            round_numbers(
+                "{\"type\": \"Point\", \"coordinates\": [-71.064544,42.28787]}",
+                3
+            ),
+            "{\"type\": \"Point\", \"coordinates\": [-71.065,42.288]}"
+        );
+        assert_eq!(round_numbers("[1.5,-0.0001,2]", 0), "[2,0,2]");
+    }
+}
@@ -0,0 +1,512 @@
+//! Encoding and decoding of [Geobuf](https://github.com/mapbox/geobuf), the protobuf encoding of
+//! GeoJSON. Only the messages used for geometries and features with properties are supported.
+
+use crate::geo::protobuf::{
+    unzigzag, write_bytes, write_message, write_packed, write_tag, write_varint_field, zigzag,
+    Reader, WIRE_FIXED64, WIRE_LEN, WIRE_VARINT,
+};
+use crate::DFResult;
+use datafusion_common::{exec_datafusion_err, exec_err, DataFusionError};
+use geo::{Coord, LineString, Polygon};
+
+/// Number of decimal digits kept for the coordinates, the maximum of geobuf.
+const PRECISION: u32 = 6;
+const DIMENSIONS: u32 = 2;
+
+const POINT: u64 = 0;
+const MULTI_POINT: u64 = 1;
+const LINE_STRING: u64 = 2;
+const MULTI_LINE_STRING: u64 = 3;
+const POLYGON: u64 = 4;
+const MULTI_POLYGON: u64 = 5;
+const GEOMETRY_COLLECTION: u64 = 6;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum GeobufValue {
+    String(String),
+    Double(f64),
+    Int(i64),
+    Bool(bool),
+    Json(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct GeobufFeature {
+    pub(crate) geometry: Option<geo::Geometry>,
+    pub(crate) properties: Vec<(String, GeobufValue)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum GeobufData {
+    FeatureCollection(Vec<GeobufFeature>),
+    Feature(GeobufFeature),
+    Geometry(geo::Geometry),
+}
+
+/// Encodes the features into a geobuf feature collection.
+pub(crate) fn encode_feature_collection(features: &[GeobufFeature]) -> Vec<u8> {
+    let mut keys: Vec<&str> = vec![];
+    for feature in features {
+        for (key, _) in &feature.properties {
+            if !keys.contains(&key.as_str()) {
+                keys.push(key);
+            }
+        }
+    }
+
+    let mut collection = vec![];
+    for feature in features {
+        write_message(&mut collection, 1, &encode_feature(feature, &keys));
+    }
+    let mut data = vec![];
+    for key in &keys {
+        write_bytes(&mut data, 1, key.as_bytes());
+    }
+    write_varint_field(&mut data, 3, PRECISION as u64);
+    write_message(&mut data, 4, &collection);
+    data
+}
+
+/// Encodes a single geometry into geobuf.
+pub(crate) fn encode_geometry(geom: &geo::Geometry) -> Vec<u8> {
+    let mut data = vec![];
+    write_varint_field(&mut data, 3, PRECISION as u64);
+    write_message(&mut data, 6, &encode_geometry_message(geom));
+    data
+}
+
+fn encode_feature(feature: &GeobufFeature, keys: &[&str]) -> Vec<u8> {
+    let mut buf = vec![];
+    if let Some(geom) = &feature.geometry {
+        write_message(&mut buf, 1, &encode_geometry_message(geom));
+    }
+    let mut properties = vec![];
+    for (i, (key, value)) in feature.properties.iter().enumerate() {
+        write_message(&mut buf, 13, &encode_value(value));
+        let key_index = keys
+            .iter()
+            .position(|k| k == key)
+            .expect("key is collected");
+        properties.push(key_index as u64);
+        properties.push(i as u64);
+    }
+    if !properties.is_empty() {
+        write_packed(&mut buf, 14, &properties);
+    }
+    buf
+}
+
+fn encode_value(value: &GeobufValue) -> Vec<u8> {
+    let mut buf = vec![];
+    match value {
+        GeobufValue::String(v) => write_bytes(&mut buf, 1, v.as_bytes()),
+        GeobufValue::Double(v) => {
+            write_tag(&mut buf, 2, WIRE_FIXED64);
+            buf.extend_from_slice(&v.to_le_bytes());
+        }
+        GeobufValue::Int(v) if *v >= 0 => write_varint_field(&mut buf, 3, *v as u64),
+        GeobufValue::Int(v) => write_varint_field(&mut buf, 4, v.unsigned_abs()),
+        GeobufValue::Bool(v) => write_varint_field(&mut buf, 5, *v as u64),
+        GeobufValue::Json(v) => write_bytes(&mut buf, 6, v.as_bytes()),
+    }
+    buf
+}
+
+fn encode_geometry_message(geom: &geo::Geometry) -> Vec<u8> {
+    let mut buf = vec![];
+    let mut lengths = vec![];
+    let mut coords = vec![];
+    let geom_type = match geom {
+        geo::Geometry::Point(point) => {
+            coords.push(quantize(point.x()));
+            coords.push(quantize(point.y()));
+            POINT
+        }
+        geo::Geometry::MultiPoint(points) => {
+            let line = points.iter().map(|p| p.0).collect::<Vec<_>>();
+            add_line(&mut coords, &line, false);
+            MULTI_POINT
+        }
+        geo::Geometry::Line(line) => {
+            add_line(&mut coords, &[line.start, line.end], false);
+            LINE_STRING
+        }
+        geo::Geometry::LineString(line) => {
+            add_line(&mut coords, &line.0, false);
+            LINE_STRING
+        }
+        geo::Geometry::MultiLineString(lines) => {
+            if lines.0.len() != 1 {
+                lengths.extend(lines.iter().map(|line| line.0.len() as u64));
+            }
+            for line in lines {
+                add_line(&mut coords, &line.0, false);
+            }
+            MULTI_LINE_STRING
+        }
+        geo::Geometry::Polygon(polygon) => {
+            add_polygon(&mut coords, &mut lengths, polygon, false);
+            POLYGON
+        }
+        geo::Geometry::Rect(rect) => {
+            add_polygon(&mut coords, &mut lengths, &rect.to_polygon(), false);
+            POLYGON
+        }
+        geo::Geometry::Triangle(triangle) => {
+            add_polygon(&mut coords, &mut lengths, &triangle.to_polygon(), false);
+            POLYGON
+        }
+        geo::Geometry::MultiPolygon(polygons) => {
+            let single_ring = polygons.0.len() == 1 && polygons.0[0].interiors().is_empty();
+            if !single_ring {
+                lengths.push(polygons.0.len() as u64);
+            }
+            for polygon in polygons {
+                add_polygon(&mut coords, &mut lengths, polygon, !single_ring);
+            }
+            MULTI_POLYGON
+        }
+        geo::Geometry::GeometryCollection(gc) => {
+            write_varint_field(&mut buf, 1, GEOMETRY_COLLECTION);
+            for geom in gc {
+                write_message(&mut buf, 4, &encode_geometry_message(geom));
+            }
+            return buf;
+        }
+    };
+    write_varint_field(&mut buf, 1, geom_type);
+    if !lengths.is_empty() {
+        write_packed(&mut buf, 2, &lengths);
+    }
+    if !coords.is_empty() {
+        let coords = coords.into_iter().map(zigzag).collect::<Vec<_>>();
+        write_packed(&mut buf, 3, &coords);
+    }
+    buf
+}
+
+/// Rings are written without their closing coord, the ring lengths are always written for
+/// multi polygons and only for polygons with holes otherwise.
+fn add_polygon(coords: &mut Vec<i64>, lengths: &mut Vec<u64>, polygon: &Polygon, multi: bool) {
+    let rings = std::iter::once(polygon.exterior())
+        .chain(polygon.interiors())
+        .collect::<Vec<_>>();
+    if multi {
+        lengths.push(rings.len() as u64);
+    }
+    if multi || rings.len() > 1 {
+        lengths.extend(
+            rings
+                .iter()
+                .map(|ring| ring.0.len().saturating_sub(1) as u64),
+        );
+    }
+    for ring in rings {
+        add_line(coords, &ring.0, true);
+    }
+}
+
+/// Coords of a line are delta encoded from the previous coord of the same line.
+fn add_line(coords: &mut Vec<i64>, line: &[Coord], closed: bool) {
+    let len = if closed {
+        line.len().saturating_sub(1)
+    } else {
+        line.len()
+    };
+    let (mut sum_x, mut sum_y) = (0, 0);
+    for coord in &line[..len] {
+        let (x, y) = (quantize(coord.x), quantize(coord.y));
+        coords.push(x - sum_x);
+        coords.push(y - sum_y);
+        (sum_x, sum_y) = (x, y);
+    }
+}
+
+fn quantize(v: f64) -> i64 {
+    (v * 10f64.powi(PRECISION as i32)).round() as i64
+}
+
+/// Decodes a geobuf message, which holds either a feature collection, a feature or a geometry.
+pub(crate) fn decode_geobuf(data: &[u8]) -> DFResult<GeobufData> {
+    let mut keys = vec![];
+    let mut dimensions = DIMENSIONS;
+    let mut precision = PRECISION;
+    let mut feature_collection = None;
+    let mut feature = None;
+    let mut geometry = None;
+    let mut reader = Reader::new(data);
+    while let Some((field, wire_type)) = reader.read_tag()? {
+        match (field, wire_type) {
+            (1, WIRE_LEN) => keys.push(reader.read_string()?),
+            (2, WIRE_VARINT) => dimensions = reader.read_varint()? as u32,
+            (3, WIRE_VARINT) => precision = reader.read_varint()? as u32,
+            (4, WIRE_LEN) => feature_collection = Some(reader.read_bytes()?),
+            (5, WIRE_LEN) => feature = Some(reader.read_bytes()?),
+            (6, WIRE_LEN) => geometry = Some(reader.read_bytes()?),
+            _ => reader.skip(wire_type)?,
+        }
+    }
+    if dimensions < 2 {
+        return exec_err!("Geobuf dimensions should be at least 2, got {}", dimensions);
+    }
+    let decoder = Decoder {
+        keys,
+        dimensions: dimensions as usize,
+        scale: 10f64.powi(precision as i32),
+    };
+
+    if let Some(collection) = feature_collection {
+        let mut features = vec![];
+        let mut reader = Reader::new(collection);
+        while let Some((field, wire_type)) = reader.read_tag()? {
+            match (field, wire_type) {
+                (1, WIRE_LEN) => features.push(decoder.decode_feature(reader.read_bytes()?)?),
+                _ => reader.skip(wire_type)?,
+            }
+        }
+        Ok(GeobufData::FeatureCollection(features))
+    } else if let Some(feature) = feature {
+        Ok(GeobufData::Feature(decoder.decode_feature(feature)?))
+    } else if let Some(geometry) = geometry {
+        Ok(GeobufData::Geometry(decoder.decode_geometry(geometry)?))
+    } else {
+        exec_err!("Geobuf has no data")
+    }
+}
+
+struct Decoder {
+    keys: Vec<String>,
+    dimensions: usize,
+    scale: f64,
+}
+
+impl Decoder {
+    fn decode_feature(&self, data: &[u8]) -> DFResult<GeobufFeature> {
+        let mut geometry = None;
+        let mut values = vec![];
+        let mut properties = vec![];
+        let mut reader = Reader::new(data);
+        while let Some((field, wire_type)) = reader.read_tag()? {
+            match (field, wire_type) {
+                (1, WIRE_LEN) => geometry = Some(self.decode_geometry(reader.read_bytes()?)?),
+                (13, WIRE_LEN) => values.push(decode_value(reader.read_bytes()?)?),
+                (14, WIRE_LEN) => properties = reader.read_packed()?,
+                _ => reader.skip(wire_type)?,
+            }
+        }
+        let properties = properties
+            .chunks(2)
+            .map(|pair| {
+                let (Some(key), Some(value)) = (
+                    self.keys.get(pair[0] as usize),
+                    pair.get(1).and_then(|i| values.get(*i as usize)),
+                ) else {
+                    return exec_err!("Invalid geobuf feature properties");
+                };
+                Ok((key.clone(), value.clone()))
+            })
+            .collect::<DFResult<Vec<_>>>()?;
+        Ok(GeobufFeature {
+            geometry,
+            properties,
+        })
+    }
+
+    fn decode_geometry(&self, data: &[u8]) -> DFResult<geo::Geometry> {
+        let mut geom_type = POINT;
+        let mut lengths = vec![];
+        let mut coords = vec![];
+        let mut geometries = vec![];
+        let mut reader = Reader::new(data);
+        while let Some((field, wire_type)) = reader.read_tag()? {
+            match (field, wire_type) {
+                (1, WIRE_VARINT) => geom_type = reader.read_varint()?,
+                (2, WIRE_LEN) => lengths = reader.read_packed()?,
+                (3, WIRE_LEN) => coords = reader.read_packed()?.into_iter().map(unzigzag).collect(),
+                (4, WIRE_LEN) => geometries.push(self.decode_geometry(reader.read_bytes()?)?),
+                _ => reader.skip(wire_type)?,
+            }
+        }
+
+        let mut coords = CoordReader {
+            coords: &coords,
+            pos: 0,
+            dimensions: self.dimensions,
+            scale: self.scale,
+        };
+        let geom = match geom_type {
+            POINT => match coords.read_points(1, false)?.first() {
+                Some(coord) => geo::Point(*coord).into(),
+                None => return exec_err!("Geobuf point has no coords"),
+            },
+            MULTI_POINT => {
+                let count = coords.remaining();
+                let points = coords.read_points(count, true)?;
+                geo::MultiPoint::from(points).into()
+            }
+            LINE_STRING => {
+                let count = coords.remaining();
+                LineString::new(coords.read_points(count, true)?).into()
+            }
+            MULTI_LINE_STRING => {
+                if lengths.is_empty() {
+                    lengths.push(coords.remaining() as u64);
+                }
+                let lines = lengths
+                    .iter()
+                    .map(|len| Ok(LineString::new(coords.read_points(*len as usize, true)?)))
+                    .collect::<DFResult<Vec<_>>>()?;
+                geo::MultiLineString::new(lines).into()
+            }
+            POLYGON => {
+                if lengths.is_empty() {
+                    lengths.push(coords.remaining() as u64);
+                }
+                coords.read_polygon(&lengths)?.into()
+            }
+            MULTI_POLYGON => {
+                if lengths.is_empty() {
+                    geo::MultiPolygon::new(vec![coords.read_polygon(&[coords.remaining() as u64])?])
+                        .into()
+                } else {
+                    let mut lengths = lengths.into_iter();
+                    let num_polygons = lengths.next().unwrap_or_default();
+                    let mut polygons = vec![];
+                    for _ in 0..num_polygons {
+                        let num_rings = lengths.next().unwrap_or_default() as usize;
+                        let ring_lengths = lengths.by_ref().take(num_rings).collect::<Vec<_>>();
+                        polygons.push(coords.read_polygon(&ring_lengths)?);
+                    }
+                    geo::MultiPolygon::new(polygons).into()
+                }
+            }
+            GEOMETRY_COLLECTION => geo::GeometryCollection::new_from(geometries).into(),
+            _ => return exec_err!("Unsupported geobuf geometry type {}", geom_type),
+        };
+        Ok(geom)
+    }
+}
+
+fn decode_value(data: &[u8]) -> DFResult<GeobufValue> {
+    let mut reader = Reader::new(data);
+    let mut value = None;
+    while let Some((field, wire_type)) = reader.read_tag()? {
+        value = Some(match (field, wire_type) {
+            (1, WIRE_LEN) => GeobufValue::String(reader.read_string()?),
+            (2, WIRE_FIXED64) => GeobufValue::Double(f64::from_le_bytes(reader.read_fixed()?)),
+            (3, WIRE_VARINT) => GeobufValue::Int(reader.read_varint()? as i64),
+            (4, WIRE_VARINT) => GeobufValue::Int((reader.read_varint()? as i64).wrapping_neg()),
+            (5, WIRE_VARINT) => GeobufValue::Bool(reader.read_varint()? != 0),
+            (6, WIRE_LEN) => GeobufValue::Json(reader.read_string()?),
+            _ => {
+                reader.skip(wire_type)?;
+                continue;
+            }
+        });
+    }
+    value.ok_or_else(|| exec_datafusion_err!("Geobuf value is empty"))
+}
+
+struct CoordReader<'a> {
+    coords: &'a [i64],
+    pos: usize,
+    dimensions: usize,
+    scale: f64,
+}
+
+impl CoordReader<'_> {
+    fn remaining(&self) -> usize {
+        (self.coords.len() - self.pos) / self.dimensions
+    }
+
+    /// Reads the coords of points, which are delta encoded if they belong to a line.
+    fn read_points(&mut self, count: usize, delta: bool) -> DFResult<Vec<Coord>> {
+        let end = self.pos + count * self.dimensions;
+        if end > self.coords.len() {
+            return exec_err!("Geobuf geometry has not enough coords");
+        }
+        let (mut x, mut y) = (0, 0);
+        let mut points = Vec::with_capacity(count);
+        for coord in self.coords[self.pos..end].chunks(self.dimensions) {
+            if delta {
+                (x, y) = (x + coord[0], y + coord[1]);
+            } else {
+                (x, y) = (coord[0], coord[1]);
+            }
+            points.push(Coord {
+                x: x as f64 / self.scale,
+                y: y as f64 / self.scale,
+            });
+        }
+        self.pos = end;
+        Ok(points)
+    }
+
+    fn read_polygon(&mut self, ring_lengths: &[u64]) -> DFResult<Polygon> {
+        let mut rings = ring_lengths
+            .iter()
+            .map(|len| {
+                let mut ring = self.read_points(*len as usize, true)?;
+                if let Some(first) = ring.first() {
+                    ring.push(*first);
+                }
+                Ok(LineString::new(ring))
+            })
+            .collect::<DFResult<Vec<_>>>()?
+            .into_iter();
+        let exterior = rings.next().unwrap_or_else(|| LineString::new(vec![]));
+        Ok(Polygon::new(exterior, rings.collect()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geo::geobuf::{
+        decode_geobuf, encode_feature_collection, encode_geometry, GeobufData, GeobufFeature,
+        GeobufValue,
+    };
+    use geo::{line_string, point, polygon};
+
+    #[test]
+    fn geobuf_round_trip() {
+        let geometries: Vec<geo::Geometry> = vec![
+            point!(x: 1.5, y: -2.25).into(),
+            line_string![(x: 0., y: 0.), (x: 1., y: 1.), (x: 2., y: 0.)].into(),
+            polygon!(
+                exterior: [(x: 0., y: 0.), (x: 4., y: 0.), (x: 4., y: 4.), (x: 0., y: 4.)],
+                interiors: [[(x: 1., y: 1.), (x: 2., y: 1.), (x: 2., y: 2.)]],
+            )
+            .into(),
+            geo::MultiPolygon::new(vec![
+                polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.)],
+                polygon![(x: 5., y: 5.), (x: 6., y: 5.), (x: 6., y: 6.)],
+            ])
+            .into(),
+            geo::GeometryCollection::new_from(vec![point!(x: 1., y: 2.).into()]).into(),
+        ];
+        for geom in geometries {
+            assert_eq!(
+                decode_geobuf(&encode_geometry(&geom)).unwrap(),
+                GeobufData::Geometry(geom)
+            );
+        }
+
+        let features = vec![
+            GeobufFeature {
+                geometry: Some(point!(x: 1., y: 2.).into()),
+                properties: vec![
+                    ("name".to_string(), GeobufValue::String("a".to_string())),
+                    ("pop".to_string(), GeobufValue::Int(-3)),
+                ],
+            },
+            GeobufFeature {
+                geometry: None,
+                properties: vec![("pop".to_string(), GeobufValue::Double(0.5))],
+            },
+        ];
+        assert_eq!(
+            decode_geobuf(&encode_feature_collection(&features)).unwrap(),
+            GeobufData::FeatureCollection(features)
+        );
+    }
+}
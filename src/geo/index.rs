@@ -49,7 +49,10 @@ mod tests {
             (x: 0., y: 0.),
             (x: -1., y: -1.)
         ];
-        let builder: GeometryArrayBuilder<i32> = vec![Some(ls0), None, Some(ls2)].as_slice().into();
+        let builder: GeometryArrayBuilder<i32> = vec![Some(ls0), None, Some(ls2)]
+            .as_slice()
+            .try_into()
+            .unwrap();
         let wkb_arr = builder.build();
 
         let index = build_rtree_index(wkb_arr).unwrap();
@@ -0,0 +1,71 @@
+//! Mapping of geometries member by member.
+
+use geo::HasDimensions;
+
+/// Applies `f` to every member of the geometry which is not a collection, recursing into
+/// (nested) collections. Empty geometries, including points with NaN coordinates, are passed
+/// through unchanged.
+pub(crate) fn map_geometry_recursive(
+    geom: geo::Geometry,
+    f: &mut impl FnMut(geo::Geometry) -> geo::Geometry,
+) -> geo::Geometry {
+    match geom {
+        geo::Geometry::GeometryCollection(gc) => geo::Geometry::GeometryCollection(
+            gc.into_iter()
+                .map(|geom| map_geometry_recursive(geom, f))
+                .collect(),
+        ),
+        geom if is_empty(&geom) => geom,
+        geom => f(geom),
+    }
+}
+
+/// Whether the geometry is empty, an empty point is decoded as a point with NaN coordinates.
+pub(crate) fn is_empty(geom: &geo::Geometry) -> bool {
+    match geom {
+        geo::Geometry::Point(point) => point.x().is_nan() && point.y().is_nan(),
+        geom => geom.is_empty(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geo::map::map_geometry_recursive;
+    use geo::{line_string, point, Translate};
+
+    #[test]
+    fn map_members_recursively() {
+        let nested = geo::GeometryCollection::new_from(vec![
+            point!(x: 1., y: 1.).into(),
+            geo::GeometryCollection::new_from(vec![
+                line_string![(x: 0., y: 0.), (x: 1., y: 1.)].into(),
+                geo::LineString::new(vec![]).into(),
+            ])
+            .into(),
+            point!(x: f64::NAN, y: f64::NAN).into(),
+        ]);
+        let mut calls = 0;
+        let mapped = map_geometry_recursive(nested.into(), &mut |geom| {
+            calls += 1;
+            geom.translate(1., 2.)
+        });
+        assert_eq!(calls, 2);
+
+        let geo::Geometry::GeometryCollection(mapped) = mapped else {
+            panic!("mapped geometry should be a collection");
+        };
+        assert_eq!(mapped.0[0], point!(x: 2., y: 3.).into());
+        let geo::Geometry::GeometryCollection(inner) = &mapped.0[1] else {
+            panic!("nested collection should be kept");
+        };
+        assert_eq!(
+            inner.0[0],
+            line_string![(x: 1., y: 2.), (x: 2., y: 3.)].into()
+        );
+        assert_eq!(inner.0[1], geo::LineString::new(vec![]).into());
+        let geo::Geometry::Point(empty) = mapped.0[2] else {
+            panic!("empty point should be kept");
+        };
+        assert!(empty.x().is_nan());
+    }
+}
@@ -1,10 +1,31 @@
 mod array;
 mod r#box;
 mod builder;
+#[cfg(feature = "geos")]
+mod capabilities;
+mod clip;
+mod crs;
 pub(crate) mod dialect;
+pub(crate) mod format;
+pub(crate) mod geobuf;
 mod index;
+pub(crate) mod map;
+pub mod mvt;
+#[cfg(feature = "geos")]
+pub(crate) mod overlay;
+#[cfg(feature = "proj")]
+pub(crate) mod proj_cache;
+pub(crate) mod protobuf;
+mod scalar;
+pub(crate) mod wkt;
+pub(crate) mod xyz;
 
 pub use array::*;
 pub use builder::*;
+#[cfg(feature = "geos")]
+pub use capabilities::*;
+pub use clip::*;
+pub use crs::*;
 pub use index::*;
 pub use r#box::*;
+pub use scalar::*;
@@ -0,0 +1,482 @@
+//! Encoding and decoding of [mapbox vector tiles](https://github.com/mapbox/vector-tile-spec),
+//! the protobuf encoding of tiles made of layers of features in tile coordinates.
+
+use crate::geo::protobuf::{
+    unzigzag, write_bytes, write_message, write_packed, write_tag, write_varint_field, zigzag,
+    Reader, WIRE_FIXED32, WIRE_FIXED64, WIRE_LEN, WIRE_VARINT,
+};
+use crate::DFResult;
+use datafusion_common::{exec_datafusion_err, exec_err, DataFusionError};
+use geo::orient::Direction;
+use geo::{Coord, LineString, MultiLineString, MultiPoint, MultiPolygon, Orient, Point, Polygon};
+use std::collections::HashMap;
+
+const VERSION: u64 = 2;
+
+const POINT: u64 = 1;
+const LINE_STRING: u64 = 2;
+const POLYGON: u64 = 3;
+
+const MOVE_TO: u32 = 1;
+const LINE_TO: u32 = 2;
+const CLOSE_PATH: u32 = 7;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MvtValue {
+    String(String),
+    Double(f64),
+    Int(i64),
+    Bool(bool),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MvtFeature {
+    /// Geometry in tile coordinates, the y axis points down.
+    pub geometry: geo::Geometry,
+    pub properties: Vec<(String, MvtValue)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MvtLayer {
+    pub name: String,
+    pub extent: u32,
+    pub features: Vec<MvtFeature>,
+}
+
+/// Encodes the layers into a tile. The coordinates are rounded to integers, features without an
+/// encodable geometry, i.e. empty geometries and geometry collections, are left out.
+pub fn encode_tile(layers: &[MvtLayer]) -> Vec<u8> {
+    let mut tile = vec![];
+    for layer in layers {
+        write_message(&mut tile, 3, &encode_layer(layer));
+    }
+    tile
+}
+
+fn encode_layer(layer: &MvtLayer) -> Vec<u8> {
+    let mut keys: HashMap<&str, u64> = HashMap::new();
+    let mut key_list = vec![];
+    // values are deduplicated by their encoding, doubles have no hash
+    let mut values: HashMap<Vec<u8>, u64> = HashMap::new();
+    let mut value_list = vec![];
+
+    let mut buf = vec![];
+    write_varint_field(&mut buf, 15, VERSION);
+    write_bytes(&mut buf, 1, layer.name.as_bytes());
+    for feature in &layer.features {
+        let Some((geom_type, commands)) = encode_geometry(&feature.geometry) else {
+            continue;
+        };
+        let mut tags = vec![];
+        for (key, value) in &feature.properties {
+            let key_index = *keys.entry(key.as_str()).or_insert_with(|| {
+                key_list.push(key.as_str());
+                key_list.len() as u64 - 1
+            });
+            let value = encode_value(value);
+            let value_index = match values.get(&value) {
+                Some(index) => *index,
+                None => {
+                    value_list.push(value.clone());
+                    values.insert(value, value_list.len() as u64 - 1);
+                    value_list.len() as u64 - 1
+                }
+            };
+            tags.push(key_index);
+            tags.push(value_index);
+        }
+        let mut message = vec![];
+        if !tags.is_empty() {
+            write_packed(&mut message, 2, &tags);
+        }
+        write_varint_field(&mut message, 3, geom_type);
+        write_packed(&mut message, 4, &commands);
+        write_message(&mut buf, 2, &message);
+    }
+    for key in key_list {
+        write_bytes(&mut buf, 3, key.as_bytes());
+    }
+    for value in value_list {
+        write_message(&mut buf, 4, &value);
+    }
+    write_varint_field(&mut buf, 5, layer.extent as u64);
+    buf
+}
+
+fn encode_value(value: &MvtValue) -> Vec<u8> {
+    let mut buf = vec![];
+    match value {
+        MvtValue::String(v) => write_bytes(&mut buf, 1, v.as_bytes()),
+        MvtValue::Double(v) => {
+            write_tag(&mut buf, 3, WIRE_FIXED64);
+            buf.extend_from_slice(&v.to_le_bytes());
+        }
+        MvtValue::Int(v) if *v >= 0 => write_varint_field(&mut buf, 5, *v as u64),
+        MvtValue::Int(v) => write_varint_field(&mut buf, 6, zigzag(*v)),
+        MvtValue::Bool(v) => write_varint_field(&mut buf, 7, *v as u64),
+    }
+    buf
+}
+
+/// Geometry type and commands of a geometry, the cursor of the commands starts at the origin.
+fn encode_geometry(geom: &geo::Geometry) -> Option<(u64, Vec<u64>)> {
+    let mut commands = Commands::default();
+    let geom_type = match geom {
+        geo::Geometry::Point(point) => {
+            commands.points(&[point.0]);
+            POINT
+        }
+        geo::Geometry::MultiPoint(points) => {
+            commands.points(&points.iter().map(|point| point.0).collect::<Vec<_>>());
+            POINT
+        }
+        geo::Geometry::Line(line) => {
+            commands.line(&[line.start, line.end]);
+            LINE_STRING
+        }
+        geo::Geometry::LineString(line) => {
+            commands.line(&line.0);
+            LINE_STRING
+        }
+        geo::Geometry::MultiLineString(lines) => {
+            for line in lines {
+                commands.line(&line.0);
+            }
+            LINE_STRING
+        }
+        geo::Geometry::Polygon(polygon) => {
+            commands.polygon(polygon);
+            POLYGON
+        }
+        geo::Geometry::MultiPolygon(polygons) => {
+            for polygon in polygons {
+                commands.polygon(polygon);
+            }
+            POLYGON
+        }
+        geo::Geometry::Rect(rect) => {
+            commands.polygon(&rect.to_polygon());
+            POLYGON
+        }
+        geo::Geometry::Triangle(triangle) => {
+            commands.polygon(&triangle.to_polygon());
+            POLYGON
+        }
+        geo::Geometry::GeometryCollection(_) => return None,
+    };
+    (!commands.commands.is_empty()).then_some((geom_type, commands.commands))
+}
+
+#[derive(Default)]
+struct Commands {
+    commands: Vec<u64>,
+    cursor: (i64, i64),
+}
+
+impl Commands {
+    fn command(&mut self, id: u32, count: usize) {
+        self.commands
+            .push(((id & 0x7) | ((count as u32) << 3)) as u64);
+    }
+
+    fn coord(&mut self, coord: Coord) {
+        let (x, y) = (coord.x.round() as i64, coord.y.round() as i64);
+        self.commands.push(zigzag(x - self.cursor.0));
+        self.commands.push(zigzag(y - self.cursor.1));
+        self.cursor = (x, y);
+    }
+
+    fn points(&mut self, points: &[Coord]) {
+        if points.is_empty() {
+            return;
+        }
+        self.command(MOVE_TO, points.len());
+        for point in points {
+            self.coord(*point);
+        }
+    }
+
+    fn line(&mut self, line: &[Coord]) {
+        if line.len() < 2 {
+            return;
+        }
+        self.command(MOVE_TO, 1);
+        self.coord(line[0]);
+        self.command(LINE_TO, line.len() - 1);
+        for coord in &line[1..] {
+            self.coord(*coord);
+        }
+    }
+
+    /// The exterior ring has a positive area in tile coordinates, i.e. it is clockwise with the y
+    /// axis pointing down, the interior rings have a negative area.
+    fn polygon(&mut self, polygon: &Polygon) {
+        let polygon = polygon.orient(Direction::Default);
+        if polygon.exterior().0.len() < 4 {
+            return;
+        }
+        for ring in std::iter::once(polygon.exterior()).chain(polygon.interiors()) {
+            // the closing coord is implied by the close path command
+            let coords = &ring.0[..ring.0.len() - 1];
+            if coords.len() < 3 {
+                continue;
+            }
+            self.command(MOVE_TO, 1);
+            self.coord(coords[0]);
+            self.command(LINE_TO, coords.len() - 1);
+            for coord in &coords[1..] {
+                self.coord(*coord);
+            }
+            self.command(CLOSE_PATH, 1);
+        }
+    }
+}
+
+/// Decodes the layers of a tile.
+pub fn decode_tile(data: &[u8]) -> DFResult<Vec<MvtLayer>> {
+    let mut layers = vec![];
+    let mut reader = Reader::new(data);
+    while let Some((field, wire_type)) = reader.read_tag()? {
+        match (field, wire_type) {
+            (3, WIRE_LEN) => layers.push(decode_layer(reader.read_bytes()?)?),
+            _ => reader.skip(wire_type)?,
+        }
+    }
+    Ok(layers)
+}
+
+fn decode_layer(data: &[u8]) -> DFResult<MvtLayer> {
+    let mut name = String::new();
+    let mut extent = 4096;
+    let mut keys = vec![];
+    let mut values = vec![];
+    let mut features = vec![];
+    let mut reader = Reader::new(data);
+    while let Some((field, wire_type)) = reader.read_tag()? {
+        match (field, wire_type) {
+            (1, WIRE_LEN) => name = reader.read_string()?,
+            (2, WIRE_LEN) => features.push(reader.read_bytes()?),
+            (3, WIRE_LEN) => keys.push(reader.read_string()?),
+            (4, WIRE_LEN) => values.push(decode_value(reader.read_bytes()?)?),
+            (5, WIRE_VARINT) => extent = reader.read_varint()? as u32,
+            _ => reader.skip(wire_type)?,
+        }
+    }
+    let features = features
+        .into_iter()
+        .map(|feature| decode_feature(feature, &keys, &values))
+        .collect::<DFResult<Vec<_>>>()?;
+    Ok(MvtLayer {
+        name,
+        extent,
+        features,
+    })
+}
+
+fn decode_feature(data: &[u8], keys: &[String], values: &[MvtValue]) -> DFResult<MvtFeature> {
+    let mut tags = vec![];
+    let mut geom_type = 0;
+    let mut commands = vec![];
+    let mut reader = Reader::new(data);
+    while let Some((field, wire_type)) = reader.read_tag()? {
+        match (field, wire_type) {
+            (2, WIRE_LEN) => tags = reader.read_packed()?,
+            (3, WIRE_VARINT) => geom_type = reader.read_varint()?,
+            (4, WIRE_LEN) => commands = reader.read_packed()?,
+            _ => reader.skip(wire_type)?,
+        }
+    }
+    let properties = tags
+        .chunks(2)
+        .map(|pair| {
+            let (Some(key), Some(value)) = (
+                keys.get(pair[0] as usize),
+                pair.get(1).and_then(|i| values.get(*i as usize)),
+            ) else {
+                return exec_err!("Invalid vector tile feature tags");
+            };
+            Ok((key.clone(), value.clone()))
+        })
+        .collect::<DFResult<Vec<_>>>()?;
+    Ok(MvtFeature {
+        geometry: decode_geometry(geom_type, &commands)?,
+        properties,
+    })
+}
+
+fn decode_value(data: &[u8]) -> DFResult<MvtValue> {
+    let mut reader = Reader::new(data);
+    let mut value = None;
+    while let Some((field, wire_type)) = reader.read_tag()? {
+        value = Some(match (field, wire_type) {
+            (1, WIRE_LEN) => MvtValue::String(reader.read_string()?),
+            (2, WIRE_FIXED32) => MvtValue::Double(f32::from_le_bytes(reader.read_fixed()?) as f64),
+            (3, WIRE_FIXED64) => MvtValue::Double(f64::from_le_bytes(reader.read_fixed()?)),
+            (4, WIRE_VARINT) => MvtValue::Int(reader.read_varint()? as i64),
+            (5, WIRE_VARINT) => match reader.read_varint()? {
+                v if v <= i64::MAX as u64 => MvtValue::Int(v as i64),
+                v => MvtValue::Double(v as f64),
+            },
+            (6, WIRE_VARINT) => MvtValue::Int(unzigzag(reader.read_varint()?)),
+            (7, WIRE_VARINT) => MvtValue::Bool(reader.read_varint()? != 0),
+            _ => {
+                reader.skip(wire_type)?;
+                continue;
+            }
+        });
+    }
+    value.ok_or_else(|| exec_datafusion_err!("Vector tile value is empty"))
+}
+
+/// Decodes the paths of the commands, a path starts at each move to.
+fn decode_paths(commands: &[u64]) -> DFResult<Vec<Vec<Coord>>> {
+    let mut paths: Vec<Vec<Coord>> = vec![];
+    let (mut x, mut y) = (0i64, 0i64);
+    let mut commands = commands.iter();
+    while let Some(command) = commands.next() {
+        let (id, count) = ((command & 0x7) as u32, (command >> 3) as usize);
+        match id {
+            MOVE_TO | LINE_TO => {
+                for _ in 0..count {
+                    let (Some(dx), Some(dy)) = (commands.next(), commands.next()) else {
+                        return exec_err!("Vector tile geometry has not enough coords");
+                    };
+                    (x, y) = (x + unzigzag(*dx), y + unzigzag(*dy));
+                    let coord = Coord {
+                        x: x as f64,
+                        y: y as f64,
+                    };
+                    match (id, paths.last_mut()) {
+                        (MOVE_TO, _) => paths.push(vec![coord]),
+                        (_, Some(path)) => path.push(coord),
+                        (_, None) => return exec_err!("Vector tile line to without move to"),
+                    }
+                }
+            }
+            CLOSE_PATH => match paths.last_mut() {
+                Some(path) if !path.is_empty() => path.push(path[0]),
+                _ => return exec_err!("Vector tile close path without move to"),
+            },
+            _ => return exec_err!("Unknown vector tile command {}", id),
+        }
+    }
+    Ok(paths)
+}
+
+fn decode_geometry(geom_type: u64, commands: &[u64]) -> DFResult<geo::Geometry> {
+    let paths = decode_paths(commands)?;
+    let geom = match geom_type {
+        POINT => {
+            let mut points = paths.into_iter().flatten().map(Point).collect::<Vec<_>>();
+            match points.len() {
+                1 => points.remove(0).into(),
+                _ => MultiPoint::new(points).into(),
+            }
+        }
+        LINE_STRING => {
+            let mut lines = paths.into_iter().map(LineString::new).collect::<Vec<_>>();
+            match lines.len() {
+                1 => lines.remove(0).into(),
+                _ => MultiLineString::new(lines).into(),
+            }
+        }
+        POLYGON => {
+            // a ring of positive area starts a polygon, the following negative ones are its holes
+            let mut polygons: Vec<Polygon> = vec![];
+            for ring in paths.into_iter().map(LineString::new) {
+                let area = ring_area(&ring);
+                if area > 0.0 {
+                    polygons.push(Polygon::new(ring, vec![]));
+                } else if area < 0.0 {
+                    match polygons.last_mut() {
+                        Some(polygon) => polygon.interiors_push(ring),
+                        None => return exec_err!("Vector tile polygon starts with a hole"),
+                    }
+                }
+            }
+            match polygons.len() {
+                1 => polygons.remove(0).into(),
+                _ => MultiPolygon::new(polygons).into(),
+            }
+        }
+        _ => return exec_err!("Unsupported vector tile geometry type {}", geom_type),
+    };
+    Ok(geom)
+}
+
+/// Signed area of the surveyor's formula.
+fn ring_area(ring: &LineString) -> f64 {
+    ring.lines()
+        .map(|line| line.start.x * line.end.y - line.end.x * line.start.y)
+        .sum::<f64>()
+        / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geo::mvt::{decode_tile, encode_tile, MvtFeature, MvtLayer, MvtValue};
+    use geo::{line_string, point, polygon};
+
+    #[test]
+    fn mvt_round_trip() {
+        let features = vec![
+            MvtFeature {
+                geometry: point!(x: 25., y: 17.).into(),
+                properties: vec![
+                    ("name".to_string(), MvtValue::String("a".to_string())),
+                    ("pop".to_string(), MvtValue::Int(-3)),
+                ],
+            },
+            MvtFeature {
+                geometry: geo::MultiPoint::new(vec![point!(x: 5., y: 7.), point!(x: 3., y: 2.)])
+                    .into(),
+                properties: vec![("name".to_string(), MvtValue::String("a".to_string()))],
+            },
+            MvtFeature {
+                geometry: line_string![(x: 2., y: 2.), (x: 2., y: 10.), (x: 10., y: 10.)].into(),
+                properties: vec![("ratio".to_string(), MvtValue::Double(0.5))],
+            },
+            MvtFeature {
+                geometry: polygon!(
+                    exterior: [(x: 0., y: 0.), (x: 10., y: 0.), (x: 10., y: 10.), (x: 0., y: 10.)],
+                    interiors: [[(x: 2., y: 2.), (x: 2., y: 4.), (x: 4., y: 4.), (x: 4., y: 2.)]],
+                )
+                .into(),
+                properties: vec![("big".to_string(), MvtValue::Bool(true))],
+            },
+            MvtFeature {
+                geometry: geo::MultiPolygon::new(vec![
+                    polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.)],
+                    polygon![(x: 5., y: 5.), (x: 6., y: 5.), (x: 6., y: 6.)],
+                ])
+                .into(),
+                properties: vec![],
+            },
+        ];
+        let layer = MvtLayer {
+            name: "places".to_string(),
+            extent: 4096,
+            features,
+        };
+        let layers = decode_tile(&encode_tile(&[layer.clone()])).unwrap();
+        assert_eq!(layers, vec![layer]);
+    }
+
+    #[test]
+    fn mvt_spec_example() {
+        // the polygon example of the vector tile specification, its exterior ring is clockwise
+        // with the y axis pointing down
+        let feature = MvtFeature {
+            geometry: polygon![(x: 3., y: 6.), (x: 8., y: 12.), (x: 20., y: 34.)].into(),
+            properties: vec![],
+        };
+        let layer = MvtLayer {
+            name: "a".to_string(),
+            extent: 4096,
+            features: vec![feature],
+        };
+        let tile = encode_tile(&[layer]);
+        let commands = [9u8, 6, 12, 18, 10, 12, 24, 44, 15];
+        assert!(tile.windows(commands.len()).any(|w| w == commands));
+    }
+}
@@ -0,0 +1,72 @@
+//! Overlays of geos geometries with an optional grid size.
+//!
+//! With a grid size the overlays run the fixed precision mode of OverlayNG, which snap rounds the
+//! inputs and the result to the grid and so avoids the topology errors of nearly coincident or
+//! self intersecting edges. The `*Prec` functions of OverlayNG need GEOS 3.9, callers check the
+//! version with [`geos_capabilities`](crate::geo::geos_capabilities).
+use crate::DFResult;
+use datafusion_common::{internal_datafusion_err, DataFusionError};
+use geos::Geom;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Overlay {
+    Intersection,
+    Union,
+    Difference,
+    SymDifference,
+}
+
+impl Overlay {
+    fn name(self) -> &'static str {
+        match self {
+            Overlay::Intersection => "intersection",
+            Overlay::Union => "union",
+            Overlay::Difference => "difference",
+            Overlay::SymDifference => "sym difference",
+        }
+    }
+}
+
+/// Overlays two geometries, the result has the srid of the first one.
+pub(crate) fn overlay(
+    op: Overlay,
+    geom0: &geos::Geometry,
+    geom1: &geos::Geometry,
+    grid_size: Option<f64>,
+) -> DFResult<geos::Geometry> {
+    let result = match (op, grid_size) {
+        (Overlay::Intersection, None) => geom0.intersection(geom1),
+        (Overlay::Union, None) => geom0.union(geom1),
+        (Overlay::Difference, None) => geom0.difference(geom1),
+        (Overlay::SymDifference, None) => geom0.sym_difference(geom1),
+        (Overlay::Intersection, Some(grid_size)) => geom0.intersection_prec(geom1, grid_size),
+        (Overlay::Union, Some(grid_size)) => geom0.union_prec(geom1, grid_size),
+        (Overlay::Difference, Some(grid_size)) => geom0.difference_prec(geom1, grid_size),
+        (Overlay::SymDifference, Some(grid_size)) => geom0.sym_difference_prec(geom1, grid_size),
+    };
+    let mut result =
+        result.map_err(|e| internal_datafusion_err!("Failed to do {}, error: {}", op.name(), e))?;
+    if let Ok(srid) = geom0.get_srid() {
+        result.set_srid(srid);
+    }
+    Ok(result)
+}
+
+/// Cascaded union of the geometries, None if there are no geometries.
+pub(crate) fn unary_union(
+    geoms: Vec<geos::Geometry>,
+    grid_size: Option<f64>,
+) -> DFResult<Option<geos::Geometry>> {
+    if geoms.is_empty() {
+        return Ok(None);
+    }
+    let collection = geos::Geometry::create_geometry_collection(geoms)
+        .map_err(|e| internal_datafusion_err!("Failed to create collection, e: {}", e))?;
+    let union = match grid_size {
+        None => collection.unary_union(),
+        Some(grid_size) => collection.unary_union_prec(grid_size),
+    };
+    let union =
+        union.map_err(|e| internal_datafusion_err!("Failed to do unary union, e: {}", e))?;
+    Ok(Some(union))
+}
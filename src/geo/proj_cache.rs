@@ -0,0 +1,161 @@
+//! Process wide cache of proj transformations.
+//!
+//! Building a transformation reads the definitions of both systems from the proj database, which
+//! takes far longer than transforming a batch of points. The transformations are kept per pair of
+//! EPSG codes, up to the capacity of [`GeoConfig`](crate::config::GeoConfig), and shared by all
+//! the functions reprojecting geometries.
+use crate::config::GeoConfig;
+use crate::metrics;
+use crate::DFResult;
+use datafusion_common::{exec_datafusion_err, internal_datafusion_err, DataFusionError};
+use proj::Proj;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, RwLock, RwLockReadGuard};
+
+/// A proj transformation, locked while converting as proj objects can't be used concurrently.
+pub(crate) type Transformation = Arc<Mutex<Proj>>;
+
+/// Returns the transformation from the source to the target EPSG code.
+pub(crate) fn transformation(source: i64, target: i64) -> DFResult<Transformation> {
+    static CACHE: OnceLock<TransformationCache> = OnceLock::new();
+    CACHE
+        .get_or_init(|| TransformationCache::new(GeoConfig::get().proj_cache_capacity))
+        .get(source, target)
+}
+
+/// Least recently used transformations, the lookups only take the read lock and the recency is
+/// a tick of a shared clock.
+#[derive(Debug)]
+pub(crate) struct TransformationCache {
+    capacity: usize,
+    clock: AtomicU64,
+    entries: RwLock<HashMap<(i64, i64), Entry>>,
+}
+
+#[derive(Debug)]
+struct Entry {
+    transformation: Transformation,
+    last_used: AtomicU64,
+}
+
+impl TransformationCache {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            clock: AtomicU64::new(0),
+            entries: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub(crate) fn get(&self, source: i64, target: i64) -> DFResult<Transformation> {
+        let key = (source, target);
+        let tick = self.clock.fetch_add(1, Ordering::Relaxed);
+        if let Some(entry) = self.read()?.get(&key) {
+            entry.last_used.fetch_max(tick, Ordering::Relaxed);
+            metrics::record_proj_cache(true);
+            return Ok(entry.transformation.clone());
+        }
+        metrics::record_proj_cache(false);
+        // built without holding the lock, a transformation inserted meanwhile by another thread
+        // is kept instead
+        let transformation = Arc::new(Mutex::new(build(source, target)?));
+        if self.capacity == 0 {
+            return Ok(transformation);
+        }
+        let mut entries = self
+            .entries
+            .write()
+            .map_err(|e| internal_datafusion_err!("Failed to lock the proj cache, e: {}", e))?;
+        if entries.len() >= self.capacity && !entries.contains_key(&key) {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used.load(Ordering::Relaxed))
+                .map(|(key, _)| *key);
+            if let Some(oldest) = oldest {
+                entries.remove(&oldest);
+            }
+        }
+        let entry = entries.entry(key).or_insert_with(|| Entry {
+            transformation,
+            last_used: AtomicU64::new(tick),
+        });
+        Ok(entry.transformation.clone())
+    }
+
+    fn read(&self) -> DFResult<RwLockReadGuard<'_, HashMap<(i64, i64), Entry>>> {
+        self.entries
+            .read()
+            .map_err(|e| internal_datafusion_err!("Failed to lock the proj cache, e: {}", e))
+    }
+}
+
+fn build(source: i64, target: i64) -> DFResult<Proj> {
+    Proj::new_known_crs(
+        &format!("EPSG:{}", source),
+        &format!("EPSG:{}", target),
+        None,
+    )
+    .map_err(|e| {
+        exec_datafusion_err!(
+            "Failed to create transformation from EPSG:{} to EPSG:{}, error: {}",
+            source,
+            target,
+            e
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geo::proj_cache::TransformationCache;
+    use rayon::prelude::*;
+    use std::sync::Arc;
+
+    fn cached(cache: &TransformationCache) -> Vec<(i64, i64)> {
+        let mut keys = cache.read().unwrap().keys().copied().collect::<Vec<_>>();
+        keys.sort();
+        keys
+    }
+
+    #[test]
+    fn evict_least_recently_used() {
+        let cache = TransformationCache::new(2);
+        let web_mercator = cache.get(4326, 3857).unwrap();
+        cache.get(4326, 32633).unwrap();
+        assert!(Arc::ptr_eq(&web_mercator, &cache.get(4326, 3857).unwrap()));
+        cache.get(3857, 4326).unwrap();
+        assert_eq!(cached(&cache), vec![(3857, 4326), (4326, 3857)]);
+
+        assert!(cache.get(4326, 999999).is_err());
+        assert_eq!(cached(&cache), vec![(3857, 4326), (4326, 3857)]);
+
+        let uncached = TransformationCache::new(0);
+        uncached.get(4326, 3857).unwrap();
+        assert!(cached(&uncached).is_empty());
+    }
+
+    #[test]
+    fn concurrent_lookups() {
+        // more pairs than the capacity, so the threads evict each other's transformations
+        let pairs = [(4326, 3857), (4326, 32633), (3857, 4326), (4326, 2154)];
+        let cache = TransformationCache::new(2);
+        let converted = (0..2000)
+            .into_par_iter()
+            .map(|i| {
+                let (source, target) = pairs[i % pairs.len()];
+                let transformation = cache.get(source, target).unwrap();
+                let proj = transformation.lock().unwrap();
+                let (x, y) = match source {
+                    4326 => (2.35, 48.85),
+                    _ => (261600.0, 6250000.0),
+                };
+                proj.convert((x, y)).unwrap()
+            })
+            .collect::<Vec<(f64, f64)>>();
+        assert!(converted
+            .iter()
+            .all(|(x, y)| x.is_finite() && y.is_finite()));
+        assert_eq!(cached(&cache).len(), 2);
+    }
+}
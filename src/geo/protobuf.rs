@@ -0,0 +1,143 @@
+//! Protobuf wire format helpers shared by the geobuf and the mapbox vector tile encodings.
+
+use crate::DFResult;
+use datafusion_common::{exec_datafusion_err, exec_err, DataFusionError};
+
+pub(crate) const WIRE_VARINT: u8 = 0;
+pub(crate) const WIRE_FIXED64: u8 = 1;
+pub(crate) const WIRE_LEN: u8 = 2;
+pub(crate) const WIRE_FIXED32: u8 = 5;
+
+pub(crate) fn zigzag(v: i64) -> u64 {
+    ((v << 1) ^ (v >> 63)) as u64
+}
+
+pub(crate) fn unzigzag(v: u64) -> i64 {
+    ((v >> 1) as i64) ^ -((v & 1) as i64)
+}
+
+pub(crate) fn write_varint(buf: &mut Vec<u8>, mut v: u64) {
+    while v >= 0x80 {
+        buf.push((v as u8) | 0x80);
+        v >>= 7;
+    }
+    buf.push(v as u8);
+}
+
+pub(crate) fn write_tag(buf: &mut Vec<u8>, field: u32, wire_type: u8) {
+    write_varint(buf, ((field as u64) << 3) | wire_type as u64);
+}
+
+pub(crate) fn write_varint_field(buf: &mut Vec<u8>, field: u32, v: u64) {
+    write_tag(buf, field, WIRE_VARINT);
+    write_varint(buf, v);
+}
+
+pub(crate) fn write_bytes(buf: &mut Vec<u8>, field: u32, bytes: &[u8]) {
+    write_tag(buf, field, WIRE_LEN);
+    write_varint(buf, bytes.len() as u64);
+    buf.extend_from_slice(bytes);
+}
+
+pub(crate) fn write_message(buf: &mut Vec<u8>, field: u32, message: &[u8]) {
+    write_bytes(buf, field, message);
+}
+
+pub(crate) fn write_packed(buf: &mut Vec<u8>, field: u32, values: &[u64]) {
+    let mut packed = vec![];
+    for v in values {
+        write_varint(&mut packed, *v);
+    }
+    write_bytes(buf, field, &packed);
+}
+
+/// Reads the fields of a protobuf message.
+pub(crate) struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    pub(crate) fn read_tag(&mut self) -> DFResult<Option<(u32, u8)>> {
+        if self.pos >= self.data.len() {
+            return Ok(None);
+        }
+        let tag = self.read_varint()?;
+        Ok(Some(((tag >> 3) as u32, (tag & 0x07) as u8)))
+    }
+
+    pub(crate) fn read_varint(&mut self) -> DFResult<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let Some(byte) = self.data.get(self.pos) else {
+                return exec_err!("Truncated protobuf varint");
+            };
+            self.pos += 1;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        exec_err!("Invalid protobuf varint")
+    }
+
+    pub(crate) fn read_bytes(&mut self) -> DFResult<&'a [u8]> {
+        let len = self.read_varint()? as usize;
+        let end = self.pos + len;
+        if end > self.data.len() {
+            return exec_err!("Truncated protobuf message");
+        }
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    pub(crate) fn read_string(&mut self) -> DFResult<String> {
+        let bytes = self.read_bytes()?;
+        String::from_utf8(bytes.to_vec())
+            .map_err(|e| exec_datafusion_err!("Invalid protobuf string, e: {}", e))
+    }
+
+    pub(crate) fn read_fixed<const N: usize>(&mut self) -> DFResult<[u8; N]> {
+        let end = self.pos + N;
+        if end > self.data.len() {
+            return exec_err!("Truncated protobuf fixed value");
+        }
+        let mut bytes = [0; N];
+        bytes.copy_from_slice(&self.data[self.pos..end]);
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    pub(crate) fn read_packed(&mut self) -> DFResult<Vec<u64>> {
+        let mut reader = Reader::new(self.read_bytes()?);
+        let mut values = vec![];
+        while reader.pos < reader.data.len() {
+            values.push(reader.read_varint()?);
+        }
+        Ok(values)
+    }
+
+    pub(crate) fn skip(&mut self, wire_type: u8) -> DFResult<()> {
+        match wire_type {
+            WIRE_VARINT => {
+                self.read_varint()?;
+            }
+            WIRE_FIXED64 => {
+                self.read_fixed::<8>()?;
+            }
+            WIRE_LEN => {
+                self.read_bytes()?;
+            }
+            WIRE_FIXED32 => {
+                self.read_fixed::<4>()?;
+            }
+            _ => return exec_err!("Unsupported protobuf wire type {}", wire_type),
+        }
+        Ok(())
+    }
+}
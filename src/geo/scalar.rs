@@ -0,0 +1,105 @@
+use crate::config::default_dialect;
+use crate::geo::dialect::{decode_wkb_dialect, wkb_type_id};
+use crate::DFResult;
+use datafusion_common::{internal_datafusion_err, internal_err, DataFusionError, ScalarValue};
+use geozero::wkb::FromWkb;
+use geozero::{GeozeroGeometry, ToWkb};
+
+/// Encodes a geometry into a binary scalar in the default dialect, e.g. to embed a geometry constant
+/// into an expr with `lit(geometry_scalar(&geom, Some(4326))?)`. Fails for a non-zero srid if the
+/// default dialect can't store it.
+pub fn geometry_scalar(geom: &geo::Geometry, srid: Option<i32>) -> DFResult<ScalarValue> {
+    let dialect = default_dialect();
+    check_srid_storable(dialect, srid)?;
+    let wkb = geom
+        .to_wkb_dialect(dialect, geom.dims(), srid, vec![])
+        .map_err(|e| internal_datafusion_err!("Failed to convert to wkb, error: {}", e))?;
+    let mut bytes = vec![wkb_type_id(dialect)];
+    bytes.extend_from_slice(&wkb);
+    Ok(ScalarValue::Binary(Some(bytes)))
+}
+
+/// Decodes a binary or large binary geometry scalar, a null scalar produces None.
+pub fn scalar_to_geometry(value: &ScalarValue) -> DFResult<Option<geo::Geometry>> {
+    let wkb = match value {
+        ScalarValue::Binary(wkb) | ScalarValue::LargeBinary(wkb) => wkb,
+        _ => return internal_err!("ScalarValue is not binary"),
+    };
+    let Some((type_id, data)) = wkb.as_ref().and_then(|wkb| wkb.split_first()) else {
+        return Ok(None);
+    };
+    let dialect = decode_wkb_dialect(*type_id)?;
+    let mut rdr = std::io::Cursor::new(data);
+    let geom = geo::Geometry::from_wkb(&mut rdr, dialect)
+        .map_err(|e| internal_datafusion_err!("Failed to parse wkb, error: {}", e))?;
+    Ok(Some(geom))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geo::dialect::decode_srid;
+    use crate::geo::{geometry_scalar, scalar_to_geometry, GeometryArray, GeometryArrayBuilder};
+    use datafusion_common::ScalarValue;
+    use geo::point;
+    use geozero::wkb::WkbDialect;
+
+    #[test]
+    fn geometry_scalar_round_trip() {
+        let geom = geo::Geometry::Point(point!(x: 1.0, y: 2.0));
+        let scalar = geometry_scalar(&geom, Some(4326)).unwrap();
+        let ScalarValue::Binary(Some(wkb)) = &scalar else {
+            panic!("geometry scalar should be binary");
+        };
+        assert_eq!(decode_srid(wkb).unwrap(), Some(4326));
+        assert_eq!(scalar_to_geometry(&scalar).unwrap(), Some(geom.clone()));
+
+        let ScalarValue::Binary(wkb) = scalar else {
+            unreachable!()
+        };
+        let large_scalar = ScalarValue::LargeBinary(wkb);
+        assert_eq!(scalar_to_geometry(&large_scalar).unwrap(), Some(geom));
+
+        assert_eq!(
+            scalar_to_geometry(&ScalarValue::Binary(None)).unwrap(),
+            None
+        );
+        assert_eq!(
+            scalar_to_geometry(&ScalarValue::LargeBinary(None)).unwrap(),
+            None
+        );
+        assert!(scalar_to_geometry(&ScalarValue::Utf8(None)).is_err());
+    }
+
+    #[test]
+    fn append_scalar() {
+        let geom = geo::Geometry::Point(point!(x: 1.0, y: 2.0));
+        let scalar = geometry_scalar(&geom, Some(4326)).unwrap();
+        let ScalarValue::Binary(wkb) = scalar.clone() else {
+            unreachable!()
+        };
+
+        let mut builder = GeometryArrayBuilder::<i64>::new(WkbDialect::Ewkb, 3);
+        builder.append_scalar(&scalar).unwrap();
+        builder
+            .append_scalar(&ScalarValue::LargeBinary(None))
+            .unwrap();
+        builder
+            .append_scalar(&ScalarValue::LargeBinary(wkb))
+            .unwrap();
+        let arr = builder.build();
+        assert_eq!(arr.geo_value(0).unwrap(), Some(geom.clone()));
+        assert_eq!(arr.geo_value(1).unwrap(), None);
+        assert_eq!(arr.geo_value(2).unwrap(), Some(geom.clone()));
+        assert_eq!(decode_srid(arr.wkb(2).unwrap()).unwrap(), Some(4326));
+
+        // re-encoded into the builder dialect
+        let mut builder = GeometryArrayBuilder::<i32>::new(WkbDialect::Wkb, 1);
+        builder.append_scalar(&scalar).unwrap();
+        let arr = builder.build();
+        assert_eq!(arr.geo_value(0).unwrap(), Some(geom));
+        assert_eq!(arr.wkb(0).unwrap()[0], 1);
+
+        let mut builder = GeometryArrayBuilder::<i32>::new(WkbDialect::Wkb, 1);
+        assert!(builder.append_scalar(&ScalarValue::Int32(Some(1))).is_err());
+    }
+}
@@ -0,0 +1,574 @@
+//! WKT reader of the text ingestion functions.
+//!
+//! The WKB writer of geozero can't write empty points, so collections with a `POINT EMPTY` member
+//! fail on the way to WKB. This reader parses the text itself and hands empty points to the writer
+//! as a point with NaN coordinates, the way GEOS and PostGIS encode them in WKB. Anything it
+//! doesn't support fails with an error quoting the offending token.
+use crate::DFResult;
+use datafusion_common::{exec_err, DataFusionError};
+use geozero::{GeomProcessor, GeozeroGeometry};
+
+/// Geometry types the reader understands, matched case insensitively.
+const GEOMETRY_TYPES: [&str; 7] = [
+    "POINT",
+    "LINESTRING",
+    "POLYGON",
+    "MULTIPOINT",
+    "MULTILINESTRING",
+    "MULTIPOLYGON",
+    "GEOMETRYCOLLECTION",
+];
+
+/// Coordinate of a parsed geometry.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct WktCoord {
+    pub x: f64,
+    pub y: f64,
+    pub z: Option<f64>,
+    pub m: Option<f64>,
+}
+
+/// Coordinate written for an empty point.
+const EMPTY_POINT: WktCoord = WktCoord {
+    x: f64::NAN,
+    y: f64::NAN,
+    z: None,
+    m: None,
+};
+
+/// A parsed WKT geometry, `None` marks an empty point.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum WktGeometry {
+    Point(Option<WktCoord>),
+    LineString(Vec<WktCoord>),
+    Polygon(Vec<Vec<WktCoord>>),
+    MultiPoint(Vec<Option<WktCoord>>),
+    MultiLineString(Vec<Vec<WktCoord>>),
+    MultiPolygon(Vec<Vec<Vec<WktCoord>>>),
+    GeometryCollection(Vec<WktGeometry>),
+}
+
+/// Parses a WKT string.
+pub(crate) fn parse_wkt(text: &str) -> DFResult<WktGeometry> {
+    let mut parser = Parser {
+        tokens: tokenize(text),
+        pos: 0,
+    };
+    let geometry = parser.geometry()?;
+    if parser.peek().is_some() {
+        return parser.unexpected("end of input");
+    }
+    Ok(geometry)
+}
+
+/// Parses an EWKT string, a WKT string with an optional `SRID=<srid>;` prefix.
+pub(crate) fn parse_ewkt(text: &str) -> DFResult<(Option<i32>, WktGeometry)> {
+    let trimmed = text.trim_start();
+    let has_prefix = trimmed
+        .get(..5)
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case("SRID="));
+    if !has_prefix {
+        return Ok((None, parse_wkt(text)?));
+    }
+    let Some((srid, wkt)) = trimmed[5..].split_once(';') else {
+        return exec_err!("Failed to parse ewkt, missing ';' after the SRID");
+    };
+    let Ok(srid) = srid.trim().parse::<i32>() else {
+        return exec_err!("Failed to parse ewkt, invalid SRID '{}'", srid);
+    };
+    Ok((Some(srid), parse_wkt(wkt)?))
+}
+
+/// Characters of the offending text quoted in a row error.
+const SNIPPET_CHARS: usize = 40;
+
+/// Wraps a parse error with the row and the beginning of the offending text.
+pub(crate) fn row_error(row: usize, text: &str, e: DataFusionError) -> DataFusionError {
+    let message = match e {
+        DataFusionError::Execution(message) => message,
+        e => e.to_string(),
+    };
+    let snippet = match text.char_indices().nth(SNIPPET_CHARS) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text.to_string(),
+    };
+    DataFusionError::Execution(format!("Row {} '{}': {}", row, snippet, message))
+}
+
+/// Splits the text into parentheses, commas and the runs of characters between them.
+fn tokenize(text: &str) -> Vec<(usize, &str)> {
+    let mut tokens = vec![];
+    let mut start = None;
+    for (i, c) in text.char_indices() {
+        if c.is_whitespace() || matches!(c, '(' | ')' | ',') {
+            if let Some(s) = start.take() {
+                tokens.push((s, &text[s..i]));
+            }
+            if !c.is_whitespace() {
+                tokens.push((i, &text[i..i + 1]));
+            }
+        } else if start.is_none() {
+            start = Some(i);
+        }
+    }
+    if let Some(s) = start {
+        tokens.push((s, &text[s..]));
+    }
+    tokens
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Dims {
+    Unspecified,
+    Z,
+    M,
+    Zm,
+}
+
+impl Dims {
+    fn parse(suffix: &str) -> Option<Self> {
+        match suffix.to_ascii_uppercase().as_str() {
+            "" => Some(Dims::Unspecified),
+            "Z" => Some(Dims::Z),
+            "M" => Some(Dims::M),
+            "ZM" => Some(Dims::Zm),
+            _ => None,
+        }
+    }
+
+    fn ordinates(&self) -> (usize, usize) {
+        match self {
+            Dims::Unspecified => (2, 4),
+            Dims::Z | Dims::M => (3, 3),
+            Dims::Zm => (4, 4),
+        }
+    }
+}
+
+struct Parser<'a> {
+    tokens: Vec<(usize, &'a str)>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&'a str> {
+        self.tokens.get(self.pos).map(|(_, token)| *token)
+    }
+
+    fn peek_is(&self, token: &str) -> bool {
+        self.peek().is_some_and(|t| t.eq_ignore_ascii_case(token))
+    }
+
+    fn unexpected<T>(&self, expected: &str) -> DFResult<T> {
+        match self.tokens.get(self.pos) {
+            Some((offset, token)) => exec_err!(
+                "Failed to parse wkt, unexpected token '{}' at offset {}, expected {}",
+                token,
+                offset,
+                expected
+            ),
+            None => exec_err!(
+                "Failed to parse wkt, unexpected end of input, expected {}",
+                expected
+            ),
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> DFResult<()> {
+        if !self.peek_is(token) {
+            return self.unexpected(&format!("'{token}'"));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    /// Consumes an `EMPTY` keyword if there is one.
+    fn empty(&mut self) -> bool {
+        let empty = self.peek_is("EMPTY");
+        if empty {
+            self.pos += 1;
+        }
+        empty
+    }
+
+    fn geometry(&mut self) -> DFResult<WktGeometry> {
+        let Some((offset, word)) = self.tokens.get(self.pos).copied() else {
+            return self.unexpected("a geometry type");
+        };
+        if !word.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            return self.unexpected("a geometry type");
+        }
+        let upper = word.to_ascii_uppercase();
+        let Some((name, mut dims)) = GEOMETRY_TYPES.iter().find_map(|name| {
+            upper
+                .strip_prefix(name)
+                .and_then(Dims::parse)
+                .map(|dims| (*name, dims))
+        }) else {
+            return exec_err!(
+                "Failed to parse wkt, unsupported geometry type '{}' at offset {}",
+                word,
+                offset
+            );
+        };
+        self.pos += 1;
+        if dims == Dims::Unspecified {
+            if let Some(suffix) = self.peek().and_then(Dims::parse) {
+                dims = suffix;
+                self.pos += 1;
+            }
+        }
+
+        let empty = self.empty();
+        let geometry = match name {
+            "POINT" if empty => WktGeometry::Point(None),
+            "POINT" => {
+                self.expect("(")?;
+                let coord = self.coord(dims)?;
+                self.expect(")")?;
+                WktGeometry::Point(Some(coord))
+            }
+            "LINESTRING" if empty => WktGeometry::LineString(vec![]),
+            "LINESTRING" => WktGeometry::LineString(self.coords(dims)?),
+            "POLYGON" if empty => WktGeometry::Polygon(vec![]),
+            "POLYGON" => WktGeometry::Polygon(self.rings(dims)?),
+            "MULTIPOINT" if empty => WktGeometry::MultiPoint(vec![]),
+            "MULTIPOINT" => WktGeometry::MultiPoint(self.list(dims, Self::point_member)?),
+            "MULTILINESTRING" if empty => WktGeometry::MultiLineString(vec![]),
+            "MULTILINESTRING" => {
+                WktGeometry::MultiLineString(self.list(dims, Self::linestring_member)?)
+            }
+            "MULTIPOLYGON" if empty => WktGeometry::MultiPolygon(vec![]),
+            "MULTIPOLYGON" => WktGeometry::MultiPolygon(self.list(dims, Self::polygon_member)?),
+            "GEOMETRYCOLLECTION" if empty => WktGeometry::GeometryCollection(vec![]),
+            _ => WktGeometry::GeometryCollection(self.list(dims, |p, _| p.geometry())?),
+        };
+        Ok(geometry)
+    }
+
+    /// Parses a parenthesized, comma separated list of items.
+    fn list<T>(
+        &mut self,
+        dims: Dims,
+        item: impl Fn(&mut Self, Dims) -> DFResult<T>,
+    ) -> DFResult<Vec<T>> {
+        self.expect("(")?;
+        let mut items = vec![item(self, dims)?];
+        while self.peek() == Some(",") {
+            self.pos += 1;
+            items.push(item(self, dims)?);
+        }
+        self.expect(")")?;
+        Ok(items)
+    }
+
+    fn coords(&mut self, dims: Dims) -> DFResult<Vec<WktCoord>> {
+        self.list(dims, Self::coord)
+    }
+
+    fn rings(&mut self, dims: Dims) -> DFResult<Vec<Vec<WktCoord>>> {
+        self.list(dims, Self::coords)
+    }
+
+    /// Multi point members may be parenthesized or not.
+    fn point_member(&mut self, dims: Dims) -> DFResult<Option<WktCoord>> {
+        if self.empty() {
+            return Ok(None);
+        }
+        if self.peek() != Some("(") {
+            return self.coord(dims).map(Some);
+        }
+        self.pos += 1;
+        let coord = self.coord(dims)?;
+        self.expect(")")?;
+        Ok(Some(coord))
+    }
+
+    fn linestring_member(&mut self, dims: Dims) -> DFResult<Vec<WktCoord>> {
+        if self.empty() {
+            return Ok(vec![]);
+        }
+        self.coords(dims)
+    }
+
+    fn polygon_member(&mut self, dims: Dims) -> DFResult<Vec<Vec<WktCoord>>> {
+        if self.empty() {
+            return Ok(vec![]);
+        }
+        self.rings(dims)
+    }
+
+    fn coord(&mut self, dims: Dims) -> DFResult<WktCoord> {
+        let (min, max) = dims.ordinates();
+        let mut ordinates = Vec::with_capacity(max);
+        while ordinates.len() < max {
+            match self.peek() {
+                Some(token)
+                    if token.starts_with(|c: char| c.is_ascii_digit() || "+-.".contains(c)) =>
+                {
+                    match token.parse::<f64>() {
+                        Ok(value) if value.is_finite() => ordinates.push(value),
+                        _ => return self.unexpected("a number"),
+                    }
+                    self.pos += 1;
+                }
+                _ if ordinates.len() < min => return self.unexpected("a number"),
+                _ => break,
+            }
+        }
+        let (z, m) = match (ordinates.len(), dims) {
+            (3, Dims::M) => (None, Some(ordinates[2])),
+            (3, _) => (Some(ordinates[2]), None),
+            (4, _) => (Some(ordinates[2]), Some(ordinates[3])),
+            _ => (None, None),
+        };
+        Ok(WktCoord {
+            x: ordinates[0],
+            y: ordinates[1],
+            z,
+            m,
+        })
+    }
+}
+
+impl GeozeroGeometry for WktGeometry {
+    fn process_geom<P: GeomProcessor>(&self, processor: &mut P) -> geozero::error::Result<()> {
+        process_geometry(self, 0, processor)
+    }
+}
+
+fn process_geometry<P: GeomProcessor>(
+    geometry: &WktGeometry,
+    idx: usize,
+    processor: &mut P,
+) -> geozero::error::Result<()> {
+    match geometry {
+        WktGeometry::Point(coord) => {
+            processor.point_begin(idx)?;
+            process_coord(&coord.unwrap_or(EMPTY_POINT), 0, processor)?;
+            processor.point_end(idx)
+        }
+        WktGeometry::LineString(coords) => process_linestring(coords, true, idx, processor),
+        WktGeometry::Polygon(rings) => process_polygon(rings, true, idx, processor),
+        WktGeometry::MultiPoint(points) => {
+            processor.multipoint_begin(points.len(), idx)?;
+            for (i, coord) in points.iter().enumerate() {
+                process_coord(&coord.unwrap_or(EMPTY_POINT), i, processor)?;
+            }
+            processor.multipoint_end(idx)
+        }
+        WktGeometry::MultiLineString(lines) => {
+            processor.multilinestring_begin(lines.len(), idx)?;
+            for (i, coords) in lines.iter().enumerate() {
+                process_linestring(coords, false, i, processor)?;
+            }
+            processor.multilinestring_end(idx)
+        }
+        WktGeometry::MultiPolygon(polygons) => {
+            processor.multipolygon_begin(polygons.len(), idx)?;
+            for (i, rings) in polygons.iter().enumerate() {
+                process_polygon(rings, false, i, processor)?;
+            }
+            processor.multipolygon_end(idx)
+        }
+        WktGeometry::GeometryCollection(geometries) => {
+            processor.geometrycollection_begin(geometries.len(), idx)?;
+            for (i, geometry) in geometries.iter().enumerate() {
+                process_geometry(geometry, i, processor)?;
+            }
+            processor.geometrycollection_end(idx)
+        }
+    }
+}
+
+fn process_coord<P: GeomProcessor>(
+    coord: &WktCoord,
+    idx: usize,
+    processor: &mut P,
+) -> geozero::error::Result<()> {
+    if processor.multi_dim() {
+        processor.coordinate(coord.x, coord.y, coord.z, coord.m, None, None, idx)
+    } else {
+        processor.xy(coord.x, coord.y, idx)
+    }
+}
+
+fn process_linestring<P: GeomProcessor>(
+    coords: &[WktCoord],
+    tagged: bool,
+    idx: usize,
+    processor: &mut P,
+) -> geozero::error::Result<()> {
+    processor.linestring_begin(tagged, coords.len(), idx)?;
+    for (i, coord) in coords.iter().enumerate() {
+        process_coord(coord, i, processor)?;
+    }
+    processor.linestring_end(tagged, idx)
+}
+
+fn process_polygon<P: GeomProcessor>(
+    rings: &[Vec<WktCoord>],
+    tagged: bool,
+    idx: usize,
+    processor: &mut P,
+) -> geozero::error::Result<()> {
+    processor.polygon_begin(tagged, rings.len(), idx)?;
+    for (i, ring) in rings.iter().enumerate() {
+        process_linestring(ring, false, i, processor)?;
+    }
+    processor.polygon_end(tagged, idx)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geo::wkt::{parse_ewkt, parse_wkt, row_error, WktCoord, WktGeometry};
+
+    fn xy(x: f64, y: f64) -> WktCoord {
+        WktCoord {
+            x,
+            y,
+            z: None,
+            m: None,
+        }
+    }
+
+    #[test]
+    fn parse_collections_with_empty_members() {
+        assert_eq!(
+            parse_wkt("GEOMETRYCOLLECTION(POINT(1 1), LINESTRING EMPTY)").unwrap(),
+            WktGeometry::GeometryCollection(vec![
+                WktGeometry::Point(Some(xy(1.0, 1.0))),
+                WktGeometry::LineString(vec![]),
+            ])
+        );
+        assert_eq!(
+            parse_wkt("geometrycollection(point empty,geometrycollection(polygon empty))").unwrap(),
+            WktGeometry::GeometryCollection(vec![
+                WktGeometry::Point(None),
+                WktGeometry::GeometryCollection(vec![WktGeometry::Polygon(vec![])]),
+            ])
+        );
+        assert_eq!(
+            parse_wkt("MULTIPOINT((1 2), EMPTY, 3 4)").unwrap(),
+            WktGeometry::MultiPoint(vec![Some(xy(1.0, 2.0)), None, Some(xy(3.0, 4.0))])
+        );
+        assert_eq!(
+            parse_wkt("MULTIPOLYGON(EMPTY,((0 0,1 0,1 1,0 0)))").unwrap(),
+            WktGeometry::MultiPolygon(vec![
+                vec![],
+                vec![vec![xy(0.0, 0.0), xy(1.0, 0.0), xy(1.0, 1.0), xy(0.0, 0.0)]]
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_coordinates() {
+        assert_eq!(
+            parse_wkt("POINT(1e3 -2.5E-2)").unwrap(),
+            WktGeometry::Point(Some(xy(1000.0, -0.025)))
+        );
+        assert_eq!(
+            parse_wkt("POINT(+.5 1.5e+2)").unwrap(),
+            WktGeometry::Point(Some(xy(0.5, 150.0)))
+        );
+        let z = WktCoord {
+            x: 1.0,
+            y: 2.0,
+            z: Some(3.0),
+            m: None,
+        };
+        assert_eq!(
+            parse_wkt("POINT Z (1 2 3)").unwrap(),
+            WktGeometry::Point(Some(z))
+        );
+        assert_eq!(
+            parse_wkt("POINTZ(1 2 3)").unwrap(),
+            WktGeometry::Point(Some(z))
+        );
+        assert_eq!(
+            parse_wkt("POINT(1 2 3)").unwrap(),
+            WktGeometry::Point(Some(z))
+        );
+        assert_eq!(
+            parse_wkt("POINT M (1 2 3)").unwrap(),
+            WktGeometry::Point(Some(WktCoord {
+                x: 1.0,
+                y: 2.0,
+                z: None,
+                m: Some(3.0),
+            }))
+        );
+    }
+
+    #[test]
+    fn errors_quote_the_offending_token() {
+        let cases = [
+            (
+                "CIRCULARSTRING(0 0,1 1,2 0)",
+                "unsupported geometry type 'CIRCULARSTRING' at offset 0",
+            ),
+            (
+                "GEOMETRYCOLLECTION(POINT(1 1),TRIANGLE((0 0,1 0,0 1,0 0)))",
+                "unsupported geometry type 'TRIANGLE' at offset 31",
+            ),
+            (
+                "POINT(1 x)",
+                "unexpected token 'x' at offset 8, expected a number",
+            ),
+            (
+                "POINT(1e 2)",
+                "unexpected token '1e' at offset 6, expected a number",
+            ),
+            (
+                "POINT(1 2) foo",
+                "unexpected token 'foo' at offset 11, expected end of input",
+            ),
+            (
+                "LINESTRING(0 0,1)",
+                "unexpected token ')' at offset 16, expected a number",
+            ),
+            (
+                "POINT Z (1 2)",
+                "unexpected token ')' at offset 12, expected a number",
+            ),
+            (
+                "POINT(1 2 3 4 5)",
+                "unexpected token '5' at offset 14, expected ')'",
+            ),
+            ("POINT(1 2", "unexpected end of input, expected ')'"),
+            ("", "unexpected end of input, expected a geometry type"),
+        ];
+        for (wkt, message) in cases {
+            let err = parse_wkt(wkt).unwrap_err().to_string();
+            assert!(err.contains(message), "{wkt}: {err}");
+        }
+    }
+
+    #[test]
+    fn parse_srid_prefix() {
+        let point = WktGeometry::Point(Some(xy(1.0, 2.0)));
+        assert_eq!(
+            parse_ewkt("SRID=4326;POINT(1 2)").unwrap(),
+            (Some(4326), point.clone())
+        );
+        assert_eq!(
+            parse_ewkt(" srid=3857 ; POINT(1 2)").unwrap(),
+            (Some(3857), point.clone())
+        );
+        assert_eq!(parse_ewkt("POINT(1 2)").unwrap(), (None, point));
+        assert!(parse_ewkt("SRID=abc;POINT(1 2)")
+            .unwrap_err()
+            .to_string()
+            .contains("invalid SRID 'abc'"));
+        assert!(parse_ewkt("SRID=4326 POINT(1 2)").is_err());
+    }
+
+    #[test]
+    fn row_error_quotes_text() {
+        let text = "LINESTRING(0 0,1 1,2 2,3 3,4 4,5 5,6 6,7 7,8 8)";
+        let err = row_error(3, text, parse_wkt("POINT(1)").unwrap_err());
+        assert!(err
+            .to_string()
+            .starts_with("Execution error: Row 3 'LINESTRING(0 0,1 1,2 2,3 3,4 4,5 5,6 6,...': "));
+    }
+}
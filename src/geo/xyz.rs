@@ -0,0 +1,124 @@
+//! Coordinate mapping on the wkb level keeping the z ordinate, geo geometries are 2d only.
+
+use crate::geo::dialect::{decode_srid, decode_wkb_dialect};
+use crate::DFResult;
+use datafusion_common::{internal_datafusion_err, internal_err, DataFusionError};
+use geozero::error::Result as GeozeroResult;
+use geozero::wkb::{process_wkb_type_geom, WkbDialect, WkbWriter};
+use geozero::{CoordDimensions, GeomProcessor};
+
+/// Maps every coordinate of a wkb prefixed by its dialect type id, 2d coordinates have a z of 0.
+/// Returns a xyz wkb in the given dialect without the type id prefix, the srid is kept.
+pub(crate) fn map_coords_xyz(
+    wkb: &[u8],
+    dialect: WkbDialect,
+    map: impl FnMut(f64, f64, f64) -> (f64, f64, f64),
+) -> DFResult<Vec<u8>> {
+    let Some((type_id, data)) = wkb.split_first() else {
+        return internal_err!("Wkb is empty");
+    };
+    let mut out = vec![];
+    let mut mapper = XyzMapper {
+        writer: WkbWriter::with_opts(
+            &mut out,
+            dialect,
+            CoordDimensions::xyz(),
+            decode_srid(wkb)?,
+            vec![],
+        ),
+        map,
+    };
+    let mut rdr = std::io::Cursor::new(data);
+    process_wkb_type_geom(&mut rdr, &mut mapper, decode_wkb_dialect(*type_id)?)
+        .map_err(|e| internal_datafusion_err!("Failed to map wkb coords, error: {}", e))?;
+    Ok(out)
+}
+
+struct XyzMapper<W: std::io::Write, F> {
+    writer: WkbWriter<W>,
+    map: F,
+}
+
+impl<W, F> GeomProcessor for XyzMapper<W, F>
+where
+    W: std::io::Write,
+    F: FnMut(f64, f64, f64) -> (f64, f64, f64),
+{
+    fn dimensions(&self) -> CoordDimensions {
+        CoordDimensions::xyz()
+    }
+
+    fn xy(&mut self, x: f64, y: f64, idx: usize) -> GeozeroResult<()> {
+        self.coordinate(x, y, None, None, None, None, idx)
+    }
+
+    fn coordinate(
+        &mut self,
+        x: f64,
+        y: f64,
+        z: Option<f64>,
+        m: Option<f64>,
+        t: Option<f64>,
+        tm: Option<u64>,
+        idx: usize,
+    ) -> GeozeroResult<()> {
+        let (x, y, z) = (self.map)(x, y, z.unwrap_or(0.0));
+        self.writer.coordinate(x, y, Some(z), m, t, tm, idx)
+    }
+
+    fn point_begin(&mut self, idx: usize) -> GeozeroResult<()> {
+        self.writer.point_begin(idx)
+    }
+
+    fn point_end(&mut self, idx: usize) -> GeozeroResult<()> {
+        self.writer.point_end(idx)
+    }
+
+    fn multipoint_begin(&mut self, size: usize, idx: usize) -> GeozeroResult<()> {
+        self.writer.multipoint_begin(size, idx)
+    }
+
+    fn multipoint_end(&mut self, idx: usize) -> GeozeroResult<()> {
+        self.writer.multipoint_end(idx)
+    }
+
+    fn linestring_begin(&mut self, tagged: bool, size: usize, idx: usize) -> GeozeroResult<()> {
+        self.writer.linestring_begin(tagged, size, idx)
+    }
+
+    fn linestring_end(&mut self, tagged: bool, idx: usize) -> GeozeroResult<()> {
+        self.writer.linestring_end(tagged, idx)
+    }
+
+    fn multilinestring_begin(&mut self, size: usize, idx: usize) -> GeozeroResult<()> {
+        self.writer.multilinestring_begin(size, idx)
+    }
+
+    fn multilinestring_end(&mut self, idx: usize) -> GeozeroResult<()> {
+        self.writer.multilinestring_end(idx)
+    }
+
+    fn polygon_begin(&mut self, tagged: bool, size: usize, idx: usize) -> GeozeroResult<()> {
+        self.writer.polygon_begin(tagged, size, idx)
+    }
+
+    fn polygon_end(&mut self, tagged: bool, idx: usize) -> GeozeroResult<()> {
+        self.writer.polygon_end(tagged, idx)
+    }
+
+    fn multipolygon_begin(&mut self, size: usize, idx: usize) -> GeozeroResult<()> {
+        self.writer.multipolygon_begin(size, idx)
+    }
+
+    fn multipolygon_end(&mut self, idx: usize) -> GeozeroResult<()> {
+        self.writer.multipolygon_end(idx)
+    }
+
+    fn geometrycollection_begin(&mut self, size: usize, idx: usize) -> GeozeroResult<()> {
+        self.writer.geometrycollection_begin(size, idx)
+    }
+
+    fn geometrycollection_end(&mut self, idx: usize) -> GeozeroResult<()> {
+        self.writer.geometrycollection_end(idx)
+    }
+}
@@ -1,4 +1,12 @@
+pub mod config;
+pub mod datasource;
+pub mod expr;
 pub mod function;
 pub mod geo;
+pub mod metrics;
+pub mod ops;
+pub mod pretty;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
 
 pub type DFResult<T> = datafusion_common::Result<T>;
@@ -0,0 +1,253 @@
+//! Opt-in execution metrics of the geometry functions.
+//!
+//! Metrics are disabled by default, call [`enable`] before running queries and
+//! [`snapshot`] afterwards to inspect how much time was spent in each function. Every scalar
+//! function records its calls and rows through the shared argument helpers, and splits its time
+//! into decoding the geometries and computing the result. The aggregates record every batch
+//! they take in the same way. The lookups of the process wide cache of proj transformations
+//! are counted as hits and misses.
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static PROJ_CACHE_HITS: AtomicU64 = AtomicU64::new(0);
+static PROJ_CACHE_MISSES: AtomicU64 = AtomicU64::new(0);
+
+fn registry() -> &'static Mutex<HashMap<String, Arc<Counters>>> {
+    static REGISTRY: OnceLock<Mutex<HashMap<String, Arc<Counters>>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn disable() {
+    ENABLED.store(false, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Clears all recorded metrics.
+pub fn reset() {
+    registry()
+        .lock()
+        .expect("metrics registry poisoned")
+        .clear();
+    PROJ_CACHE_HITS.store(0, Ordering::Relaxed);
+    PROJ_CACHE_MISSES.store(0, Ordering::Relaxed);
+}
+
+/// Returns the metrics recorded so far, keyed by function name.
+pub fn snapshot() -> MetricsSnapshot {
+    let registry = registry().lock().expect("metrics registry poisoned");
+    let functions = registry
+        .iter()
+        .map(|(name, counters)| (name.clone(), counters.load()))
+        .collect();
+    MetricsSnapshot {
+        functions,
+        proj_cache: CacheMetrics {
+            hits: PROJ_CACHE_HITS.load(Ordering::Relaxed),
+            misses: PROJ_CACHE_MISSES.load(Ordering::Relaxed),
+        },
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MetricsSnapshot {
+    pub functions: BTreeMap<String, FunctionMetrics>,
+    /// Lookups of the proj transformations of `ST_Transform`.
+    pub proj_cache: CacheMetrics,
+}
+
+impl MetricsSnapshot {
+    pub fn function(&self, name: &str) -> Option<&FunctionMetrics> {
+        self.functions.get(name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FunctionMetrics {
+    pub calls: u64,
+    pub rows: u64,
+    /// Time spent reading the wkb of the args into geo or geos geometries, summed over the threads.
+    pub decode_time: Duration,
+    /// Time spent in the function apart from decoding, summed over the threads for the functions
+    /// running rows in parallel.
+    pub compute_time: Duration,
+    /// Invalid inputs repaired with make valid, by the functions which can repair them.
+    pub repaired: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheMetrics {
+    pub hits: u64,
+    /// Lookups which built the value, including the ones racing another thread building it.
+    pub misses: u64,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    calls: AtomicU64,
+    rows: AtomicU64,
+    decode_nanos: AtomicU64,
+    compute_nanos: AtomicU64,
+    repaired: AtomicU64,
+}
+
+impl Counters {
+    fn load(&self) -> FunctionMetrics {
+        FunctionMetrics {
+            calls: self.calls.load(Ordering::Relaxed),
+            rows: self.rows.load(Ordering::Relaxed),
+            decode_time: Duration::from_nanos(self.decode_nanos.load(Ordering::Relaxed)),
+            compute_time: Duration::from_nanos(self.compute_nanos.load(Ordering::Relaxed)),
+            repaired: self.repaired.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Counts a lookup of the proj transformation cache.
+#[cfg(feature = "proj")]
+pub(crate) fn record_proj_cache(hit: bool) {
+    if !is_enabled() {
+        return;
+    }
+    let counter = if hit {
+        &PROJ_CACHE_HITS
+    } else {
+        &PROJ_CACHE_MISSES
+    };
+    counter.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Records one invocation of the function, the returned recorder does nothing if metrics are disabled.
+/// Until the recorder is dropped the geometries decoded on this thread, and on the rayon threads of
+/// [`par_rows`](crate::function::args::par_rows), count as the decode time of the function, the
+/// rest of its lifetime is the compute time unless the function times its compute explicitly.
+pub(crate) fn record_call(name: &str, rows: usize) -> Recorder {
+    if !is_enabled() {
+        return Recorder {
+            call: None,
+            previous: None,
+        };
+    }
+    let counters = registry()
+        .lock()
+        .expect("metrics registry poisoned")
+        .entry(name.to_string())
+        .or_default()
+        .clone();
+    counters.calls.fetch_add(1, Ordering::Relaxed);
+    counters.rows.fetch_add(rows as u64, Ordering::Relaxed);
+    let call = Arc::new(Call {
+        counters,
+        start: Instant::now(),
+        decode_nanos: AtomicU64::new(0),
+        compute_timed: AtomicBool::new(false),
+    });
+    let previous = CURRENT.with(|current| current.replace(Some(call.clone())));
+    Recorder {
+        call: Some(call),
+        previous,
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<Arc<Call>>> = const { RefCell::new(None) };
+}
+
+/// The invocation running on this thread, passed on to the threads working for it.
+pub(crate) fn current_call() -> Option<Arc<Call>> {
+    CURRENT.with(|current| current.borrow().clone())
+}
+
+/// Runs `f` as part of the invocation, the previous one is restored afterwards.
+pub(crate) fn with_call<T>(call: Option<Arc<Call>>, f: impl FnOnce() -> T) -> T {
+    let previous = CURRENT.with(|current| current.replace(call));
+    let result = f();
+    CURRENT.with(|current| *current.borrow_mut() = previous);
+    result
+}
+
+/// Times the decoding of a geometry for the invocation running on this thread, if any.
+#[inline]
+pub(crate) fn decode<T>(f: impl FnOnce() -> T) -> T {
+    let Some(call) = current_call() else {
+        return f();
+    };
+    let start = Instant::now();
+    let result = f();
+    let nanos = start.elapsed().as_nanos() as u64;
+    call.decode_nanos.fetch_add(nanos, Ordering::Relaxed);
+    call.counters
+        .decode_nanos
+        .fetch_add(nanos, Ordering::Relaxed);
+    result
+}
+
+#[derive(Debug)]
+pub(crate) struct Call {
+    counters: Arc<Counters>,
+    start: Instant,
+    decode_nanos: AtomicU64,
+    compute_timed: AtomicBool,
+}
+
+impl Drop for Call {
+    fn drop(&mut self) {
+        if self.compute_timed.load(Ordering::Relaxed) {
+            return;
+        }
+        let elapsed = self.start.elapsed().as_nanos() as u64;
+        let compute = elapsed.saturating_sub(self.decode_nanos.load(Ordering::Relaxed));
+        self.counters
+            .compute_nanos
+            .fetch_add(compute, Ordering::Relaxed);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct Recorder {
+    call: Option<Arc<Call>>,
+    previous: Option<Arc<Call>>,
+}
+
+impl Recorder {
+    /// Times the compute of a row, the functions running rows in parallel time them explicitly
+    /// as their run time is shorter than the time spent on all threads.
+    #[inline]
+    pub(crate) fn compute<T>(&self, f: impl FnOnce() -> T) -> T {
+        let Some(call) = &self.call else {
+            return f();
+        };
+        call.compute_timed.store(true, Ordering::Relaxed);
+        let start = Instant::now();
+        let result = f();
+        call.counters
+            .compute_nanos
+            .fetch_add(start.elapsed().as_nanos() as u64, Ordering::Relaxed);
+        result
+    }
+
+    pub(crate) fn repaired(&self, count: u64) {
+        if let Some(call) = &self.call {
+            call.counters.repaired.fetch_add(count, Ordering::Relaxed);
+        }
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        if self.call.is_some() {
+            let previous = self.previous.take();
+            CURRENT.with(|current| *current.borrow_mut() = previous);
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use crate::config::{check_cancelled, GeoConfig};
+use crate::function::args::par_rows;
+use crate::geo::GeometryArray;
+use crate::DFResult;
+use arrow_array::{Float64Array, GenericBinaryArray, OffsetSizeTrait};
+use datafusion_common::{exec_err, DataFusionError};
+
+/// Distances between every geometry of `a` and every geometry of `b` in row major order, the
+/// distance of `a[i]` and `b[j]` is at `i * b.len() + j`. Null and empty geometries have null
+/// distances like with `ST_Distance`. Each geometry is decoded once and the rows are computed in
+/// parallel, the number of cells is limited by `GeoConfig::max_distance_matrix_cells`.
+pub fn distance_matrix<O: OffsetSizeTrait, F: OffsetSizeTrait>(
+    a: &GenericBinaryArray<O>,
+    b: &GenericBinaryArray<F>,
+) -> DFResult<Float64Array> {
+    let max_cells = GeoConfig::get().max_distance_matrix_cells;
+    match a.geom_len().checked_mul(b.geom_len()) {
+        Some(cells) if cells <= max_cells => {}
+        _ => {
+            return exec_err!(
+                "Distance matrix of {} x {} geometries exceeds the limit of {} cells",
+                a.geom_len(),
+                b.geom_len(),
+                max_cells
+            )
+        }
+    }
+
+    #[cfg(feature = "geos")]
+    let rows = {
+        use datafusion_common::internal_datafusion_err;
+        use geos::Geom;
+        let (geoms0, geoms1) = (decode_geos(a)?, decode_geos(b)?);
+        par_rows(geoms0.len(), |i| {
+            check_cancelled(i)?;
+            geoms1
+                .iter()
+                .map(|geom1| match (&geoms0[i], geom1) {
+                    (Some(geom0), Some(geom1)) => geom0.distance(geom1).map(Some).map_err(|e| {
+                        internal_datafusion_err!("Failed to do distance, error: {}", e)
+                    }),
+                    _ => Ok(None),
+                })
+                .collect::<DFResult<Vec<Option<f64>>>>()
+        })?
+    };
+    #[cfg(not(feature = "geos"))]
+    let rows = {
+        use geo::EuclideanDistance;
+        let (geoms0, geoms1) = (decode_geo(a)?, decode_geo(b)?);
+        par_rows(geoms0.len(), |i| {
+            check_cancelled(i)?;
+            Ok(geoms1
+                .iter()
+                .map(|geom1| match (&geoms0[i], geom1) {
+                    (Some(geom0), Some(geom1)) => Some(geom0.euclidean_distance(geom1)),
+                    _ => None,
+                })
+                .collect::<Vec<Option<f64>>>())
+        })?
+    };
+    Ok(rows.into_iter().flatten().collect())
+}
+
+/// Decodes every geometry once, empty geometries are None.
+#[cfg(feature = "geos")]
+fn decode_geos(arr: &dyn GeometryArray) -> DFResult<Vec<Option<geos::Geometry>>> {
+    use geos::Geom;
+    (0..arr.geom_len())
+        .map(|i| {
+            Ok(arr
+                .geos_value(i)?
+                .filter(|geom| !geom.is_empty().unwrap_or(true)))
+        })
+        .collect()
+}
+
+/// Decodes every geometry once, empty geometries are None.
+#[cfg(not(feature = "geos"))]
+fn decode_geo(arr: &dyn GeometryArray) -> DFResult<Vec<Option<geo::Geometry>>> {
+    use crate::geo::map::is_empty;
+    (0..arr.geom_len())
+        .map(|i| Ok(arr.geo_value(i)?.filter(|geom| !is_empty(geom))))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::function::DistanceUdf;
+    use crate::geo::GeometryArrayBuilder;
+    use crate::ops::distance_matrix;
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::Float64Type;
+    use arrow_array::BinaryArray;
+    use datafusion_expr::{ColumnarValue, ScalarUDFImpl};
+    use geo::{line_string, point, polygon};
+    use std::sync::Arc;
+
+    fn binary_array(geoms: &[Option<geo::Geometry>]) -> BinaryArray {
+        let builder: GeometryArrayBuilder<i32> = geoms.try_into().unwrap();
+        builder.build()
+    }
+
+    #[test]
+    fn distance_matrix_matches_pairwise_distances() {
+        let a = vec![
+            Some(point!(x: 0., y: 0.).into()),
+            None,
+            Some(line_string![(x: 0., y: 5.), (x: 10., y: 5.)].into()),
+        ];
+        let b = vec![
+            Some(point!(x: 3., y: 4.).into()),
+            Some(polygon![(x: 2., y: 2.), (x: 4., y: 2.), (x: 4., y: 4.), (x: 2., y: 2.)].into()),
+            Some(geo::MultiPoint::<f64>::new(vec![]).into()),
+            None,
+        ];
+        let matrix = distance_matrix(&binary_array(&a), &binary_array(&b)).unwrap();
+        assert_eq!(matrix.len(), 12);
+        assert_eq!(matrix.value(0), 5.0);
+        assert_eq!(matrix.value(8), 1.0);
+
+        // the same pairs evaluated row by row by ST_Distance
+        let pairs0 = a
+            .iter()
+            .flat_map(|geom0| b.iter().map(move |_| geom0.clone()))
+            .collect::<Vec<_>>();
+        let pairs1 = a.iter().flat_map(|_| b.clone()).collect::<Vec<_>>();
+        let distances = DistanceUdf::new()
+            .invoke(&[
+                ColumnarValue::Array(Arc::new(binary_array(&pairs0))),
+                ColumnarValue::Array(Arc::new(binary_array(&pairs1))),
+            ])
+            .unwrap();
+        let ColumnarValue::Array(distances) = distances else {
+            panic!("expected an array");
+        };
+        assert_eq!(distances.as_primitive::<Float64Type>(), &matrix);
+    }
+
+    #[test]
+    fn distance_matrix_limit() {
+        let nulls = binary_array(&vec![None; 4000]);
+        let err = distance_matrix(&nulls, &nulls).unwrap_err();
+        assert!(
+            err.to_string().contains(
+                "Distance matrix of 4000 x 4000 geometries exceeds the limit of 10000000 cells"
+            ),
+            "{}",
+            err
+        );
+        assert_eq!(
+            distance_matrix(&nulls, &binary_array(&[])).unwrap().len(),
+            0
+        );
+    }
+}
@@ -0,0 +1,294 @@
+use crate::config::default_dialect;
+use crate::geo::dialect::decode_srid;
+use crate::geo::{GeometryArray, GeometryArrayBuilder};
+use crate::DFResult;
+use arrow_array::cast::AsArray;
+use arrow_array::{ArrayRef, GenericBinaryArray, OffsetSizeTrait, RecordBatch};
+use arrow_schema::DataType;
+use datafusion_common::{exec_err, DataFusionError};
+use geo::{BooleanOps, BoundingRect, Intersects};
+use rstar::primitives::{GeomWithData, Rectangle};
+use rstar::{RTree, AABB};
+use std::sync::Arc;
+
+type EraserIndex = RTree<GeomWithData<Rectangle<[f64; 2]>, usize>>;
+
+/// Erases the right layer from the left one, e.g. land minus water. Each left geometry gets all
+/// overlapping polygonal right geometries subtracted, which is the same as differencing it with the
+/// union of the right geometries it joins with. Only the left geometry column is replaced, the
+/// other columns are kept as is. Non polygonal right geometries have no area and erase nothing.
+pub fn erase(
+    left_batches: &[RecordBatch],
+    right_batches: &[RecordBatch],
+    left_geom_col: &str,
+    right_geom_col: &str,
+) -> DFResult<Vec<RecordBatch>> {
+    let mut erasers = vec![];
+    for batch in right_batches {
+        let arr = geometry_column(batch, right_geom_col)?;
+        match arr.data_type() {
+            DataType::Binary => collect_erasers(arr.as_binary::<i32>(), &mut erasers)?,
+            DataType::LargeBinary => collect_erasers(arr.as_binary::<i64>(), &mut erasers)?,
+            _ => unreachable!(),
+        }
+    }
+    let index: EraserIndex = RTree::bulk_load(
+        erasers
+            .iter()
+            .enumerate()
+            .filter_map(|(i, eraser)| {
+                eraser.bounding_rect().map(|rect| {
+                    let rectangle = Rectangle::from_corners(rect.min().into(), rect.max().into());
+                    GeomWithData::new(rectangle, i)
+                })
+            })
+            .collect(),
+    );
+
+    left_batches
+        .iter()
+        .map(|batch| -> DFResult<RecordBatch> {
+            let geom_index = batch.schema().index_of(left_geom_col)?;
+            let arr = geometry_column(batch, left_geom_col)?;
+            let erased: ArrayRef = match arr.data_type() {
+                DataType::Binary => {
+                    Arc::new(erase_array(arr.as_binary::<i32>(), &index, &erasers)?)
+                }
+                DataType::LargeBinary => {
+                    Arc::new(erase_array(arr.as_binary::<i64>(), &index, &erasers)?)
+                }
+                _ => unreachable!(),
+            };
+            let mut columns = batch.columns().to_vec();
+            columns[geom_index] = erased;
+            Ok(RecordBatch::try_new(batch.schema(), columns)?)
+        })
+        .collect()
+}
+
+fn geometry_column<'a>(batch: &'a RecordBatch, name: &str) -> DFResult<&'a ArrayRef> {
+    let Some(arr) = batch.column_by_name(name) else {
+        return exec_err!("Geometry column {} not found", name);
+    };
+    match arr.data_type() {
+        DataType::Binary | DataType::LargeBinary => Ok(arr),
+        data_type => exec_err!("Column {} is not a geometry column: {}", name, data_type),
+    }
+}
+
+fn collect_erasers<O: OffsetSizeTrait>(
+    wkb_arr: &GenericBinaryArray<O>,
+    erasers: &mut Vec<geo::MultiPolygon>,
+) -> DFResult<()> {
+    for i in 0..wkb_arr.geom_len() {
+        if let Some(polygons) = wkb_arr.geo_value(i)?.and_then(polygonal) {
+            erasers.push(polygons);
+        }
+    }
+    Ok(())
+}
+
+fn erase_array<O: OffsetSizeTrait>(
+    wkb_arr: &GenericBinaryArray<O>,
+    index: &EraserIndex,
+    erasers: &[geo::MultiPolygon],
+) -> DFResult<GenericBinaryArray<O>> {
+    let mut builder = GeometryArrayBuilder::<O>::new(default_dialect(), wkb_arr.geom_len());
+    for i in 0..wkb_arr.geom_len() {
+        let Some(wkb) = wkb_arr.wkb(i) else {
+            builder.append_null();
+            continue;
+        };
+        let srid = decode_srid(wkb)?;
+        let geom = wkb_arr.geo_value(i)?.map(|geom| {
+            let overlapping = match geom.bounding_rect() {
+                Some(rect) => index
+                    .locate_in_envelope_intersecting(&AABB::from_corners(
+                        rect.min().into(),
+                        rect.max().into(),
+                    ))
+                    .map(|item| &erasers[item.data])
+                    .collect::<Vec<_>>(),
+                None => vec![],
+            };
+            erase_geometry(geom, &overlapping)
+        });
+        builder.append_geo_geometry_with_srid(&geom, srid)?;
+    }
+    Ok(builder.build())
+}
+
+fn polygonal(geom: geo::Geometry) -> Option<geo::MultiPolygon> {
+    match geom {
+        geo::Geometry::Polygon(polygon) => Some(polygon.into()),
+        geo::Geometry::MultiPolygon(polygons) => Some(polygons),
+        geo::Geometry::Rect(rect) => Some(rect.to_polygon().into()),
+        geo::Geometry::Triangle(triangle) => Some(triangle.to_polygon().into()),
+        geo::Geometry::GeometryCollection(gc) => {
+            let polygons = gc
+                .into_iter()
+                .filter_map(polygonal)
+                .flat_map(|polygons| polygons.0)
+                .collect::<Vec<_>>();
+            (!polygons.is_empty()).then(|| polygons.into())
+        }
+        _ => None,
+    }
+}
+
+/// Subtracts the erasers one by one, points are dropped and lines are clipped.
+fn erase_geometry(geom: geo::Geometry, erasers: &[&geo::MultiPolygon]) -> geo::Geometry {
+    if erasers.is_empty() {
+        return geom;
+    }
+    let erased = |point: &geo::Point| erasers.iter().any(|eraser| eraser.intersects(point));
+    let erase_lines = |lines: geo::MultiLineString| {
+        erasers
+            .iter()
+            .fold(lines, |lines, eraser| eraser.clip(&lines, true))
+    };
+    match geom {
+        geo::Geometry::Point(point) if erased(&point) => geo::MultiPoint::new(vec![]).into(),
+        geo::Geometry::MultiPoint(points) => points
+            .into_iter()
+            .filter(|point| !erased(point))
+            .collect::<geo::MultiPoint>()
+            .into(),
+        geo::Geometry::Line(line) => erase_lines(geo::LineString::from(line).into()).into(),
+        geo::Geometry::LineString(line) => erase_lines(line.into()).into(),
+        geo::Geometry::MultiLineString(lines) => erase_lines(lines).into(),
+        geo::Geometry::GeometryCollection(gc) => gc
+            .into_iter()
+            .map(|geom| erase_geometry(geom, erasers))
+            .collect::<geo::GeometryCollection>()
+            .into(),
+        geom => match polygonal(geom.clone()) {
+            Some(polygons) => erasers
+                .iter()
+                .fold(polygons, |polygons, eraser| polygons.difference(eraser))
+                .into(),
+            None => geom,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geo::{GeometryArray, GeometryArrayBuilder};
+    use crate::ops::erase;
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::Int32Type;
+    use arrow_array::{Int32Array, RecordBatch};
+    use arrow_schema::{DataType, Field, Schema};
+    use geo::{line_string, polygon, Area, BoundingRect, EuclideanLength};
+    use std::sync::Arc;
+
+    /// Left: two squares, a line and a null, right: two squares covering the right part of the
+    /// first left square.
+    fn layers() -> (RecordBatch, RecordBatch) {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("geom", DataType::Binary, true),
+        ]));
+        let left: Vec<Option<geo::Geometry>> = vec![
+            Some(polygon![(x: 0., y: 0.), (x: 4., y: 0.), (x: 4., y: 4.), (x: 0., y: 4.)].into()),
+            Some(
+                polygon![(x: 10., y: 10.), (x: 11., y: 10.), (x: 11., y: 11.), (x: 10., y: 11.)]
+                    .into(),
+            ),
+            Some(line_string![(x: 0., y: 1.), (x: 6., y: 1.)].into()),
+            None,
+        ];
+        let left: GeometryArrayBuilder<i32> = left.as_slice().try_into().unwrap();
+        let left = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2, 3, 4])),
+                Arc::new(left.build()),
+            ],
+        )
+        .unwrap();
+        let right: GeometryArrayBuilder<i32> = vec![
+            Some(polygon![(x: 3., y: -1.), (x: 5., y: -1.), (x: 5., y: 2.), (x: 3., y: 2.)]),
+            Some(polygon![(x: 3., y: 2.), (x: 5., y: 2.), (x: 5., y: 5.), (x: 3., y: 5.)]),
+        ]
+        .as_slice()
+        .try_into()
+        .unwrap();
+        let right = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2])),
+                Arc::new(right.build()),
+            ],
+        )
+        .unwrap();
+        (left, right)
+    }
+
+    #[test]
+    fn erase_overlapping_squares() {
+        let (left, right) = layers();
+        let batches = erase(&[left], &[right], "geom", "geom").unwrap();
+        assert_eq!(batches.len(), 1);
+        let ids = batches[0].column(0).as_primitive::<Int32Type>();
+        assert_eq!(ids.values().to_vec(), vec![1, 2, 3, 4]);
+        let arr = batches[0].column(1).as_binary::<i32>();
+
+        let erased = arr.geo_value(0).unwrap().unwrap();
+        assert_eq!(erased.unsigned_area(), 12.0);
+        let rect = erased.bounding_rect().unwrap();
+        assert_eq!((rect.min().x, rect.max().x), (0.0, 3.0));
+
+        let untouched = arr.geo_value(1).unwrap().unwrap();
+        assert_eq!(untouched.unsigned_area(), 1.0);
+
+        let geo::Geometry::MultiLineString(line) = arr.geo_value(2).unwrap().unwrap() else {
+            panic!("erased line should be a multi linestring");
+        };
+        assert_eq!(line.euclidean_length(), 4.0);
+
+        assert!(arr.geo_value(3).unwrap().is_none());
+    }
+
+    #[cfg(feature = "geos")]
+    #[tokio::test]
+    async fn erase_equals_difference_with_joined_union() {
+        use crate::function::{DifferenceUdf, EqualsUdf, IntersectsUdf, UnionUdaf};
+        use arrow::util::pretty::pretty_format_batches;
+        use datafusion::prelude::SessionContext;
+        use datafusion_expr::{AggregateUDF, ScalarUDF};
+
+        let (left, right) = layers();
+        let erased = erase(&[left.clone()], &[right.clone()], "geom", "geom").unwrap();
+
+        let ctx = SessionContext::new();
+        ctx.register_udf(ScalarUDF::from(DifferenceUdf::new()));
+        ctx.register_udf(ScalarUDF::from(EqualsUdf::new()));
+        ctx.register_udf(ScalarUDF::from(IntersectsUdf::new()));
+        ctx.register_udaf(AggregateUDF::from(UnionUdaf::new()));
+        ctx.register_batch("l", left).unwrap();
+        ctx.register_batch("r", right).unwrap();
+        ctx.register_batch("erased", erased[0].clone()).unwrap();
+        // each left geometry minus the union of the right geometries it joins with
+        let df = ctx
+            .sql(
+                "select erased.id,                 ST_Equals(erased.geom, coalesce(ST_Difference(l.geom, u.geom), l.geom)) as equal                 from erased join l on erased.id = l.id                 left join (select l.id, st_union_agg(r.geom) as geom                 from l join r on ST_Intersects(l.geom, r.geom) group by l.id) as u on l.id = u.id                 order by erased.id",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            pretty_format_batches(&df.collect().await.unwrap())
+                .unwrap()
+                .to_string(),
+            "+----+-------+
+| id | equal |
++----+-------+
+| 1  | true  |
+| 2  | true  |
+| 3  | true  |
+| 4  |       |
++----+-------+"
+        );
+    }
+}
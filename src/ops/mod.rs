@@ -0,0 +1,5 @@
+mod distance_matrix;
+mod erase;
+
+pub use distance_matrix::*;
+pub use erase::*;
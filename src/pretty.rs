@@ -0,0 +1,270 @@
+//! Pretty printing of record batches with readable geometry columns.
+//!
+//! [`pretty_format_geometry_batches`] works like arrow's `pretty_format_batches`, but prints
+//! geometries as wkt instead of their wkb bytes. Long geometries are truncated, only the printed
+//! prefix of the wkt is produced.
+use crate::datasource::{EXTENSION_NAME_KEY, GEOARROW_WKB};
+use crate::geo::dialect::{decode_wkb_dialect, WkbHeader};
+use crate::geo::format::format_numbers;
+use crate::DFResult;
+use arrow_array::cast::AsArray;
+use arrow_array::{Array, ArrayRef, RecordBatch, StringArray};
+use arrow_cast::pretty::pretty_format_batches;
+use arrow_schema::{DataType, Field, Schema};
+use datafusion_common::{exec_datafusion_err, DataFusionError};
+use geozero::wkb::{process_wkb_type_geom, WkbDialect};
+use geozero::wkt::WktWriter;
+use std::fmt::Display;
+use std::io::Write;
+use std::sync::Arc;
+
+/// Maximum number of wkt characters printed per geometry.
+const MAX_WKT_CHARS: usize = 40;
+
+/// Formats the batches as a table like `pretty_format_batches`, geometry columns are printed as
+/// wkt. Binary columns are geometries when tagged as `geoarrow.wkb` or when every value starts
+/// with a wkb header of the crate's dialect prefixed format. Geometries longer than 40 characters
+/// are truncated and followed by their size, e.g. `POLYGON((0 0,…[1.2 KB]`.
+pub fn pretty_format_geometry_batches(batches: &[RecordBatch]) -> DFResult<impl Display> {
+    let Some(first) = batches.first() else {
+        return Ok(pretty_format_batches(batches)?);
+    };
+    let schema = first.schema();
+    let encodings = (0..schema.fields().len())
+        .map(|index| geometry_encoding(schema.field(index), batches, index))
+        .collect::<Vec<_>>();
+    let fields = schema
+        .fields()
+        .iter()
+        .zip(&encodings)
+        .map(|(field, encoding)| match encoding {
+            Some(_) => Arc::new(Field::new(
+                field.name(),
+                DataType::Utf8,
+                field.is_nullable(),
+            )),
+            None => field.clone(),
+        })
+        .collect::<Vec<_>>();
+    let text_schema = Arc::new(Schema::new(fields));
+    let text_batches = batches
+        .iter()
+        .map(|batch| {
+            let columns = batch
+                .columns()
+                .iter()
+                .zip(&encodings)
+                .map(|(arr, encoding)| match encoding {
+                    Some(encoding) => render_column(arr, *encoding),
+                    None => Ok(arr.clone()),
+                })
+                .collect::<DFResult<Vec<_>>>()?;
+            Ok(RecordBatch::try_new(text_schema.clone(), columns)?)
+        })
+        .collect::<DFResult<Vec<_>>>()?;
+    Ok(pretty_format_batches(&text_batches)?)
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Encoding {
+    /// Wkb prefixed by its dialect type id, the format of the crate's functions.
+    Prefixed,
+    /// Plain iso wkb of the `geoarrow.wkb` extension type.
+    Plain,
+}
+
+fn geometry_encoding(field: &Field, batches: &[RecordBatch], index: usize) -> Option<Encoding> {
+    if !matches!(field.data_type(), DataType::Binary | DataType::LargeBinary) {
+        return None;
+    }
+    if field
+        .metadata()
+        .get(EXTENSION_NAME_KEY)
+        .is_some_and(|name| name == GEOARROW_WKB)
+    {
+        return Some(Encoding::Plain);
+    }
+    // only the headers are read, the values are decoded when rendered
+    let mut has_value = false;
+    for batch in batches {
+        let arr = batch.column(index);
+        for i in 0..arr.len() {
+            if let Some(value) = binary_value(arr, i) {
+                WkbHeader::parse(value).ok()?;
+                has_value = true;
+            }
+        }
+    }
+    has_value.then_some(Encoding::Prefixed)
+}
+
+fn binary_value(arr: &ArrayRef, index: usize) -> Option<&[u8]> {
+    if arr.is_null(index) {
+        return None;
+    }
+    match arr.data_type() {
+        DataType::Binary => Some(arr.as_binary::<i32>().value(index)),
+        _ => Some(arr.as_binary::<i64>().value(index)),
+    }
+}
+
+fn render_column(arr: &ArrayRef, encoding: Encoding) -> DFResult<ArrayRef> {
+    let texts = (0..arr.len())
+        .map(|i| {
+            binary_value(arr, i)
+                .map(|value| render_geometry(value, encoding))
+                .transpose()
+        })
+        .collect::<DFResult<Vec<_>>>()?;
+    Ok(Arc::new(StringArray::from(texts)))
+}
+
+/// Renders the wkt of a geometry, truncated to its first characters followed by the size of the
+/// value if it is longer. The wkt writer is stopped once the prefix is full.
+fn render_geometry(value: &[u8], encoding: Encoding) -> DFResult<String> {
+    let (dialect, wkb) = match encoding {
+        Encoding::Prefixed => match value.split_first() {
+            Some((type_id, wkb)) => (decode_wkb_dialect(*type_id)?, wkb),
+            None => return Ok(String::new()),
+        },
+        Encoding::Plain => (WkbDialect::Wkb, value),
+    };
+    let mut prefix = PrefixWriter::new(MAX_WKT_CHARS);
+    let result = process_wkb_type_geom(
+        &mut std::io::Cursor::new(wkb),
+        &mut WktWriter::new(&mut prefix),
+        dialect,
+    );
+    let truncated = result.is_err() && prefix.is_full();
+    if !truncated {
+        result.map_err(|e| exec_datafusion_err!("Failed to render geometry, error: {}", e))?;
+    }
+    let text = format_numbers(String::from_utf8_lossy(&prefix.text).into_owned());
+    match truncated {
+        true => Ok(format!("{}…[{}]", text, format_size(value.len()))),
+        false => Ok(text),
+    }
+}
+
+fn format_size(bytes: usize) -> String {
+    const KB: f64 = 1024.0;
+    let bytes = bytes as f64;
+    if bytes < KB {
+        format!("{} B", bytes)
+    } else if bytes < KB * KB {
+        format!("{:.1} KB", bytes / KB)
+    } else {
+        format!("{:.1} MB", bytes / (KB * KB))
+    }
+}
+
+/// Keeps the first bytes written and fails the writes once they are full.
+struct PrefixWriter {
+    text: Vec<u8>,
+    limit: usize,
+}
+
+impl PrefixWriter {
+    fn new(limit: usize) -> Self {
+        Self {
+            text: Vec::with_capacity(limit),
+            limit,
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.text.len() >= self.limit
+    }
+}
+
+impl Write for PrefixWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.is_full() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::WriteZero,
+                "prefix is full",
+            ));
+        }
+        let len = buf.len().min(self.limit - self.text.len());
+        self.text.extend_from_slice(&buf[..len]);
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::datasource::{EXTENSION_NAME_KEY, GEOARROW_WKB};
+    use crate::geo::GeometryArrayBuilder;
+    use crate::pretty::pretty_format_geometry_batches;
+    use arrow_array::{BinaryArray, Int64Array, RecordBatch};
+    use arrow_schema::{DataType, Field, Schema};
+    use geozero::{CoordDimensions, ToWkb};
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    #[test]
+    fn pretty_format_geometries() {
+        let line =
+            geo::LineString::from((0..200).map(|i| (i as f64, i as f64)).collect::<Vec<_>>());
+        let geoms: Vec<Option<geo::Geometry>> = vec![
+            Some(geo::point! { x: 1.0, y: 1.0 }.into()),
+            Some(line.into()),
+            None,
+        ];
+        let builder: GeometryArrayBuilder<i32> = geoms.as_slice().try_into().unwrap();
+        let plain_wkb = geo::Geometry::from(geo::point! { x: 1.5, y: 2.0 })
+            .to_wkb(CoordDimensions::xy())
+            .unwrap();
+        let geoarrow_field = Field::new("plain", DataType::Binary, true).with_metadata(
+            HashMap::from([(EXTENSION_NAME_KEY.to_string(), GEOARROW_WKB.to_string())]),
+        );
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("geom", DataType::Binary, true),
+            geoarrow_field,
+            Field::new("bytes", DataType::Binary, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int64Array::from(vec![1, 2, 3])),
+                Arc::new(builder.build()),
+                Arc::new(BinaryArray::from_opt_vec(vec![
+                    Some(plain_wkb.as_slice()),
+                    None,
+                    None,
+                ])),
+                Arc::new(BinaryArray::from_opt_vec(vec![
+                    Some(b"abc".as_slice()),
+                    None,
+                    Some(b"d".as_slice()),
+                ])),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            pretty_format_geometry_batches(&[batch])
+                .unwrap()
+                .to_string(),
+            "+----+---------------------------------------------------+--------------+--------+
+| id | geom                                              | plain        | bytes  |
++----+---------------------------------------------------+--------------+--------+
+| 1  | POINT(1 1)                                        | POINT(1.5 2) | 616263 |
+| 2  | LINESTRING(0 0,1 1,2 2,3 3,4 4,5 5,6 6,7…[3.1 KB] |              |        |
+| 3  |                                                   |              | 64     |
++----+---------------------------------------------------+--------------+--------+"
+        );
+    }
+
+    #[test]
+    fn pretty_format_without_batches() {
+        assert_eq!(
+            pretty_format_geometry_batches(&[]).unwrap().to_string(),
+            "++\n++"
+        );
+    }
+}
@@ -0,0 +1,211 @@
+//! Helpers for testing geometry columns, enabled by the `test-utils` feature.
+
+use crate::geo::GeometryArray;
+use arrow_array::cast::AsArray;
+use arrow_array::Array;
+use arrow_schema::DataType;
+
+/// Asserts two geometry arrays hold the same geometries regardless of their wkb dialect.
+/// Geometry types and structure must match exactly and coordinates within `tolerance`,
+/// otherwise panics naming the first mismatching row and coordinate.
+pub fn assert_geometry_array_eq(left: &dyn Array, right: &dyn Array, tolerance: f64) {
+    let left = geometry_array(left);
+    let right = geometry_array(right);
+    assert_eq!(
+        left.geom_len(),
+        right.geom_len(),
+        "geometry arrays have different lengths"
+    );
+    for i in 0..left.geom_len() {
+        let left_geom = left.geo_value(i).expect("left geometry is invalid");
+        let right_geom = right.geo_value(i).expect("right geometry is invalid");
+        let result = match (&left_geom, &right_geom) {
+            (Some(left_geom), Some(right_geom)) => {
+                compare_geometry(left_geom, right_geom, tolerance)
+            }
+            (None, None) => Ok(()),
+            _ => Err(format!(
+                "left is {:?}, right is {:?}",
+                left_geom.as_ref().map(geometry_name),
+                right_geom.as_ref().map(geometry_name)
+            )),
+        };
+        if let Err(diff) = result {
+            panic!("geometry arrays differ at row {}: {}", i, diff);
+        }
+    }
+}
+
+fn geometry_array(arr: &dyn Array) -> &dyn GeometryArray {
+    match arr.data_type() {
+        DataType::Binary => arr.as_binary::<i32>(),
+        DataType::LargeBinary => arr.as_binary::<i64>(),
+        data_type => panic!("{} is not a geometry array type", data_type),
+    }
+}
+
+fn geometry_name(geom: &geo::Geometry) -> &'static str {
+    match geom {
+        geo::Geometry::Point(_) => "Point",
+        geo::Geometry::Line(_) => "Line",
+        geo::Geometry::LineString(_) => "LineString",
+        geo::Geometry::Polygon(_) => "Polygon",
+        geo::Geometry::MultiPoint(_) => "MultiPoint",
+        geo::Geometry::MultiLineString(_) => "MultiLineString",
+        geo::Geometry::MultiPolygon(_) => "MultiPolygon",
+        geo::Geometry::GeometryCollection(_) => "GeometryCollection",
+        geo::Geometry::Rect(_) => "Rect",
+        geo::Geometry::Triangle(_) => "Triangle",
+    }
+}
+
+fn compare_geometry(
+    left: &geo::Geometry,
+    right: &geo::Geometry,
+    tolerance: f64,
+) -> Result<(), String> {
+    use geo::Geometry::*;
+    match (left, right) {
+        (Point(l), Point(r)) => compare_coords(&[l.0], &[r.0], tolerance),
+        (Line(l), Line(r)) => compare_coords(&[l.start, l.end], &[r.start, r.end], tolerance),
+        (LineString(l), LineString(r)) => compare_coords(&l.0, &r.0, tolerance),
+        (Polygon(l), Polygon(r)) => compare_polygon(l, r, tolerance),
+        (MultiPoint(l), MultiPoint(r)) => {
+            compare_members(&l.0, &r.0, |l, r| compare_coords(&[l.0], &[r.0], tolerance))
+        }
+        (MultiLineString(l), MultiLineString(r)) => {
+            compare_members(&l.0, &r.0, |l, r| compare_coords(&l.0, &r.0, tolerance))
+        }
+        (MultiPolygon(l), MultiPolygon(r)) => {
+            compare_members(&l.0, &r.0, |l, r| compare_polygon(l, r, tolerance))
+        }
+        (GeometryCollection(l), GeometryCollection(r)) => {
+            compare_members(&l.0, &r.0, |l, r| compare_geometry(l, r, tolerance))
+        }
+        (Rect(l), Rect(r)) => compare_coords(&[l.min(), l.max()], &[r.min(), r.max()], tolerance),
+        (Triangle(l), Triangle(r)) => compare_coords(&l.to_array(), &r.to_array(), tolerance),
+        _ => Err(format!(
+            "left is {}, right is {}",
+            geometry_name(left),
+            geometry_name(right)
+        )),
+    }
+}
+
+fn compare_polygon(
+    left: &geo::Polygon,
+    right: &geo::Polygon,
+    tolerance: f64,
+) -> Result<(), String> {
+    compare_coords(&left.exterior().0, &right.exterior().0, tolerance)
+        .map_err(|diff| format!("exterior {}", diff))?;
+    compare_members(left.interiors(), right.interiors(), |l, r| {
+        compare_coords(&l.0, &r.0, tolerance)
+    })
+    .map_err(|diff| format!("interior {}", diff))
+}
+
+fn compare_members<T>(
+    left: &[T],
+    right: &[T],
+    compare: impl Fn(&T, &T) -> Result<(), String>,
+) -> Result<(), String> {
+    if left.len() != right.len() {
+        return Err(format!(
+            "left has {} members, right has {}",
+            left.len(),
+            right.len()
+        ));
+    }
+    for (i, (l, r)) in left.iter().zip(right.iter()).enumerate() {
+        compare(l, r).map_err(|diff| format!("member {}: {}", i, diff))?;
+    }
+    Ok(())
+}
+
+#[allow(clippy::neg_cmp_op_on_partial_ord)]
+fn compare_coords(left: &[geo::Coord], right: &[geo::Coord], tolerance: f64) -> Result<(), String> {
+    if left.len() != right.len() {
+        return Err(format!(
+            "left has {} coordinates, right has {}",
+            left.len(),
+            right.len()
+        ));
+    }
+    for (i, (l, r)) in left.iter().zip(right.iter()).enumerate() {
+        // written as negated comparisons so that NaN coordinates never match
+        if !((l.x - r.x).abs() <= tolerance) || !((l.y - r.y).abs() <= tolerance) {
+            return Err(format!(
+                "coordinate {}: left is ({} {}), right is ({} {})",
+                i, l.x, l.y, r.x, r.y
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geo::GeometryArrayBuilder;
+    use crate::test_utils::assert_geometry_array_eq;
+    use geo::{line_string, polygon};
+    use geozero::wkb::WkbDialect;
+
+    #[test]
+    fn geometry_array_eq_across_dialects() {
+        let geoms = vec![
+            Some(geo::Geometry::LineString(
+                line_string![(x: 0., y: 0.), (x: 1., y: 1.)],
+            )),
+            None,
+            Some(geo::Geometry::Polygon(
+                polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.)],
+            )),
+        ];
+        let mut wkb_builder = GeometryArrayBuilder::<i32>::new(WkbDialect::Wkb, 3);
+        let mut ewkb_builder = GeometryArrayBuilder::<i64>::new(WkbDialect::Ewkb, 3);
+        for geom in geoms.iter() {
+            wkb_builder.append_geo_geometry(geom).unwrap();
+            ewkb_builder.append_geo_geometry(geom).unwrap();
+        }
+        assert_geometry_array_eq(&wkb_builder.build(), &ewkb_builder.build(), 0.0);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "geometry arrays differ at row 1: member 0: coordinate 1: left is (1 1), right is (1 1.5)"
+    )]
+    fn geometry_array_eq_diff() {
+        let left = vec![
+            Some(geo::Geometry::LineString(
+                line_string![(x: 0., y: 0.), (x: 1., y: 1.)],
+            )),
+            Some(geo::Geometry::MultiLineString(
+                vec![line_string![(x: 0., y: 0.), (x: 1., y: 1.)]].into(),
+            )),
+        ];
+        let right = vec![
+            Some(geo::Geometry::LineString(
+                line_string![(x: 0., y: 0.), (x: 1., y: 1.2)],
+            )),
+            Some(geo::Geometry::MultiLineString(
+                vec![line_string![(x: 0., y: 0.), (x: 1., y: 1.5)]].into(),
+            )),
+        ];
+        let left: GeometryArrayBuilder<i32> = left.as_slice().try_into().unwrap();
+        let right: GeometryArrayBuilder<i32> = right.as_slice().try_into().unwrap();
+        assert_geometry_array_eq(&left.build(), &right.build(), 0.25);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "geometry arrays differ at row 0: coordinate 0: left is (NaN 1), right is (0 1)"
+    )]
+    fn geometry_array_eq_nan() {
+        let left = vec![Some(geo::Geometry::Point(geo::Point::new(f64::NAN, 1.0)))];
+        let right = vec![Some(geo::Geometry::Point(geo::Point::new(0.0, 1.0)))];
+        let left: GeometryArrayBuilder<i32> = left.as_slice().try_into().unwrap();
+        let right: GeometryArrayBuilder<i32> = right.as_slice().try_into().unwrap();
+        assert_geometry_array_eq(&left.build(), &right.build(), f64::INFINITY);
+    }
+}
@@ -0,0 +1,95 @@
+#![cfg(feature = "datasource")]
+
+use arrow_array::RecordBatch;
+use arrow_schema::{DataType, Field, Schema};
+use datafusion::prelude::{SessionConfig, SessionContext};
+use datafusion_geo::config::CancellationFlag;
+use datafusion_geo::function::register_all;
+use datafusion_geo::geo::GeometryArrayBuilder;
+use geo::LineString;
+use std::sync::Arc;
+
+/// A zigzag line of many vertices, the distance between two of them is costly.
+fn zigzag(vertices: usize, y: f64) -> Option<LineString> {
+    let coords = (0..vertices)
+        .map(|i| (i as f64, y + (i % 2) as f64))
+        .collect::<Vec<_>>();
+    Some(LineString::from(coords))
+}
+
+/// A session checking its own cancellation flag.
+fn cancellable_session(flag: &Arc<CancellationFlag>) -> SessionContext {
+    let config = SessionConfig::new().with_extension(flag.clone());
+    let ctx = SessionContext::new_with_config(config);
+    register_all(&ctx, false);
+    ctx
+}
+
+#[tokio::test]
+async fn cancel_queries() {
+    let cancellation = Arc::new(CancellationFlag::new());
+    let other_cancellation = Arc::new(CancellationFlag::new());
+
+    let rows = 100;
+    let lines0 = (0..rows).map(|_| zigzag(1000, 0.0)).collect::<Vec<_>>();
+    let lines1 = (0..rows).map(|_| zigzag(1000, 5.0)).collect::<Vec<_>>();
+    let builder0: GeometryArrayBuilder<i32> = lines0.as_slice().try_into().unwrap();
+    let builder1: GeometryArrayBuilder<i32> = lines1.as_slice().try_into().unwrap();
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("a", DataType::Binary, true),
+        Field::new("b", DataType::Binary, true),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema,
+        vec![Arc::new(builder0.build()), Arc::new(builder1.build())],
+    )
+    .unwrap();
+
+    let ctx = cancellable_session(&cancellation);
+    ctx.register_batch("lines", batch).unwrap();
+
+    // the flag is set before the queries run, so they fail at their first row
+    cancellation.cancel();
+    let err = ctx
+        .sql("select ST_Distance(a, b) from lines")
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("query cancelled"), "{}", err);
+
+    // the accumulators of the aggregate functions check the flag too
+    for sql in [
+        "select ST_Extent(a) from lines",
+        "select ST_SummaryStats(a) from lines",
+    ] {
+        let err = ctx.sql(sql).await.unwrap().collect().await.unwrap_err();
+        assert!(err.to_string().contains("query cancelled"), "{}", err);
+    }
+    #[cfg(feature = "geos")]
+    {
+        let sql = "select ST_Union_Agg(a) from lines";
+        let err = ctx.sql(sql).await.unwrap().collect().await.unwrap_err();
+        assert!(err.to_string().contains("query cancelled"), "{}", err);
+    }
+
+    // the flag stays set until it is reset
+    let sql = "select ST_Distance(ST_GeomFromText(a), ST_GeomFromText(b)) \
+        from (values ('POINT(0 0)', 'POINT(3 4)')) as t(a, b)";
+    let err = ctx.sql(sql).await.unwrap().collect().await.unwrap_err();
+    assert!(err.to_string().contains("query cancelled"), "{}", err);
+
+    // the queries of other sessions keep running
+    let other_ctx = cancellable_session(&other_cancellation);
+    let batches = other_ctx.sql(sql).await.unwrap().collect().await.unwrap();
+    assert_eq!(batches[0].num_rows(), 1);
+    let plain_ctx = SessionContext::new();
+    register_all(&plain_ctx, false);
+    let batches = plain_ctx.sql(sql).await.unwrap().collect().await.unwrap();
+    assert_eq!(batches[0].num_rows(), 1);
+
+    cancellation.reset();
+    let batches = ctx.sql(sql).await.unwrap().collect().await.unwrap();
+    assert_eq!(batches[0].num_rows(), 1);
+}
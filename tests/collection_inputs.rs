@@ -0,0 +1,77 @@
+use arrow_array::cast::AsArray;
+use datafusion::logical_expr::ScalarUDF;
+use datafusion::prelude::SessionContext;
+use datafusion_geo::function::{
+    AffineUdf, ApplyXYUdf, AsTextUdf, GeomFromTextUdf, NormalizeForCompareUdf, ScaleUdf,
+    SimplifyForScaleUdf, TranslateUdf,
+};
+
+const COLLECTION: &str = "GEOMETRYCOLLECTION(POINT(1 1),LINESTRING(0 0,2 2))";
+const EMPTIES: [&str; 3] = [
+    "GEOMETRYCOLLECTION EMPTY",
+    "LINESTRING EMPTY",
+    "MULTIPOLYGON EMPTY",
+];
+
+#[tokio::test]
+async fn geometry_functions_keep_collections_and_empties() {
+    let ctx = SessionContext::new();
+    ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+    ctx.register_udf(ScalarUDF::from(AsTextUdf::new()));
+    ctx.register_udf(ScalarUDF::from(TranslateUdf::new()));
+    ctx.register_udf(ScalarUDF::from(AffineUdf::new()));
+    ctx.register_udf(ScalarUDF::from(ApplyXYUdf::new()));
+    ctx.register_udf(ScalarUDF::from(ScaleUdf::new()));
+    ctx.register_udf(ScalarUDF::from(SimplifyForScaleUdf::new()));
+    ctx.register_udf(ScalarUDF::from(NormalizeForCompareUdf::new()));
+
+    // function call on g, expected text of the collection
+    let cases = [
+        (
+            "ST_Translate(g, 1.0, 2.0)",
+            "GEOMETRYCOLLECTION(POINT(2 3),LINESTRING(1 2,3 4))",
+        ),
+        (
+            "ST_Affine(g, 1.0, 0.0, 0.0, 1.0, 1.0, 2.0)",
+            "GEOMETRYCOLLECTION(POINT(2 3),LINESTRING(1 2,3 4))",
+        ),
+        (
+            "ST_ApplyXY(g, 'x + 1', 'y + 2')",
+            "GEOMETRYCOLLECTION(POINT(2 3),LINESTRING(1 2,3 4))",
+        ),
+        (
+            "ST_Scale(g, 2.0, 2.0)",
+            "GEOMETRYCOLLECTION(POINT(2 2),LINESTRING(0 0,4 4))",
+        ),
+        ("ST_SimplifyForScale(g, 1)", COLLECTION),
+        ("ST_NormalizeForCompare(g)", COLLECTION),
+    ];
+    for (call, expected) in cases {
+        let wkts = std::iter::once(COLLECTION)
+            .chain(EMPTIES)
+            .map(|wkt| format!("('{}')", wkt))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let sql = format!(
+            "select ST_AsText({}) as result, ST_AsText(g) as input \
+            from (select ST_GeomFromText(wkt) as g from (values {}) as t(wkt))",
+            call, wkts
+        );
+        let batches = ctx.sql(&sql).await.unwrap().collect().await.unwrap();
+        let results = batches
+            .iter()
+            .flat_map(|batch| {
+                let result = batch.column(0).as_string::<i32>();
+                let input = batch.column(1).as_string::<i32>();
+                (0..batch.num_rows())
+                    .map(|i| (result.value(i).to_string(), input.value(i).to_string()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(results.len(), 1 + EMPTIES.len(), "{}", call);
+        assert_eq!(results[0].0, expected, "{}", call);
+        for (result, input) in &results[1..] {
+            assert_eq!(result, input, "{}", call);
+        }
+    }
+}
@@ -0,0 +1,241 @@
+use datafusion::logical_expr::ScalarUDF;
+use datafusion::prelude::SessionContext;
+use datafusion_common::ScalarValue;
+use datafusion_geo::config::GeoConfig;
+use datafusion_geo::function::GeomFromTextUdf;
+use datafusion_geo::geo::{geometry_scalar, GeometryArrayBuilder};
+use geo::point;
+use geozero::wkb::WkbDialect;
+use std::sync::Once;
+
+// the config is process wide, so it is only flipped in this test binary, before any test reads it
+fn set_wkb_dialect() {
+    static SET: Once = Once::new();
+    SET.call_once(|| {
+        GeoConfig::set(GeoConfig {
+            default_dialect: WkbDialect::Wkb,
+            ..Default::default()
+        })
+        .unwrap()
+    });
+}
+
+/// The type id prefix of plain wkb.
+const WKB_PREFIX: u8 = 1;
+
+/// Functions returning binary values which are not stored geometries.
+#[cfg(feature = "datasource")]
+const NOT_GEOMETRIES: [&str; 4] = ["ST_AsBinary", "ST_AsEWKB", "st_asgeobuf", "st_asmvt"];
+
+/// A query calling the function for every function of the crate returning a geometry.
+#[cfg(feature = "datasource")]
+fn geometry_queries() -> Vec<(&'static str, &'static str)> {
+    #[allow(unused_mut)]
+    let mut queries = vec![
+        ("ST_Affine", "select ST_Affine(ST_GeomFromText('POINT(1 1)'), 1.0, 0.0, 0.0, 1.0, 1.0, 1.0)"),
+        ("ST_ApplyXY", "select ST_ApplyXY(ST_GeomFromText('POINT(1 1)'), 'x + 1', 'y')"),
+        ("ST_AsMVTGeom", "select ST_AsMVTGeom(ST_GeomFromText('POLYGON((0 0,10 0,10 10,0 10,0 0))'), Box2D(ST_GeomFromText('LINESTRING(0 0,4096 4096)')))"),
+        ("ST_AssertGeometryType", "select ST_AssertGeometryType(ST_GeomFromText('POINT(1 1)'), 'POINT')"),
+        ("ST_CleanGeometry", "select ST_CleanGeometry(ST_GeomFromText('POLYGON((0 0,2 0,2 2,0 2,0 0))'))"),
+        ("ST_ClipByBox2D", "select ST_ClipByBox2D(ST_GeomFromText('POLYGON((0 0,2 0,2 2,0 2,0 0))'), Box2D(ST_GeomFromText('LINESTRING(0 0,1 1)')))"),
+        ("ST_DumpSegments", "select ST_DumpSegments(ST_GeomFromText('LINESTRING(0 0,1 1,2 0)'))"),
+        ("ST_Envelope", "select ST_Envelope(ST_GeomFromText('LINESTRING(0 0,1 1)'))"),
+        ("ST_FromGeobuf", "select ST_FromGeobuf(geobuf) from (select st_asgeobuf(ST_GeomFromText('POINT(1 2)')) as geobuf)"),
+        ("ST_GeomFromEWKT", "select ST_GeomFromEWKT('POINT(1 1)')"),
+        ("ST_GeomFromGeoJSON", "select ST_GeomFromGeoJSON('{\"type\":\"Point\",\"coordinates\":[1,1]}')"),
+        ("ST_GeomFromText", "select ST_GeomFromText('POINT(1 1)')"),
+        ("ST_GeomFromWKB", "select ST_GeomFromWKB(ST_AsBinary(ST_GeomFromText('POINT(1 1)')))"),
+        ("ST_GeometricMedian", "select ST_GeometricMedian(ST_GeomFromText('MULTIPOINT(0 0,1 1,2 0)'))"),
+        ("ST_GeometryN", "select ST_GeometryN(ST_GeomFromText('MULTIPOINT(0 0,1 1)'), 1)"),
+        ("ST_LineExtend", "select ST_LineExtend(ST_GeomFromText('LINESTRING(0 0,1 1)'), 1.0)"),
+        ("ST_NormalizeForCompare", "select ST_NormalizeForCompare(ST_GeomFromText('POLYGON((0 0,2 0,2 2,0 2,0 0))'))"),
+        ("ST_PixelAsPolygon", "select ST_PixelAsPolygon(Box2D(ST_GeomFromText('LINESTRING(0 0,10 5)')), 10, 5, 0, 0)"),
+        ("ST_ReducePoints", "select ST_ReducePoints(ST_GeomFromText('LINESTRING(0 0,1 1,2 0,3 1,4 0)'), 3)"),
+        ("ST_RingN", "select ST_RingN(ST_GeomFromText('POLYGON((0 0,2 0,2 2,0 2,0 0))'), 1)"),
+        ("ST_Rotate", "select ST_Rotate(ST_GeomFromText('LINESTRING(0 0,1 1)'), 1.0)"),
+        ("ST_RotateX", "select ST_RotateX(ST_GeomFromText('LINESTRING(0 0,1 1)'), 1.0)"),
+        ("ST_RotateY", "select ST_RotateY(ST_GeomFromText('LINESTRING(0 0,1 1)'), 1.0)"),
+        ("ST_Scale", "select ST_Scale(ST_GeomFromText('LINESTRING(0 0,1 1)'), 2.0, 2.0)"),
+        ("ST_ScaleToFit", "select ST_ScaleToFit(ST_GeomFromText('LINESTRING(10 20,20 40)'), Box2D(ST_GeomFromText('LINESTRING(10 20,20 40)')), Box2D(ST_GeomFromText('LINESTRING(0 0,100 100)')))"),
+        ("ST_Simplify", "select ST_Simplify(ST_GeomFromText('LINESTRING(0 0,1 0.01,2 0)'), 0.1)"),
+        ("ST_SimplifyForScale", "select ST_SimplifyForScale(ST_GeomFromText('LINESTRING(0 0,1 0.01,2 0)'), 10)"),
+        ("ST_SnapPointToGrid", "select ST_SnapPointToGrid(ST_GeomFromText('POINT(1.2 1.7)'), 1.0)"),
+        ("ST_TileEnvelope", "select ST_TileEnvelope(1, 0, 0)"),
+        ("ST_ToLargeGeometry", "select ST_ToLargeGeometry(ST_GeomFromText('POINT(1 1)'))"),
+        ("ST_ToSmallGeometry", "select ST_ToSmallGeometry(ST_ToLargeGeometry(ST_GeomFromText('POINT(1 1)')))"),
+        ("ST_Translate", "select ST_Translate(ST_GeomFromText('LINESTRING(0 0,1 1)'), 1.0, 1.0)"),
+    ];
+    #[cfg(feature = "geos")]
+    queries.extend([
+        ("ST_Boundary", "select ST_Boundary(ST_GeomFromText('POLYGON((0 0,2 0,2 2,0 2,0 0))'))"),
+        ("ST_Buffer", "select ST_Buffer(ST_GeomFromText('POINT(0 0)'), 1.0, 8::Integer)"),
+        ("ST_Difference", "select ST_Difference(ST_GeomFromText('POLYGON((0 0,2 0,2 2,0 2,0 0))'), ST_GeomFromText('POLYGON((1 1,3 1,3 3,1 3,1 1))'))"),
+        ("ST_Intersection", "select ST_Intersection(ST_GeomFromText('POLYGON((0 0,2 0,2 2,0 2,0 0))'), ST_GeomFromText('POLYGON((1 1,3 1,3 3,1 3,1 1))'))"),
+        ("ST_IsValidDetail", "select ST_IsValidDetail(ST_GeomFromText('POLYGON((0 0,1 1,1 0,0 1,0 0))'))"),
+        ("ST_MakeEnvelope", "select ST_MakeEnvelope(0.0, 0.0, 1.0, 1.0)"),
+        ("ST_Split", "select ST_Split(ST_GeomFromText('POLYGON((0 0,2 0,2 2,0 2,0 0))'), ST_GeomFromText('LINESTRING(1 -1,1 3)'))"),
+        ("ST_SymDifference", "select ST_SymDifference(ST_GeomFromText('POLYGON((0 0,2 0,2 2,0 2,0 0))'), ST_GeomFromText('POLYGON((1 1,3 1,3 3,1 3,1 1))'))"),
+        ("ST_Union", "select ST_Union(ST_GeomFromText('POLYGON((0 0,2 0,2 2,0 2,0 0))'), ST_GeomFromText('POLYGON((1 1,3 1,3 3,1 3,1 1))'))"),
+        ("ST_UnionArray", "select ST_UnionArray(make_array(ST_GeomFromText('POLYGON((0 0,2 0,2 2,0 2,0 0))'), ST_GeomFromText('POLYGON((1 1,3 1,3 3,1 3,1 1))')))"),
+        ("st_coverageinvalidedges", "select st_coverageinvalidedges(ST_GeomFromText(wkt)) from (values ('POLYGON((0 0,2 0,2 2,0 2,0 0))'), ('POLYGON((1 0,3 0,3 2,1 2,1 0))')) as t(wkt)"),
+        ("st_coverageunion", "select st_coverageunion(ST_GeomFromText(wkt)) from (values ('POLYGON((0 0,1 0,1 1,0 1,0 0))'), ('POLYGON((1 0,2 0,2 1,1 1,1 0))')) as t(wkt)"),
+        ("st_union_agg", "select st_union_agg(ST_GeomFromText(wkt)) from (values ('POLYGON((0 0,2 0,2 2,0 2,0 0))'), ('POLYGON((1 1,3 1,3 3,1 3,1 1))')) as t(wkt)"),
+    ]);
+    queries
+}
+
+/// The stored geometries of a column, including the ones in lists and structs.
+#[cfg(feature = "datasource")]
+fn geometry_values(arr: &arrow_array::ArrayRef) -> Vec<Vec<u8>> {
+    use arrow_array::cast::AsArray;
+    use arrow_array::Array;
+    use arrow_schema::DataType;
+
+    match arr.data_type() {
+        DataType::Binary => arr
+            .as_binary::<i32>()
+            .iter()
+            .flatten()
+            .map(<[u8]>::to_vec)
+            .collect(),
+        DataType::LargeBinary => arr
+            .as_binary::<i64>()
+            .iter()
+            .flatten()
+            .map(<[u8]>::to_vec)
+            .collect(),
+        DataType::List(_) => geometry_values(arr.as_list::<i32>().values()),
+        DataType::Struct(_) => arr
+            .as_struct()
+            .columns()
+            .iter()
+            .flat_map(geometry_values)
+            .collect(),
+        _ => vec![],
+    }
+}
+
+#[cfg(feature = "datasource")]
+#[tokio::test]
+async fn functions_honor_default_dialect() {
+    use datafusion_geo::function::{manifest, register_all};
+    use std::collections::BTreeSet;
+
+    set_wkb_dialect();
+    let ctx = SessionContext::new();
+    register_all(&ctx, true);
+
+    // the functions of the manifest with a signature returning a geometry
+    let manifest = manifest();
+    let geometry_functions = manifest
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|entry| {
+            entry["signatures"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .any(|signature| {
+                    signature["return_type"]
+                        .as_str()
+                        .is_some_and(|return_type| return_type.contains("Binary"))
+                })
+        })
+        .map(|entry| entry["name"].as_str().unwrap())
+        .filter(|name| !NOT_GEOMETRIES.contains(name))
+        .collect::<BTreeSet<_>>();
+    let queries = geometry_queries();
+    #[allow(unused_mut)]
+    let mut covered = queries
+        .iter()
+        .map(|(name, _)| *name)
+        .collect::<BTreeSet<_>>();
+    #[cfg(feature = "proj")]
+    covered.insert("ST_Transform");
+    assert_eq!(geometry_functions, covered);
+
+    let state = ctx.state();
+    for (name, sql) in queries {
+        if !state.scalar_functions().contains_key(name)
+            && !state.aggregate_functions().contains_key(name)
+        {
+            // left out by register_all as the linked geos is too old
+            continue;
+        }
+        let batches = ctx.sql(sql).await.unwrap().collect().await.unwrap();
+        let wkbs = batches
+            .iter()
+            .flat_map(|batch| batch.columns().iter().flat_map(geometry_values))
+            .collect::<Vec<_>>();
+        assert!(!wkbs.is_empty(), "{} returned no geometry: {}", name, sql);
+        for wkb in wkbs {
+            assert_eq!(wkb[0], WKB_PREFIX, "{}: {}", name, sql);
+        }
+    }
+
+    // the transformed geometry has the target srid, which the wkb dialect can't store
+    #[cfg(feature = "proj")]
+    {
+        let sql = "select ST_Transform(ST_GeomFromText('POINT(1 1)'), 4326, 3857)";
+        let err = match ctx.sql(sql).await {
+            Ok(df) => df.collect().await.unwrap_err(),
+            Err(err) => err,
+        };
+        assert!(
+            err.to_string()
+                .contains("Srid 3857 can't be stored in the Wkb dialect"),
+            "{}",
+            err
+        );
+    }
+}
+
+#[tokio::test]
+async fn builders_honor_default_dialect() {
+    set_wkb_dialect();
+    assert!(GeoConfig::set(GeoConfig::default()).is_err());
+
+    let builder: GeometryArrayBuilder<i32> = vec![Some(point!(x: 1.0, y: 1.0))]
+        .as_slice()
+        .try_into()
+        .unwrap();
+    assert_eq!(builder.build().value(0)[0], WKB_PREFIX);
+
+    let ScalarValue::Binary(Some(wkb)) =
+        geometry_scalar(&point!(x: 1.0, y: 1.0).into(), None).unwrap()
+    else {
+        panic!("geometry scalar should be binary");
+    };
+    assert_eq!(wkb[0], WKB_PREFIX);
+}
+
+#[tokio::test]
+async fn srid_requires_a_dialect_with_srids() {
+    set_wkb_dialect();
+    let expected = "Srid 4326 can't be stored in the Wkb dialect";
+    let err = geometry_scalar(&point!(x: 1.0, y: 1.0).into(), Some(4326)).unwrap_err();
+    assert!(err.to_string().contains(expected), "{}", err);
+    // srid 0 means no srid
+    geometry_scalar(&point!(x: 1.0, y: 1.0).into(), Some(0)).unwrap();
+
+    let ctx = SessionContext::new();
+    ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+    let mut queries = vec![
+        "select ST_GeomFromText(wkt, 4326) from (values ('POINT(1 1)')) as t(wkt)",
+        "select ST_GeomFromText(wkt) from (values ('SRID=4326;POINT(1 1)')) as t(wkt)",
+    ];
+    #[cfg(feature = "geos")]
+    {
+        use datafusion_geo::function::MakeEnvelopeUdf;
+        ctx.register_udf(ScalarUDF::from(MakeEnvelopeUdf::new()));
+        queries.push("select ST_MakeEnvelope(0.0, 0.0, 1.0, 1.0, 4326)");
+    }
+    for sql in queries {
+        let err = match ctx.sql(sql).await {
+            Ok(df) => df.collect().await.unwrap_err(),
+            Err(err) => err,
+        };
+        assert!(err.to_string().contains(expected), "{}: {}", sql, err);
+    }
+}
@@ -0,0 +1,86 @@
+use datafusion::logical_expr::ScalarUDF;
+use datafusion::prelude::SessionContext;
+use datafusion_geo::config::GeoConfig;
+use datafusion_geo::function::{GeomFromTextUdf, TranslateUdf};
+use datafusion_geo::geo::GeometryArrayBuilder;
+
+fn linestring(vertices: usize) -> String {
+    let coords = (0..vertices)
+        .map(|i| format!("{} {}", i, i))
+        .collect::<Vec<_>>();
+    format!("LINESTRING({})", coords.join(","))
+}
+
+// the config is process wide, so the tiny limits are only set in this test binary
+#[tokio::test]
+async fn geometry_size_limits() {
+    GeoConfig::set(GeoConfig {
+        max_wkb_bytes: 512,
+        max_vertices: 10,
+        ..Default::default()
+    })
+    .unwrap();
+
+    let ctx = SessionContext::new();
+    ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+    ctx.register_udf(ScalarUDF::from(TranslateUdf::new()));
+
+    // within both limits
+    let sql = format!(
+        "select ST_Translate(ST_GeomFromText('{}'), 1.0, 1.0)",
+        linestring(10)
+    );
+    ctx.sql(&sql).await.unwrap().collect().await.unwrap();
+
+    let sql = format!(
+        "select ST_Translate(ST_GeomFromText(wkt), 1.0, 1.0) \
+        from (values ('POINT(0 0)'), ('{}')) as t(wkt)",
+        linestring(12)
+    );
+    let err = ctx.sql(&sql).await.unwrap().collect().await.unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Geometry at row 1 has 12 vertices, exceeding the limit of 10"),
+        "{}",
+        err
+    );
+
+    let sql = format!("select ST_GeomFromText('{}')", linestring(40));
+    let err = match ctx.sql(&sql).await {
+        Ok(df) => df.collect().await.unwrap_err(),
+        Err(err) => err,
+    };
+    assert!(
+        err.to_string().contains("exceeding the limit of 512"),
+        "{}",
+        err
+    );
+
+    let line = geo::LineString::from((0..12).map(|i| (i as f64, i as f64)).collect::<Vec<_>>());
+    let err = GeometryArrayBuilder::<i32>::try_from(vec![None, Some(line)].as_slice()).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Geometry at row 1 has 12 vertices, exceeding the limit of 10"),
+        "{}",
+        err
+    );
+
+    #[cfg(feature = "geos")]
+    {
+        use datafusion_geo::function::BufferUdf;
+        ctx.register_udf(ScalarUDF::from(BufferUdf::new()));
+        let err = ctx
+            .sql("select ST_Buffer(ST_GeomFromText(wkt), 1.0, 8::Integer) from (values ('POINT(0 0)')) as t(wkt)")
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Geometry at row 0 has 33 vertices, exceeding the limit of 10"),
+            "{}",
+            err
+        );
+    }
+}
@@ -0,0 +1,82 @@
+#![cfg(all(feature = "geos", feature = "datasource"))]
+
+use datafusion::prelude::SessionContext;
+use datafusion_geo::function::register_all;
+use datafusion_geo::geo::{geos_capabilities, GeosCapabilities, GeosVersion};
+use std::sync::Once;
+
+// the capabilities are process wide, so they are only forced in this test binary
+fn force_geos_3_7() {
+    static SET: Once = Once::new();
+    SET.call_once(|| {
+        GeosCapabilities::set(GeosCapabilities {
+            version: Some(GeosVersion::new(3, 7, 2)),
+        })
+        .unwrap()
+    });
+}
+
+#[tokio::test]
+async fn unsupported_functions_fail_when_invoked() {
+    force_geos_3_7();
+    assert_eq!(geos_capabilities().version, Some(GeosVersion::new(3, 7, 2)));
+    let ctx = SessionContext::new();
+    register_all(&ctx, false);
+
+    let err = ctx
+        .sql(
+            "select ST_Intersection(ST_GeomFromText('POLYGON((0 0,2 0,2 2,0 2,0 0))'), \
+            ST_GeomFromText('POLYGON((1 1,3 1,3 3,1 3,1 1))'), 0.1)",
+        )
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string().contains(
+            "ST_Intersection with a grid size requires GEOS >= 3.9.0, the linked GEOS is 3.7.2"
+        ),
+        "{}",
+        err
+    );
+    // without a grid size the overlay does not depend on the version
+    ctx.sql(
+        "select ST_Intersection(ST_GeomFromText('POLYGON((0 0,2 0,2 2,0 2,0 0))'), \
+        ST_GeomFromText('POLYGON((1 1,3 1,3 3,1 3,1 1))'))",
+    )
+    .await
+    .unwrap()
+    .collect()
+    .await
+    .unwrap();
+
+    let err = ctx
+        .sql(
+            "select ST_CoverageUnion(ST_GeomFromText(wkt)) from (values \
+            ('POLYGON((0 0,1 0,1 1,0 1,0 0))'), \
+            ('POLYGON((1 0,2 0,2 1,1 1,1 0))')) as t(wkt)",
+        )
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("st_coverageunion requires GEOS >= 3.8.0, the linked GEOS is 3.7.2"),
+        "{}",
+        err
+    );
+}
+
+#[tokio::test]
+async fn register_all_skips_unsupported_functions() {
+    force_geos_3_7();
+    let ctx = SessionContext::new();
+    register_all(&ctx, true);
+    let state = ctx.state();
+    assert!(!state.aggregate_functions().contains_key("st_coverageunion"));
+    // functions only partially depending on the version stay registered
+    assert!(state.scalar_functions().contains_key("ST_Intersection"));
+}
@@ -0,0 +1,69 @@
+use datafusion::logical_expr::ScalarUDF;
+use datafusion::prelude::SessionContext;
+use datafusion_geo::function::{GeomFromTextUdf, IntersectsUdf, LengthUdf};
+use datafusion_geo::metrics;
+use std::time::Duration;
+
+#[tokio::test]
+async fn function_metrics() {
+    metrics::enable();
+    metrics::reset();
+
+    let ctx = SessionContext::new();
+    ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+    ctx.register_udf(ScalarUDF::from(IntersectsUdf::new()));
+    ctx.register_udf(ScalarUDF::from(LengthUdf::new()));
+    let df = ctx
+        .sql("select ST_Intersects(ST_GeomFromText(wkt), ST_GeomFromText('POINT(1 1)')) from (values ('POINT(1 1)'), ('POINT(2 2)')) as t(wkt)")
+        .await
+        .unwrap();
+    let _ = df.collect().await.unwrap();
+
+    let snapshot = metrics::snapshot();
+    let intersects = snapshot.function("ST_Intersects").unwrap();
+    assert!(intersects.calls > 0);
+    assert!(intersects.rows >= 2);
+    assert!(intersects.decode_time > Duration::ZERO);
+    assert!(intersects.compute_time > Duration::ZERO);
+
+    // the functions without explicit timing report their decode and compute time too
+    let df = ctx
+        .sql("select ST_Length(ST_GeomFromText(wkt)) from (values ('LINESTRING(0 0,3 4)'), ('LINESTRING(0 0,1 1,2 0)'), (null)) as t(wkt)")
+        .await
+        .unwrap();
+    let _ = df.collect().await.unwrap();
+    let snapshot = metrics::snapshot();
+    let length = snapshot.function("ST_Length").unwrap();
+    assert!(length.calls > 0);
+    assert!(length.rows >= 3);
+    assert!(length.decode_time > Duration::ZERO);
+    assert!(length.compute_time > Duration::ZERO);
+    let from_text = snapshot.function("ST_GeomFromText").unwrap();
+    assert!(from_text.compute_time > Duration::ZERO);
+
+    // the transformation is built by the first query and reused by the second one
+    #[cfg(feature = "proj")]
+    {
+        ctx.register_udf(ScalarUDF::from(
+            datafusion_geo::function::TransformUdf::new(),
+        ));
+        for _ in 0..2 {
+            let df = ctx
+                .sql("select ST_Transform(ST_GeomFromText('POINT(2.35 48.85)', 4326), 2154)")
+                .await
+                .unwrap();
+            let _ = df.collect().await.unwrap();
+        }
+        let proj_cache = metrics::snapshot().proj_cache;
+        assert_eq!((proj_cache.hits, proj_cache.misses), (1, 1));
+    }
+
+    metrics::disable();
+    metrics::reset();
+    let df = ctx
+        .sql("select ST_Intersects(ST_GeomFromText('POINT(1 1)'), ST_GeomFromText('POINT(1 1)'))")
+        .await
+        .unwrap();
+    let _ = df.collect().await.unwrap();
+    assert!(metrics::snapshot().functions.is_empty());
+}
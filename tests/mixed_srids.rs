@@ -0,0 +1,24 @@
+use arrow_array::cast::AsArray;
+use datafusion::logical_expr::ScalarUDF;
+use datafusion::prelude::SessionContext;
+use datafusion_geo::config::GeoConfig;
+use datafusion_geo::function::{GeomFromTextUdf, IntersectsUdf};
+
+// the config is process wide, so the srid check is only disabled in this test binary
+#[tokio::test]
+async fn predicates_ignore_mixed_srids_when_disabled() {
+    GeoConfig::set(GeoConfig {
+        check_srids: false,
+        ..Default::default()
+    })
+    .unwrap();
+
+    let ctx = SessionContext::new();
+    ctx.register_udf(ScalarUDF::from(GeomFromTextUdf::new()));
+    ctx.register_udf(ScalarUDF::from(IntersectsUdf::new()));
+    let sql = "select ST_Intersects(\
+        ST_GeomFromText('POLYGON((0 0,2 0,2 2,0 2,0 0))', 4326), \
+        ST_GeomFromText('POINT(1 1)', 3857))";
+    let batches = ctx.sql(sql).await.unwrap().collect().await.unwrap();
+    assert!(batches[0].column(0).as_boolean().value(0));
+}